@@ -0,0 +1,282 @@
+use lum_log::{debug, info};
+
+use crate::{
+    http::HttpClient,
+    provider::{
+        AddRecordInput, DeleteRecordInput, Feature, GetRecordsInput, Provider, ProviderError,
+        Result,
+    },
+    types::dns::{Record, RecordType, RecordValue},
+};
+
+pub const ACME_CHALLENGE_LABEL: &str = "_acme-challenge";
+
+/// Returns the name of the TXT record used for the DNS-01 challenge of `domain`.
+///
+/// Wildcard domains are validated through the challenge record of their base domain.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::acme::challenge_record_name;
+///
+/// assert_eq!(challenge_record_name("example.com"), "_acme-challenge.example.com");
+/// assert_eq!(challenge_record_name("*.example.com"), "_acme-challenge.example.com");
+/// ```
+pub fn challenge_record_name(domain: &str) -> String {
+    let domain = domain.strip_prefix("*.").unwrap_or(domain);
+    let domain = domain.trim_end_matches('.');
+    format!("{}.{}", ACME_CHALLENGE_LABEL, domain)
+}
+
+/// Builds the TXT record holding the DNS-01 challenge `token_value` for `domain`.
+pub fn challenge_record(domain: &str, token_value: &str, ttl: Option<u32>) -> Record {
+    Record {
+        domain: challenge_record_name(domain),
+        value: RecordValue::TXT(token_value.to_string()),
+        ttl,
+    }
+}
+
+fn ensure_supported(provider: &dyn Provider, feature: Feature) -> Result<()> {
+    if provider.is_feature_supported(&feature) {
+        Ok(())
+    } else {
+        Err(ProviderError::UnsupportedFeature(
+            feature,
+            provider.get_provider_name(),
+        ))
+    }
+}
+
+/// Publishes a DNS-01 challenge for `domain` in `zone`.
+///
+/// The challenge is always added rather than updated, so several challenges for the same name
+/// (e.g. for `example.com` and `*.example.com` in one order) can exist at the same time.
+pub async fn set_acme_challenge(
+    provider: &dyn Provider,
+    http: &dyn HttpClient,
+    zone: &str,
+    domain: &str,
+    token_value: &str,
+    ttl: Option<u32>,
+) -> Result<Record> {
+    ensure_supported(provider, Feature::AddRecord)?;
+
+    let record = challenge_record(domain, token_value, ttl);
+    let input = AddRecordInput {
+        domain: zone,
+        record: &record,
+    };
+
+    provider.add_record(http, &input).await?;
+    info!("Set ACME challenge {}", record.domain);
+
+    Ok(record)
+}
+
+/// Removes DNS-01 challenges for `domain` in `zone`.
+///
+/// With a `token_value`, only the challenge with that value is removed and other simultaneous
+/// challenges on the same name are left alone. Without one, all challenge TXT records on the
+/// name are removed. Returns the removed records.
+pub async fn clear_acme_challenge(
+    provider: &dyn Provider,
+    http: &dyn HttpClient,
+    zone: &str,
+    domain: &str,
+    token_value: Option<&str>,
+) -> Result<Vec<Record>> {
+    ensure_supported(provider, Feature::DeleteRecord)?;
+
+    let records = match token_value {
+        Some(token_value) => vec![challenge_record(domain, token_value, None)],
+        None => {
+            let name = challenge_record_name(domain);
+            let input = GetRecordsInput {
+                domain: zone,
+                subdomains: vec![name.as_str()],
+                record_type: Some(RecordType::TXT),
+            };
+
+            provider
+                .get_records(http, &input)
+                .await?
+                .into_iter()
+                .filter(|record| matches!(record.value, RecordValue::TXT(_)))
+                .collect()
+        }
+    };
+
+    for record in records.iter() {
+        let input = DeleteRecordInput {
+            domain: zone,
+            record,
+        };
+
+        provider.delete_record(http, &input).await?;
+        debug!("Deleted ACME challenge record {:?}", record);
+    }
+
+    info!(
+        "Cleared {} ACME challenge(s) for {}",
+        records.len(),
+        challenge_record_name(domain)
+    );
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{GetAllRecordsInput, UpdateRecordInput};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingProvider {
+        records: Mutex<Vec<Record>>,
+    }
+
+    #[async_trait]
+    impl Provider for RecordingProvider {
+        fn get_provider_name(&self) -> &'static str {
+            "Recording"
+        }
+
+        fn get_supported_features(&self) -> Vec<Feature> {
+            vec![
+                Feature::GetRecords,
+                Feature::GetAllRecords,
+                Feature::AddRecord,
+                Feature::DeleteRecord,
+            ]
+        }
+
+        async fn get_all_records(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &GetAllRecordsInput,
+        ) -> Result<Vec<Record>> {
+            Ok(self.records.lock().unwrap().clone())
+        }
+
+        async fn add_record(&self, _http: &dyn HttpClient, input: &AddRecordInput) -> Result<()> {
+            self.records.lock().unwrap().push(input.record.clone());
+            Ok(())
+        }
+
+        async fn update_record(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &UpdateRecordInput,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn delete_record(
+            &self,
+            _http: &dyn HttpClient,
+            input: &DeleteRecordInput,
+        ) -> Result<()> {
+            let mut records = self.records.lock().unwrap();
+            records.retain(|record| {
+                record.domain != input.record.domain
+                    || !matches!(
+                        (&record.value, &input.record.value),
+                        (RecordValue::TXT(a), RecordValue::TXT(b)) if a == b
+                    )
+            });
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_challenge_record() {
+        let record = challenge_record("www.example.com", "token123", Some(60));
+
+        assert_eq!(record.domain, "_acme-challenge.www.example.com");
+        assert_eq!(record.ttl, Some(60));
+        match record.value {
+            RecordValue::TXT(value) => assert_eq!(value, "token123"),
+            _ => panic!("Expected TXT record"),
+        }
+    }
+
+    #[test]
+    fn test_challenge_record_name_wildcard_and_trailing_dot() {
+        assert_eq!(
+            challenge_record_name("*.example.com."),
+            "_acme-challenge.example.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_acme_challenge_keeps_simultaneous_challenges() {
+        let provider = RecordingProvider::default();
+        let reqwest = reqwest::Client::new();
+
+        set_acme_challenge(&provider, &reqwest, "example.com", "example.com", "a", None)
+            .await
+            .unwrap();
+        set_acme_challenge(
+            &provider,
+            &reqwest,
+            "example.com",
+            "*.example.com",
+            "b",
+            None,
+        )
+        .await
+        .unwrap();
+
+        let records = provider.records.lock().unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(
+            records
+                .iter()
+                .all(|record| record.domain == "_acme-challenge.example.com")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clear_acme_challenge_with_value_only_removes_that_value() {
+        let provider = RecordingProvider::default();
+        let reqwest = reqwest::Client::new();
+
+        set_acme_challenge(&provider, &reqwest, "example.com", "example.com", "a", None)
+            .await
+            .unwrap();
+        set_acme_challenge(&provider, &reqwest, "example.com", "example.com", "b", None)
+            .await
+            .unwrap();
+
+        clear_acme_challenge(&provider, &reqwest, "example.com", "example.com", Some("a"))
+            .await
+            .unwrap();
+
+        let records = provider.records.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(matches!(&records[0].value, RecordValue::TXT(value) if value == "b"));
+    }
+
+    #[tokio::test]
+    async fn test_clear_acme_challenge_without_value_removes_all() {
+        let provider = RecordingProvider::default();
+        let reqwest = reqwest::Client::new();
+
+        set_acme_challenge(&provider, &reqwest, "example.com", "example.com", "a", None)
+            .await
+            .unwrap();
+        set_acme_challenge(&provider, &reqwest, "example.com", "example.com", "b", None)
+            .await
+            .unwrap();
+
+        let cleared = clear_acme_challenge(&provider, &reqwest, "example.com", "example.com", None)
+            .await
+            .unwrap();
+
+        assert_eq!(cleared.len(), 2);
+        assert!(provider.records.lock().unwrap().is_empty());
+    }
+}
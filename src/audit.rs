@@ -0,0 +1,231 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+};
+
+use chrono::{SecondsFormat, Utc};
+use lum_libs::{serde::Serialize, serde_json};
+use lum_log::warn;
+use thiserror::Error;
+
+use crate::{
+    lock::{self, DEFAULT_LOCK_TIMEOUT, FileLock, LockError},
+    types::dns::{Record, RecordType},
+};
+
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Lock(#[from] LockError),
+}
+
+/// What was done to a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub enum Action {
+    Add,
+    Update,
+    Delete,
+}
+
+/// A change made to a record at a provider, written as one JSON line to the audit log.
+///
+/// Values are in their canonical form, see [`RecordValue::canonical_content`]. `old_value` is
+/// only known for updates of records published by a previous run or read from the provider.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::audit::{Action, AuditEntry};
+/// use dnrs::types::dns::{Record, RecordValue};
+/// use std::net::Ipv4Addr;
+///
+/// let record = Record {
+///     domain: "home.example.com".to_string(),
+///     value: RecordValue::A(Ipv4Addr::new(5, 6, 7, 8)),
+///     ttl: None,
+/// };
+///
+/// let entry = AuditEntry::update("Hetzner1", Some("1.2.3.4".to_string()), &record);
+/// assert_eq!(entry.action, Action::Update);
+/// assert_eq!(entry.old_value.as_deref(), Some("1.2.3.4"));
+/// assert_eq!(entry.new_value.as_deref(), Some("5.6.7.8"));
+/// ```
+///
+/// [`RecordValue::canonical_content`]: crate::types::dns::RecordValue::canonical_content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct AuditEntry {
+    /// When the change was made, in RFC 3339 format and UTC.
+    pub timestamp: String,
+    pub provider: String,
+    pub domain: String,
+    #[serde(rename = "type")]
+    pub record_type: RecordType,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub action: Action,
+}
+
+impl AuditEntry {
+    fn new(
+        action: Action,
+        provider_name: &str,
+        record: &Record,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    ) -> Self {
+        AuditEntry {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            provider: provider_name.to_string(),
+            domain: record.domain.clone(),
+            record_type: record.value.record_type(),
+            old_value,
+            new_value,
+            action,
+        }
+    }
+
+    pub fn add(provider_name: &str, record: &Record) -> Self {
+        let new_value = Some(record.value.canonical_content());
+        Self::new(Action::Add, provider_name, record, None, new_value)
+    }
+
+    /// An update of a record to the value of `record`, from `old_value` if it is known.
+    pub fn update(provider_name: &str, old_value: Option<String>, record: &Record) -> Self {
+        let new_value = Some(record.value.canonical_content());
+        Self::new(Action::Update, provider_name, record, old_value, new_value)
+    }
+
+    pub fn delete(provider_name: &str, record: &Record) -> Self {
+        let old_value = Some(record.value.canonical_content());
+        Self::new(Action::Delete, provider_name, record, old_value, None)
+    }
+}
+
+/// Appends `entries` to the audit log at `path` as JSON lines, creating it if needed.
+///
+/// All lines are written with a single append while holding the lock file of the log, see
+/// [`lock::lock_path`], so concurrent dnrs instances never interleave their lines.
+pub fn append(path: &Path, entries: &[AuditEntry]) -> Result<(), AuditError> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry)?);
+        lines.push('\n');
+    }
+
+    let _lock = FileLock::acquire(&lock::lock_path(path), DEFAULT_LOCK_TIMEOUT)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(lines.as_bytes())?;
+    file.sync_data()?;
+
+    Ok(())
+}
+
+/// Appends `entries` to the audit log at `path`, if one is configured.
+///
+/// Failures are logged, an audit log that can not be written never fails the run, as the
+/// changes were already made.
+pub fn record(path: Option<&Path>, entries: &[AuditEntry]) {
+    let Some(path) = path else {
+        return;
+    };
+
+    if let Err(e) = append(path, entries) {
+        warn!("Failed to write audit log {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dns::RecordValue;
+    use lum_libs::serde_json::Value;
+    use std::{fs, net::Ipv4Addr};
+
+    fn record(ip: Ipv4Addr) -> Record {
+        Record {
+            domain: "home.example.com".to_string(),
+            value: RecordValue::A(ip),
+            ttl: Some(300),
+        }
+    }
+
+    #[test]
+    fn test_two_updates_append_two_lines() {
+        let dir = std::env::temp_dir().join("dnrs_test_audit_log");
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.jsonl");
+
+        let first = AuditEntry::update("Hetzner1", None, &record(Ipv4Addr::new(1, 2, 3, 4)));
+        append(&path, &[first]).unwrap();
+        let second = AuditEntry::update(
+            "Hetzner1",
+            Some("1.2.3.4".to_string()),
+            &record(Ipv4Addr::new(5, 6, 7, 8)),
+        );
+        append(&path, &[second]).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        for line in lines.iter() {
+            let mut keys: Vec<&str> = line
+                .as_object()
+                .unwrap()
+                .keys()
+                .map(String::as_str)
+                .collect();
+            keys.sort_unstable();
+            assert_eq!(
+                keys,
+                [
+                    "action",
+                    "domain",
+                    "new_value",
+                    "old_value",
+                    "provider",
+                    "timestamp",
+                    "type"
+                ]
+            );
+            assert_eq!(line["provider"], "Hetzner1");
+            assert_eq!(line["domain"], "home.example.com");
+            assert_eq!(line["type"], "A");
+            assert_eq!(line["action"], "Update");
+            assert!(line["timestamp"].as_str().unwrap().ends_with('Z'));
+        }
+        assert_eq!(lines[0]["old_value"], Value::Null);
+        assert_eq!(lines[0]["new_value"], "1.2.3.4");
+        assert_eq!(lines[1]["old_value"], "1.2.3.4");
+        assert_eq!(lines[1]["new_value"], "5.6.7.8");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_delete_has_no_new_value() {
+        let entry = AuditEntry::delete("Netcup1", &record(Ipv4Addr::new(1, 2, 3, 4)));
+        assert_eq!(entry.action, Action::Delete);
+        assert_eq!(entry.old_value.as_deref(), Some("1.2.3.4"));
+        assert_eq!(entry.new_value, None);
+    }
+}
@@ -1,9 +1,39 @@
+pub mod acme;
+pub mod add;
 pub mod auto;
 pub mod command;
+pub mod delete;
+pub mod export;
+pub mod format;
 pub mod generate_config;
 pub mod get;
+pub mod import;
+pub mod providers;
+pub mod prune;
+pub mod resolve;
+pub mod test_resolver;
+pub mod validate;
+pub mod wait;
+pub mod watch;
+pub mod write;
 
-use std::future::Future;
+use std::{
+    borrow::Cow,
+    ffi::OsString,
+    fs,
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+};
+
+use lum_log::warn;
+
+use crate::{
+    Config,
+    provider::{Provider, ProviderError, naming},
+    types::dns::{self, Record, RecordType},
+    zone::{self, ZoneError},
+};
 
 pub use command::{Command, Subcommand};
 
@@ -13,3 +43,113 @@ pub trait ExecutableCommand<'input> {
 
     fn execute(&self, input: &'input Self::I) -> impl Future<Output = Self::R>;
 }
+
+/// Returns `config` with the zone id of `domain` at the provider `provider_name` set to
+/// `zone_id`, given with `--zone-id`. Providers that do not address zones by id ignore it.
+pub fn with_zone_id<'config>(
+    config: &'config Config,
+    provider_name: &str,
+    domain: &str,
+    zone_id: Option<&str>,
+) -> Cow<'config, Config> {
+    let Some(zone_id) = zone_id else {
+        return Cow::Borrowed(config);
+    };
+
+    let mut config = config.clone();
+    let provider = config
+        .providers
+        .iter_mut()
+        .find(|provider| provider.name() == provider_name);
+    if let Some(provider) = provider
+        && !provider.set_zone_id(domain, zone_id)
+    {
+        warn!(
+            "Ignoring --zone-id, provider {} does not address zones by id",
+            provider_name
+        );
+    }
+
+    Cow::Owned(config)
+}
+
+/// Returns the zone `fqdn` is managed in at `provider`: `zone` if given with `--zone`, otherwise
+/// the zone the provider derives, see [`Provider::zone_of`]. An explicit zone is needed where a
+/// subdomain is delegated as a zone of its own.
+///
+/// Returns [`ProviderError::RecordOutsideZone`] if `fqdn` is not within an explicit `zone`.
+pub fn zone_of(
+    provider: &dyn Provider,
+    fqdn: &str,
+    zone: Option<&str>,
+) -> Result<String, ProviderError> {
+    let Some(zone) = zone else {
+        return provider
+            .zone_of(fqdn)
+            .ok_or_else(|| ProviderError::NoZone(fqdn.to_string()));
+    };
+
+    if !naming::is_within(fqdn, zone) {
+        return Err(ProviderError::RecordOutsideZone {
+            record: fqdn.to_string(),
+            zone: zone.to_string(),
+        });
+    }
+
+    Ok(zone.trim_end_matches('.').to_string())
+}
+
+/// Parses the record `fqdn` of `zone` given on the command line, with `value` written as in a
+/// zone file.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::cli::parse_record;
+/// use dnrs::types::dns::RecordType;
+///
+/// let value = vec!["10".to_string(), "mail".to_string()];
+/// let record = parse_record("example.com", RecordType::MX, &value, Some(300), "example.com")
+///     .unwrap();
+/// assert_eq!(record.to_string(), "example.com. 300 IN MX 10 mail.example.com.");
+/// ```
+pub fn parse_record(
+    fqdn: &str,
+    record_type: RecordType,
+    value: &[String],
+    ttl: Option<u32>,
+    zone: &str,
+) -> Result<Record, ZoneError> {
+    let ttl = ttl.map(|ttl| format!("{} ", ttl)).unwrap_or_default();
+    let line = format!(
+        "{} {}IN {} {}",
+        dns::absolute_name(fqdn),
+        ttl,
+        record_type,
+        value.join(" ")
+    );
+    let mut records = zone::parse(&line, zone)?;
+
+    Ok(records.remove(0))
+}
+
+/// Writes the output of a command to `path`, or prints it to stdout if no path is given.
+///
+/// The file is written to a temporary file next to it first and renamed, so other tools never
+/// read a partially written file.
+pub fn write_output(path: Option<&Path>, output: &str) -> io::Result<()> {
+    let Some(path) = path else {
+        print!("{}", output);
+        return Ok(());
+    };
+
+    let temp_path = temp_path(path);
+    fs::write(&temp_path, output)?;
+    fs::rename(&temp_path, path)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
@@ -0,0 +1,139 @@
+use std::marker::PhantomData;
+
+use clap::{Args, Parser, Subcommand as ClapSubcommand};
+use lum_log::info;
+use thiserror::Error;
+
+use crate::{
+    Config, acme,
+    cli::{
+        ExecutableCommand,
+        wait::{self, WaitArgs},
+    },
+    provider::{ProviderError, get_provider},
+};
+
+#[derive(Debug)]
+pub struct Input<'config> {
+    pub config: &'config Config,
+    pub reqwest: reqwest::Client,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Provider error: {0}")]
+    Provider(#[from] ProviderError),
+
+    #[error("{0}")]
+    Wait(#[from] wait::Error),
+}
+
+#[derive(Debug, Args)]
+pub struct ChallengeArgs {
+    /// Name of the provider managing the zone
+    #[clap(display_order = 1)]
+    pub provider: String,
+
+    /// Domain the certificate is issued for (e.g. www.example.com or *.example.com)
+    #[clap(display_order = 2)]
+    pub domain: String,
+
+    /// Zone the challenge record is created in (defaults to the domain)
+    #[clap(long, display_order = 4)]
+    pub zone: Option<String>,
+}
+
+impl ChallengeArgs {
+    fn zone(&self) -> &str {
+        self.zone
+            .as_deref()
+            .unwrap_or_else(|| self.domain.strip_prefix("*.").unwrap_or(&self.domain))
+    }
+}
+
+#[derive(Debug, ClapSubcommand)]
+pub enum Action {
+    /// Publish a DNS-01 challenge TXT record
+    Set {
+        #[command(flatten)]
+        challenge: ChallengeArgs,
+
+        /// Challenge value provided by the ACME client
+        #[clap(display_order = 3)]
+        value: String,
+
+        /// TTL of the challenge record
+        #[clap(long, display_order = 5)]
+        ttl: Option<u32>,
+
+        #[command(flatten)]
+        wait: WaitArgs,
+    },
+
+    /// Remove DNS-01 challenge TXT records
+    Clear {
+        #[command(flatten)]
+        challenge: ChallengeArgs,
+
+        /// Only remove the challenge with this value (removes all challenges if omitted)
+        #[clap(display_order = 3)]
+        value: Option<String>,
+    },
+}
+
+/// Manage ACME DNS-01 challenge records
+#[derive(Debug, Parser)]
+#[command(version, about, long_about = None, propagate_version = true)]
+pub struct Command<'command> {
+    #[clap(skip)]
+    _phantom: PhantomData<&'command ()>,
+
+    #[command(subcommand)]
+    pub action: Action,
+}
+
+impl<'command> ExecutableCommand<'command> for Command<'command> {
+    type I = Input<'command>;
+    type R = Result<(), Error>;
+
+    async fn execute(&self, input: &'command Self::I) -> Self::R {
+        let config = input.config;
+        let reqwest = &input.reqwest;
+
+        match &self.action {
+            Action::Set {
+                challenge,
+                value,
+                ttl,
+                wait,
+            } => {
+                let provider = get_provider(&challenge.provider, config)?;
+                let record = acme::set_acme_challenge(
+                    provider.as_ref(),
+                    reqwest,
+                    challenge.zone(),
+                    &challenge.domain,
+                    value,
+                    *ttl,
+                )
+                .await?;
+
+                info!("Challenge record: {:#?}", record);
+                wait.wait_for(&[&record]).await?;
+            }
+            Action::Clear { challenge, value } => {
+                let provider = get_provider(&challenge.provider, config)?;
+                acme::clear_acme_challenge(
+                    provider.as_ref(),
+                    reqwest,
+                    challenge.zone(),
+                    &challenge.domain,
+                    value.as_deref(),
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
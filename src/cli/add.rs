@@ -0,0 +1,279 @@
+use std::marker::PhantomData;
+
+use clap::Parser;
+use lum_log::info;
+use thiserror::Error;
+
+use crate::{
+    Config,
+    audit::{self, AuditEntry},
+    cli::{self, ExecutableCommand, wait, write::WriteArgs},
+    provider::{GetAllRecordsInput, ProviderError, UpsertRecordInput, get_provider, naming},
+    types::dns::{Record, RecordType},
+    zone::ZoneError,
+};
+
+#[derive(Debug)]
+pub struct Input<'config> {
+    pub config: &'config Config,
+    pub reqwest: reqwest::Client,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Provider error: {0}")]
+    Provider(#[from] ProviderError),
+
+    #[error("Invalid record: {0}")]
+    Record(#[from] ZoneError),
+
+    #[error("{0}")]
+    Wait(#[from] wait::Error),
+}
+
+/// Add a record, given by its fully qualified name, replacing one of the same name and type
+#[derive(Debug, Parser)]
+#[command(version, about, long_about = None, propagate_version = true)]
+pub struct Command<'command> {
+    #[clap(skip)]
+    _phantom: PhantomData<&'command ()>,
+
+    /// Name of the provider to add the record to
+    #[clap(display_order = 1)]
+    provider: String,
+
+    /// Fully qualified name of the record, e.g. www.example.com
+    #[clap(display_order = 2)]
+    name: String,
+
+    /// Type of the record, e.g. A
+    #[clap(display_order = 3)]
+    record_type: RecordType,
+
+    /// Value of the record as written in a zone file, e.g. `10 mail.example.com.` for MX. May be
+    /// left out for A and AAAA records given with `--ipv4` or `--ipv6`
+    #[clap(display_order = 4, num_args = 1.., required_unless_present_any = ["ipv4", "ipv6"])]
+    value: Vec<String>,
+
+    /// TTL of the record in seconds, the provider's default if not given
+    #[clap(long)]
+    ttl: Option<u32>,
+
+    /// Zone the record belongs to, derived from the name by default. Needed for subdomains that
+    /// are delegated as zones of their own
+    #[clap(long)]
+    zone: Option<String>,
+
+    #[command(flatten)]
+    write: WriteArgs,
+}
+
+impl Command<'_> {
+    /// Returns the value of the record: the address given with `--ipv4` for A and `--ipv6` for
+    /// AAAA records, the positional value otherwise.
+    fn value(&self) -> Vec<String> {
+        let address = match self.record_type {
+            RecordType::A => self.write.ipv4.map(|ip| ip.to_string()),
+            RecordType::AAAA => self.write.ipv6.map(|ip| ip.to_string()),
+            _ => None,
+        };
+
+        address.map_or_else(|| self.value.clone(), |address| vec![address])
+    }
+
+    /// Parses the record to write in `zone`, with the TTL of `--ttl-override` if given.
+    fn record(&self, zone: &str) -> Result<Record, Error> {
+        let mut record =
+            cli::parse_record(&self.name, self.record_type, &self.value(), self.ttl, zone)?;
+        self.write
+            .apply_ttl_override(std::slice::from_mut(&mut record));
+
+        Ok(record)
+    }
+}
+
+/// Returns the audit entry for writing `record` to `zone`. It is an update of the record with the
+/// same name and type in `existing` if there is one, otherwise an addition.
+fn audit_entry(
+    provider_name: &str,
+    existing: &[Record],
+    record: &Record,
+    zone: &str,
+) -> AuditEntry {
+    let replaced = existing.iter().find(|existing| {
+        existing.value.wire_type() == record.value.wire_type()
+            && naming::same_name(&existing.domain, &record.domain, zone)
+    });
+
+    match replaced {
+        Some(replaced) => AuditEntry::update(
+            provider_name,
+            Some(replaced.value.canonical_content()),
+            record,
+        ),
+        None => AuditEntry::add(provider_name, record),
+    }
+}
+
+impl<'command> ExecutableCommand<'command> for Command<'command> {
+    type I = Input<'command>;
+    type R = Result<(), Error>;
+
+    async fn execute(&self, input: &'command Self::I) -> Self::R {
+        let provider = get_provider(&self.provider, input.config)?;
+        let zone = cli::zone_of(provider.as_ref(), &self.name, self.zone.as_deref())?;
+        let record = provider.clamp_ttl(&self.record(&zone)?);
+        provider.validate_zone(&zone, std::slice::from_ref(&record))?;
+        provider.validate_records(std::slice::from_ref(&record))?;
+
+        // Read before writing, so the audit log knows the value that gets replaced
+        let get_all_records_input = GetAllRecordsInput { domain: &zone };
+        let existing = provider
+            .get_all_records(&input.reqwest, &get_all_records_input)
+            .await?;
+        let audit_entry = audit_entry(&self.provider, &existing, &record, &zone);
+
+        let upsert_record_input = UpsertRecordInput {
+            domain: &zone,
+            record: &record,
+        };
+        provider
+            .upsert_record(&input.reqwest, &upsert_record_input)
+            .await?;
+        match &audit_entry.old_value {
+            Some(old_value) => info!(
+                "Replaced {} with {} in zone {} at {}",
+                old_value, record, zone, self.provider
+            ),
+            None => info!("Added {} to zone {} at {}", record, zone, self.provider),
+        }
+
+        audit::record(input.config.audit_log.as_deref(), &[audit_entry]);
+
+        self.write.wait.wait_for(&[&record]).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{audit::Action, types::dns::RecordValue};
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_zone_is_derived_from_fqdn() {
+        let config = Config::default();
+        let provider = get_provider("Hetzner1", &config).unwrap();
+        let name = "api.staging.example.co.uk";
+
+        assert_eq!(
+            cli::zone_of(provider.as_ref(), name, None).unwrap(),
+            "example.co.uk"
+        );
+        assert_eq!(
+            cli::zone_of(provider.as_ref(), name, Some("staging.example.co.uk.")).unwrap(),
+            "staging.example.co.uk"
+        );
+        assert!(matches!(
+            cli::zone_of(provider.as_ref(), name, Some("example.com")),
+            Err(ProviderError::RecordOutsideZone { .. })
+        ));
+        assert!(matches!(
+            cli::zone_of(provider.as_ref(), "co.uk", None),
+            Err(ProviderError::NoZone(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_add_command() {
+        let command = Command::try_parse_from([
+            "add",
+            "Hetzner1",
+            "api.staging.example.co.uk",
+            "TXT",
+            "\"v=spf1 -all\"",
+            "--ttl",
+            "300",
+        ])
+        .unwrap();
+
+        let record = cli::parse_record(
+            &command.name,
+            command.record_type,
+            &command.value,
+            command.ttl,
+            "example.co.uk",
+        )
+        .unwrap();
+        assert_eq!(record.domain, "api.staging.example.co.uk");
+        assert_eq!(record.ttl, Some(300));
+        assert!(matches!(record.value, RecordValue::TXT(ref text) if text == "v=spf1 -all"));
+    }
+
+    #[test]
+    fn test_write_options_apply_to_add() {
+        let command = Command::try_parse_from([
+            "add",
+            "Hetzner1",
+            "home.example.com",
+            "A",
+            "--ipv4",
+            "1.2.3.4",
+            "--ttl",
+            "300",
+            "--ttl-override",
+            "60",
+            "--wait",
+        ])
+        .unwrap();
+        assert!(command.write.wait.wait);
+
+        let record = command.record("example.com").unwrap();
+        assert!(matches!(record.value, RecordValue::A(ip) if ip.to_string() == "1.2.3.4"));
+        assert_eq!(record.ttl, Some(60));
+
+        assert!(Command::try_parse_from(["add", "Hetzner1", "home.example.com", "A"]).is_err());
+        assert!(
+            Command::try_parse_from([
+                "add",
+                "Hetzner1",
+                "home.example.com",
+                "A",
+                "--ipv4",
+                "1.2.3"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_replaced_record_is_audited_as_update() {
+        let a_record = |domain: &str, ip: Ipv4Addr| Record {
+            domain: domain.to_string(),
+            value: RecordValue::A(ip),
+            ttl: None,
+        };
+        let existing = vec![
+            a_record("home.example.com", Ipv4Addr::new(1, 1, 1, 1)),
+            Record {
+                domain: "www.example.com".to_string(),
+                value: RecordValue::TXT("hello".to_string()),
+                ttl: None,
+            },
+        ];
+
+        let record = a_record("HOME.example.com.", Ipv4Addr::new(2, 2, 2, 2));
+        let entry = audit_entry("Hetzner1", &existing, &record, "example.com");
+        assert_eq!(entry.action, Action::Update);
+        assert_eq!(entry.old_value.as_deref(), Some("1.1.1.1"));
+        assert_eq!(entry.new_value.as_deref(), Some("2.2.2.2"));
+
+        // A record of another type is not replaced
+        let record = a_record("www.example.com", Ipv4Addr::new(2, 2, 2, 2));
+        let entry = audit_entry("Hetzner1", &existing, &record, "example.com");
+        assert_eq!(entry.action, Action::Add);
+        assert_eq!(entry.old_value, None);
+    }
+}
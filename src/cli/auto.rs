@@ -1,25 +1,62 @@
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    net::{Ipv4Addr, Ipv6Addr},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 use clap::Parser;
-use lum_log::{error, info};
+use lum_log::{error, info, warn};
 use thiserror::Error;
 
 use crate::{
     Config,
-    cli::ExecutableCommand,
-    resolver::{self, IpResolverError, Ipv4ResolverConfig, Ipv6ResolverConfig},
+    audit::{self, AuditEntry},
+    cli::{ExecutableCommand, wait, write::WriteArgs},
+    metrics::Metrics,
+    notification::{self, Notification},
+    provider::{Feature, Provider, ProviderError, get_provider},
+    resolver::{self, AddressOverride, IpResolverError, Ipv4ResolverConfig, Ipv6ResolverConfig},
+    state::State,
+    timing::Timings,
+    update::{self, DomainFailure, DomainUpdate, UpdateSummary},
 };
 
 #[derive(Debug)]
 pub struct Input<'config> {
     pub config: &'config Config,
     pub reqwest: reqwest::Client,
+    pub state_path: Option<&'config Path>,
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Failed to resolve IPv4 and IPv6 addresses: {0}; {1}")]
     ResolveIp(IpResolverError, IpResolverError),
+
+    #[error("Provider error: {0}")]
+    Provider(#[from] ProviderError),
+
+    #[error(
+        "Failed to update {} of {} domains: {}",
+        .failures.len(),
+        .succeeded + .failures.len(),
+        join_failures(.failures)
+    )]
+    UpdateFailed {
+        succeeded: usize,
+        failures: Vec<DomainFailure>,
+    },
+
+    #[error("{0}")]
+    Wait(#[from] wait::Error),
+
+    #[error("Unknown provider '{0}', it is not configured")]
+    UnknownProvider(String),
+
+    #[error("Failed to serialize timing summary: {0}")]
+    Json(#[from] lum_libs::serde_json::Error),
 }
 
 /// Update providers as defined in the configuration file
@@ -28,48 +65,897 @@ pub enum Error {
 pub struct Command<'command> {
     #[clap(skip)]
     _phantom: PhantomData<&'command ()>,
+
+    /// Maximum number of domains to update at the same time
+    #[clap(long, default_value = "4")]
+    pub concurrency: NonZeroUsize,
+
+    /// Print a machine-readable JSON summary with timings to stdout when done
+    #[clap(long, default_value = "false")]
+    pub json: bool,
+
+    /// Write Prometheus metrics to this file, e.g. for the node_exporter textfile collector
+    #[clap(long)]
+    pub metrics_file: Option<PathBuf>,
+
+    /// Update all records, even those unchanged since the last successful run
+    #[clap(long, default_value = "false")]
+    pub ignore_state: bool,
+
+    /// Only update the providers with these names
+    #[clap(long, num_args = 1.., conflicts_with = "exclude")]
+    pub only: Vec<String>,
+
+    /// Do not update the providers with these names
+    #[clap(long, num_args = 1..)]
+    pub exclude: Vec<String>,
+
+    /// Give up on a domain at a provider after this many seconds and continue with the others
+    #[clap(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub provider_timeout: Option<u64>,
+
+    /// Exit with this code instead of 0 if a record was changed, 10 if no code is given
+    #[clap(
+        long,
+        value_name = "CODE",
+        num_args = 0..=1,
+        default_missing_value = "10",
+        value_parser = clap::value_parser!(i32).range(3..=255)
+    )]
+    pub exit_code_on_change: Option<i32>,
+
+    #[command(flatten)]
+    pub write: WriteArgs,
 }
 
-impl<'command> ExecutableCommand<'command> for Command<'command> {
-    type I = Input<'command>;
-    type R = Result<(), Error>;
+/// Joins failures into a single line for error messages.
+pub fn join_failures(failures: &[DomainFailure]) -> String {
+    failures
+        .iter()
+        .map(|failure| failure.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
 
-    async fn execute(&self, input: &'command Self::I) -> Self::R {
+/// Fails with [`Error::UpdateFailed`] if any domain of the summary failed to update.
+pub(crate) fn check_summary(summary: &UpdateSummary) -> Result<(), Error> {
+    let failures = summary.failures();
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    Err(Error::UpdateFailed {
+        succeeded: summary.results.len() - failures.len(),
+        failures,
+    })
+}
+
+impl Command<'_> {
+    /// Checks that all names given to `--only` and `--exclude` are configured providers.
+    pub(crate) fn check_provider_filter(&self, config: &Config) -> Result<(), Error> {
+        for name in self.only.iter().chain(self.exclude.iter()) {
+            if !config
+                .providers
+                .iter()
+                .any(|provider| provider.name() == name)
+            {
+                return Err(Error::UnknownProvider(name.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the exit code of a successful run: the one of `--exit-code-on-change` if a record
+    /// was `changed`, otherwise 0. Failed runs exit with 1, or 2 if only some domains failed.
+    pub fn exit_code(&self, changed: bool) -> i32 {
+        match self.exit_code_on_change {
+            Some(exit_code) if changed => exit_code,
+            _ => 0,
+        }
+    }
+
+    /// Returns whether the provider is selected by `--only` and `--exclude`.
+    pub(crate) fn is_provider_selected(&self, provider_name: &str) -> bool {
+        if !self.only.is_empty() {
+            return self.only.iter().any(|name| name == provider_name);
+        }
+
+        !self.exclude.iter().any(|name| name == provider_name)
+    }
+}
+
+/// Logs the outcome of every record of an update pass, failures as errors.
+pub(crate) fn log_outcomes(summary: &UpdateSummary) {
+    for result in summary.results.iter() {
+        for (updated, line) in result.outcome_lines() {
+            if updated {
+                info!("{}", line);
+            } else {
+                error!("{}", line);
+            }
+        }
+    }
+}
+
+/// Returns an [`Notification::IpChange`] if a resolved address differs from the one stored by
+/// the previous run. Addresses that were not stored yet or could not be resolved do not count
+/// as a change.
+fn ip_change(
+    state: &State,
+    ipv4: Option<Ipv4Addr>,
+    ipv6: Option<Ipv6Addr>,
+) -> Option<Notification> {
+    let ipv4_changed = state.ipv4.is_some() && ipv4.is_some() && state.ipv4 != ipv4;
+    let ipv6_changed = state.ipv6.is_some() && ipv6.is_some() && state.ipv6 != ipv6;
+    if !ipv4_changed && !ipv6_changed {
+        return None;
+    }
+
+    Some(Notification::IpChange {
+        previous_ipv4: state.ipv4,
+        ipv4: ipv4.or(state.ipv4),
+        previous_ipv6: state.ipv6,
+        ipv6: ipv6.or(state.ipv6),
+    })
+}
+
+/// Notifies the records updated and the domains that failed in an update pass.
+async fn notify_outcome(
+    notifiers: &[Box<dyn notification::Notifier + '_>],
+    reqwest: &reqwest::Client,
+    summary: &UpdateSummary,
+) {
+    let records: Vec<String> = summary
+        .results
+        .iter()
+        .flat_map(|result| {
+            result.updated_records().map(|record| {
+                format!(
+                    "{} {:?} at {}",
+                    record.domain,
+                    record.value.record_type(),
+                    result.provider_name
+                )
+            })
+        })
+        .collect();
+    if !records.is_empty() {
+        let success = Notification::UpdateSuccess { records };
+        notification::notify_all(notifiers, reqwest, &success).await;
+    }
+
+    let errors: Vec<String> = summary
+        .failures()
+        .iter()
+        .map(|failure| failure.to_string())
+        .collect();
+    if !errors.is_empty() {
+        let failure = Notification::UpdateFailure { errors };
+        notification::notify_all(notifiers, reqwest, &failure).await;
+    }
+}
+
+/// Resolves the public IPv4 and IPv6 addresses, succeeding if at least one of them resolves.
+/// Addresses given in `address_override` are used as they are.
+async fn resolve_addresses(
+    config: &Config,
+    reqwest: &reqwest::Client,
+    address_override: &AddressOverride,
+) -> Result<(Option<Ipv4Addr>, Option<Ipv6Addr>), Error> {
+    let ipv4 = match address_override.ipv4 {
+        Some(ipv4) => Ok(ipv4),
+        None => resolver::resolve_ipv4(&Ipv4ResolverConfig::from(config), reqwest).await,
+    };
+
+    let ipv6 = match address_override.ipv6 {
+        Some(ipv6) => Ok(ipv6),
+        None => resolver::resolve_ipv6(&Ipv6ResolverConfig::from(config), reqwest).await,
+    };
+
+    match (ipv4, ipv6) {
+        (Ok(ipv4), Ok(ipv6)) => {
+            info!("Successfully resolved IPv4 address: {}", ipv4);
+            info!("Successfully resolved IPv6 address: {}", ipv6);
+            Ok((Some(ipv4), Some(ipv6)))
+        }
+        (Ok(ipv4), Err(ipv6_err)) => {
+            info!("Successfully resolved IPv4 address: {}", ipv4);
+            error!(
+                "Failed to resolve IPv6 address: {}. Still proceeding with IPv4 address update.",
+                ipv6_err
+            );
+            Ok((Some(ipv4), None))
+        }
+        (Err(ipv4_err), Ok(ipv6)) => {
+            info!("Successfully resolved IPv6 address: {}", ipv6);
+            error!(
+                "Failed to resolve IPv4 address: {}. Still proceeding with IPv6 address update.",
+                ipv4_err
+            );
+            Ok((None, Some(ipv6)))
+        }
+        (Err(ipv4_err), Err(ipv6_err)) => Err(Error::ResolveIp(ipv4_err, ipv6_err)),
+    }
+}
+
+/// Like [`resolve_addresses`], also returning how long the resolution took.
+async fn timed_resolution(
+    config: &Config,
+    reqwest: &reqwest::Client,
+    address_override: &AddressOverride,
+) -> (
+    Result<(Option<Ipv4Addr>, Option<Ipv6Addr>), Error>,
+    Duration,
+) {
+    let start = Instant::now();
+    let addresses = resolve_addresses(config, reqwest, address_override).await;
+    (addresses, start.elapsed())
+}
+
+impl<'command> Command<'command> {
+    /// Updates the records once and returns whether any of them was changed. This is a run of
+    /// `auto` and of every iteration of `watch`.
+    pub async fn run_once(&self, input: &'command Input<'command>) -> Result<bool, Error> {
         let config = input.config;
-        let reqwest = reqwest::Client::new();
+        let reqwest = &input.reqwest;
+        self.check_provider_filter(config)?;
 
-        let ipv4_resolver_config = Ipv4ResolverConfig::from(config);
-        let ipv4 = resolver::resolve_ipv4(&ipv4_resolver_config, &reqwest).await;
+        let notifiers = notification::notifiers(&config.notifications);
+        let (addresses, resolution) =
+            timed_resolution(config, reqwest, &self.write.address_override()).await;
+        let (ipv4, ipv6) = match addresses {
+            Ok(addresses) => addresses,
+            Err(e) => {
+                let failure = Notification::UpdateFailure {
+                    errors: vec![e.to_string()],
+                };
+                notification::notify_all(&notifiers, reqwest, &failure).await;
+                notification::heartbeat(&config.notifications, reqwest, false).await;
+                return Err(e);
+            }
+        };
 
-        let ipv6_resolver_config = Ipv6ResolverConfig::from(config);
-        let ipv6 = resolver::resolve_ipv6(&ipv6_resolver_config, &reqwest).await;
+        // Records may name other providers than the one of their DNS config
+        let mut providers: Vec<(&str, Box<dyn Provider + 'command>)> = Vec::new();
+        for dns_config in config.dns.iter() {
+            for (_, record_configs) in dns_config.domains() {
+                for record_config in record_configs {
+                    for provider_name in record_config.target_providers(dns_config.provider_name())
+                    {
+                        if !self.is_provider_selected(provider_name)
+                            || providers.iter().any(|(name, _)| *name == provider_name)
+                        {
+                            continue;
+                        }
 
-        match (ipv4, ipv6) {
-            (Ok(ipv4), Ok(ipv6)) => {
-                info!("Successfully resolved IPv4 address: {}", ipv4);
-                info!("Successfully resolved IPv6 address: {}", ipv6);
+                        let provider = get_provider(provider_name, config)?;
+                        providers.push((provider_name, provider));
+                    }
+                }
             }
-            (Ok(ipv4), Err(ipv6_err)) => {
-                info!("Successfully resolved IPv4 address: {}", ipv4);
-                error!(
-                    "Failed to resolve IPv6 address: {}. Still proceeding with IPv4 address update.",
-                    ipv6_err
+        }
+
+        let state = match input.state_path {
+            Some(path) => State::load(path),
+            None => State::default(),
+        };
+
+        if let Some(ip_change) = ip_change(&state, ipv4, ipv6) {
+            info!("{}", ip_change.message());
+            notification::notify_all(&notifiers, reqwest, &ip_change).await;
+        }
+
+        let mut updates = Vec::new();
+        for dns_config in config.dns.iter() {
+            for (domain, record_configs) in dns_config.domains() {
+                let groups = update::records_by_provider(
+                    dns_config.provider_name(),
+                    record_configs,
+                    ipv4,
+                    ipv6,
                 );
+
+                for (provider_name, mut records) in groups {
+                    let Some(provider) = providers
+                        .iter()
+                        .find(|(name, _)| *name == provider_name)
+                        .map(|(_, provider)| provider.as_ref())
+                    else {
+                        // Not selected by --only or --exclude
+                        continue;
+                    };
+
+                    if !provider
+                        .get_supported_features()
+                        .contains(&Feature::UpdateRecord)
+                    {
+                        warn!(
+                            "Skipping {} at {}: provider does not support updating records",
+                            domain, provider_name
+                        );
+                        continue;
+                    }
+
+                    self.write.apply_ttl_override(&mut records);
+                    if !self.ignore_state {
+                        records.retain(|record| {
+                            let unchanged = state.is_unchanged(provider_name, record);
+                            if unchanged {
+                                info!(
+                                    "Skipping {} at {}: unchanged since last run",
+                                    record.domain, provider_name
+                                );
+                            }
+                            !unchanged
+                        });
+                    }
+
+                    if records.is_empty() {
+                        continue;
+                    }
+
+                    updates.push(DomainUpdate {
+                        provider_name,
+                        provider,
+                        domain: domain.to_string(),
+                        records,
+                    });
+                }
             }
-            (Err(ipv4_err), Ok(ipv6)) => {
-                info!("Successfully resolved IPv6 address: {}", ipv6);
-                error!(
-                    "Failed to resolve IPv4 address: {}. Still proceeding with IPv6 address update.",
-                    ipv4_err
-                );
+        }
+
+        let provider_timeout = self.provider_timeout.map(Duration::from_secs);
+        let summary =
+            update::update_domains(reqwest, &updates, self.concurrency, provider_timeout).await;
+        log_outcomes(&summary);
+
+        // Records that were updated are remembered even if others of their domain failed
+        let mut published_state = State {
+            ipv4,
+            ipv6,
+            ..Default::default()
+        };
+        let mut audit_entries = Vec::new();
+        for result in summary.results.iter() {
+            for record in result.updated_records() {
+                let old_value = state
+                    .published(&result.provider_name, record)
+                    .map(|published| published.value.clone());
+                audit_entries.push(AuditEntry::update(&result.provider_name, old_value, record));
+                published_state.record_published(&result.provider_name, record);
+            }
+        }
+        audit::record(config.audit_log.as_deref(), &audit_entries);
+
+        if let Some(path) = input.state_path
+            && let Err(e) = published_state.merge_into_file(path)
+        {
+            warn!("Failed to write state file {}: {}", path.display(), e);
+        }
+
+        if let Some(path) = &self.metrics_file {
+            let mut metrics = Metrics::default();
+            for result in summary.results.iter() {
+                if !result.is_success() {
+                    metrics.record_error(&result.provider_name);
+                    continue;
+                }
+
+                metrics.record_success(&result.provider_name, result.updated_records().count());
             }
-            (Err(ipv4_err), Err(ipv6_err)) => {
-                return Err(Error::ResolveIp(ipv4_err, ipv6_err));
+
+            if let Err(e) = metrics.write(path, chrono::Utc::now().timestamp()) {
+                warn!("Failed to write metrics file {}: {}", path.display(), e);
             }
         }
 
-        //TODO: Update providers here
+        notify_outcome(&notifiers, reqwest, &summary).await;
 
-        Ok(())
+        // The heartbeat reports the final result, so it waits for propagation as well
+        let outcome = self.finish_run(resolution, &summary, &updates).await;
+        notification::heartbeat(&config.notifications, reqwest, outcome.is_ok()).await;
+        outcome
+    }
+
+    /// Reports the timings and outcome of a run and waits for the updated records if `--wait` was
+    /// given. Returns whether any record was changed.
+    async fn finish_run(
+        &self,
+        resolution: Duration,
+        summary: &UpdateSummary,
+        updates: &[DomainUpdate<'_>],
+    ) -> Result<bool, Error> {
+        let timings = Timings::new(resolution, summary);
+        if self.json {
+            println!("{}", timings.to_json()?);
+        } else {
+            timings.log();
+        }
+
+        let failed = summary.failed().count();
+        info!(
+            "Updated {} of {} domains",
+            summary.results.len() - failed,
+            summary.results.len()
+        );
+        for result in summary.timed_out() {
+            warn!(
+                "Timed out: {} at {} after {:.1}s",
+                result.domain,
+                result.provider_name,
+                result.elapsed.as_secs_f64()
+            );
+        }
+
+        check_summary(summary)?;
+
+        let updated_records: Vec<_> = updates
+            .iter()
+            .flat_map(|update| update.records.iter())
+            .collect();
+        self.write.wait.wait_for(&updated_records).await?;
+
+        let changed = summary
+            .results
+            .iter()
+            .any(|result| result.updated_records().next().is_some());
+        Ok(changed)
+    }
+}
+
+impl<'command> ExecutableCommand<'command> for Command<'command> {
+    type I = Input<'command>;
+    type R = Result<bool, Error>;
+
+    /// Updates the records and returns whether any of them was changed.
+    async fn execute(&self, input: &'command Self::I) -> Self::R {
+        self.run_once(input).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{
+            dns::{self, AutomaticRecordConfig, RecordConfig, ResolveType},
+            notification::{Event, NotifierConfig, WebhookConfig},
+            provider::Provider as ProviderConfig,
+            resolver::IpResolverType,
+        },
+        provider::namecheap,
+        types::dns::{Record, RecordValue},
+        update::UpdateSummary,
+    };
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_json, method, path, query_param},
+    };
+
+    fn parse(args: &[&str]) -> Command<'static> {
+        Command::try_parse_from([&["auto"], args].concat()).unwrap()
+    }
+
+    #[test]
+    fn test_only_selects_given_providers() {
+        let command = parse(&["--only", "Nitrado1", "Netcup1"]);
+        command.check_provider_filter(&Config::default()).unwrap();
+
+        assert!(command.is_provider_selected("Nitrado1"));
+        assert!(command.is_provider_selected("Netcup1"));
+        assert!(!command.is_provider_selected("Hetzner1"));
+    }
+
+    #[test]
+    fn test_exclude_skips_given_providers() {
+        let command = parse(&["--exclude", "Hetzner1"]);
+        command.check_provider_filter(&Config::default()).unwrap();
+
+        assert!(!command.is_provider_selected("Hetzner1"));
+        assert!(command.is_provider_selected("Nitrado1"));
+    }
+
+    #[test]
+    fn test_unknown_provider_in_filter() {
+        let command = parse(&["--exclude", "Hetzner1", "Typo1"]);
+        let result = command.check_provider_filter(&Config::default());
+        assert!(matches!(result, Err(Error::UnknownProvider(name)) if name == "Typo1"));
+
+        let result =
+            Command::try_parse_from(["auto", "--only", "Nitrado1", "--exclude", "Hetzner1"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_ttl_override_replaces_record_ttls() {
+        let mut records = vec![
+            Record {
+                domain: "a.example.com".to_string(),
+                value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+                ttl: Some(3600),
+            },
+            Record {
+                domain: "b.example.com".to_string(),
+                value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 5)),
+                ttl: None,
+            },
+        ];
+
+        parse(&[]).write.apply_ttl_override(&mut records);
+        assert_eq!(records[0].ttl, Some(3600));
+        assert_eq!(records[1].ttl, None);
+
+        parse(&["--ttl-override", "60"])
+            .write
+            .apply_ttl_override(&mut records);
+        assert!(records.iter().all(|record| record.ttl == Some(60)));
+
+        assert!(Command::try_parse_from(["auto", "--ttl-override", "0"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_timings_contain_resolution_duration() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ipv4"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("1.2.3.4")
+                    .set_delay(Duration::from_millis(20)),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/ipv6"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("::1"))
+            .mount(&server)
+            .await;
+
+        let mut config = Config::default();
+        config.resolver.ipv4[0].url = format!("{}/ipv4", server.uri());
+        config.resolver.ipv4[0].type_ = IpResolverType::Raw;
+        config.resolver.ipv6[0].url = format!("{}/ipv6", server.uri());
+        config.resolver.ipv6[0].type_ = IpResolverType::Raw;
+
+        let reqwest = reqwest::Client::new();
+        let (addresses, resolution) =
+            timed_resolution(&config, &reqwest, &AddressOverride::default()).await;
+        assert!(addresses.unwrap().0.is_some());
+
+        let timings = Timings::new(resolution, &UpdateSummary::default());
+        assert!(timings.resolution_ms >= 20.0);
+        assert!(timings.to_json().unwrap().contains("\"resolution_ms\":"));
+    }
+
+    fn namecheap_config(name: &str, server: &MockServer) -> ProviderConfig {
+        ProviderConfig::Namecheap(namecheap::Config {
+            name: name.to_string(),
+            hosts: vec!["home".to_string()],
+            api_base_url: server.uri(),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_automatic_record_reaches_every_listed_provider() {
+        let resolver_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ipv4"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("1.2.3.4"))
+            .mount(&resolver_server)
+            .await;
+
+        let primary = MockServer::start().await;
+        let secondary = MockServer::start().await;
+        for server in [&primary, &secondary] {
+            Mock::given(method("GET"))
+                .and(path("/update"))
+                .and(query_param("host", "home"))
+                .and(query_param("ip", "1.2.3.4"))
+                .respond_with(ResponseTemplate::new(200).set_body_string(
+                    "<interface-response><ErrCount>0</ErrCount><IP>1.2.3.4</IP></interface-response>",
+                ))
+                .expect(1)
+                .mount(server)
+                .await;
+        }
+
+        let mut config = Config::default();
+        config.resolver.ipv4[0].url = format!("{}/ipv4", resolver_server.uri());
+        config.resolver.ipv4[0].type_ = IpResolverType::Raw;
+        config.resolver.ipv6[0].url = format!("{}/ipv6", resolver_server.uri());
+        config.resolver.ipv6[0].type_ = IpResolverType::Raw;
+        config.providers = vec![
+            namecheap_config("Primary1", &primary),
+            namecheap_config("Secondary1", &secondary),
+        ];
+        config.dns = vec![dns::Type::Namecheap(namecheap::DnsConfig {
+            provider_name: "Primary1".to_string(),
+            domains: vec![namecheap::DomainConfig {
+                domain: "example.com".to_string(),
+                records: vec![RecordConfig::Automatic(AutomaticRecordConfig {
+                    domain: "home.example.com".to_string(),
+                    ttl: None,
+                    resolve_type: ResolveType::IPv4,
+                    ipv6_suffix: None,
+                    providers: vec!["Primary1".to_string(), "Secondary1".to_string()],
+                })],
+            }],
+            ..Default::default()
+        })];
+        config.validate().unwrap();
+
+        let input = Input {
+            config: &config,
+            reqwest: reqwest::Client::new(),
+            state_path: None,
+        };
+        parse(&[]).execute(&input).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_address_override_skips_resolution() {
+        let resolver_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("9.9.9.9"))
+            .expect(0)
+            .mount(&resolver_server)
+            .await;
+
+        let provider = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/update"))
+            .and(query_param("ip", "1.2.3.4"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "<interface-response><ErrCount>0</ErrCount><IP>1.2.3.4</IP></interface-response>",
+            ))
+            .expect(1)
+            .mount(&provider)
+            .await;
+
+        let mut config = Config::default();
+        config.resolver.ipv4[0].url = resolver_server.uri();
+        config.resolver.ipv4[0].type_ = IpResolverType::Raw;
+        config.resolver.ipv6[0].url = resolver_server.uri();
+        config.resolver.ipv6[0].type_ = IpResolverType::Raw;
+        config.providers = vec![namecheap_config("Namecheap1", &provider)];
+        config.dns = vec![dns::Type::Namecheap(namecheap::DnsConfig {
+            provider_name: "Namecheap1".to_string(),
+            domains: vec![namecheap::DomainConfig {
+                domain: "example.com".to_string(),
+                records: vec![RecordConfig::Automatic(AutomaticRecordConfig {
+                    domain: "home.example.com".to_string(),
+                    ttl: None,
+                    resolve_type: ResolveType::IPv4,
+                    ipv6_suffix: None,
+                    providers: Vec::new(),
+                })],
+            }],
+            ..Default::default()
+        })];
+
+        let input = Input {
+            config: &config,
+            reqwest: reqwest::Client::new(),
+            state_path: None,
+        };
+        parse(&["--ipv4", "1.2.3.4", "--ipv6", "2001:db8::1"])
+            .execute(&input)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_exit_code_on_change() {
+        assert_eq!(parse(&[]).exit_code(true), 0);
+        assert_eq!(parse(&["--exit-code-on-change"]).exit_code(true), 10);
+        assert_eq!(parse(&["--exit-code-on-change"]).exit_code(false), 0);
+        assert_eq!(parse(&["--exit-code-on-change", "20"]).exit_code(true), 20);
+        assert!(Command::try_parse_from(["auto", "--exit-code-on-change", "1"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_run_exits_with_zero() {
+        let provider = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/update"))
+            .and(query_param("ip", "1.2.3.4"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "<interface-response><ErrCount>0</ErrCount><IP>1.2.3.4</IP></interface-response>",
+            ))
+            .expect(1)
+            .mount(&provider)
+            .await;
+
+        let dir = std::env::temp_dir().join("dnrs_test_auto_exit_code");
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+        std::fs::create_dir_all(&dir).unwrap();
+        let state_path = dir.join("state.json");
+
+        let config = Config {
+            providers: vec![namecheap_config("Namecheap1", &provider)],
+            dns: vec![dns::Type::Namecheap(namecheap::DnsConfig {
+                provider_name: "Namecheap1".to_string(),
+                domains: vec![namecheap::DomainConfig {
+                    domain: "example.com".to_string(),
+                    records: vec![RecordConfig::Automatic(AutomaticRecordConfig {
+                        domain: "home.example.com".to_string(),
+                        ttl: None,
+                        resolve_type: ResolveType::IPv4,
+                        ipv6_suffix: None,
+                        providers: Vec::new(),
+                    })],
+                }],
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+
+        let input = Input {
+            config: &config,
+            reqwest: reqwest::Client::new(),
+            state_path: Some(&state_path),
+        };
+        let command = parse(&[
+            "--ipv4",
+            "1.2.3.4",
+            "--ipv6",
+            "2001:db8::1",
+            "--exit-code-on-change",
+        ]);
+
+        let changed = command.execute(&input).await.unwrap();
+        assert_eq!(command.exit_code(changed), 10);
+
+        // The record is unchanged since the first run, so it is not sent again
+        let changed = command.execute(&input).await.unwrap();
+        assert_eq!(command.exit_code(changed), 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_address_override_is_validated() {
+        assert!(Command::try_parse_from(["auto", "--ipv4", "1.2.3"]).is_err());
+        assert!(Command::try_parse_from(["auto", "--ipv6", "1.2.3.4"]).is_err());
+        assert_eq!(
+            parse(&["--ipv6", "::1"]).write.address_override(),
+            AddressOverride {
+                ipv4: None,
+                ipv6: Some(Ipv6Addr::LOCALHOST),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ip_change_is_notified() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ipv4"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("1.2.3.4"))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/webhook"))
+            .and(body_json(lum_libs::serde_json::json!({
+                "event": "IpChange",
+                "previous_ipv4": "5.6.7.8",
+                "ipv4": "1.2.3.4",
+                "previous_ipv6": null,
+                "ipv6": null,
+                "message": "Public address changed: IPv4 5.6.7.8 -> 1.2.3.4",
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = std::env::temp_dir().join("dnrs_test_auto_ip_change");
+        std::fs::create_dir_all(&dir).unwrap();
+        let state_path = dir.join("state.json");
+        State {
+            ipv4: Some(Ipv4Addr::new(5, 6, 7, 8)),
+            ..Default::default()
+        }
+        .save(&state_path)
+        .unwrap();
+
+        let mut config = Config::default();
+        config.resolver.ipv4[0].url = format!("{}/ipv4", server.uri());
+        config.resolver.ipv4[0].type_ = IpResolverType::Raw;
+        config.resolver.ipv6[0].url = format!("{}/ipv6", server.uri());
+        config.resolver.ipv6[0].type_ = IpResolverType::Raw;
+        config.notifications.notifiers = vec![NotifierConfig::Webhook(WebhookConfig {
+            url: format!("{}/webhook", server.uri()).into(),
+            events: vec![Event::IpChange],
+            headers: Default::default(),
+        })];
+        config.dns = vec![];
+
+        let input = Input {
+            config: &config,
+            reqwest: reqwest::Client::new(),
+            state_path: Some(&state_path),
+        };
+        parse(&[]).execute(&input).await.unwrap();
+        assert_eq!(
+            State::load(&state_path).ipv4,
+            Some(Ipv4Addr::new(1, 2, 3, 4))
+        );
+
+        // The stored address is current now, so a second run notifies nothing
+        parse(&[]).execute(&input).await.unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_timeout_is_no_successful_heartbeat() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/update"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "<interface-response><ErrCount>0</ErrCount><IP>1.2.3.4</IP></interface-response>",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/ping"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/ping/fail"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = Config {
+            providers: vec![namecheap_config("Namecheap1", &server)],
+            dns: vec![dns::Type::Namecheap(namecheap::DnsConfig {
+                provider_name: "Namecheap1".to_string(),
+                domains: vec![namecheap::DomainConfig {
+                    domain: "example.com".to_string(),
+                    records: vec![RecordConfig::Automatic(AutomaticRecordConfig {
+                        domain: "home.example.com".to_string(),
+                        ttl: None,
+                        resolve_type: ResolveType::IPv4,
+                        ipv6_suffix: None,
+                        providers: Vec::new(),
+                    })],
+                }],
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+        config.notifications.heartbeat_url = Some(format!("{}/ping", server.uri()).into());
+        config.notifications.heartbeat_on_failure = true;
+
+        let input = Input {
+            config: &config,
+            reqwest: reqwest::Client::new(),
+            state_path: None,
+        };
+        let command = parse(&[
+            "--ipv4",
+            "1.2.3.4",
+            "--wait",
+            "--wait-timeout",
+            "0",
+            "--wait-resolver",
+            "127.0.0.1:9",
+        ]);
+
+        // The update succeeds, but the record is never served by the resolver
+        assert!(matches!(
+            command.execute(&input).await,
+            Err(Error::Wait(wait::Error::NotPropagated(_)))
+        ));
     }
 }
@@ -1,11 +1,20 @@
-use std::marker::PhantomData;
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
 
-use clap::{Parser, Subcommand as ClapSubcommand};
+use clap::{ArgAction, Parser, Subcommand as ClapSubcommand};
+use lum_log::{info, log::LevelFilter};
 use thiserror::Error;
 
 use crate::{
     Config,
-    cli::{ExecutableCommand, auto, generate_config, get},
+    cli::{
+        ExecutableCommand, acme, add, auto, delete, export, generate_config, get, import,
+        providers, prune, resolve, test_resolver, validate, watch,
+    },
+    config::{FirstRun, Parsing, http::HttpConfigError},
 };
 
 #[derive(Debug, ClapSubcommand)]
@@ -13,24 +22,75 @@ use crate::{
 pub enum Subcommand<'a> {
     Auto(auto::Command<'a>),
     Get(get::Command<'a>),
+    Add(add::Command<'a>),
+    Delete(delete::Command<'a>),
     GenerateConfig(generate_config::Command<'a>),
+    Acme(acme::Command<'a>),
+    Export(export::Command<'a>),
+    Import(import::Command<'a>),
+    Prune(prune::Command<'a>),
+    Watch(watch::Command<'a>),
+    Validate(validate::Command<'a>),
+    Resolve(resolve::Command<'a>),
+    TestResolver(test_resolver::Command<'a>),
+    Providers(providers::Command<'a>),
 }
 
 #[derive(Debug)]
 pub struct Input<'config> {
     pub config: &'config Config,
+    pub state_path: Option<&'config Path>,
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("Failed to set up HTTP client: {0}")]
+    HttpClient(#[from] HttpConfigError),
+
+    #[error("Unknown provider '{0}' in --provider-url, it is not configured")]
+    UnknownProvider(String),
+
+    #[error("Provider '{0}' in --provider-url has no HTTP API")]
+    NoHttpApi(String),
+
     #[error("Failed to execute auto subcommand: {0}")]
     Auto(#[from] auto::Error),
 
     #[error("Failed to execute get subcommand: {0}")]
     Get(#[from] get::Error),
 
+    #[error("Failed to execute add subcommand: {0}")]
+    Add(#[from] add::Error),
+
+    #[error("Failed to execute delete subcommand: {0}")]
+    Delete(#[from] delete::Error),
+
     #[error("Failed to execute generate-config subcommand: {0}")]
     GenerateConfig(#[from] generate_config::Error),
+
+    #[error("Failed to execute acme subcommand: {0}")]
+    Acme(#[from] acme::Error),
+
+    #[error("Failed to execute export subcommand: {0}")]
+    Export(#[from] export::Error),
+
+    #[error("Failed to execute import subcommand: {0}")]
+    Import(#[from] import::Error),
+
+    #[error("Failed to execute prune subcommand: {0}")]
+    Prune(#[from] prune::Error),
+
+    #[error("Failed to execute watch subcommand: {0}")]
+    Watch(#[from] watch::Error),
+
+    #[error("Failed to execute validate subcommand: {0}")]
+    Validate(#[from] validate::Error),
+
+    #[error("Failed to execute resolve subcommand: {0}")]
+    Resolve(#[from] resolve::Error),
+
+    #[error("Failed to execute test-resolver subcommand: {0}")]
+    TestResolver(#[from] test_resolver::Error),
 }
 
 /// dnrs
@@ -39,47 +99,230 @@ pub enum Error {
 pub struct Command<'command> {
     #[clap(skip)]
     _phantom: PhantomData<&'command ()>,
-    /*
-    TODO: Implement when supported by lum_log
-    /// Show verbose output
-    #[clap(short, long, default_value = "false")]
-    pub verbose: bool,
-    */
+    /// Show verbose output, repeat for more (-v: debug, -vv: trace)
+    #[clap(short, long, global = true, action = ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Only show warnings and errors
+    #[clap(short, long, global = true, default_value = "false")]
+    pub quiet: bool,
+
+    /// Directory or single YAML file to read the configuration from, defaults to the platform's
+    /// config directory
+    #[clap(short, long, global = true)]
+    pub config_dir: Option<PathBuf>,
+
+    /// Config file merged over the config directory, can be given multiple times; later files
+    /// override earlier ones
+    // Not global, clap would only keep the files given after the subcommand
+    #[clap(long)]
+    pub config_file: Vec<PathBuf>,
+
+    /// Reach the API of a provider at another URL, e.g. a sandbox or a mock server, as
+    /// <NAME>=<URL>; can be given multiple times
+    // Not global for the same reason as --config-file
+    #[clap(long, value_name = "NAME=URL", value_parser = parse_provider_url)]
+    pub provider_url: Vec<(String, String)>,
+
+    /// Do not create the config directory on first run, use the defaults in memory instead
+    #[clap(long, global = true, default_value = "false")]
+    pub no_write_config: bool,
+
+    /// Fail if the config directory does not exist instead of creating it or using defaults
+    #[clap(
+        long,
+        global = true,
+        env = "DNRS_NO_BOOTSTRAP",
+        default_value = "false",
+        conflicts_with = "no_write_config"
+    )]
+    pub no_bootstrap: bool,
+
+    /// Fail on unknown keys in config files instead of ignoring them, to catch typos
+    #[clap(
+        long,
+        global = true,
+        env = "DNRS_STRICT_CONFIG",
+        default_value = "false"
+    )]
+    pub strict_config: bool,
+
     #[command(subcommand)]
     pub subcommand: Subcommand<'command>,
 }
 
+impl Command<'_> {
+    /// Returns what to do if the config directory does not exist, selected by
+    /// `--no-write-config` and `--no-bootstrap`.
+    pub fn first_run(&self) -> FirstRun {
+        if self.no_bootstrap {
+            FirstRun::Fail
+        } else if self.no_write_config {
+            FirstRun::Defaults
+        } else {
+            FirstRun::Bootstrap
+        }
+    }
+
+    /// Returns `config` with the API URLs given with `--provider-url`, failing if a provider is
+    /// not configured.
+    pub fn with_provider_urls<'config>(
+        &self,
+        config: &'config Config,
+    ) -> Result<Cow<'config, Config>, Error> {
+        if self.provider_url.is_empty() {
+            return Ok(Cow::Borrowed(config));
+        }
+
+        let mut config = config.clone();
+        for (provider_name, url) in self.provider_url.iter() {
+            let provider = config
+                .providers
+                .iter_mut()
+                .find(|provider| provider.name() == provider_name)
+                .ok_or_else(|| Error::UnknownProvider(provider_name.clone()))?;
+            if !provider.set_api_base_url(url) {
+                return Err(Error::NoHttpApi(provider_name.clone()));
+            }
+            info!("Reaching provider {} at {}", provider_name, url);
+        }
+
+        Ok(Cow::Owned(config))
+    }
+
+    /// Returns how config files are parsed, see `--strict-config`.
+    pub fn parsing(&self) -> Parsing {
+        if self.strict_config {
+            Parsing::Strict
+        } else {
+            Parsing::Lenient
+        }
+    }
+
+    /// Returns the log level selected by `--verbose` and `--quiet`.
+    pub fn log_level(&self) -> LevelFilter {
+        if self.quiet {
+            return LevelFilter::Warn;
+        }
+
+        match self.verbose {
+            0 => LevelFilter::Info,
+            1 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        }
+    }
+}
+
+/// Parses `<name>=<url>` of `--provider-url`. A trailing slash of the URL is removed, as the
+/// providers append their paths to it.
+fn parse_provider_url(text: &str) -> Result<(String, String), String> {
+    let (provider_name, url) = text
+        .split_once('=')
+        .ok_or_else(|| format!("invalid provider URL '{}', expected <NAME>=<URL>", text))?;
+    url::Url::parse(url).map_err(|e| format!("invalid URL '{}': {}", url, e))?;
+
+    Ok((
+        provider_name.to_string(),
+        url.trim_end_matches('/').to_string(),
+    ))
+}
+
 impl<'command> ExecutableCommand<'command> for Command<'command> {
     type I = Input<'command>;
-    type R = Result<(), Error>;
+    type R = Result<i32, Error>;
 
+    /// Executes the subcommand and returns the exit code of the successful run.
     async fn execute(&self, input: &'command Self::I) -> Self::R {
-        let config = input.config;
-        let reqwest = reqwest::Client::new();
+        let config = &*self.with_provider_urls(input.config)?;
+        let reqwest = config.http.build_client()?;
 
+        let mut exit_code = 0;
         match &self.subcommand {
             Subcommand::Auto(subcommand) => {
-                let input = auto::Input { config, reqwest };
+                let input = auto::Input {
+                    config,
+                    reqwest,
+                    state_path: input.state_path,
+                };
+                let changed = subcommand.execute(&input).await?;
+                exit_code = subcommand.exit_code(changed);
+            }
+            Subcommand::Watch(subcommand) => {
+                let input = auto::Input {
+                    config,
+                    reqwest,
+                    state_path: input.state_path,
+                };
+                let changed = subcommand.execute(&input).await?;
+                exit_code = subcommand.auto.exit_code(changed);
+            }
+            Subcommand::Validate(subcommand) => {
+                let input = validate::Input { config, reqwest };
+                subcommand.execute(&input).await?;
+            }
+            Subcommand::Resolve(subcommand) => {
+                let input = resolve::Input { config, reqwest };
+                subcommand.execute(&input).await?;
+            }
+            Subcommand::TestResolver(subcommand) => {
+                let input = test_resolver::Input { config, reqwest };
                 subcommand.execute(&input).await?;
             }
             Subcommand::Get(subcommand) => {
                 let input = get::Input { config, reqwest };
                 subcommand.execute(&input).await?;
             }
+            Subcommand::Add(subcommand) => {
+                let input = add::Input { config, reqwest };
+                subcommand.execute(&input).await?;
+            }
+            Subcommand::Delete(subcommand) => {
+                let input = delete::Input { config, reqwest };
+                subcommand.execute(&input).await?;
+            }
             Subcommand::GenerateConfig(subcommand) => {
                 let input = generate_config::Input { config };
                 subcommand.execute(&input).await?;
             }
+            Subcommand::Acme(subcommand) => {
+                let input = acme::Input { config, reqwest };
+                subcommand.execute(&input).await?;
+            }
+            Subcommand::Export(subcommand) => {
+                let input = export::Input { config, reqwest };
+                subcommand.execute(&input).await?;
+            }
+            Subcommand::Import(subcommand) => {
+                let input = import::Input { config, reqwest };
+                subcommand.execute(&input).await?;
+            }
+            Subcommand::Prune(subcommand) => {
+                let input = prune::Input { config, reqwest };
+                subcommand.execute(&input).await?;
+            }
+            Subcommand::Providers(subcommand) => {
+                let input = providers::Input { config };
+                subcommand.execute(&input).await;
+            }
         }
 
-        Ok(())
+        Ok(exit_code)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        config::{provider::Provider, resolver::IpResolverType},
+        provider::njalla,
+    };
     use clap::Parser;
+    use lum_libs::serde_json::json;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path},
+    };
 
     #[test]
     fn test_parse_auto_command() {
@@ -93,7 +336,7 @@ mod tests {
 
     #[test]
     fn test_parse_get_command() {
-        let args = vec!["dnrs", "get", "nitrado", "example.com"];
+        let args = vec!["dnrs", "get", "nitrado", "example.com", "--all"];
         let command = Command::try_parse_from(args).unwrap();
         match command.subcommand {
             Subcommand::Get(_) => (),
@@ -101,6 +344,178 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_acme_set_command() {
+        let args = vec![
+            "dnrs",
+            "acme",
+            "set",
+            "hetzner",
+            "*.example.com",
+            "token",
+            "--ttl",
+            "60",
+        ];
+        let command = Command::try_parse_from(args).unwrap();
+        match command.subcommand {
+            Subcommand::Acme(acme) => match acme.action {
+                acme::Action::Set {
+                    challenge, value, ..
+                } => {
+                    assert_eq!(challenge.domain, "*.example.com");
+                    assert_eq!(value, "token");
+                }
+                _ => panic!("Expected set action"),
+            },
+            _ => panic!("Expected Acme subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_parse_no_write_config_flag() {
+        let args = vec!["dnrs", "auto", "--no-write-config"];
+        let command = Command::try_parse_from(args).unwrap();
+        assert!(command.no_write_config);
+
+        let args = vec!["dnrs", "auto"];
+        let command = Command::try_parse_from(args).unwrap();
+        assert!(!command.no_write_config);
+    }
+
+    #[test]
+    fn test_parse_no_bootstrap_flag() {
+        let args = vec!["dnrs", "auto", "--no-bootstrap"];
+        let command = Command::try_parse_from(args).unwrap();
+        assert_eq!(command.first_run(), FirstRun::Fail);
+
+        let args = vec!["dnrs", "auto", "--no-write-config"];
+        let command = Command::try_parse_from(args).unwrap();
+        assert_eq!(command.first_run(), FirstRun::Defaults);
+
+        let args = vec!["dnrs", "auto", "--no-bootstrap", "--no-write-config"];
+        assert!(Command::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_parse_config_dir_flag() {
+        let args = vec!["dnrs", "auto", "--config-dir", "/etc/dnrs"];
+        let command = Command::try_parse_from(args).unwrap();
+        assert_eq!(command.config_dir, Some(PathBuf::from("/etc/dnrs")));
+
+        let args = vec![
+            "dnrs",
+            "-c",
+            "profiles/home",
+            "get",
+            "nitrado",
+            "example.com",
+            "--all",
+        ];
+        let command = Command::try_parse_from(args).unwrap();
+        assert_eq!(command.config_dir, Some(PathBuf::from("profiles/home")));
+
+        let args = vec!["dnrs", "auto"];
+        let command = Command::try_parse_from(args).unwrap();
+        assert_eq!(command.config_dir, None);
+    }
+
+    #[test]
+    fn test_parse_config_file_flags_in_order() {
+        let args = vec![
+            "dnrs",
+            "--config-file",
+            "base.yaml",
+            "--config-file",
+            "secret.yaml",
+            "--config-file",
+            "override.yaml",
+            "auto",
+        ];
+        let command = Command::try_parse_from(args).unwrap();
+        assert_eq!(
+            command.config_file,
+            [
+                PathBuf::from("base.yaml"),
+                PathBuf::from("secret.yaml"),
+                PathBuf::from("override.yaml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verbosity_flags_map_to_log_level() {
+        let cases = [
+            (vec!["dnrs", "auto"], LevelFilter::Info),
+            (vec!["dnrs", "auto", "-v"], LevelFilter::Debug),
+            (vec!["dnrs", "-vv", "auto"], LevelFilter::Trace),
+            (vec!["dnrs", "auto", "-vvv"], LevelFilter::Trace),
+            (vec!["dnrs", "auto", "--quiet"], LevelFilter::Warn),
+        ];
+        for (args, level) in cases {
+            let command = Command::try_parse_from(&args).unwrap();
+            assert_eq!(command.log_level(), level, "{:?}", args);
+        }
+
+        let args = vec!["dnrs", "auto", "-v", "--quiet"];
+        assert!(Command::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_parse_export_command() {
+        let args = vec!["dnrs", "export", "Hetzner1", "example.com", "--json"];
+        let command = Command::try_parse_from(args).unwrap();
+        match command.subcommand {
+            Subcommand::Export(export) => assert!(export.json),
+            _ => panic!("Expected Export subcommand"),
+        }
+
+        let args = vec![
+            "dnrs",
+            "export",
+            "Hetzner1",
+            "example.com",
+            "--json",
+            "--zone",
+        ];
+        assert!(Command::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_parse_resolve_command() {
+        let args = vec!["dnrs", "resolve", "--family", "4", "--json"];
+        let command = Command::try_parse_from(args).unwrap();
+        match command.subcommand {
+            Subcommand::Resolve(resolve) => {
+                assert_eq!(resolve.family, resolve::Family::Ipv4);
+                assert!(resolve.json);
+            }
+            _ => panic!("Expected Resolve subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_parse_test_resolver_command() {
+        let args = vec![
+            "dnrs",
+            "test-resolver",
+            "--url",
+            "https://ip.example.com",
+            "--type",
+            "JSON:data.ip",
+        ];
+        let command = Command::try_parse_from(args).unwrap();
+        match command.subcommand {
+            Subcommand::TestResolver(test_resolver) => {
+                assert_eq!(test_resolver.url, "https://ip.example.com");
+                assert!(matches!(
+                    test_resolver.resolver_type,
+                    IpResolverType::JSON(path) if path == "data.ip"
+                ));
+            }
+            _ => panic!("Expected TestResolver subcommand"),
+        }
+    }
+
     #[test]
     fn test_parse_generate_config_command() {
         let args = vec!["dnrs", "generate-config"];
@@ -110,4 +525,85 @@ mod tests {
             _ => panic!("Expected GenerateConfig subcommand"),
         }
     }
+
+    #[test]
+    fn test_parse_prune_command() {
+        let args = vec!["dnrs", "prune", "--yes", "--include-system"];
+        let command = Command::try_parse_from(args).unwrap();
+        match command.subcommand {
+            Subcommand::Prune(prune) => {
+                assert!(prune.yes);
+                assert!(prune.include_system);
+            }
+            _ => panic!("Expected Prune subcommand"),
+        }
+
+        let args = vec!["dnrs", "prune", "--yes", "--dry-run"];
+        assert!(Command::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_parse_provider_url() {
+        assert_eq!(
+            parse_provider_url("Hetzner1=http://127.0.0.1:8080/api/").unwrap(),
+            (
+                "Hetzner1".to_string(),
+                "http://127.0.0.1:8080/api".to_string()
+            )
+        );
+        assert!(parse_provider_url("Hetzner1").is_err());
+        assert!(parse_provider_url("Hetzner1=not a url").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_provider_url_redirects_provider() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/sandbox"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "result": {"records": []},
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = Config {
+            providers: vec![Provider::Njalla(njalla::Config {
+                api_base_url: "http://127.0.0.1:1".to_string(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+        let input = Input {
+            config: &config,
+            state_path: None,
+        };
+        let provider_url = format!("Njalla1={}/sandbox", server.uri());
+
+        let command = Command::try_parse_from([
+            "dnrs",
+            "--provider-url",
+            &provider_url,
+            "get",
+            "Njalla1",
+            "example.com",
+            "--all",
+        ])
+        .unwrap();
+        command.execute(&input).await.unwrap();
+
+        let command = Command::try_parse_from([
+            "dnrs",
+            "--provider-url",
+            "Missing1=http://127.0.0.1:1",
+            "get",
+            "Njalla1",
+            "example.com",
+            "--all",
+        ])
+        .unwrap();
+        let result = command.execute(&input).await;
+        assert!(matches!(result, Err(Error::UnknownProvider(name)) if name == "Missing1"));
+    }
 }
@@ -0,0 +1,85 @@
+use std::marker::PhantomData;
+
+use clap::Parser;
+use lum_log::info;
+use thiserror::Error;
+
+use crate::{
+    Config,
+    audit::{self, AuditEntry},
+    cli::{self, ExecutableCommand},
+    provider::{DeleteRecordInput, ProviderError, get_provider},
+    types::dns::RecordType,
+    zone::ZoneError,
+};
+
+#[derive(Debug)]
+pub struct Input<'config> {
+    pub config: &'config Config,
+    pub reqwest: reqwest::Client,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Provider error: {0}")]
+    Provider(#[from] ProviderError),
+
+    #[error("Invalid record: {0}")]
+    Record(#[from] ZoneError),
+}
+
+/// Delete a record, given by its fully qualified name
+#[derive(Debug, Parser)]
+#[command(version, about, long_about = None, propagate_version = true)]
+pub struct Command<'command> {
+    #[clap(skip)]
+    _phantom: PhantomData<&'command ()>,
+
+    /// Name of the provider to delete the record from
+    #[clap(display_order = 1)]
+    provider: String,
+
+    /// Fully qualified name of the record, e.g. www.example.com
+    #[clap(display_order = 2)]
+    name: String,
+
+    /// Type of the record, e.g. A
+    #[clap(display_order = 3)]
+    record_type: RecordType,
+
+    /// Value of the record as written in a zone file, e.g. `10 mail.example.com.` for MX
+    #[clap(display_order = 4, required = true, num_args = 1..)]
+    value: Vec<String>,
+
+    /// Zone the record belongs to, derived from the name by default. Needed for subdomains that
+    /// are delegated as zones of their own
+    #[clap(long)]
+    zone: Option<String>,
+}
+
+impl<'command> ExecutableCommand<'command> for Command<'command> {
+    type I = Input<'command>;
+    type R = Result<(), Error>;
+
+    async fn execute(&self, input: &'command Self::I) -> Self::R {
+        let provider = get_provider(&self.provider, input.config)?;
+        let zone = cli::zone_of(provider.as_ref(), &self.name, self.zone.as_deref())?;
+        let record = cli::parse_record(&self.name, self.record_type, &self.value, None, &zone)?;
+
+        let delete_record_input = DeleteRecordInput {
+            domain: &zone,
+            record: &record,
+        };
+        provider
+            .delete_record(&input.reqwest, &delete_record_input)
+            .await?;
+        info!("Deleted {} from zone {} at {}", record, zone, self.provider);
+
+        audit::record(
+            input.config.audit_log.as_deref(),
+            &[AuditEntry::delete(&self.provider, &record)],
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use std::{io, marker::PhantomData, path::PathBuf};
+
+use clap::Parser;
+use lum_libs::serde_json;
+use thiserror::Error;
+
+use crate::{
+    Config,
+    cli::{self, ExecutableCommand, format::Format},
+    provider::{GetAllRecordsInput, ProviderError, get_provider},
+};
+
+#[derive(Debug)]
+pub struct Input<'config> {
+    pub config: &'config Config,
+    pub reqwest: reqwest::Client,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Provider error: {0}")]
+    Provider(#[from] ProviderError),
+
+    #[error("Failed to serialize records: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Failed to write output file: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Export all records of a domain as a zone file
+#[derive(Debug, Parser)]
+#[command(version, about, long_about = None, propagate_version = true)]
+pub struct Command<'command> {
+    #[clap(skip)]
+    _phantom: PhantomData<&'command ()>,
+
+    /// Name of the provider to export records from
+    #[clap(display_order = 1)]
+    provider: String,
+
+    /// Domain to export
+    #[clap(display_order = 2)]
+    domain: String,
+
+    /// Print the records as JSON, same as --format json
+    #[clap(long, default_value = "false", conflicts_with_all = ["zone", "format"])]
+    pub json: bool,
+
+    /// Print the records as an RFC 1035 zone file, same as --format zone
+    #[clap(long, default_value = "false", conflicts_with = "format")]
+    pub zone: bool,
+
+    /// How to print the records, an RFC 1035 zone file by default
+    #[clap(long, value_enum)]
+    pub format: Option<Format>,
+
+    /// Write the records to this file instead of stdout, replacing it atomically
+    #[clap(long)]
+    pub output_file: Option<PathBuf>,
+
+    /// Zone id of the domain at the provider, skipping its lookup. Only used by Hetzner
+    #[clap(long)]
+    pub zone_id: Option<String>,
+}
+
+impl<'command> ExecutableCommand<'command> for Command<'command> {
+    type I = Input<'command>;
+    type R = Result<(), Error>;
+
+    async fn execute(&self, input: &'command Self::I) -> Self::R {
+        let config = cli::with_zone_id(
+            input.config,
+            &self.provider,
+            &self.domain,
+            self.zone_id.as_deref(),
+        );
+        let provider = get_provider(&self.provider, &config)?;
+        let get_all_records_input = GetAllRecordsInput {
+            domain: self.domain.as_str(),
+        };
+        let records = provider
+            .get_all_records(&input.reqwest, &get_all_records_input)
+            .await?;
+
+        let format = match self.format {
+            Some(format) => format,
+            None if self.json => Format::Json,
+            None => Format::Zone,
+        };
+        let output = format.render(&self.domain, &records)?;
+        cli::write_output(self.output_file.as_deref(), &output)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::provider::Provider as ProviderConfig, provider::njalla, zone};
+    use lum_libs::serde_json::json;
+    use std::fs;
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers::method};
+
+    #[tokio::test]
+    async fn test_output_file_contains_zone() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "result": {"records": [
+                    {"id": 1, "name": "www", "type": "A", "content": "1.2.3.4", "ttl": 300},
+                ]},
+            })))
+            .mount(&server)
+            .await;
+
+        let dir = std::env::temp_dir().join("dnrs_test_export_output_file");
+        fs::create_dir_all(&dir).unwrap();
+        let output_file = dir.join("example.com.zone");
+
+        let config = Config {
+            providers: vec![ProviderConfig::Njalla(njalla::Config {
+                api_base_url: server.uri(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+        let input = Input {
+            config: &config,
+            reqwest: reqwest::Client::new(),
+        };
+        let command = Command::try_parse_from([
+            "export",
+            "Njalla1",
+            "example.com",
+            "--output-file",
+            output_file.to_str().unwrap(),
+        ])
+        .unwrap();
+        command.execute(&input).await.unwrap();
+
+        let written = fs::read_to_string(&output_file).unwrap();
+        let records = vec![crate::types::dns::Record {
+            domain: "www.example.com".to_string(),
+            value: crate::types::dns::RecordValue::A("1.2.3.4".parse().unwrap()),
+            ttl: Some(300),
+        }];
+        assert_eq!(written, zone::render("example.com", &records));
+        assert!(!dir.join("example.com.zone.tmp").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
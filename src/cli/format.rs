@@ -0,0 +1,138 @@
+use std::io::{self, IsTerminal};
+
+use clap::ValueEnum;
+use lum_libs::serde_json;
+
+use crate::{types::dns::Record, zone};
+
+/// How commands print records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Rust debug representation
+    Debug,
+    /// Pretty-printed JSON
+    Json,
+    /// Aligned columns of name, TTL, type and value
+    Table,
+    /// RFC 1035 zone file
+    Zone,
+}
+
+impl Format {
+    /// Returns [`Format::Table`] if stdout is a terminal and [`Format::Json`] if it is piped.
+    pub fn for_stdout() -> Format {
+        if io::stdout().is_terminal() {
+            Format::Table
+        } else {
+            Format::Json
+        }
+    }
+
+    /// Renders the records of `domain` in this format.
+    pub fn render(self, domain: &str, records: &[Record]) -> Result<String, serde_json::Error> {
+        let output = match self {
+            Format::Debug => format!("{:#?}\n", records),
+            Format::Json => format!("{}\n", serde_json::to_string_pretty(records)?),
+            Format::Table => table(records),
+            Format::Zone => zone::render(domain, records),
+        };
+
+        Ok(output)
+    }
+}
+
+/// Renders `records` as a table with aligned columns and a header line. Records without a TTL
+/// show `-`.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::cli::format::table;
+/// use dnrs::types::dns::{Record, RecordValue};
+/// use std::net::Ipv4Addr;
+///
+/// let records = vec![Record {
+///     domain: "www.example.com".to_string(),
+///     value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+///     ttl: Some(300),
+/// }];
+///
+/// assert_eq!(
+///     table(&records),
+///     "NAME             TTL  TYPE  VALUE\nwww.example.com  300  A     1.2.3.4\n"
+/// );
+/// ```
+pub fn table(records: &[Record]) -> String {
+    let mut rows = vec![[
+        "NAME".to_string(),
+        "TTL".to_string(),
+        "TYPE".to_string(),
+        "VALUE".to_string(),
+    ]];
+    for record in records {
+        rows.push([
+            record.domain.clone(),
+            record
+                .ttl
+                .map_or_else(|| "-".to_string(), |ttl| ttl.to_string()),
+            record.value.record_type().to_string(),
+            record.value.to_string(),
+        ]);
+    }
+
+    let mut widths = [0; 4];
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut output = String::new();
+    for [name, ttl, record_type, value] in rows {
+        output.push_str(&format!(
+            "{:name_width$}  {:ttl_width$}  {:type_width$}  {}\n",
+            name,
+            ttl,
+            record_type,
+            value,
+            name_width = widths[0],
+            ttl_width = widths[1],
+            type_width = widths[2],
+        ));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dns::RecordValue;
+
+    #[test]
+    fn test_table_aligns_columns() {
+        let records = vec![
+            Record {
+                domain: "example.com".to_string(),
+                value: RecordValue::TXT("v=spf1 -all".to_string()),
+                ttl: Some(3600),
+            },
+            Record {
+                domain: "mail.example.com".to_string(),
+                value: RecordValue::AAAA("2001:db8::1".parse().unwrap()),
+                ttl: None,
+            },
+        ];
+
+        let output = table(&records);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "NAME              TTL   TYPE  VALUE",
+                "example.com       3600  TXT   \"v=spf1 -all\"",
+                "mail.example.com  -     AAAA  2001:db8::1",
+            ]
+        );
+    }
+}
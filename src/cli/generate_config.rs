@@ -1,10 +1,14 @@
-use std::{io, marker::PhantomData};
+use std::{
+    io,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
 
 use clap::Parser;
 use lum_log::info;
 use thiserror::Error;
 
-use crate::{Config, cli::ExecutableCommand};
+use crate::{Config, cli::ExecutableCommand, provider::available_providers};
 
 #[derive(Debug)]
 pub struct Input<'config> {
@@ -21,6 +25,9 @@ pub enum Error {
 
     #[error("Config error: {0}")]
     Config(#[from] anyhow::Error),
+
+    #[error("Unknown provider type {0}, expected one of: {1}")]
+    UnknownProviderType(String, String),
 }
 
 /// Generate configuration directory structure
@@ -37,6 +44,92 @@ pub struct Command<'command> {
     /// Force overwrite existing files
     #[clap(short, long, default_value = "false")]
     pub force: bool,
+
+    /// Print the example configuration to stdout instead of writing any files
+    #[clap(long)]
+    pub stdout: bool,
+
+    /// Only emit the provider and DNS examples of these provider types, e.g. `hetzner`
+    #[clap(long, num_args = 1.., long_help = include_help())]
+    pub include: Vec<String>,
+}
+
+fn include_help() -> String {
+    format!(
+        "Only emit the provider and DNS examples of these provider types, e.g. `hetzner`\n\n\
+        Provider types: {}",
+        available_providers().join(", ")
+    )
+}
+
+/// Returns the provider type an example file belongs to, e.g. `hetzner` for both
+/// `providers/hetzner.yaml` and `dns/hetzner-domains.yaml`. Shared files have none.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use dnrs::cli::generate_config::provider_type;
+///
+/// assert_eq!(provider_type(Path::new("providers/hetzner.yaml")), Some("hetzner"));
+/// assert_eq!(provider_type(Path::new("dns/hetzner-domains.yaml")), Some("hetzner"));
+/// assert_eq!(provider_type(Path::new("resolver.yaml")), None);
+/// ```
+pub fn provider_type(path: &Path) -> Option<&str> {
+    let stem = path.file_stem()?.to_str()?;
+    match path.parent()?.to_str()? {
+        "providers" => Some(stem),
+        "dns" => stem.strip_suffix("-domains"),
+        _ => None,
+    }
+}
+
+/// Concatenates example configuration files, each preceded by a comment naming its path.
+pub fn render_example(files: &[(PathBuf, String)]) -> String {
+    let sections: Vec<String> = files
+        .iter()
+        .map(|(path, contents)| format!("# {}\n{}", path.display(), contents))
+        .collect();
+
+    sections.join("\n")
+}
+
+impl Command<'_> {
+    /// Returns the example files to emit: the shared files and those of the included provider
+    /// types, or of all provider types if none are given.
+    pub fn example_files(&self) -> Result<Vec<(PathBuf, String)>, Error> {
+        let files = Config::example_files()?;
+        if self.include.is_empty() {
+            return Ok(files);
+        }
+
+        let known = available_providers();
+        for include in self.include.iter() {
+            if !known
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(include))
+            {
+                return Err(Error::UnknownProviderType(
+                    include.clone(),
+                    known.join(", "),
+                ));
+            }
+        }
+
+        let files = files
+            .iter()
+            .filter(|(path, _)| match provider_type(path) {
+                Some(provider_type) => self
+                    .include
+                    .iter()
+                    .any(|include| include.eq_ignore_ascii_case(provider_type)),
+                None => true,
+            })
+            .cloned()
+            .collect();
+
+        Ok(files)
+    }
 }
 
 impl<'command> ExecutableCommand<'command> for Command<'command> {
@@ -44,7 +137,13 @@ impl<'command> ExecutableCommand<'command> for Command<'command> {
     type R = Result<(), Error>;
 
     async fn execute(&self, _input: &'command Self::I) -> Self::R {
-        let config_dir = std::path::Path::new(&self.output);
+        let files = self.example_files()?;
+        if self.stdout {
+            print!("{}", render_example(&files));
+            return Ok(());
+        }
+
+        let config_dir = Path::new(&self.output);
 
         if config_dir.exists() && !self.force {
             info!(
@@ -54,10 +153,84 @@ impl<'command> ExecutableCommand<'command> for Command<'command> {
             return Ok(());
         }
 
-        Config::create_example_structure(config_dir)?;
+        Config::write_example_files(config_dir, &files)?;
 
         info!("Configuration structure created in {:?}", config_dir);
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_example_is_yaml_of_every_file() {
+        let rendered = render_example(&Config::example_files().unwrap());
+        assert!(rendered.starts_with("# resolver.yaml\n"));
+        assert!(rendered.contains("# providers/hetzner.yaml\n"));
+        assert!(rendered.contains("# dns/njalla-domains.yaml\n"));
+
+        let sections: Vec<&str> = rendered.split("\n# ").collect();
+        assert_eq!(sections.len(), Config::example_files().unwrap().len());
+        for section in sections {
+            let (_, yaml) = section.split_once('\n').unwrap();
+            assert!(!yaml.trim().is_empty());
+            serde_yaml_ng::from_str::<serde_yaml_ng::Value>(yaml).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stdout_writes_nothing() {
+        let output = std::env::temp_dir().join(format!(
+            "dnrs_generate_config_stdout_{}",
+            std::process::id()
+        ));
+        let command = Command::try_parse_from([
+            "generate-config",
+            "--stdout",
+            "--output",
+            output.to_str().unwrap(),
+        ])
+        .unwrap();
+        let config = Config::default();
+
+        command.execute(&Input { config: &config }).await.unwrap();
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn test_include_emits_only_given_provider_type() {
+        let command = Command::try_parse_from(["generate-config", "--include", "Hetzner"]).unwrap();
+
+        let paths: Vec<PathBuf> = command
+            .example_files()
+            .unwrap()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "resolver.yaml",
+                "http.yaml",
+                "notifications.yaml",
+                "log.yaml",
+                "providers/hetzner.yaml",
+                "dns/hetzner-domains.yaml",
+            ]
+            .map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn test_include_rejects_unknown_provider_type() {
+        let command = Command::try_parse_from(["generate-config", "--include", "bind"]).unwrap();
+
+        assert!(matches!(
+            command.example_files(),
+            Err(Error::UnknownProviderType(name, _)) if name == "bind"
+        ));
+    }
+}
@@ -1,17 +1,16 @@
-use std::marker::PhantomData;
+use std::{io, marker::PhantomData, path::PathBuf};
 
+use chrono::{DateTime, NaiveDate, Utc};
 use clap::{Args, Parser};
+use lum_libs::serde_json;
 use lum_log::{error, info};
 use thiserror::Error;
 
 use crate::{
     Config,
-    cli::ExecutableCommand,
-    config::provider::Provider as ProviderConfig,
-    provider::{
-        GetAllRecordsInput, GetRecordsInput, Provider, hetzner::HetznerProvider,
-        netcup::NetcupProvider, nitrado::NitradoProvider,
-    },
+    cli::{self, ExecutableCommand, format::Format},
+    provider::{GetAllRecordsInput, GetRecordsInput, ProviderError, get_provider, naming},
+    types::dns::{Record, RecordType},
 };
 
 #[derive(Debug)]
@@ -22,22 +21,33 @@ pub struct Input<'config> {
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("The given provider is not configured: {0}")]
-    ProviderNotConfigured(String),
-
     #[error("Provider error: {0}")]
-    ProviderError(#[from] anyhow::Error),
+    Provider(#[from] ProviderError),
+
+    #[error("Failed to serialize records: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Failed to write output file: {0}")]
+    Io(#[from] io::Error),
 }
 
+/// Which records to get. At least one is required, and --all excludes the others.
 #[derive(Debug, Args)]
+#[group(required = true, multiple = true)]
 pub struct SubdomainArgs {
-    /// Subdomains to get records for
+    /// Subdomains to get records for, fully qualified or relative to the domain. `*` is the
+    /// wildcard record itself, use --match for patterns
     #[clap(display_order = 3)]
     subdomains: Vec<String>,
 
     /// Get all records
-    #[clap(short, long, default_value = "false", display_order = 3)]
+    #[clap(short, long, conflicts_with = "subdomains", display_order = 3)]
     pub all: bool,
+
+    /// Get records whose name matches this glob pattern, fully qualified (`*.example.com`) or
+    /// relative to the domain (`api-*`). Can be given several times
+    #[clap(long = "match", conflicts_with = "all", display_order = 3)]
+    pub patterns: Vec<String>,
 }
 
 /// Get one or more DNS records from a provider
@@ -57,34 +67,77 @@ pub struct Command<'command> {
 
     #[command(flatten)]
     subdomain_args: SubdomainArgs,
+
+    /// Only get records of this type, e.g. A
+    #[clap(long = "type")]
+    record_type: Option<RecordType>,
+
+    /// Only get records modified after this time, as RFC 3339 (2024-01-31T12:00:00Z) or a
+    /// date (2024-01-31). Needs a provider that reports modification times
+    #[clap(long, value_parser = parse_timestamp)]
+    modified_since: Option<DateTime<Utc>>,
+
+    /// How to print the records. Defaults to table on a terminal and json when piped
+    #[clap(long, value_enum)]
+    format: Option<Format>,
+
+    /// Also write the records as JSON to this file, replacing it atomically
+    #[clap(long)]
+    output_file: Option<PathBuf>,
+
+    /// Zone id of the domain at the provider, skipping its lookup. Only used by Hetzner
+    #[clap(long)]
+    pub zone_id: Option<String>,
 }
 
-//TODO: Move
-fn get_provider<'config>(
-    name: &str,
-    config: &'config Config,
-) -> Option<Box<dyn Provider + 'config>> {
-    for provider in config.providers.iter() {
-        match provider {
-            ProviderConfig::Nitrado(nitrado_config) => {
-                if name == nitrado_config.name {
-                    return Some(Box::new(NitradoProvider::new(nitrado_config)));
-                }
-            }
-            ProviderConfig::Hetzner(hetzner_config) => {
-                if name == hetzner_config.name {
-                    return Some(Box::new(HetznerProvider::new(hetzner_config)));
-                }
-            }
-            ProviderConfig::Netcup(netcup_config) => {
-                if name == netcup_config.name {
-                    return Some(Box::new(NetcupProvider::new(netcup_config)));
-                }
-            }
-        }
+/// Parses an RFC 3339 timestamp or a date, which means midnight UTC.
+fn parse_timestamp(text: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(text) {
+        return Ok(timestamp.with_timezone(&Utc));
     }
 
-    None
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map(|date| date.and_time(Default::default()).and_utc())
+        .map_err(|_| {
+            format!(
+                "invalid timestamp '{}', expected RFC 3339 or YYYY-MM-DD",
+                text
+            )
+        })
+}
+
+impl Command<'_> {
+    /// Returns the fully qualified names of the given subdomains.
+    fn subdomains(&self) -> Vec<String> {
+        self.subdomain_args
+            .subdomains
+            .iter()
+            .map(|subdomain| naming::qualify(subdomain, &self.domain))
+            .collect()
+    }
+
+    /// Applies the type and subdomain filters to records that were fetched unfiltered.
+    fn filter_records(&self, records: Vec<Record>) -> Vec<Record> {
+        let subdomains = self.subdomains();
+        records
+            .into_iter()
+            .filter(|record| {
+                self.record_type
+                    .is_none_or(|record_type| record.value.record_type() == record_type)
+            })
+            .filter(|record| {
+                self.subdomain_args.all
+                    || subdomains
+                        .iter()
+                        .any(|subdomain| naming::same_name(subdomain, &record.domain, &self.domain))
+                    || self
+                        .subdomain_args
+                        .patterns
+                        .iter()
+                        .any(|pattern| naming::name_matches(pattern, &record.domain, &self.domain))
+            })
+            .collect()
+    }
 }
 
 impl<'command> ExecutableCommand<'command> for Command<'command> {
@@ -92,45 +145,47 @@ impl<'command> ExecutableCommand<'command> for Command<'command> {
     type R = Result<(), Error>;
 
     async fn execute(&self, input: &'command Self::I) -> Self::R {
-        if self.subdomain_args.all && !self.subdomain_args.subdomains.is_empty() {
-            error!("Cannot specify both --all and specific subdomains");
-            return Err(Error::ProviderError(anyhow::anyhow!(
-                "Cannot specify both --all and specific subdomains"
-            )));
-        }
-
-        if !self.subdomain_args.all && self.subdomain_args.subdomains.is_empty() {
-            error!("Must specify either --all or specific subdomains");
-            return Err(Error::ProviderError(anyhow::anyhow!(
-                "Must specify either --all or specific subdomains"
-            )));
-        }
-
-        let config = input.config;
         let provider_name = self.provider.as_str();
+        let config = cli::with_zone_id(
+            input.config,
+            provider_name,
+            &self.domain,
+            self.zone_id.as_deref(),
+        );
 
-        let provider = match get_provider(provider_name, config) {
-            Some(p) => p,
-            None => return Err(Error::ProviderNotConfigured(provider_name.to_string())),
-        };
+        let provider = get_provider(provider_name, &config)?;
 
-        let reqwest = reqwest::Client::new();
+        let reqwest = &input.reqwest;
 
-        let results = if self.subdomain_args.all {
+        let results = if let Some(since) = self.modified_since {
             let input = GetAllRecordsInput {
                 domain: self.domain.as_str(),
             };
 
-            provider.get_all_records(reqwest, &input).await
+            provider
+                .get_records_modified_since(reqwest, &input, since)
+                .await
+                .map(|records| self.filter_records(records))
+        } else if self.subdomain_args.all || !self.subdomain_args.patterns.is_empty() {
+            let input = GetAllRecordsInput {
+                domain: self.domain.as_str(),
+            };
+
+            let records = match self.record_type {
+                Some(record_type) => {
+                    provider
+                        .get_records_of_type(reqwest, &input, record_type)
+                        .await
+                }
+                None => provider.get_all_records(reqwest, &input).await,
+            };
+            records.map(|records| self.filter_records(records))
         } else {
+            let subdomains = self.subdomains();
             let input = GetRecordsInput {
                 domain: self.domain.as_str(),
-                subdomains: self
-                    .subdomain_args
-                    .subdomains
-                    .iter()
-                    .map(|s| s.as_str())
-                    .collect(),
+                subdomains: subdomains.iter().map(|s| s.as_str()).collect(),
+                record_type: self.record_type,
             };
 
             provider.get_records(reqwest, &input).await
@@ -144,7 +199,15 @@ impl<'command> ExecutableCommand<'command> for Command<'command> {
             Ok(records) => records,
         };
 
-        info!("Records: {:#?}", records);
+        let format = self.format.unwrap_or_else(Format::for_stdout);
+        print!("{}", format.render(&self.domain, &records)?);
+
+        if let Some(path) = &self.output_file {
+            let output = format!("{}\n", serde_json::to_string_pretty(&records)?);
+            cli::write_output(Some(path), &output)?;
+            info!("Wrote {} records to {}", records.len(), path.display());
+        }
+
         Ok(())
     }
 }
@@ -152,48 +215,224 @@ impl<'command> ExecutableCommand<'command> for Command<'command> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::provider::{hetzner, netcup, nitrado};
+    use crate::{
+        config::provider::Provider as ProviderConfig,
+        provider::{hetzner, njalla},
+    };
+    use lum_libs::serde_json::json;
+    use std::fs;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path, query_param},
+    };
 
     #[test]
-    fn test_get_provider_nitrado() {
-        let mut config = Config::default();
-        config.providers = vec![ProviderConfig::Nitrado(nitrado::Config {
-            name: "TestNitrado".to_string(),
-            ..Default::default()
-        })];
-
-        let provider = get_provider("TestNitrado", &config).unwrap();
-        assert_eq!(provider.get_provider_name(), "Nitrado");
+    fn test_parse_timestamp_accepts_rfc3339_and_dates() {
+        assert_eq!(
+            parse_timestamp("2024-01-31T14:00:00+02:00")
+                .unwrap()
+                .to_rfc3339(),
+            "2024-01-31T12:00:00+00:00"
+        );
+        assert_eq!(
+            parse_timestamp("2024-01-31").unwrap().to_rfc3339(),
+            "2024-01-31T00:00:00+00:00"
+        );
+        assert!(parse_timestamp("last tuesday").is_err());
     }
 
     #[test]
-    fn test_get_provider_hetzner() {
-        let mut config = Config::default();
-        config.providers = vec![ProviderConfig::Hetzner(hetzner::Config {
-            name: "TestHetzner".to_string(),
-            ..Default::default()
-        })];
+    fn test_parse_type_flag() {
+        let command =
+            Command::try_parse_from(["get", "Hetzner1", "example.com", "--all", "--type", "aaaa"])
+                .unwrap();
+        assert_eq!(command.record_type, Some(RecordType::AAAA));
+
+        let error =
+            Command::try_parse_from(["get", "Hetzner1", "example.com", "--all", "--type", "PTR"])
+                .unwrap_err();
+        assert!(error.to_string().contains("expected one of: A, AAAA"));
+    }
 
-        let provider = get_provider("TestHetzner", &config).unwrap();
-        assert_eq!(provider.get_provider_name(), "Hetzner");
+    #[test]
+    fn test_all_conflicts_with_subdomains() {
+        let error =
+            Command::try_parse_from(["get", "Njalla1", "example.com", "--all", "www"]).unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+
+        let error =
+            Command::try_parse_from(["get", "Njalla1", "example.com", "--all", "--match", "api-*"])
+                .unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
     }
 
     #[test]
-    fn test_get_provider_netcup() {
-        let mut config = Config::default();
-        config.providers = vec![ProviderConfig::Netcup(netcup::Config {
-            name: "TestNetcup".to_string(),
-            ..Default::default()
-        })];
+    fn test_subdomains_all_or_match_are_required() {
+        let error = Command::try_parse_from(["get", "Njalla1", "example.com"]).unwrap_err();
+        assert_eq!(
+            error.kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        );
+
+        assert!(Command::try_parse_from(["get", "Njalla1", "example.com", "--all"]).is_ok());
+        assert!(Command::try_parse_from(["get", "Njalla1", "example.com", "www"]).is_ok());
+        assert!(
+            Command::try_parse_from(["get", "Njalla1", "example.com", "--match", "api-*"]).is_ok()
+        );
+    }
 
-        let provider = get_provider("TestNetcup", &config).unwrap();
-        assert_eq!(provider.get_provider_name(), "Netcup");
+    #[test]
+    fn test_match_filters_records_by_glob() {
+        let records: Vec<Record> = ["example.com", "api-eu.example.com", "www.example.com"]
+            .into_iter()
+            .map(|domain| Record {
+                domain: domain.to_string(),
+                value: crate::types::dns::RecordValue::A("1.2.3.4".parse().unwrap()),
+                ttl: None,
+            })
+            .collect();
+
+        let command = Command::try_parse_from([
+            "get",
+            "Njalla1",
+            "example.com",
+            "--match",
+            "api-*",
+            "www.example.com",
+        ])
+        .unwrap();
+        let names: Vec<String> = command
+            .filter_records(records.clone())
+            .into_iter()
+            .map(|record| record.domain)
+            .collect();
+        assert_eq!(names, vec!["api-eu.example.com", "www.example.com"]);
+
+        let command =
+            Command::try_parse_from(["get", "Njalla1", "example.com", "--match", "*.example.com"])
+                .unwrap();
+        assert_eq!(command.filter_records(records).len(), 2);
     }
 
     #[test]
-    fn test_get_provider_not_found() {
-        let config = Config::default();
-        let provider = get_provider("NonExistent", &config);
-        assert!(provider.is_none());
+    fn test_wildcard_subdomain_is_literal_and_match_is_a_pattern() {
+        let records: Vec<Record> = ["*.example.com", "www.example.com", "example.com"]
+            .into_iter()
+            .map(|domain| Record {
+                domain: domain.to_string(),
+                value: crate::types::dns::RecordValue::A("1.2.3.4".parse().unwrap()),
+                ttl: None,
+            })
+            .collect();
+        let names = |command: Command| -> Vec<String> {
+            command
+                .filter_records(records.clone())
+                .into_iter()
+                .map(|record| record.domain)
+                .collect()
+        };
+
+        for subdomain in ["*", "*.example.com"] {
+            let command =
+                Command::try_parse_from(["get", "Njalla1", "example.com", subdomain]).unwrap();
+            assert_eq!(names(command), vec!["*.example.com"]);
+        }
+
+        let command =
+            Command::try_parse_from(["get", "Njalla1", "example.com", "--match", "*.example.com"])
+                .unwrap();
+        assert_eq!(names(command), vec!["*.example.com", "www.example.com"]);
+    }
+
+    #[tokio::test]
+    async fn test_output_file_contains_records() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "result": {"records": [
+                    {"id": 1, "name": "www", "type": "A", "content": "1.2.3.4", "ttl": 300},
+                ]},
+            })))
+            .mount(&server)
+            .await;
+
+        let dir = std::env::temp_dir().join("dnrs_test_get_output_file");
+        fs::create_dir_all(&dir).unwrap();
+        let output_file = dir.join("records.json");
+
+        let config = Config {
+            providers: vec![ProviderConfig::Njalla(njalla::Config {
+                api_base_url: server.uri(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+        let input = Input {
+            config: &config,
+            reqwest: reqwest::Client::new(),
+        };
+        let command = Command::try_parse_from([
+            "get",
+            "Njalla1",
+            "example.com",
+            "--all",
+            "--output-file",
+            output_file.to_str().unwrap(),
+        ])
+        .unwrap();
+        command.execute(&input).await.unwrap();
+
+        let written: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output_file).unwrap()).unwrap();
+        let expected = serde_json::to_value(vec![Record {
+            domain: "www.example.com".to_string(),
+            value: crate::types::dns::RecordValue::A("1.2.3.4".parse().unwrap()),
+            ttl: Some(300),
+        }])
+        .unwrap();
+        assert_eq!(written, expected);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_zone_id_flag_skips_hetzner_zone_lookup() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/zones"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"zones": []}"#))
+            .expect(0)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("zone_id", "zone9"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"records": []})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = Config {
+            providers: vec![ProviderConfig::Hetzner(hetzner::Config {
+                api_base_url: server.uri(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+        let input = Input {
+            config: &config,
+            reqwest: reqwest::Client::new(),
+        };
+        let command = Command::try_parse_from([
+            "get",
+            "Hetzner1",
+            "example.com",
+            "--all",
+            "--zone-id",
+            "zone9",
+        ])
+        .unwrap();
+        command.execute(&input).await.unwrap();
     }
 }
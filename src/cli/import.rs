@@ -0,0 +1,332 @@
+use std::{fs, io, marker::PhantomData, path::PathBuf};
+
+use clap::Parser;
+use lum_log::{error, info};
+use thiserror::Error;
+
+use crate::{
+    Config,
+    audit::{self, AuditEntry},
+    cli::{self, ExecutableCommand, prune},
+    provider::{
+        AddRecordInput, DeleteRecordInput, GetAllRecordsInput, ProviderError, UpdateRecordInput,
+        get_provider,
+    },
+    types::dns::Record,
+    zone::{self, ZoneError},
+};
+
+#[derive(Debug)]
+pub struct Input<'config> {
+    pub config: &'config Config,
+    pub reqwest: reqwest::Client,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Failed to read zone file: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Invalid zone file: {0}")]
+    Zone(#[from] ZoneError),
+
+    #[error("Provider error: {0}")]
+    Provider(#[from] ProviderError),
+
+    #[error("Failed to apply {failed} of {total} changes")]
+    ApplyFailed { failed: usize, total: usize },
+}
+
+/// Apply the records of a zone file to a provider
+#[derive(Debug, Parser)]
+#[command(version, about, long_about = None, propagate_version = true)]
+pub struct Command<'command> {
+    #[clap(skip)]
+    _phantom: PhantomData<&'command ()>,
+
+    /// Name of the provider to import records to
+    #[clap(display_order = 1)]
+    provider: String,
+
+    /// Domain of the zone, used as initial $ORIGIN
+    #[clap(display_order = 2)]
+    domain: String,
+
+    /// Zone file to import
+    #[clap(display_order = 3)]
+    file: PathBuf,
+
+    /// Only print the changes, do not apply them
+    #[clap(long, default_value = "false")]
+    pub dry_run: bool,
+
+    /// Delete records of the provider that are not in the zone file, except the SOA and NS records
+    /// at the apex
+    #[clap(long, default_value = "false")]
+    pub delete_missing: bool,
+
+    /// Zone id of the domain at the provider, skipping its lookup. Only used by Hetzner
+    #[clap(long)]
+    pub zone_id: Option<String>,
+}
+
+/// Changes needed to make a provider's records match a zone file.
+#[derive(Debug, Default)]
+pub struct ImportPlan {
+    pub add: Vec<Record>,
+    pub update: Vec<Record>,
+    pub delete: Vec<Record>,
+}
+
+fn same_name(a: &Record, b: &Record) -> bool {
+    a.domain
+        .trim_end_matches('.')
+        .eq_ignore_ascii_case(b.domain.trim_end_matches('.'))
+        && a.value.wire_type() == b.value.wire_type()
+}
+
+/// Returns the canonical value of the existing record of the name and type of `record`, the one
+/// an update replaces.
+fn replaced_value(existing: &[Record], record: &Record) -> Option<String> {
+    existing
+        .iter()
+        .find(|existing| same_name(existing, record))
+        .map(|existing| existing.value.canonical_content())
+}
+
+/// Compares the records of a zone file with the existing records of a provider.
+///
+/// Records already present are left alone. A record replaces an existing one of its name and
+/// type if it is the only record of that name and type in the file, otherwise it is added.
+/// Existing records that are neither in the file nor replaced are to be deleted; the SOA and NS
+/// records at the apex of `zone` are never deleted.
+pub fn plan_import(file_records: &[Record], existing: &[Record], zone: &str) -> ImportPlan {
+    let mut plan = ImportPlan::default();
+    let mut replaced = Vec::new();
+
+    for record in file_records {
+        if existing
+            .iter()
+            .any(|existing| existing.semantically_equals(record))
+        {
+            continue;
+        }
+
+        let siblings = file_records
+            .iter()
+            .filter(|other| same_name(other, record))
+            .count();
+        let current = existing.iter().position(|existing| {
+            same_name(existing, record)
+                && !file_records
+                    .iter()
+                    .any(|other| other.semantically_equals(existing))
+        });
+
+        match current {
+            Some(index) if siblings == 1 => {
+                replaced.push(index);
+                plan.update.push(record.clone());
+            }
+            _ => plan.add.push(record.clone()),
+        }
+    }
+
+    for (index, record) in existing.iter().enumerate() {
+        if prune::is_system_record(record, zone) || replaced.contains(&index) {
+            continue;
+        }
+        if !file_records
+            .iter()
+            .any(|other| other.semantically_equals(record))
+        {
+            plan.delete.push(record.clone());
+        }
+    }
+
+    plan
+}
+
+impl<'command> ExecutableCommand<'command> for Command<'command> {
+    type I = Input<'command>;
+    type R = Result<(), Error>;
+
+    async fn execute(&self, input: &'command Self::I) -> Self::R {
+        let text = fs::read_to_string(&self.file)?;
+        let file_records = zone::parse(&text, &self.domain)?;
+        info!(
+            "Read {} records from {}",
+            file_records.len(),
+            self.file.display()
+        );
+
+        let config = cli::with_zone_id(
+            input.config,
+            &self.provider,
+            &self.domain,
+            self.zone_id.as_deref(),
+        );
+        let provider = get_provider(&self.provider, &config)?;
+        let reqwest = &input.reqwest;
+        let get_all_records_input = GetAllRecordsInput {
+            domain: self.domain.as_str(),
+        };
+        let existing = provider
+            .get_all_records(reqwest, &get_all_records_input)
+            .await?;
+
+        let mut plan = plan_import(&file_records, &existing, &self.domain);
+        if !self.delete_missing {
+            plan.delete.clear();
+        }
+
+        for record in plan.add.iter() {
+            info!("Add: {}", record);
+        }
+        for record in plan.update.iter() {
+            info!("Update: {}", record);
+        }
+        for record in plan.delete.iter() {
+            info!("Delete: {}", record);
+        }
+
+        let total = plan.add.len() + plan.update.len() + plan.delete.len();
+        if self.dry_run {
+            info!("Dry run, not applying {} changes", total);
+            return Ok(());
+        }
+
+        let domain = self.domain.as_str();
+        let provider_name = self.provider.as_str();
+        let mut failed = 0;
+        let mut audit_entries = Vec::new();
+        for record in plan.add.iter() {
+            let record = &provider.clamp_ttl(record);
+            let input = AddRecordInput { domain, record };
+            match provider.add_record(reqwest, &input).await {
+                Ok(()) => audit_entries.push(AuditEntry::add(provider_name, record)),
+                Err(e) => {
+                    error!("Failed to add {}: {}", record, e);
+                    failed += 1;
+                }
+            }
+        }
+        for record in plan.update.iter() {
+            let record = &provider.clamp_ttl(record);
+            let input = UpdateRecordInput { domain, record };
+            match provider.update_record(reqwest, &input).await {
+                Ok(()) => {
+                    let old_value = replaced_value(&existing, record);
+                    audit_entries.push(AuditEntry::update(provider_name, old_value, record));
+                }
+                Err(e) => {
+                    error!("Failed to update {}: {}", record, e);
+                    failed += 1;
+                }
+            }
+        }
+        for record in plan.delete.iter() {
+            let input = DeleteRecordInput { domain, record };
+            match provider.delete_record(reqwest, &input).await {
+                Ok(()) => audit_entries.push(AuditEntry::delete(provider_name, record)),
+                Err(e) => {
+                    error!("Failed to delete {}: {}", record, e);
+                    failed += 1;
+                }
+            }
+        }
+        audit::record(input.config.audit_log.as_deref(), &audit_entries);
+
+        info!("Applied {} of {} changes", total - failed, total);
+        if failed > 0 {
+            return Err(Error::ApplyFailed { failed, total });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dns::RecordValue;
+    use std::net::Ipv4Addr;
+
+    const ZONE: &str = "\
+$ORIGIN example.com.
+$TTL 300
+@       IN  A      1.1.1.1
+        IN  MX     10 mail ; the mail server
+www 60  IN  CNAME  @
+mail    IN  A      2.2.2.2
+$ORIGIN sub.example.com.
+api     IN  TXT    \"part one\" \"part two\"
+";
+
+    fn a_record(domain: &str, ip: Ipv4Addr) -> Record {
+        Record {
+            domain: domain.to_string(),
+            value: RecordValue::A(ip),
+            ttl: Some(300),
+        }
+    }
+
+    #[test]
+    fn test_parse_zone_file() {
+        let records = zone::parse(ZONE, "example.com").unwrap();
+        let lines: Vec<String> = records.iter().map(|record| record.to_string()).collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "example.com. 300 IN A 1.1.1.1",
+                "example.com. 300 IN MX 10 mail.example.com.",
+                "www.example.com. 60 IN CNAME example.com.",
+                "mail.example.com. 300 IN A 2.2.2.2",
+                "api.sub.example.com. 300 IN TXT \"part one\" \"part two\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_import_and_prune_set() {
+        let file_records = zone::parse(ZONE, "example.com").unwrap();
+        let existing = vec![
+            a_record("example.com", Ipv4Addr::new(1, 1, 1, 1)),
+            a_record("mail.example.com", Ipv4Addr::new(9, 9, 9, 9)),
+            a_record("old.example.com", Ipv4Addr::new(3, 3, 3, 3)),
+            Record {
+                domain: "example.com".to_string(),
+                value: RecordValue::SOA(
+                    "ns1.example.com. admin.example.com. 1 7200 3600 1209600 300".to_string(),
+                ),
+                ttl: Some(300),
+            },
+            Record {
+                domain: "example.com".to_string(),
+                value: RecordValue::NS("ns1.example.com".to_string()),
+                ttl: Some(86400),
+            },
+            Record {
+                domain: "sub.example.com".to_string(),
+                value: RecordValue::NS("ns1.example.net".to_string()),
+                ttl: Some(86400),
+            },
+        ];
+
+        let plan = plan_import(&file_records, &existing, "example.com");
+
+        let update: Vec<&str> = plan.update.iter().map(|r| r.domain.as_str()).collect();
+        assert_eq!(update, vec!["mail.example.com"]);
+        assert_eq!(plan.add.len(), 3);
+        let delete: Vec<String> = plan.delete.iter().map(|r| r.to_string()).collect();
+        // Delegations below the apex are ordinary records
+        assert_eq!(
+            delete,
+            vec![
+                "old.example.com. 300 IN A 3.3.3.3",
+                "sub.example.com. 86400 IN NS ns1.example.net.",
+            ]
+        );
+    }
+}
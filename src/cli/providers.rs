@@ -0,0 +1,102 @@
+use std::marker::PhantomData;
+
+use clap::Parser;
+
+use crate::{Config, cli::ExecutableCommand, provider::available_providers};
+
+#[derive(Debug)]
+pub struct Input<'config> {
+    pub config: &'config Config,
+}
+
+/// List the supported provider types and the configured providers of each
+#[derive(Debug, Parser)]
+#[command(version, about, long_about = None, propagate_version = true)]
+pub struct Command<'command> {
+    #[clap(skip)]
+    _phantom: PhantomData<&'command ()>,
+}
+
+/// Returns one line per supported provider type, followed by the names of the providers of that
+/// type in `config`. Each name is followed by the file the provider was loaded from, if any.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::Config;
+/// use dnrs::cli::providers::provider_lines;
+///
+/// let lines = provider_lines(&Config::default());
+/// assert!(lines.contains(&"Hetzner: Hetzner1".to_string()));
+/// ```
+pub fn provider_lines(config: &Config) -> Vec<String> {
+    available_providers()
+        .iter()
+        .map(|provider_type| {
+            let names: Vec<String> = config
+                .providers
+                .iter()
+                .filter(|provider| provider.provider_type() == *provider_type)
+                .map(|provider| match provider.source_path() {
+                    Some(path) => format!("{} ({})", provider.name(), path.display()),
+                    None => provider.name().to_string(),
+                })
+                .collect();
+
+            if names.is_empty() {
+                provider_type.to_string()
+            } else {
+                format!("{}: {}", provider_type, names.join(", "))
+            }
+        })
+        .collect()
+}
+
+impl<'command> ExecutableCommand<'command> for Command<'command> {
+    type I = Input<'command>;
+    type R = ();
+
+    async fn execute(&self, input: &'command Self::I) -> Self::R {
+        for line in provider_lines(input.config) {
+            println!("{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::provider::Provider, provider::hetzner};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_unconfigured_provider_types_are_listed() {
+        let config = Config {
+            providers: Vec::new(),
+            ..Default::default()
+        };
+
+        assert_eq!(provider_lines(&config), available_providers());
+    }
+
+    #[test]
+    fn test_provider_names_show_their_files() {
+        let config = Config {
+            providers: vec![
+                Provider::Hetzner(hetzner::Config {
+                    source_path: Some(PathBuf::from("/etc/dnrs/providers/hetzner.yaml")),
+                    ..Default::default()
+                }),
+                Provider::Hetzner(hetzner::Config {
+                    name: "Hetzner2".to_string(),
+                    ..Default::default()
+                }),
+            ],
+            ..Default::default()
+        };
+
+        assert!(provider_lines(&config).contains(
+            &"Hetzner: Hetzner1 (/etc/dnrs/providers/hetzner.yaml), Hetzner2".to_string()
+        ));
+    }
+}
@@ -0,0 +1,336 @@
+use std::marker::PhantomData;
+
+use clap::Parser;
+use lum_log::{error, info, warn};
+use thiserror::Error;
+
+use crate::{
+    Config,
+    audit::{self, AuditEntry},
+    cli::ExecutableCommand,
+    config::dns::{RecordConfig, ResolveType},
+    provider::{
+        DeleteRecordInput, Feature, GetAllRecordsInput, ProviderError, get_provider,
+        naming::{is_apex, same_name},
+    },
+    types::dns::{Record, RecordType},
+};
+
+#[derive(Debug)]
+pub struct Input<'config> {
+    pub config: &'config Config,
+    pub reqwest: reqwest::Client,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Provider error: {0}")]
+    Provider(#[from] ProviderError),
+
+    #[error("Refusing to delete {0} records without --yes")]
+    NotConfirmed(usize),
+
+    #[error("Failed to delete {failed} of {total} records")]
+    DeleteFailed { failed: usize, total: usize },
+}
+
+/// Delete records of the configured domains that are not in the configuration
+#[derive(Debug, Parser)]
+#[command(version, about, long_about = None, propagate_version = true)]
+pub struct Command<'command> {
+    #[clap(skip)]
+    _phantom: PhantomData<&'command ()>,
+
+    /// Delete the records without asking for confirmation
+    #[clap(long, default_value = "false", conflicts_with = "dry_run")]
+    pub yes: bool,
+
+    /// Only print the records that would be deleted
+    #[clap(long, default_value = "false")]
+    pub dry_run: bool,
+
+    /// Also delete NS and SOA records at the apex of a domain
+    #[clap(long, default_value = "false")]
+    pub include_system: bool,
+}
+
+/// Returns whether `record` is one of the records a DNS provider manages for a zone itself, the
+/// SOA and NS records at its apex.
+pub(crate) fn is_system_record(record: &Record, zone: &str) -> bool {
+    matches!(record.value.record_type(), RecordType::SOA | RecordType::NS)
+        && is_apex(&record.domain, zone)
+}
+
+/// Returns whether `record` is represented by `record_config`, meaning a record of its name and
+/// type is published from the configuration.
+fn is_managed(record: &Record, record_config: &RecordConfig, zone: &str) -> bool {
+    let (name, record_type) = match record_config {
+        RecordConfig::Manual(manual) => (&manual.domain, manual.value.wire_type()),
+        RecordConfig::Automatic(automatic) => {
+            let record_type = match automatic.resolve_type {
+                ResolveType::IPv4 => RecordType::A,
+                ResolveType::IPv6 => RecordType::AAAA,
+            };
+            (&automatic.domain, record_type)
+        }
+    };
+
+    record.value.wire_type() == record_type && same_name(&record.domain, name, zone)
+}
+
+/// Returns the live records of `zone` that no record config represents.
+///
+/// SOA and NS records at the apex are kept unless `include_system` is set.
+pub fn prune_set(
+    zone: &str,
+    record_configs: &[&RecordConfig],
+    live: &[Record],
+    include_system: bool,
+) -> Vec<Record> {
+    live.iter()
+        .filter(|record| include_system || !is_system_record(record, zone))
+        .filter(|record| {
+            !record_configs
+                .iter()
+                .any(|record_config| is_managed(record, record_config, zone))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Returns the record configs published to `domain` at `provider_name`, from the DNS configs of
+/// all providers, as records can list the providers they are published to.
+fn managed_records<'config>(
+    config: &'config Config,
+    provider_name: &str,
+    domain: &str,
+) -> Vec<&'config RecordConfig> {
+    let mut managed = Vec::new();
+    for dns_config in config.dns.iter() {
+        for (other_domain, record_configs) in dns_config.domains() {
+            if !other_domain.eq_ignore_ascii_case(domain) {
+                continue;
+            }
+
+            managed.extend(record_configs.iter().filter(|record_config| {
+                record_config
+                    .target_providers(dns_config.provider_name())
+                    .contains(&provider_name)
+            }));
+        }
+    }
+
+    managed
+}
+
+impl<'command> ExecutableCommand<'command> for Command<'command> {
+    type I = Input<'command>;
+    type R = Result<(), Error>;
+
+    async fn execute(&self, input: &'command Self::I) -> Self::R {
+        let config = input.config;
+        let reqwest = &input.reqwest;
+
+        let mut deletions = Vec::new();
+        for dns_config in config.dns.iter() {
+            let provider_name = dns_config.provider_name();
+            let provider = get_provider(provider_name, config)?;
+            if !provider.is_feature_supported(&Feature::DeleteRecord) {
+                warn!(
+                    "Skipping {}: provider does not support deleting records",
+                    provider_name
+                );
+                continue;
+            }
+
+            for (domain, _) in dns_config.domains() {
+                let get_all_records_input = GetAllRecordsInput { domain };
+                let live = provider
+                    .get_all_records(reqwest, &get_all_records_input)
+                    .await?;
+
+                let managed = managed_records(config, provider_name, domain);
+                for record in prune_set(domain, &managed, &live, self.include_system) {
+                    info!("Delete at {}: {}", provider_name, record);
+                    deletions.push((provider_name, domain, record));
+                }
+            }
+        }
+
+        let total = deletions.len();
+        if self.dry_run {
+            info!("Dry run, not deleting {} records", total);
+            return Ok(());
+        }
+        if total == 0 {
+            info!("No records to delete");
+            return Ok(());
+        }
+        if !self.yes {
+            return Err(Error::NotConfirmed(total));
+        }
+
+        let mut failed = 0;
+        let mut audit_entries = Vec::new();
+        for (provider_name, domain, record) in deletions.iter() {
+            let provider = get_provider(provider_name, config)?;
+            let input = DeleteRecordInput { domain, record };
+            match provider.delete_record(reqwest, &input).await {
+                Ok(()) => audit_entries.push(AuditEntry::delete(provider_name, record)),
+                Err(e) => {
+                    error!("Failed to delete {} at {}: {}", record, provider_name, e);
+                    failed += 1;
+                }
+            }
+        }
+        audit::record(config.audit_log.as_deref(), &audit_entries);
+
+        info!("Deleted {} of {} records", total - failed, total);
+        if failed > 0 {
+            return Err(Error::DeleteFailed { failed, total });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::dns::{self, AutomaticRecordConfig},
+        provider::nitrado,
+        types::dns::{MxRecord, RecordValue},
+    };
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    fn record(domain: &str, value: RecordValue) -> Record {
+        Record {
+            domain: domain.to_string(),
+            value,
+            ttl: Some(300),
+        }
+    }
+
+    fn automatic(domain: &str, resolve_type: ResolveType) -> RecordConfig {
+        RecordConfig::Automatic(AutomaticRecordConfig {
+            domain: domain.to_string(),
+            ttl: None,
+            resolve_type,
+            ipv6_suffix: None,
+            providers: Vec::new(),
+        })
+    }
+
+    fn live_records() -> Vec<Record> {
+        vec![
+            record(
+                "example.com",
+                RecordValue::SOA(
+                    "ns1.example.com. admin.example.com. 1 7200 3600 1209600 300".to_string(),
+                ),
+            ),
+            record(
+                "example.com",
+                RecordValue::NS("ns1.example.com".to_string()),
+            ),
+            record(
+                "home.example.com",
+                RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+            ),
+            record("home.example.com", RecordValue::AAAA(Ipv6Addr::LOCALHOST)),
+            record(
+                "example.com",
+                RecordValue::MX(MxRecord {
+                    priority: 10,
+                    target: "old-mail.example.com".to_string(),
+                }),
+            ),
+            record("old.example.com", RecordValue::A(Ipv4Addr::new(5, 6, 7, 8))),
+            record(
+                "sub.example.com",
+                RecordValue::NS("ns1.other.net".to_string()),
+            ),
+        ]
+    }
+
+    fn deleted(records: &[Record]) -> Vec<String> {
+        records.iter().map(|record| record.to_string()).collect()
+    }
+
+    #[test]
+    fn test_prune_set_keeps_managed_and_system_records() {
+        let configs = [
+            automatic("home.example.com", ResolveType::IPv4),
+            RecordConfig::Manual(record(
+                "example.com",
+                RecordValue::MX(MxRecord {
+                    priority: 10,
+                    target: "mail.example.com".to_string(),
+                }),
+            )),
+        ];
+        let configs: Vec<&RecordConfig> = configs.iter().collect();
+
+        let prune = prune_set("example.com", &configs, &live_records(), false);
+
+        assert_eq!(
+            deleted(&prune),
+            vec![
+                "home.example.com. 300 IN AAAA ::1",
+                "old.example.com. 300 IN A 5.6.7.8",
+                "sub.example.com. 300 IN NS ns1.other.net.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prune_set_with_system_records() {
+        let configs = [
+            automatic("HOME.example.com.", ResolveType::IPv4),
+            automatic("home.example.com", ResolveType::IPv6),
+        ];
+        let configs: Vec<&RecordConfig> = configs.iter().collect();
+
+        let prune = prune_set("example.com", &configs, &live_records(), true);
+
+        let types: Vec<RecordType> = prune
+            .iter()
+            .map(|record| record.value.record_type())
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                RecordType::SOA,
+                RecordType::NS,
+                RecordType::MX,
+                RecordType::A,
+                RecordType::NS,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_managed_records_include_records_fanned_out_from_other_providers() {
+        let config = Config {
+            dns: vec![dns::Type::Nitrado(nitrado::DnsConfig {
+                domains: vec![nitrado::DomainConfig {
+                    domain: "example.com".to_string(),
+                    records: vec![RecordConfig::Automatic(AutomaticRecordConfig {
+                        domain: "home.example.com".to_string(),
+                        ttl: None,
+                        resolve_type: ResolveType::IPv4,
+                        ipv6_suffix: None,
+                        providers: vec!["Nitrado1".to_string(), "Hetzner1".to_string()],
+                    })],
+                }],
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+
+        assert_eq!(managed_records(&config, "Hetzner1", "example.com").len(), 1);
+        assert_eq!(managed_records(&config, "Nitrado1", "EXAMPLE.com").len(), 1);
+        assert_eq!(managed_records(&config, "Netcup1", "example.com").len(), 0);
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+use clap::{Parser, ValueEnum};
+use lum_libs::{serde::Serialize, serde_json};
+use lum_log::error;
+use thiserror::Error;
+
+use crate::{
+    Config,
+    cli::ExecutableCommand,
+    resolver::{self, IpResolverError, Ipv4ResolverConfig, Ipv6ResolverConfig},
+};
+
+#[derive(Debug)]
+pub struct Input<'config> {
+    pub config: &'config Config,
+    pub reqwest: reqwest::Client,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Failed to resolve IPv4 address: {0}")]
+    ResolveIpv4(IpResolverError),
+
+    #[error("Failed to resolve IPv6 address: {0}")]
+    ResolveIpv6(IpResolverError),
+
+    #[error("Failed to resolve IPv4 and IPv6 addresses: {0}; {1}")]
+    ResolveIp(IpResolverError, IpResolverError),
+
+    #[error("Failed to serialize addresses: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Address families to resolve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Family {
+    #[value(name = "4")]
+    Ipv4,
+    #[value(name = "6")]
+    Ipv6,
+    Both,
+}
+
+/// The public addresses found by the configured resolvers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct ResolvedAddresses {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv4: Option<Ipv4Addr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6: Option<Ipv6Addr>,
+}
+
+impl Display for ResolvedAddresses {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut lines = Vec::new();
+        if let Some(ipv4) = self.ipv4 {
+            lines.push(format!("IPv4: {}", ipv4));
+        }
+        if let Some(ipv6) = self.ipv6 {
+            lines.push(format!("IPv6: {}", ipv6));
+        }
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Print the public IP addresses found by the configured resolvers, without contacting any
+/// provider
+#[derive(Debug, Parser)]
+#[command(version, about, long_about = None, propagate_version = true)]
+pub struct Command<'command> {
+    #[clap(skip)]
+    _phantom: PhantomData<&'command ()>,
+
+    /// Address families to resolve
+    #[clap(long, value_enum, default_value = "both")]
+    pub family: Family,
+
+    /// Print the addresses as JSON
+    #[clap(long, default_value = "false")]
+    pub json: bool,
+}
+
+impl Command<'_> {
+    /// Resolves the selected address families. With both families, succeeds if at least one of
+    /// them resolves.
+    pub async fn resolve(
+        &self,
+        config: &Config,
+        reqwest: &reqwest::Client,
+    ) -> Result<ResolvedAddresses, Error> {
+        let ipv4 = match self.family {
+            Family::Ipv4 | Family::Both => {
+                let ipv4_resolver_config = Ipv4ResolverConfig::from(config);
+                Some(resolver::resolve_ipv4(&ipv4_resolver_config, reqwest).await)
+            }
+            Family::Ipv6 => None,
+        };
+        let ipv6 = match self.family {
+            Family::Ipv6 | Family::Both => {
+                let ipv6_resolver_config = Ipv6ResolverConfig::from(config);
+                Some(resolver::resolve_ipv6(&ipv6_resolver_config, reqwest).await)
+            }
+            Family::Ipv4 => None,
+        };
+
+        match (ipv4, ipv6) {
+            (Some(Err(ipv4_err)), Some(Err(ipv6_err))) => Err(Error::ResolveIp(ipv4_err, ipv6_err)),
+            (Some(Err(ipv4_err)), None) => Err(Error::ResolveIpv4(ipv4_err)),
+            (None, Some(Err(ipv6_err))) => Err(Error::ResolveIpv6(ipv6_err)),
+            (ipv4, ipv6) => {
+                let ipv4 = ipv4.and_then(|result| {
+                    result
+                        .inspect_err(|e| error!("Failed to resolve IPv4 address: {}", e))
+                        .ok()
+                });
+                let ipv6 = ipv6.and_then(|result| {
+                    result
+                        .inspect_err(|e| error!("Failed to resolve IPv6 address: {}", e))
+                        .ok()
+                });
+
+                Ok(ResolvedAddresses { ipv4, ipv6 })
+            }
+        }
+    }
+}
+
+impl<'command> ExecutableCommand<'command> for Command<'command> {
+    type I = Input<'command>;
+    type R = Result<(), Error>;
+
+    async fn execute(&self, input: &'command Self::I) -> Self::R {
+        let addresses = self.resolve(input.config, &input.reqwest).await?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&addresses)?);
+        } else {
+            println!("{}", addresses);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::resolver::{self as resolver_config, IpResolver, IpResolverType};
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path},
+    };
+
+    fn parse(args: &[&str]) -> Command<'static> {
+        Command::try_parse_from(std::iter::once("resolve").chain(args.iter().copied())).unwrap()
+    }
+
+    fn raw_resolver(server: &MockServer, at: &str) -> IpResolver {
+        IpResolver {
+            url: format!("{}{}", server.uri(), at),
+            type_: IpResolverType::Raw,
+            priority: None,
+            weight: 1,
+            auth: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_family() {
+        assert_eq!(parse(&[]).family, Family::Both);
+        assert_eq!(parse(&["--family", "4"]).family, Family::Ipv4);
+        assert_eq!(parse(&["--family", "6", "--json"]).family, Family::Ipv6);
+        assert!(Command::try_parse_from(["resolve", "--family", "5"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_with_mock_resolver() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ipv4"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("1.2.3.4\n"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/ipv6"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let config = Config {
+            resolver: resolver_config::Config {
+                ipv4: vec![raw_resolver(&server, "/ipv4")],
+                ipv6: vec![raw_resolver(&server, "/ipv6")],
+                quorum: None,
+            },
+            ..Default::default()
+        };
+        let reqwest = reqwest::Client::new();
+
+        let addresses = parse(&[]).resolve(&config, &reqwest).await.unwrap();
+        assert_eq!(addresses.ipv4, Some(Ipv4Addr::new(1, 2, 3, 4)));
+        assert_eq!(addresses.ipv6, None);
+        assert_eq!(addresses.to_string(), "IPv4: 1.2.3.4");
+        assert_eq!(
+            serde_json::to_string(&addresses).unwrap(),
+            r#"{"ipv4":"1.2.3.4"}"#
+        );
+
+        let result = parse(&["--family", "6"]).resolve(&config, &reqwest).await;
+        assert!(matches!(result, Err(Error::ResolveIpv6(_))));
+    }
+}
@@ -0,0 +1,145 @@
+use std::{marker::PhantomData, net::IpAddr};
+
+use clap::Parser;
+use thiserror::Error;
+
+use crate::{
+    Config,
+    cli::ExecutableCommand,
+    config::resolver::{IpResolver, IpResolverType},
+    resolver::{self, IpResolverError},
+};
+
+#[derive(Debug)]
+pub struct Input<'config> {
+    pub config: &'config Config,
+    pub reqwest: reqwest::Client,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Failed to resolve address: {0}")]
+    Resolve(#[from] IpResolverError),
+}
+
+/// Query a single resolver URL, without the configuration, and print the address it returns
+#[derive(Debug, Parser)]
+#[command(version, about, long_about = None, propagate_version = true)]
+pub struct Command<'command> {
+    #[clap(skip)]
+    _phantom: PhantomData<&'command ()>,
+
+    /// URL of the resolver
+    #[clap(long)]
+    pub url: String,
+
+    /// How to read the address from the response: raw, json:<path> or regex:<pattern>
+    #[clap(long = "type", default_value = "raw", value_parser = parse_resolver_type)]
+    pub resolver_type: IpResolverType,
+}
+
+/// Parses `raw`, `json:<path>` or `regex:<pattern>`, ignoring the case of the prefix.
+fn parse_resolver_type(text: &str) -> Result<IpResolverType, String> {
+    if text.eq_ignore_ascii_case("raw") {
+        return Ok(IpResolverType::Raw);
+    }
+
+    let (kind, argument) = text.split_once(':').ok_or_else(|| {
+        format!(
+            "invalid resolver type '{}', expected raw, json:<path> or regex:<pattern>",
+            text
+        )
+    })?;
+
+    match kind.to_ascii_lowercase().as_str() {
+        "json" => Ok(IpResolverType::JSON(argument.to_string())),
+        "regex" => Ok(IpResolverType::Regex(argument.to_string())),
+        _ => Err(format!(
+            "unknown resolver type '{}', expected raw, json or regex",
+            kind
+        )),
+    }
+}
+
+impl Command<'_> {
+    pub fn resolver(&self) -> IpResolver {
+        IpResolver {
+            url: self.url.clone(),
+            type_: self.resolver_type.clone(),
+            priority: None,
+            weight: 1,
+            auth: None,
+        }
+    }
+}
+
+impl<'command> ExecutableCommand<'command> for Command<'command> {
+    type I = Input<'command>;
+    type R = Result<(), Error>;
+
+    async fn execute(&self, input: &'command Self::I) -> Self::R {
+        let ip: IpAddr = resolver::resolve_ip_internal(&self.resolver(), &input.reqwest).await?;
+        println!("{}", ip);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path},
+    };
+
+    fn parse(args: &[&str]) -> Command<'static> {
+        Command::try_parse_from(std::iter::once("test-resolver").chain(args.iter().copied()))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_resolver_type() {
+        assert!(matches!(
+            parse_resolver_type("RAW").unwrap(),
+            IpResolverType::Raw
+        ));
+        assert!(matches!(
+            parse_resolver_type("JSON:data.ip").unwrap(),
+            IpResolverType::JSON(path) if path == "data.ip"
+        ));
+        assert!(matches!(
+            parse_resolver_type(r"regex:IP: (\d+\.\d+\.\d+\.\d+)").unwrap(),
+            IpResolverType::Regex(pattern) if pattern == r"IP: (\d+\.\d+\.\d+\.\d+)"
+        ));
+        assert!(parse_resolver_type("json").is_err());
+        assert!(parse_resolver_type("xml:ip").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_json_path_with_mock_resolver() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ip"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"{"data": {"ip": "1.2.3.4"}}"#),
+            )
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/ip", server.uri());
+        let reqwest = reqwest::Client::new();
+
+        let command = parse(&["--url", &url, "--type", "JSON:data.ip"]);
+        let ip: IpAddr = resolver::resolve_ip_internal(&command.resolver(), &reqwest)
+            .await
+            .unwrap();
+        assert_eq!(ip, IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)));
+
+        let command = parse(&["--url", &url, "--type", "json:data.ipv6"]);
+        let result: Result<IpAddr, _> =
+            resolver::resolve_ip_internal(&command.resolver(), &reqwest).await;
+        assert!(matches!(result, Err(IpResolverError::JsonParse(_))));
+    }
+}
@@ -0,0 +1,268 @@
+use std::marker::PhantomData;
+
+use clap::Parser;
+use lum_log::{error, info};
+use thiserror::Error;
+
+use crate::{Config, cli::ExecutableCommand, provider::get_provider};
+
+#[derive(Debug)]
+pub struct Input<'config> {
+    pub config: &'config Config,
+    pub reqwest: reqwest::Client,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Configuration has {0} problems")]
+    Invalid(usize),
+}
+
+/// Validate the configuration and check that every provider accepts its credentials
+#[derive(Debug, Parser)]
+#[command(version, about, long_about = None, propagate_version = true)]
+pub struct Command<'command> {
+    #[clap(skip)]
+    _phantom: PhantomData<&'command ()>,
+
+    /// Only check the configuration itself, without contacting the providers
+    #[clap(long, default_value = "false")]
+    pub offline: bool,
+}
+
+impl Command<'_> {
+    /// Runs the structural checks of [`Config::validate`], returning one message per problem.
+    fn check_structure(&self, config: &Config) -> Vec<String> {
+        match config.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.iter().map(|error| error.to_string()).collect(),
+        }
+    }
+
+    /// Checks every provider with the first domain configured for it, returning one message
+    /// per failing provider.
+    async fn check_providers(&self, config: &Config, reqwest: &reqwest::Client) -> Vec<String> {
+        let mut problems = Vec::new();
+        for provider_config in config.providers.iter() {
+            let name = provider_config.name();
+            let domain = config
+                .dns
+                .iter()
+                .filter(|dns| dns.provider_name() == name)
+                .flat_map(|dns| dns.domains())
+                .map(|(domain, _)| domain)
+                .next();
+            let Some(domain) = domain else {
+                info!("Skipping provider {}: no domains configured", name);
+                continue;
+            };
+
+            let result = match get_provider(name, config) {
+                Ok(provider) => provider.check(reqwest, domain).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => info!("Provider {} is reachable for {}", name, domain),
+                Err(e) => problems.push(format!("Provider {} failed for {}: {}", name, domain, e)),
+            }
+        }
+
+        problems
+    }
+}
+
+impl<'command> ExecutableCommand<'command> for Command<'command> {
+    type I = Input<'command>;
+    type R = Result<(), Error>;
+
+    async fn execute(&self, input: &'command Self::I) -> Self::R {
+        let config = input.config;
+
+        let mut problems = self.check_structure(config);
+        if self.offline {
+            info!("Offline, not contacting the providers");
+        } else {
+            problems.extend(self.check_providers(config, &input.reqwest).await);
+        }
+
+        if problems.is_empty() {
+            info!("Configuration is valid");
+            return Ok(());
+        }
+
+        for problem in problems.iter() {
+            error!("{}", problem);
+        }
+        Err(Error::Invalid(problems.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{
+            dns::{self, AutomaticRecordConfig, RecordConfig, ResolveType},
+            provider::Provider,
+        },
+        provider::nitrado,
+        types::{self, dns::RecordValue},
+    };
+    use std::path::PathBuf;
+
+    fn offline() -> Command<'static> {
+        Command::try_parse_from(["validate", "--offline"]).unwrap()
+    }
+
+    fn input(config: &Config) -> Input<'_> {
+        Input {
+            config,
+            reqwest: reqwest::Client::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_offline_valid_config() {
+        let config = Config::default();
+        offline().execute(&input(&config)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_offline_reports_all_problems() {
+        let mut config = Config::default();
+        config
+            .providers
+            .push(Provider::Nitrado(nitrado::Config::default()));
+        config.dns.push(dns::Type::Nitrado(nitrado::DnsConfig {
+            provider_name: "Missing1".to_string(),
+            ..Default::default()
+        }));
+
+        let problems = offline().check_structure(&config);
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("Nitrado1"));
+        assert!(problems[1].contains("Missing1"));
+
+        let result = offline().execute(&input(&config)).await;
+        assert!(matches!(result, Err(Error::Invalid(2))));
+    }
+
+    #[test]
+    fn test_problems_name_the_offending_files() {
+        let nitrado = |path: &str| {
+            Provider::Nitrado(nitrado::Config {
+                source_path: Some(PathBuf::from(path)),
+                ..Default::default()
+            })
+        };
+        let config = Config {
+            providers: vec![
+                nitrado("/etc/dnrs/providers/nitrado.yaml"),
+                nitrado("/etc/dnrs/providers/nitrado-copy.yaml"),
+            ],
+            dns: vec![dns::Type::Nitrado(nitrado::DnsConfig {
+                provider_name: "Missing1".to_string(),
+                source_path: Some(PathBuf::from("/etc/dnrs/dns/missing.yaml")),
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+
+        let problems = offline().check_structure(&config);
+        assert_eq!(
+            problems,
+            vec![
+                "Provider name Nitrado1 is used more than once (in \
+                 /etc/dnrs/providers/nitrado.yaml, /etc/dnrs/providers/nitrado-copy.yaml)",
+                "DNS config references provider Missing1, which is not configured (in \
+                 /etc/dnrs/dns/missing.yaml)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_offline_reports_unknown_record_provider() {
+        let config = Config {
+            dns: vec![dns::Type::Nitrado(nitrado::DnsConfig {
+                domains: vec![nitrado::DomainConfig {
+                    domain: "example.com".to_string(),
+                    records: vec![RecordConfig::Automatic(AutomaticRecordConfig {
+                        domain: "home.example.com".to_string(),
+                        ttl: None,
+                        resolve_type: ResolveType::IPv4,
+                        ipv6_suffix: None,
+                        providers: vec!["Nitrado1".to_string(), "Missing1".to_string()],
+                    })],
+                }],
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+
+        let problems = offline().check_structure(&config);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("Missing1"));
+    }
+
+    #[test]
+    fn test_offline_reports_records_outside_zone() {
+        let record = |domain: &str| {
+            RecordConfig::Automatic(AutomaticRecordConfig {
+                domain: domain.to_string(),
+                ttl: None,
+                resolve_type: ResolveType::IPv4,
+                ipv6_suffix: None,
+                providers: Vec::new(),
+            })
+        };
+        let config = Config {
+            providers: vec![Provider::Nitrado(nitrado::Config::default())],
+            dns: vec![dns::Type::Nitrado(nitrado::DnsConfig {
+                domains: vec![
+                    nitrado::DomainConfig {
+                        domain: "example.co.uk".to_string(),
+                        records: vec![record("www.example.co.uk"), record("www.example.com")],
+                    },
+                    nitrado::DomainConfig {
+                        domain: "co.uk".to_string(),
+                        records: vec![record("www.co.uk")],
+                    },
+                ],
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+
+        let problems = offline().check_structure(&config);
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].contains("www.example.com"));
+        assert!(problems[1].contains("co.uk"));
+    }
+
+    #[test]
+    fn test_offline_rejects_cname_at_apex() {
+        let cname = |domain: &str| {
+            RecordConfig::Manual(types::dns::Record {
+                domain: domain.to_string(),
+                value: RecordValue::CNAME("example.net".to_string()),
+                ttl: None,
+            })
+        };
+        let config = Config {
+            providers: vec![Provider::Nitrado(nitrado::Config::default())],
+            dns: vec![dns::Type::Nitrado(nitrado::DnsConfig {
+                domains: vec![nitrado::DomainConfig {
+                    domain: "example.com".to_string(),
+                    records: vec![cname("example.com"), cname("www.example.com")],
+                }],
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+
+        let problems = offline().check_structure(&config);
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+        assert!(problems[0].contains("example.com is the apex"));
+        assert!(problems[0].contains("ALIAS"));
+    }
+}
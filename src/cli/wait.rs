@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use clap::Args;
+use lum_log::{info, warn};
+use thiserror::Error;
+
+use crate::{
+    propagation::{self, PropagationError},
+    types::dns::Record,
+};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Propagation check failed: {0}")]
+    Propagation(#[from] PropagationError),
+
+    #[error("Records did not propagate in time: {0:?}")]
+    NotPropagated(Vec<String>),
+}
+
+/// Options for waiting until written records are served by DNS
+#[derive(Debug, Args)]
+pub struct WaitArgs {
+    /// Wait until the written records are served by the propagation resolvers
+    #[clap(long, default_value = "false")]
+    pub wait: bool,
+
+    /// Maximum number of seconds to wait for propagation
+    #[clap(long, default_value = "120", requires = "wait")]
+    pub wait_timeout: u64,
+
+    /// DNS servers to check for propagation (IP address, optionally with port)
+    #[clap(long = "wait-resolver", default_values = ["1.1.1.1", "8.8.8.8"], requires = "wait")]
+    pub wait_resolvers: Vec<String>,
+}
+
+impl WaitArgs {
+    /// Waits for all `records` to propagate if `--wait` was given.
+    pub async fn wait_for(&self, records: &[&Record]) -> Result<(), Error> {
+        if !self.wait {
+            return Ok(());
+        }
+
+        let resolvers: Vec<&str> = self.wait_resolvers.iter().map(String::as_str).collect();
+        let timeout = Duration::from_secs(self.wait_timeout);
+
+        let mut not_propagated = Vec::new();
+        for record in records {
+            let report = propagation::wait_for_propagation(record, &resolvers, timeout).await?;
+            if report.is_complete() {
+                info!("{} propagated to {:?}", record.domain, report.agreed);
+            } else {
+                warn!(
+                    "{} did not propagate to {:?} within {}s",
+                    record.domain, report.pending, self.wait_timeout
+                );
+                not_propagated.push(record.domain.clone());
+            }
+        }
+
+        if !not_propagated.is_empty() {
+            return Err(Error::NotPropagated(not_propagated));
+        }
+
+        Ok(())
+    }
+}
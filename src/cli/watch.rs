@@ -0,0 +1,346 @@
+use std::{future::Future, marker::PhantomData, time::Duration};
+
+use clap::Parser;
+use lum_log::{error, info};
+use thiserror::Error;
+use tokio::time::{self, Instant};
+
+use crate::{
+    Config,
+    cli::{ExecutableCommand, auto},
+    config::dns::RecordConfig,
+};
+
+/// Poll interval of `--interval auto` if no managed record has a TTL.
+const DEFAULT_AUTO_INTERVAL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("{0}")]
+    Auto(#[from] auto::Error),
+}
+
+/// Run auto repeatedly, updating providers whenever the addresses change
+#[derive(Debug, Parser)]
+#[command(version, about, long_about = None, propagate_version = true)]
+#[group(id = "watch")]
+pub struct Command<'command> {
+    #[clap(skip)]
+    _phantom: PhantomData<&'command ()>,
+
+    /// Seconds between two runs, or `auto` to use the shortest TTL of the managed records
+    #[clap(long, default_value = "300", value_parser = parse_interval)]
+    pub interval: Interval,
+
+    /// Shortest interval in seconds `--interval auto` may choose
+    #[clap(long, default_value = "60")]
+    pub min_interval: u64,
+
+    /// Longest interval in seconds `--interval auto` may choose
+    #[clap(long, default_value = "3600")]
+    pub max_interval: u64,
+
+    /// Delay the first run by a random 0..SPLAY seconds and every later run by a small random
+    /// jitter, so instances started together do not query the resolvers at the same time
+    #[clap(long)]
+    pub splay: Option<u64>,
+
+    /// Run once and exit, exactly like auto
+    #[clap(long, conflicts_with_all = ["interval", "min_interval", "max_interval", "splay"])]
+    pub once: bool,
+
+    #[command(flatten)]
+    pub auto: auto::Command<'command>,
+}
+
+/// How long `watch` waits between two runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interval {
+    Seconds(u64),
+    /// Derived from the shortest TTL of the managed records, see [`auto_interval`].
+    Auto,
+}
+
+fn parse_interval(text: &str) -> Result<Interval, String> {
+    if text.eq_ignore_ascii_case("auto") {
+        return Ok(Interval::Auto);
+    }
+
+    text.parse()
+        .map(Interval::Seconds)
+        .map_err(|_| format!("invalid interval '{}', expected seconds or auto", text))
+}
+
+/// Returns the shortest TTL of the records published to the providers accepted by `selected`.
+/// Records without a TTL use the provider default and are ignored.
+fn min_managed_ttl(config: &Config, selected: impl Fn(&str) -> bool) -> Option<u32> {
+    let mut min_ttl: Option<u32> = None;
+    for dns_config in config.dns.iter() {
+        for (_, record_configs) in dns_config.domains() {
+            for record_config in record_configs {
+                let is_selected = record_config
+                    .target_providers(dns_config.provider_name())
+                    .into_iter()
+                    .any(&selected);
+                let ttl = match record_config {
+                    RecordConfig::Manual(record) => record.ttl,
+                    RecordConfig::Automatic(automatic_config) => automatic_config.ttl,
+                };
+
+                if let Some(ttl) = ttl.filter(|_| is_selected) {
+                    min_ttl = Some(min_ttl.map_or(ttl, |min_ttl| min_ttl.min(ttl)));
+                }
+            }
+        }
+    }
+
+    min_ttl
+}
+
+/// Returns the poll interval of `--interval auto`: the shortest managed TTL, so changes are
+/// published about as fast as resolvers pick them up, clamped to `min..=max`.
+fn auto_interval(min_ttl: Option<u32>, min: Duration, max: Duration) -> Duration {
+    let interval = min_ttl
+        .map(|ttl| Duration::from_secs(ttl.into()))
+        .unwrap_or(DEFAULT_AUTO_INTERVAL);
+    interval.clamp(min, max.max(min))
+}
+
+/// Returns a random duration in `0..max`.
+fn random_delay(rng: &mut fastrand::Rng, max: Duration) -> Duration {
+    let max_millis = max.as_millis() as u64;
+    if max_millis == 0 {
+        return Duration::ZERO;
+    }
+
+    Duration::from_millis(rng.u64(0..max_millis))
+}
+
+/// When the runs of `watch` happen: every `interval` after `start`, each delayed by a random
+/// jitter below `jitter`. Runs are scheduled relative to `start`, so jitter does not drift.
+#[derive(Debug)]
+struct Schedule {
+    start: Instant,
+    interval: Duration,
+    jitter: Duration,
+}
+
+impl Schedule {
+    /// Starts the schedule after a random splay. Later runs are jittered by at most a tenth of
+    /// the interval.
+    fn new(now: Instant, interval: Duration, splay: Duration, rng: &mut fastrand::Rng) -> Self {
+        Schedule {
+            start: now + random_delay(rng, splay),
+            interval,
+            jitter: splay.min(interval / 10),
+        }
+    }
+
+    /// Returns when the first run after `now` is due. Runs missed because the previous one
+    /// took longer than the interval are skipped.
+    fn next_run(&self, now: Instant, rng: &mut fastrand::Rng) -> Instant {
+        if now < self.start {
+            return self.start;
+        }
+
+        let interval = self.interval.as_millis().max(1);
+        let run = (now - self.start).as_millis() / interval + 1;
+        self.start + self.interval * run as u32 + random_delay(rng, self.jitter)
+    }
+}
+
+impl Command<'_> {
+    /// Calls `run_once` a single time with `--once`, otherwise on the schedule until the
+    /// process is stopped. Failed runs are logged and retried at the next run.
+    async fn repeat<F, Fut>(&self, config: &Config, mut run_once: F) -> Result<bool, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<bool, auto::Error>>,
+    {
+        if self.once {
+            return Ok(run_once().await?);
+        }
+
+        self.auto.check_provider_filter(config)?;
+
+        let mut rng = fastrand::Rng::new();
+        let interval = match self.interval {
+            Interval::Seconds(seconds) => Duration::from_secs(seconds),
+            Interval::Auto => {
+                let min_ttl = min_managed_ttl(config, |provider_name| {
+                    self.auto.is_provider_selected(provider_name)
+                });
+                let interval = auto_interval(
+                    min_ttl,
+                    Duration::from_secs(self.min_interval),
+                    Duration::from_secs(self.max_interval),
+                );
+                info!(
+                    "Checking every {}s, shortest managed TTL is {:?}",
+                    interval.as_secs(),
+                    min_ttl
+                );
+                interval
+            }
+        };
+        let splay = Duration::from_secs(self.splay.unwrap_or(0));
+        let schedule = Schedule::new(Instant::now(), interval, splay, &mut rng);
+
+        let mut next_run = schedule.start;
+        loop {
+            time::sleep_until(next_run).await;
+
+            if let Err(e) = run_once().await {
+                error!("Run failed: {}", e);
+            }
+
+            next_run = schedule.next_run(Instant::now(), &mut rng);
+            info!(
+                "Next run in {}s",
+                (next_run - Instant::now()).as_secs_f64().round()
+            );
+        }
+    }
+}
+
+impl<'command> ExecutableCommand<'command> for Command<'command> {
+    type I = auto::Input<'command>;
+    type R = Result<bool, Error>;
+
+    /// Runs auto repeatedly, or once with `--once`. Returns whether a single run changed any
+    /// record, a repeated run only returns on error.
+    async fn execute(&self, input: &'command Self::I) -> Self::R {
+        self.repeat(input.config, move || self.auto.run_once(input))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::dns::{self, AutomaticRecordConfig, ResolveType},
+        provider::hetzner,
+    };
+    use std::{cell::Cell, future};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_first_run_is_delayed_within_splay() {
+        let splay = Duration::from_secs(30);
+        for seed in 0..20 {
+            let mut rng = fastrand::Rng::with_seed(seed);
+            let start = Instant::now();
+            let schedule = Schedule::new(start, Duration::from_secs(300), splay, &mut rng);
+
+            time::sleep_until(schedule.start).await;
+            let delay = start.elapsed();
+            assert!(delay < splay, "first run delayed by {:?}", delay);
+        }
+    }
+
+    #[test]
+    fn test_jitter_does_not_drift() {
+        let mut rng = fastrand::Rng::with_seed(1);
+        let start = Instant::now();
+        let interval = Duration::from_secs(60);
+        let schedule = Schedule::new(start, interval, Duration::from_secs(30), &mut rng);
+        assert_eq!(schedule.jitter, Duration::from_secs(6));
+
+        let mut now = schedule.start;
+        for run in 1..=1000u32 {
+            now = schedule.next_run(now, &mut rng);
+            let nominal = schedule.start + interval * run;
+            assert!(now >= nominal && now < nominal + schedule.jitter);
+        }
+    }
+
+    #[test]
+    fn test_overlong_run_skips_missed_runs() {
+        let mut rng = fastrand::Rng::with_seed(1);
+        let start = Instant::now();
+        let interval = Duration::from_secs(60);
+        let schedule = Schedule::new(start, interval, Duration::ZERO, &mut rng);
+
+        let next = schedule.next_run(start + Duration::from_secs(150), &mut rng);
+        assert_eq!(next, start + Duration::from_secs(180));
+    }
+
+    fn automatic(domain: &str, ttl: Option<u32>, providers: &[&str]) -> RecordConfig {
+        RecordConfig::Automatic(AutomaticRecordConfig {
+            domain: domain.to_string(),
+            ttl,
+            resolve_type: ResolveType::IPv4,
+            ipv6_suffix: None,
+            providers: providers.iter().map(|name| name.to_string()).collect(),
+        })
+    }
+
+    fn counting_run(runs: &Cell<u32>) -> impl FnMut() -> future::Ready<Result<bool, auto::Error>> {
+        move || {
+            runs.set(runs.get() + 1);
+            future::ready(Ok(true))
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_once_runs_a_single_time() {
+        let config = Config::default();
+        let runs = Cell::new(0);
+
+        let command = Command::try_parse_from(["watch", "--once"]).unwrap();
+        let changed = command.repeat(&config, counting_run(&runs)).await.unwrap();
+        assert!(changed);
+        assert_eq!(runs.get(), 1);
+
+        let command = Command::try_parse_from(["watch", "--interval", "300"]).unwrap();
+        let repeated = time::timeout(
+            Duration::from_secs(650),
+            command.repeat(&config, counting_run(&runs)),
+        )
+        .await;
+        assert!(repeated.is_err());
+        assert_eq!(runs.get(), 4);
+
+        assert!(Command::try_parse_from(["watch", "--once", "--interval", "60"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("120"), Ok(Interval::Seconds(120)));
+        assert_eq!(parse_interval("auto"), Ok(Interval::Auto));
+        assert!(parse_interval("soon").is_err());
+    }
+
+    #[test]
+    fn test_auto_interval_uses_min_ttl_within_clamps() {
+        let config = Config {
+            dns: vec![dns::Type::Hetzner(hetzner::DnsConfig {
+                domains: vec![hetzner::DomainConfig {
+                    domain: "example.com".to_string(),
+                    zone_id: None,
+                    records: vec![
+                        automatic("home.example.com", Some(600), &[]),
+                        automatic("nas.example.com", None, &[]),
+                        automatic("vpn.example.com", Some(120), &["Desec1"]),
+                    ],
+                }],
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+
+        let min_ttl = min_managed_ttl(&config, |_| true);
+        assert_eq!(min_ttl, Some(120));
+        let only_hetzner = min_managed_ttl(&config, |name| name == "Hetzner1");
+        assert_eq!(only_hetzner, Some(600));
+
+        let secs = Duration::from_secs;
+        assert_eq!(auto_interval(min_ttl, secs(60), secs(3600)), secs(120));
+        assert_eq!(auto_interval(min_ttl, secs(180), secs(3600)), secs(180));
+        assert_eq!(auto_interval(only_hetzner, secs(60), secs(300)), secs(300));
+        assert_eq!(
+            auto_interval(None, secs(60), secs(3600)),
+            DEFAULT_AUTO_INTERVAL
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use clap::Args;
+
+use crate::{cli::wait::WaitArgs, resolver::AddressOverride, types::dns::Record};
+
+/// Options of commands that write records, shared by `auto` and `add`
+#[derive(Debug, Args)]
+pub struct WriteArgs {
+    /// Publish every record with this TTL in seconds instead of the configured one
+    #[clap(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub ttl_override: Option<u32>,
+
+    /// Publish this IPv4 address instead of resolving it
+    #[clap(long)]
+    pub ipv4: Option<Ipv4Addr>,
+
+    /// Publish this IPv6 address instead of resolving it
+    #[clap(long)]
+    pub ipv6: Option<Ipv6Addr>,
+
+    #[command(flatten)]
+    pub wait: WaitArgs,
+}
+
+impl WriteArgs {
+    /// Replaces the TTL of every record if `--ttl-override` is given.
+    pub fn apply_ttl_override(&self, records: &mut [Record]) {
+        if let Some(ttl) = self.ttl_override {
+            for record in records.iter_mut() {
+                record.ttl = Some(ttl);
+            }
+        }
+    }
+
+    /// Returns the addresses given with `--ipv4` and `--ipv6`.
+    pub fn address_override(&self) -> AddressOverride {
+        AddressOverride {
+            ipv4: self.ipv4,
+            ipv6: self.ipv6,
+        }
+    }
+}
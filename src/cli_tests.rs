@@ -3,7 +3,7 @@ use std::process::Command;
 #[test]
 fn test_help() {
     let output = Command::new("cargo")
-        .args(&["run", "--", "--help"])
+        .args(["run", "--", "--help"])
         .output()
         .expect("failed to execute process");
 
@@ -16,7 +16,7 @@ fn test_help() {
 #[test]
 fn test_generate_config_help() {
     let output = Command::new("cargo")
-        .args(&["run", "--", "generate-config", "--help"])
+        .args(["run", "--", "generate-config", "--help"])
         .output()
         .expect("failed to execute process");
 
@@ -33,7 +33,7 @@ fn test_generate_config_execution() {
     }
 
     let output = Command::new("cargo")
-        .args(&[
+        .args([
             "run",
             "--",
             "generate-config",
@@ -52,3 +52,33 @@ fn test_generate_config_execution() {
     // Cleanup
     std::fs::remove_dir_all(&temp_dir).unwrap();
 }
+
+#[test]
+fn test_config_dir_flag_selects_directory() {
+    let temp_dir = std::env::temp_dir().join("dnrs_test_config_dir");
+    if temp_dir.exists() {
+        std::fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    // The provider does not exist, so the command fails after the config has been loaded
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "--config-dir",
+            temp_dir.to_str().unwrap(),
+            "get",
+            "MissingProvider",
+            "example.com",
+            "--all",
+        ])
+        .output()
+        .expect("failed to execute process");
+
+    assert!(!output.status.success());
+    assert!(temp_dir.join("resolver.yaml").exists());
+    assert!(temp_dir.join("providers").exists());
+
+    // Cleanup
+    std::fs::remove_dir_all(&temp_dir).unwrap();
+}
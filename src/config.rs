@@ -1,18 +1,35 @@
 //TODO: No anyhow
 use anyhow::Result;
 use lum_config::MergeFrom;
-use lum_libs::serde::{Deserialize, Serialize};
-use lum_log::{debug, error, info};
-use std::{fs, path::Path};
+use lum_libs::serde::{Deserialize, Serialize, de::DeserializeOwned};
+use lum_log::{debug, error, info, warn};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
 
 use crate::{
-    config::provider::Provider,
-    provider::{hetzner, netcup, nitrado},
+    config::{dns::RecordConfig, file::ConfigFile, provider::Provider},
+    lock::{DEFAULT_LOCK_TIMEOUT, FileLock},
+    provider::{
+        axfr, desec, digitalocean, hetzner, namecheap, naming, netcup, nitrado, njalla, route53,
+    },
+    types::dns::{RecordValidationError, RecordValue},
 };
 
 pub mod dns;
+pub mod file;
+pub mod http;
+pub mod log;
+pub mod notification;
 pub mod provider;
 pub mod resolver;
+pub mod secret;
+
+/// Name of the lock file inside the config directory, held while the example structure is
+/// created.
+pub const CONFIG_LOCK_FILE_NAME: &str = "config.lock";
 
 /// Configuration for the dnrs application.
 ///
@@ -23,43 +40,509 @@ pub mod resolver;
 #[serde(default)]
 pub struct Config {
     pub resolver: resolver::Config,
+    pub http: http::Config,
+    pub notifications: notification::Config,
+    pub log: log::Config,
+
+    /// File every record change made by `auto`, `watch`, `add`, `delete`, `import` and `prune` is
+    /// appended to, one JSON line per change. Read from `audit.yaml` in the directory layout.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit_log: Option<PathBuf>,
+
     pub providers: Vec<Provider>,
     pub dns: Vec<dns::Type>,
 }
 
+/// Errors of [`Config::load_from_directory_strict`] for configuration that is absent.
+#[derive(Debug, Error)]
+pub enum MissingConfigError {
+    #[error("Config file or directory {0:?} is missing")]
+    Missing(PathBuf),
+
+    #[error("No provider config found in {0:?}")]
+    NoProviders(PathBuf),
+}
+
+/// What [`Config::load_or_create`] does when the config directory does not exist yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirstRun {
+    /// Write the example structure to the directory and use the defaults.
+    Bootstrap,
+    /// Use the defaults in memory without writing anything.
+    Defaults,
+    /// Fail with [`MissingConfigError::Missing`].
+    Fail,
+}
+
+/// How keys of config files that dnrs does not know are treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Parsing {
+    /// Unknown keys are logged and ignored, so config files written for newer versions load.
+    #[default]
+    Lenient,
+    /// Unknown keys are a [`UnknownKeyError`], catching typos like `api_ky`.
+    Strict,
+}
+
+/// An unknown key found while parsing a config file with [`Parsing::Strict`].
+#[derive(Debug, Error)]
+#[error("Unknown key {key} in {path:?}")]
+pub struct UnknownKeyError {
+    /// Path of the key within the file, e.g. `resolver.ipv4[0].urll`.
+    pub key: String,
+    pub path: PathBuf,
+}
+
+/// Parses the YAML `content` of the config file at `path`, treating unknown keys according to
+/// `parsing`.
+pub(crate) fn parse_yaml<T: DeserializeOwned>(
+    content: &str,
+    path: &Path,
+    parsing: Parsing,
+) -> Result<T> {
+    let mut unknown_keys = Vec::new();
+    let deserializer = serde_yaml_ng::Deserializer::from_str(content);
+    let value = serde_ignored::deserialize(deserializer, |key| unknown_keys.push(key.to_string()))?;
+
+    for key in unknown_keys {
+        match parsing {
+            Parsing::Strict => {
+                return Err(UnknownKeyError {
+                    key,
+                    path: path.into(),
+                }
+                .into());
+            }
+            Parsing::Lenient => warn!("Ignoring unknown key {} in {:?}", key, path),
+        }
+    }
+
+    Ok(value)
+}
+
+/// Contents of `audit.yaml`, holding the top-level `audit_log` setting in the directory layout.
+#[derive(Debug, Default, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+#[serde(default)]
+struct AuditFile {
+    audit_log: Option<PathBuf>,
+}
+
+/// A problem found by [`Config::validate`].
+#[derive(Debug, Error)]
+pub enum ValidationError {
+    #[error(
+        "Provider name {name} is used more than once{sources}",
+        sources = sources_suffix(.paths.iter().flatten())
+    )]
+    DuplicateProviderName {
+        name: String,
+        /// Files of every provider of that name, `None` for built-in defaults.
+        paths: Vec<Option<PathBuf>>,
+    },
+
+    #[error(
+        "DNS config references provider {name}, which is not configured{source}",
+        source = sources_suffix(.path.iter())
+    )]
+    UnknownProvider {
+        name: String,
+        /// File of the referencing DNS config, `None` for built-in defaults.
+        path: Option<PathBuf>,
+    },
+
+    #[error("Domain {0} is a public suffix and can not be managed as a zone")]
+    NoZone(String),
+
+    #[error("Record {record} is outside of domain {domain}")]
+    RecordOutsideZone { record: String, domain: String },
+
+    #[error("Invalid record {domain}: {error}")]
+    InvalidRecord {
+        domain: String,
+        error: RecordValidationError,
+    },
+
+    #[error(
+        "{0} is the apex of its zone and can not be a CNAME, {hint}",
+        hint = naming::CNAME_AT_APEX_HINT
+    )]
+    CnameAtApex(String),
+}
+
+/// Formats the files configs were loaded from for messages, e.g. ` (in a.yaml, b.yaml)`. Empty if
+/// there are none.
+fn sources_suffix<'path>(paths: impl Iterator<Item = &'path PathBuf>) -> String {
+    let paths: Vec<String> = paths.map(|path| path.display().to_string()).collect();
+    if paths.is_empty() {
+        String::new()
+    } else {
+        format!(" (in {})", paths.join(", "))
+    }
+}
+
 impl Config {
+    /// Checks the loaded configuration for problems that would only show up while updating:
+    /// provider names have to be unique, DNS configs have to reference configured providers,
+    /// domains have to be zones below a public suffix, records have to lie within their domain
+    /// and manual records have to be valid and no CNAME at the apex. All problems found are
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::Config;
+    ///
+    /// assert!(Config::default().validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        for (index, provider) in self.providers.iter().enumerate() {
+            let name = provider.name();
+            let first = self.providers.iter().position(|other| other.name() == name);
+            let is_duplicate = self.providers[index + 1..]
+                .iter()
+                .any(|other| other.name() == name);
+            if first == Some(index) && is_duplicate {
+                let paths = self
+                    .providers
+                    .iter()
+                    .filter(|other| other.name() == name)
+                    .map(|other| other.source_path().map(Path::to_path_buf))
+                    .collect();
+                errors.push(ValidationError::DuplicateProviderName {
+                    name: name.to_string(),
+                    paths,
+                });
+            }
+        }
+
+        for dns in self.dns.iter() {
+            let provider_name = dns.provider_name();
+            if !self
+                .providers
+                .iter()
+                .any(|provider| provider.name() == provider_name)
+            {
+                errors.push(ValidationError::UnknownProvider {
+                    name: provider_name.to_string(),
+                    path: dns.source_path().map(Path::to_path_buf),
+                });
+            }
+
+            for (domain, record_configs) in dns.domains() {
+                if naming::registrable_zone(domain).is_none() {
+                    errors.push(ValidationError::NoZone(domain.to_string()));
+                }
+
+                for record_config in record_configs {
+                    let record_domain = match record_config {
+                        RecordConfig::Manual(record) => &record.domain,
+                        RecordConfig::Automatic(automatic_config) => &automatic_config.domain,
+                    };
+                    if !naming::is_within(record_domain, domain) {
+                        errors.push(ValidationError::RecordOutsideZone {
+                            record: record_domain.clone(),
+                            domain: domain.to_string(),
+                        });
+                    }
+
+                    if let RecordConfig::Automatic(automatic_config) = record_config {
+                        for name in automatic_config.providers.iter() {
+                            if !self
+                                .providers
+                                .iter()
+                                .any(|provider| provider.name() == name)
+                            {
+                                errors.push(ValidationError::UnknownProvider {
+                                    name: name.clone(),
+                                    path: dns.source_path().map(Path::to_path_buf),
+                                });
+                            }
+                        }
+                    }
+
+                    if let RecordConfig::Manual(record) = record_config
+                        && let Err(error) = record.value.validate()
+                    {
+                        errors.push(ValidationError::InvalidRecord {
+                            domain: record.domain.clone(),
+                            error,
+                        });
+                    }
+
+                    if let RecordConfig::Manual(record) = record_config
+                        && matches!(record.value, RecordValue::CNAME(_))
+                        && naming::is_apex(&record.domain, domain)
+                    {
+                        errors.push(ValidationError::CnameAtApex(record.domain.clone()));
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Loads the configuration from `config_dir`, using defaults for missing files and
+    /// directories.
     pub fn load_from_directory(config_dir: impl AsRef<Path>) -> Result<Self> {
-        let config_dir = config_dir.as_ref();
-        let resolver = Self::load_resolver_config(config_dir)?;
-        let providers = Self::load_provider_configs(&config_dir.join("providers"))?;
-        let dns = Self::load_dns_configs(&config_dir.join("dns"))?;
+        Self::load(config_dir.as_ref(), false, Parsing::Lenient)
+    }
+
+    /// Loads the configuration from `config_dir` like [`Config::load_from_directory`], but fails
+    /// with a [`MissingConfigError`] if `resolver.yaml`, the `providers` or `dns` directory, or
+    /// every provider config is missing.
+    pub fn load_from_directory_strict(config_dir: impl AsRef<Path>) -> Result<Self> {
+        Self::load(config_dir.as_ref(), true, Parsing::Lenient)
+    }
+
+    /// Loads the configuration from a single file holding all of it, as the directory layout
+    /// does in separate files. Its files are parsed according to `parsing`.
+    ///
+    /// Like with a config directory, missing sections use their defaults and the providers and
+    /// DNS configs are merged by name over the built-in ones.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use dnrs::Config;
+    /// use dnrs::config::Parsing;
+    ///
+    /// let config = Config::load_from_file("/etc/dnrs/config.yaml", Parsing::Strict).unwrap();
+    /// println!("{} providers", config.providers.len());
+    /// ```
+    pub fn load_from_file(path: impl AsRef<Path>, parsing: Parsing) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let loaded_config: Config = parse_yaml(&content, path, parsing)?;
+
+        let mut config = Config::default().merge_from(loaded_config);
+        config.resolve_secrets()?;
+        debug!("Config loaded from {:?}", path);
+
+        Ok(config)
+    }
+
+    fn load(config_dir: &Path, strict: bool, parsing: Parsing) -> Result<Self> {
+        let resolver = Self::load_resolver_config(config_dir, strict, parsing)?;
+        let http = Self::load_http_config(config_dir, parsing)?;
+        let notifications = Self::load_notification_config(config_dir, parsing)?;
+        let log = Self::load_log_config(config_dir, parsing)?;
+        let audit_log = Self::load_audit_log(config_dir, parsing)?;
+        let providers = Self::load_provider_configs(config_dir.join("providers"), strict, parsing)?;
+        let dns = Self::load_dns_configs(config_dir.join("dns"), strict, parsing)?;
 
         let loaded_config = Config {
             resolver,
+            http,
+            notifications,
+            log,
+            audit_log,
             providers,
             dns,
         };
 
         let default_config = Config::default();
-        Ok(default_config.merge_from(loaded_config))
+        let mut config = default_config.merge_from(loaded_config);
+        config.resolve_secrets()?;
+
+        for provider in config.providers.iter() {
+            match provider.source_path() {
+                Some(path) => debug!("Provider {} loaded from {:?}", provider.name(), path),
+                None => debug!("Provider {} uses built-in defaults", provider.name()),
+            }
+        }
+        for dns in config.dns.iter() {
+            match dns.source_path() {
+                Some(path) => debug!(
+                    "DNS config for provider {} loaded from {:?}",
+                    dns.provider_name(),
+                    path
+                ),
+                None => debug!(
+                    "DNS config for provider {} uses built-in defaults",
+                    dns.provider_name()
+                ),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Reads the secrets of all providers, resolvers and notifiers from their files and
+    /// environment variables.
+    fn resolve_secrets(&mut self) -> Result<()> {
+        for provider in self.providers.iter_mut() {
+            for secret in provider.secrets_mut() {
+                secret.resolve()?;
+            }
+        }
+        for secret in self.resolver.secrets_mut() {
+            secret.resolve()?;
+        }
+        for secret in self.notifications.secrets_mut() {
+            secret.resolve()?;
+        }
+
+        Ok(())
+    }
+
+    /// Merges `files` into this configuration from left to right, so later files win, and
+    /// resolves the secrets they added.
+    pub fn with_files(self, files: Vec<ConfigFile>) -> Result<Self> {
+        if files.is_empty() {
+            return Ok(self);
+        }
+
+        let mut config = files
+            .into_iter()
+            .fold(self, |config, file| config.merge_from(file));
+        config.resolve_secrets()?;
+
+        Ok(config)
     }
 
-    fn load_resolver_config(config_dir: impl AsRef<Path>) -> Result<resolver::Config> {
+    /// Loads the configuration from `config_dir`, bootstrapping it on first run.
+    ///
+    /// An existing directory is only read, never written. What happens if the directory does not
+    /// exist is decided by `first_run`, see [`FirstRun`]. Its files are parsed according to
+    /// `parsing`.
+    pub fn load_or_create(
+        config_dir: impl AsRef<Path>,
+        first_run: FirstRun,
+        parsing: Parsing,
+    ) -> Result<Self> {
+        let config_dir = config_dir.as_ref();
+        let lock_path = config_dir.join(CONFIG_LOCK_FILE_NAME);
+        if config_dir.exists() {
+            // Another instance may still be creating the example structure
+            let _lock = lock_path
+                .exists()
+                .then(|| FileLock::acquire(&lock_path, DEFAULT_LOCK_TIMEOUT))
+                .transpose()?;
+            return Self::load(config_dir, false, parsing);
+        }
+
+        match first_run {
+            FirstRun::Bootstrap => {}
+            FirstRun::Defaults => {
+                info!(
+                    "Config directory {:?} does not exist, using defaults without writing them",
+                    config_dir
+                );
+                return Ok(Config::default());
+            }
+            FirstRun::Fail => return Err(MissingConfigError::Missing(config_dir.into()).into()),
+        }
+
+        info!("Config directory does not exist, creating default structure...");
+        fs::create_dir_all(config_dir)?;
+
+        // Concurrent first runs serialize here, only the first one writes the example files
+        let _lock = FileLock::acquire(&lock_path, DEFAULT_LOCK_TIMEOUT)?;
+        if config_dir.join("providers").exists() {
+            return Self::load(config_dir, false, parsing);
+        }
+
+        Self::create_example_structure(config_dir)?;
+        info!(
+            "Created default config structure at: {}",
+            config_dir.display()
+        );
+        info!("Please configure your providers and DNS settings, then run again.");
+
+        //TODO: Handle first run better (signal to caller that config was created)
+        Ok(Config::default())
+    }
+
+    fn load_resolver_config(
+        config_dir: impl AsRef<Path>,
+        strict: bool,
+        parsing: Parsing,
+    ) -> Result<resolver::Config> {
         let resolver_path = config_dir.as_ref().join("resolver.yaml");
 
-        //TODO: Fail with error if resolver config is missing
         if resolver_path.exists() {
-            let content = fs::read_to_string(resolver_path)?;
-            Ok(serde_yaml_ng::from_str(&content)?)
+            let content = fs::read_to_string(&resolver_path)?;
+            parse_yaml(&content, &resolver_path, parsing)
+        } else if strict {
+            Err(MissingConfigError::Missing(resolver_path).into())
         } else {
             Ok(resolver::Config::default())
         }
     }
 
-    fn load_provider_configs(providers_dir: impl AsRef<Path>) -> Result<Vec<Provider>> {
+    /// Loads `http.yaml`, which is optional even when loading strictly.
+    fn load_http_config(config_dir: impl AsRef<Path>, parsing: Parsing) -> Result<http::Config> {
+        let http_path = config_dir.as_ref().join("http.yaml");
+
+        if http_path.exists() {
+            let content = fs::read_to_string(&http_path)?;
+            parse_yaml(&content, &http_path, parsing)
+        } else {
+            Ok(http::Config::default())
+        }
+    }
+
+    /// Loads `notifications.yaml`, which is optional even when loading strictly.
+    fn load_notification_config(
+        config_dir: impl AsRef<Path>,
+        parsing: Parsing,
+    ) -> Result<notification::Config> {
+        let notification_path = config_dir.as_ref().join("notifications.yaml");
+
+        if notification_path.exists() {
+            let content = fs::read_to_string(&notification_path)?;
+            parse_yaml(&content, &notification_path, parsing)
+        } else {
+            Ok(notification::Config::default())
+        }
+    }
+
+    /// Loads the audit log path from `audit.yaml`, which is optional even when loading strictly.
+    fn load_audit_log(config_dir: impl AsRef<Path>, parsing: Parsing) -> Result<Option<PathBuf>> {
+        let audit_path = config_dir.as_ref().join("audit.yaml");
+
+        if audit_path.exists() {
+            let content = fs::read_to_string(&audit_path)?;
+            let audit_file: AuditFile = parse_yaml(&content, &audit_path, parsing)?;
+            Ok(audit_file.audit_log)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Loads `log.yaml`, which is optional even when loading strictly.
+    ///
+    /// Public, as the logger is set up from it before the rest of the configuration is loaded.
+    pub fn load_log_config(config_dir: impl AsRef<Path>, parsing: Parsing) -> Result<log::Config> {
+        let log_path = config_dir.as_ref().join("log.yaml");
+
+        if log_path.exists() {
+            let content = fs::read_to_string(&log_path)?;
+            parse_yaml(&content, &log_path, parsing)
+        } else {
+            Ok(log::Config::default())
+        }
+    }
+
+    fn load_provider_configs(
+        providers_dir: impl AsRef<Path>,
+        strict: bool,
+        parsing: Parsing,
+    ) -> Result<Vec<Provider>> {
         let providers_dir = providers_dir.as_ref();
-        //TODO: Fail with error if providers config is missing
         if !providers_dir.exists() {
+            if strict {
+                return Err(MissingConfigError::Missing(providers_dir.to_path_buf()).into());
+            }
+
             info!(
                 "Providers directory {:?} does not exist, using defaults",
                 providers_dir
@@ -68,6 +551,12 @@ impl Config {
                 Provider::Nitrado(nitrado::Config::default()),
                 Provider::Hetzner(hetzner::Config::default()),
                 Provider::Netcup(netcup::Config::default()),
+                Provider::Route53(route53::Config::default()),
+                Provider::Desec(desec::Config::default()),
+                Provider::DigitalOcean(digitalocean::Config::default()),
+                Provider::Namecheap(namecheap::Config::default()),
+                Provider::Njalla(njalla::Config::default()),
+                Provider::Axfr(axfr::Config::default()),
             ]);
         }
 
@@ -78,7 +567,7 @@ impl Config {
 
             if path
                 .extension()
-                .map_or(false, |ext| ext == "yaml" || ext == "yml")
+                .is_some_and(|ext| ext == "yaml" || ext == "yml")
             {
                 let content = fs::read_to_string(&path)?;
 
@@ -90,20 +579,60 @@ impl Config {
                 //TODO: Hardcoded config file names. Detect type differently?
                 match file_stem {
                     "hetzner" => {
-                        let config: hetzner::Config = serde_yaml_ng::from_str(&content)?;
+                        let mut config: hetzner::Config = parse_yaml(&content, &path, parsing)?;
+                        config.source_path = Some(path.clone());
                         configs.push(Provider::Hetzner(config));
                         debug!("Loaded Hetzner provider config from {:?}", path);
                     }
                     "nitrado" => {
-                        let config: nitrado::Config = serde_yaml_ng::from_str(&content)?;
+                        let mut config: nitrado::Config = parse_yaml(&content, &path, parsing)?;
+                        config.source_path = Some(path.clone());
                         configs.push(Provider::Nitrado(config));
                         debug!("Loaded Nitrado provider config from {:?}", path);
                     }
                     "netcup" => {
-                        let config: netcup::Config = serde_yaml_ng::from_str(&content)?;
+                        let mut config: netcup::Config = parse_yaml(&content, &path, parsing)?;
+                        config.source_path = Some(path.clone());
                         configs.push(Provider::Netcup(config));
                         debug!("Loaded Netcup provider config from {:?}", path);
                     }
+                    "route53" => {
+                        let mut config: route53::Config = parse_yaml(&content, &path, parsing)?;
+                        config.source_path = Some(path.clone());
+                        configs.push(Provider::Route53(config));
+                        debug!("Loaded Route53 provider config from {:?}", path);
+                    }
+                    "desec" => {
+                        let mut config: desec::Config = parse_yaml(&content, &path, parsing)?;
+                        config.source_path = Some(path.clone());
+                        configs.push(Provider::Desec(config));
+                        debug!("Loaded deSEC provider config from {:?}", path);
+                    }
+                    "digitalocean" => {
+                        let mut config: digitalocean::Config =
+                            parse_yaml(&content, &path, parsing)?;
+                        config.source_path = Some(path.clone());
+                        configs.push(Provider::DigitalOcean(config));
+                        debug!("Loaded DigitalOcean provider config from {:?}", path);
+                    }
+                    "namecheap" => {
+                        let mut config: namecheap::Config = parse_yaml(&content, &path, parsing)?;
+                        config.source_path = Some(path.clone());
+                        configs.push(Provider::Namecheap(config));
+                        debug!("Loaded Namecheap provider config from {:?}", path);
+                    }
+                    "njalla" => {
+                        let mut config: njalla::Config = parse_yaml(&content, &path, parsing)?;
+                        config.source_path = Some(path.clone());
+                        configs.push(Provider::Njalla(config));
+                        debug!("Loaded Njalla provider config from {:?}", path);
+                    }
+                    "axfr" => {
+                        let mut config: axfr::Config = parse_yaml(&content, &path, parsing)?;
+                        config.source_path = Some(path.clone());
+                        configs.push(Provider::Axfr(config));
+                        debug!("Loaded AXFR provider config from {:?}", path);
+                    }
                     _ => {
                         error!("Unknown provider config file: {}", path.display());
                     }
@@ -112,6 +641,10 @@ impl Config {
         }
 
         if configs.is_empty() {
+            if strict {
+                return Err(MissingConfigError::NoProviders(providers_dir.to_path_buf()).into());
+            }
+
             info!("No provider configs found, using defaults");
             configs.push(Provider::Nitrado(nitrado::Config::default()));
             configs.push(Provider::Hetzner(hetzner::Config::default()));
@@ -120,11 +653,18 @@ impl Config {
         Ok(configs)
     }
 
-    fn load_dns_configs(dns_dir: impl AsRef<Path>) -> Result<Vec<dns::Type>> {
+    fn load_dns_configs(
+        dns_dir: impl AsRef<Path>,
+        strict: bool,
+        parsing: Parsing,
+    ) -> Result<Vec<dns::Type>> {
         let dns_dir = dns_dir.as_ref();
 
-        //TODO: Fail with error if dns config is missing
         if !dns_dir.exists() {
+            if strict {
+                return Err(MissingConfigError::Missing(dns_dir.to_path_buf()).into());
+            }
+
             info!(
                 "DNS directory {:?} does not exist, using empty configs",
                 dns_dir
@@ -139,7 +679,7 @@ impl Config {
 
             if path
                 .extension()
-                .map_or(false, |ext| ext == "yaml" || ext == "yml")
+                .is_some_and(|ext| ext == "yaml" || ext == "yml")
             {
                 let content = fs::read_to_string(&path)?;
 
@@ -150,17 +690,45 @@ impl Config {
 
                 //TODO: Hardcoded config file names. Detect type differently?
                 if file_stem.contains("hetzner") {
-                    let config: hetzner::DnsConfig = serde_yaml_ng::from_str(&content)?;
+                    let mut config: hetzner::DnsConfig = parse_yaml(&content, &path, parsing)?;
+                    config.source_path = Some(path.clone());
                     configs.push(dns::Type::Hetzner(config));
                     debug!("Loaded Hetzner DNS config from {:?}", path);
                 } else if file_stem.contains("nitrado") {
-                    let config: nitrado::DnsConfig = serde_yaml_ng::from_str(&content)?;
+                    let mut config: nitrado::DnsConfig = parse_yaml(&content, &path, parsing)?;
+                    config.source_path = Some(path.clone());
                     configs.push(dns::Type::Nitrado(config));
                     debug!("Loaded Nitrado DNS config from {:?}", path);
                 } else if file_stem.contains("netcup") {
-                    let config: netcup::DnsConfig = serde_yaml_ng::from_str(&content)?;
+                    let mut config: netcup::DnsConfig = parse_yaml(&content, &path, parsing)?;
+                    config.source_path = Some(path.clone());
                     configs.push(dns::Type::Netcup(config));
                     debug!("Loaded Netcup DNS config from {:?}", path);
+                } else if file_stem.contains("route53") {
+                    let mut config: route53::DnsConfig = parse_yaml(&content, &path, parsing)?;
+                    config.source_path = Some(path.clone());
+                    configs.push(dns::Type::Route53(config));
+                    debug!("Loaded Route53 DNS config from {:?}", path);
+                } else if file_stem.contains("desec") {
+                    let mut config: desec::DnsConfig = parse_yaml(&content, &path, parsing)?;
+                    config.source_path = Some(path.clone());
+                    configs.push(dns::Type::Desec(config));
+                    debug!("Loaded deSEC DNS config from {:?}", path);
+                } else if file_stem.contains("digitalocean") {
+                    let mut config: digitalocean::DnsConfig = parse_yaml(&content, &path, parsing)?;
+                    config.source_path = Some(path.clone());
+                    configs.push(dns::Type::DigitalOcean(config));
+                    debug!("Loaded DigitalOcean DNS config from {:?}", path);
+                } else if file_stem.contains("namecheap") {
+                    let mut config: namecheap::DnsConfig = parse_yaml(&content, &path, parsing)?;
+                    config.source_path = Some(path.clone());
+                    configs.push(dns::Type::Namecheap(config));
+                    debug!("Loaded Namecheap DNS config from {:?}", path);
+                } else if file_stem.contains("njalla") {
+                    let mut config: njalla::DnsConfig = parse_yaml(&content, &path, parsing)?;
+                    config.source_path = Some(path.clone());
+                    configs.push(dns::Type::Njalla(config));
+                    debug!("Loaded Njalla DNS config from {:?}", path);
                 } else {
                     error!(
                         "Cannot determine DNS config type for file: {}",
@@ -174,45 +742,119 @@ impl Config {
         Ok(configs)
     }
 
+    /// Returns the files of the example configuration as paths relative to the config directory
+    /// and their YAML contents.
+    pub fn example_files() -> Result<Vec<(PathBuf, String)>> {
+        let files = [
+            (
+                "resolver.yaml",
+                serde_yaml_ng::to_string(&resolver::Config::default())?,
+            ),
+            (
+                "http.yaml",
+                serde_yaml_ng::to_string(&http::Config::default())?,
+            ),
+            (
+                "notifications.yaml",
+                serde_yaml_ng::to_string(&notification::Config::default())?,
+            ),
+            (
+                "log.yaml",
+                serde_yaml_ng::to_string(&log::Config::default())?,
+            ),
+            (
+                "providers/hetzner.yaml",
+                serde_yaml_ng::to_string(&hetzner::Config::default())?,
+            ),
+            (
+                "providers/nitrado.yaml",
+                serde_yaml_ng::to_string(&nitrado::Config::default())?,
+            ),
+            (
+                "providers/netcup.yaml",
+                serde_yaml_ng::to_string(&netcup::Config::default())?,
+            ),
+            (
+                "providers/route53.yaml",
+                serde_yaml_ng::to_string(&route53::Config::default())?,
+            ),
+            (
+                "providers/desec.yaml",
+                serde_yaml_ng::to_string(&desec::Config::default())?,
+            ),
+            (
+                "providers/digitalocean.yaml",
+                serde_yaml_ng::to_string(&digitalocean::Config::default())?,
+            ),
+            (
+                "providers/namecheap.yaml",
+                serde_yaml_ng::to_string(&namecheap::Config::default())?,
+            ),
+            (
+                "providers/njalla.yaml",
+                serde_yaml_ng::to_string(&njalla::Config::default())?,
+            ),
+            (
+                "providers/axfr.yaml",
+                serde_yaml_ng::to_string(&axfr::Config::default())?,
+            ),
+            (
+                "dns/hetzner-domains.yaml",
+                serde_yaml_ng::to_string(&hetzner::DnsConfig::default())?,
+            ),
+            (
+                "dns/nitrado-domains.yaml",
+                serde_yaml_ng::to_string(&nitrado::DnsConfig::default())?,
+            ),
+            (
+                "dns/netcup-domains.yaml",
+                serde_yaml_ng::to_string(&netcup::DnsConfig::default())?,
+            ),
+            (
+                "dns/route53-domains.yaml",
+                serde_yaml_ng::to_string(&route53::DnsConfig::default())?,
+            ),
+            (
+                "dns/desec-domains.yaml",
+                serde_yaml_ng::to_string(&desec::DnsConfig::default())?,
+            ),
+            (
+                "dns/digitalocean-domains.yaml",
+                serde_yaml_ng::to_string(&digitalocean::DnsConfig::default())?,
+            ),
+            (
+                "dns/namecheap-domains.yaml",
+                serde_yaml_ng::to_string(&namecheap::DnsConfig::default())?,
+            ),
+            (
+                "dns/njalla-domains.yaml",
+                serde_yaml_ng::to_string(&njalla::DnsConfig::default())?,
+            ),
+        ];
+
+        Ok(files
+            .into_iter()
+            .map(|(path, contents)| (PathBuf::from(path), contents))
+            .collect())
+    }
+
     pub fn create_example_structure(config_dir: impl AsRef<Path>) -> Result<()> {
+        Self::write_example_files(config_dir, &Self::example_files()?)
+    }
+
+    /// Writes `files`, as returned by [`Config::example_files`], into `config_dir`.
+    pub fn write_example_files(
+        config_dir: impl AsRef<Path>,
+        files: &[(PathBuf, String)],
+    ) -> Result<()> {
         let config_dir = config_dir.as_ref();
 
         fs::create_dir_all(config_dir.join("providers"))?;
         fs::create_dir_all(config_dir.join("dns"))?;
 
-        let resolver_config = resolver::Config::default();
-        let resolver_yaml = serde_yaml_ng::to_string(&resolver_config)?;
-        fs::write(config_dir.join("resolver.yaml"), resolver_yaml)?;
-
-        let hetzner_config = hetzner::Config::default();
-        let hetzner_yaml = serde_yaml_ng::to_string(&hetzner_config)?;
-        fs::write(config_dir.join("providers/hetzner.yaml"), hetzner_yaml)?;
-
-        let nitrado_config = nitrado::Config::default();
-        let nitrado_yaml = serde_yaml_ng::to_string(&nitrado_config)?;
-        fs::write(config_dir.join("providers/nitrado.yaml"), nitrado_yaml)?;
-
-        let netcup_config = netcup::Config::default();
-        let netcup_yaml = serde_yaml_ng::to_string(&netcup_config)?;
-        fs::write(config_dir.join("providers/netcup.yaml"), netcup_yaml)?;
-
-        let hetzner_dns_config = hetzner::DnsConfig::default();
-        let hetzner_dns_yaml = serde_yaml_ng::to_string(&hetzner_dns_config)?;
-        fs::write(
-            config_dir.join("dns/hetzner-domains.yaml"),
-            hetzner_dns_yaml,
-        )?;
-
-        let nitrado_dns_config = nitrado::DnsConfig::default();
-        let nitrado_dns_yaml = serde_yaml_ng::to_string(&nitrado_dns_config)?;
-        fs::write(
-            config_dir.join("dns/nitrado-domains.yaml"),
-            nitrado_dns_yaml,
-        )?;
-
-        let netcup_dns_config = netcup::DnsConfig::default();
-        let netcup_dns_yaml = serde_yaml_ng::to_string(&netcup_dns_config)?;
-        fs::write(config_dir.join("dns/netcup-domains.yaml"), netcup_dns_yaml)?;
+        for (path, contents) in files {
+            fs::write(config_dir.join(path), contents)?;
+        }
 
         info!("Created example config structure in {:?}", config_dir);
         Ok(())
@@ -223,20 +865,97 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             resolver: resolver::Config::default(),
+            http: http::Config::default(),
+            notifications: notification::Config::default(),
+            log: log::Config::default(),
+            audit_log: None,
             providers: vec![
                 Provider::Nitrado(nitrado::Config::default()),
                 Provider::Hetzner(hetzner::Config::default()),
                 Provider::Netcup(netcup::Config::default()),
+                Provider::Route53(route53::Config::default()),
+                Provider::Desec(desec::Config::default()),
+                Provider::DigitalOcean(digitalocean::Config::default()),
+                Provider::Namecheap(namecheap::Config::default()),
+                Provider::Njalla(njalla::Config::default()),
+                Provider::Axfr(axfr::Config::default()),
             ],
             dns: vec![
                 dns::Type::Nitrado(nitrado::DnsConfig::default()),
                 dns::Type::Hetzner(hetzner::DnsConfig::default()),
                 dns::Type::Netcup(netcup::DnsConfig::default()),
+                dns::Type::Route53(route53::DnsConfig::default()),
+                dns::Type::Desec(desec::DnsConfig::default()),
+                dns::Type::DigitalOcean(digitalocean::DnsConfig::default()),
+                dns::Type::Namecheap(namecheap::DnsConfig::default()),
+                dns::Type::Njalla(njalla::DnsConfig::default()),
             ],
         }
     }
 }
 
+/// Merges `other` into `base`, keyed by `key`.
+///
+/// An entry of `other` replaces the first entry of `base` with the same key, other entries of
+/// `other` are appended. Entries of `base` without a counterpart are kept, in their order.
+fn merge_by_key<T>(base: Vec<T>, other: Vec<T>, key: impl Fn(&T) -> &str) -> Vec<T> {
+    let base_len = base.len();
+    let mut merged: Vec<Option<T>> = base.into_iter().map(Some).collect();
+    let mut replaced = vec![false; base_len];
+    let mut appended = Vec::new();
+
+    for entry in other {
+        let index = (0..base_len).find(|&index| {
+            !replaced[index]
+                && merged[index]
+                    .as_ref()
+                    .is_some_and(|base| key(base) == key(&entry))
+        });
+        match index {
+            Some(index) => {
+                merged[index] = Some(entry);
+                replaced[index] = true;
+            }
+            None => appended.push(entry),
+        }
+    }
+
+    merged.into_iter().flatten().chain(appended).collect()
+}
+
+impl MergeFrom<Self> for Config {
+    /// Merges another configuration into this one.
+    ///
+    /// Values from `other` will override values in `self`. Providers are merged by name and DNS
+    /// configs by provider name, so overriding one provider keeps all others.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::Config;
+    /// use lum_config::MergeFrom;
+    ///
+    /// let mut config = Config::default();
+    /// let mut other = Config::default();
+    /// other.resolver.ipv4[0].url = "https://example.com".to_string();
+    ///
+    /// let merged = config.merge_from(other);
+    /// assert_eq!(merged.resolver.ipv4[0].url, "https://example.com");
+    /// assert_eq!(merged.providers.len(), Config::default().providers.len());
+    /// ```
+    fn merge_from(self, other: Self) -> Self {
+        Self {
+            resolver: other.resolver,
+            http: other.http,
+            notifications: other.notifications,
+            log: other.log,
+            audit_log: other.audit_log.or(self.audit_log),
+            providers: merge_by_key(self.providers, other.providers, Provider::name),
+            dns: merge_by_key(self.dns, other.dns, dns::Type::provider_name),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -246,49 +965,114 @@ mod tests {
         let default_config = Config::default();
         let other = Config {
             resolver: resolver::Config {
-                ipv4: resolver::IpResolver {
+                ipv4: vec![resolver::IpResolver {
                     url: "https://new.ipv4.com".to_string(),
                     type_: resolver::IpResolverType::Raw,
-                },
-                ipv6: resolver::IpResolver {
+                    priority: None,
+                    weight: 1,
+                    auth: None,
+                }],
+                ipv6: vec![resolver::IpResolver {
                     url: "https://new.ipv6.com".to_string(),
                     type_: resolver::IpResolverType::Raw,
-                },
+                    priority: None,
+                    weight: 1,
+                    auth: None,
+                }],
+                quorum: None,
             },
+            http: http::Config::default(),
+            notifications: notification::Config::default(),
+            log: log::Config::default(),
+            audit_log: None,
             providers: vec![],
             dns: vec![],
         };
 
         let merged = default_config.clone().merge_from(other.clone());
 
-        assert_eq!(merged.resolver.ipv4.url, "https://new.ipv4.com");
+        assert_eq!(merged.resolver.ipv4[0].url, "https://new.ipv4.com");
         assert_eq!(merged.providers.len(), default_config.providers.len());
         assert_eq!(merged.dns.len(), default_config.dns.len());
     }
 
     #[test]
-    fn test_config_merge_from_not_empty() {
+    fn test_config_merge_from_appends_new_providers() {
         let default_config = Config::default();
         let other = Config {
             resolver: resolver::Config::default(),
-            providers: vec![Provider::Nitrado(
-                nitrado::Config {
-                    name: "OtherNitrado".to_string(),
-                    ..Default::default()
-                },
-            )],
-            dns: vec![],
+            http: http::Config::default(),
+            notifications: notification::Config::default(),
+            log: log::Config::default(),
+            audit_log: None,
+            providers: vec![Provider::Nitrado(nitrado::Config {
+                name: "OtherNitrado".to_string(),
+                ..Default::default()
+            })],
+            dns: vec![dns::Type::Nitrado(nitrado::DnsConfig {
+                provider_name: "OtherNitrado".to_string(),
+                ..Default::default()
+            })],
         };
 
         let merged = default_config.clone().merge_from(other.clone());
 
-        assert_eq!(merged.providers.len(), 1);
-        if let Provider::Nitrado(config) = &merged.providers[0] {
+        assert_eq!(merged.providers.len(), default_config.providers.len() + 1);
+        assert_eq!(merged.providers[0].name(), "Nitrado1");
+        if let Some(Provider::Nitrado(config)) = merged.providers.last() {
             assert_eq!(config.name, "OtherNitrado");
         } else {
             panic!("Expected Nitrado provider");
         }
+        assert_eq!(merged.dns.len(), default_config.dns.len() + 1);
+        assert_eq!(merged.dns.last().unwrap().provider_name(), "OtherNitrado");
+    }
+
+    #[test]
+    fn test_config_merge_from_overrides_provider_by_name() {
+        let default_config = Config::default();
+        let other = Config {
+            providers: vec![Provider::Hetzner(hetzner::Config {
+                api_key: "my-hetzner-key".into(),
+                ..Default::default()
+            })],
+            dns: vec![dns::Type::Hetzner(hetzner::DnsConfig {
+                domains: vec![hetzner::DomainConfig {
+                    domain: "example.com".to_string(),
+                    zone_id: None,
+                    records: vec![],
+                }],
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+
+        let merged = default_config.clone().merge_from(other);
+
+        let names: Vec<&str> = merged.providers.iter().map(Provider::name).collect();
+        let default_names: Vec<&str> = default_config
+            .providers
+            .iter()
+            .map(Provider::name)
+            .collect();
+        assert_eq!(names, default_names);
+        let hetzner = merged
+            .providers
+            .iter()
+            .find_map(|provider| match provider {
+                Provider::Hetzner(config) => Some(config),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(hetzner.api_key.as_str(), "my-hetzner-key");
+
         assert_eq!(merged.dns.len(), default_config.dns.len());
+        let hetzner_dns = merged
+            .dns
+            .iter()
+            .find(|dns| dns.provider_name() == "Hetzner1")
+            .unwrap();
+        assert_eq!(hetzner_dns.domains()[0].0, "example.com");
     }
 
     #[test]
@@ -296,6 +1080,63 @@ mod tests {
         let config = Config::default();
         assert!(!config.providers.is_empty());
         assert!(!config.dns.is_empty());
+        assert!(config.audit_log.is_none());
+    }
+
+    #[test]
+    fn test_config_merge_from_keeps_audit_log() {
+        let base = Config {
+            audit_log: Some(PathBuf::from("/var/log/dnrs/audit.jsonl")),
+            ..Default::default()
+        };
+
+        let merged = base.clone().merge_from(Config::default());
+        assert_eq!(merged.audit_log, base.audit_log);
+
+        let other = Config {
+            audit_log: Some(PathBuf::from("audit.jsonl")),
+            ..Default::default()
+        };
+        let merged = base.merge_from(other);
+        assert_eq!(merged.audit_log, Some(PathBuf::from("audit.jsonl")));
+
+        let config: Config = serde_yaml_ng::from_str("audit_log: audit.jsonl").unwrap();
+        assert_eq!(config.audit_log, Some(PathBuf::from("audit.jsonl")));
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let mut config = Config {
+            providers: vec![
+                Provider::Netcup(netcup::Config {
+                    api_key: "netcup-key-value".into(),
+                    api_password: "netcup-password-value".into(),
+                    ..Default::default()
+                }),
+                Provider::Desec(desec::Config {
+                    token: "desec-token-value".into(),
+                    ..Default::default()
+                }),
+            ],
+            ..Default::default()
+        };
+        config
+            .http
+            .headers
+            .insert("Proxy-Authorization".to_string(), "proxy-value".to_string());
+
+        let debug = format!("{:?}", config);
+        for secret in [
+            "netcup-key-value",
+            "netcup-password-value",
+            "desec-token-value",
+            "proxy-value",
+        ] {
+            assert!(!debug.contains(secret), "{} leaked: {}", secret, debug);
+        }
+        assert!(debug.contains("Netcup1"));
+        assert!(debug.contains("Desec1"));
+        assert!(debug.contains("Proxy-Authorization"));
     }
 
     #[test]
@@ -309,8 +1150,196 @@ mod tests {
         Config::create_example_structure(&temp_dir).unwrap();
 
         let config = Config::load_from_directory(&temp_dir).unwrap();
-        assert_eq!(config.providers.len(), 3);
-        assert_eq!(config.dns.len(), 3);
+        assert_eq!(config.providers.len(), 9);
+        assert_eq!(config.dns.len(), 8);
+        assert!(config.audit_log.is_none());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_audit_log_from_directory() {
+        let temp_dir = std::env::temp_dir().join("dnrs_audit_log_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        fs::create_dir_all(&temp_dir).unwrap();
+        Config::create_example_structure(&temp_dir).unwrap();
+        fs::write(
+            temp_dir.join("audit.yaml"),
+            "audit_log: /var/log/dnrs/audit.jsonl\n",
+        )
+        .unwrap();
+
+        let config = Config::load_from_directory_strict(&temp_dir).unwrap();
+        assert_eq!(
+            config.audit_log,
+            Some(PathBuf::from("/var/log/dnrs/audit.jsonl"))
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_from_directory_sets_source_path() {
+        let temp_dir = std::env::temp_dir().join("dnrs_source_path_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        Config::create_example_structure(&temp_dir).unwrap();
+
+        let config = Config::load_from_directory(&temp_dir).unwrap();
+        let nitrado = config
+            .providers
+            .iter()
+            .find(|provider| matches!(provider, Provider::Nitrado(_)))
+            .unwrap();
+        assert_eq!(
+            nitrado.source_path(),
+            Some(temp_dir.join("providers/nitrado.yaml").as_path())
+        );
+        assert!(config.dns.iter().all(|dns| {
+            dns.source_path()
+                .is_some_and(|path| path.starts_with(temp_dir.join("dns")))
+        }));
+        assert!(Config::default().providers[0].source_path().is_none());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_key_is_reported_in_strict_mode_only() {
+        let temp_dir = std::env::temp_dir().join("dnrs_strict_parsing_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        Config::create_example_structure(&temp_dir).unwrap();
+        Config::load(&temp_dir, true, Parsing::Strict).unwrap();
+
+        let hetzner_path = temp_dir.join("providers/hetzner.yaml");
+        fs::write(
+            &hetzner_path,
+            "name: Hetzner1\napi_key: key\napi_base_url: https://dns.hetzner.com/api/v1\nrequests_per_secnd: 5\n",
+        )
+        .unwrap();
+
+        let config = Config::load(&temp_dir, false, Parsing::Lenient).unwrap();
+        let hetzner = config
+            .providers
+            .iter()
+            .find_map(|provider| match provider {
+                Provider::Hetzner(config) => Some(config),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(hetzner.requests_per_second, None);
+
+        let error = Config::load(&temp_dir, false, Parsing::Strict).unwrap_err();
+        let error = error.downcast::<UnknownKeyError>().unwrap();
+        assert_eq!(error.key, "requests_per_secnd");
+        assert_eq!(error.path, hetzner_path);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_from_directory_resolves_secret_files() {
+        let temp_dir = std::env::temp_dir().join("dnrs_secret_file_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        fs::create_dir_all(temp_dir.join("providers")).unwrap();
+        let secret_path = temp_dir.join("hetzner_secret");
+        fs::write(&secret_path, "  secret_from_file\n").unwrap();
+        fs::write(
+            temp_dir.join("providers/hetzner.yaml"),
+            format!(
+                "name: Hetzner1\napi_key:\n  file: {}\napi_base_url: https://dns.hetzner.com/api/v1\nrequests_per_second: null\n",
+                secret_path.display()
+            ),
+        )
+        .unwrap();
+
+        let config = Config::load_from_directory(&temp_dir).unwrap();
+        let Some(Provider::Hetzner(hetzner)) = config
+            .providers
+            .iter()
+            .find(|provider| provider.name() == "Hetzner1")
+        else {
+            panic!("Expected Hetzner provider");
+        };
+        assert_eq!(hetzner.api_key.as_str(), "secret_from_file");
+
+        fs::remove_file(&secret_path).unwrap();
+        let error = Config::load_from_directory(&temp_dir).unwrap_err();
+        assert!(error.downcast_ref::<secret::SecretError>().is_some());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_from_file_matches_directory_layout() {
+        let temp_dir = std::env::temp_dir().join("dnrs_single_file_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        fs::create_dir_all(temp_dir.join("providers")).unwrap();
+        fs::create_dir_all(temp_dir.join("dns")).unwrap();
+
+        let resolver = r#"
+ipv4:
+  - url: https://ipv4.example.com
+    type: Raw
+ipv6: []
+"#;
+        let provider = r#"
+name: Hetzner1
+api_key: test-key
+api_base_url: https://dns.hetzner.com/api/v1
+requests_per_second: 2.0
+"#;
+        let dns = r#"
+provider_name: Hetzner1
+domains:
+  - domain: example.com
+    records:
+      - !Manual
+        domain: example.com
+        type: MX
+        priority: 10
+        target: mail.example.com
+"#;
+        fs::write(temp_dir.join("resolver.yaml"), resolver).unwrap();
+        fs::write(temp_dir.join("providers/hetzner.yaml"), provider).unwrap();
+        fs::write(temp_dir.join("dns/hetzner-domains.yaml"), dns).unwrap();
+
+        let indent = |yaml: &str| yaml.trim().replace('\n', "\n    ");
+        let file = temp_dir.join("config.yaml");
+        fs::write(
+            &file,
+            format!(
+                "resolver:\n    {}\nproviders:\n  - !Hetzner\n    {}\ndns:\n  - !Hetzner\n    {}\n",
+                indent(resolver),
+                indent(provider),
+                indent(dns)
+            ),
+        )
+        .unwrap();
+
+        let from_directory = Config::load_from_directory(&temp_dir).unwrap();
+        let from_file = Config::load_from_file(&file, Parsing::Strict).unwrap();
+        assert_eq!(
+            serde_yaml_ng::to_string(&from_file).unwrap(),
+            serde_yaml_ng::to_string(&from_directory).unwrap()
+        );
+        assert_eq!(from_file.providers.len(), Config::default().providers.len());
+        assert_eq!(from_file.resolver.ipv4[0].url, "https://ipv4.example.com");
+        let hetzner_dns = from_file
+            .dns
+            .iter()
+            .find(|dns| dns.provider_name() == "Hetzner1")
+            .unwrap();
+        assert_eq!(hetzner_dns.domains().len(), 1);
 
         fs::remove_dir_all(&temp_dir).unwrap();
     }
@@ -326,6 +1355,180 @@ mod tests {
         assert!(!config.providers.is_empty());
     }
 
+    #[test]
+    fn test_load_from_directory_strict_errors_on_missing() {
+        let temp_dir = std::env::temp_dir().join("dnrs_strict_missing_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        Config::create_example_structure(&temp_dir).unwrap();
+        assert!(Config::load_from_directory_strict(&temp_dir).is_ok());
+
+        fs::remove_dir_all(temp_dir.join("dns")).unwrap();
+        let error = Config::load_from_directory_strict(&temp_dir).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<MissingConfigError>(),
+            Some(MissingConfigError::Missing(path)) if *path == temp_dir.join("dns")
+        ));
+
+        fs::remove_file(temp_dir.join("resolver.yaml")).unwrap();
+        let error = Config::load_from_directory_strict(&temp_dir).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<MissingConfigError>(),
+            Some(MissingConfigError::Missing(path)) if *path == temp_dir.join("resolver.yaml")
+        ));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_from_directory_lenient_uses_defaults() {
+        let temp_dir = std::env::temp_dir().join("dnrs_lenient_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        fs::create_dir_all(temp_dir.join("providers")).unwrap();
+
+        let config = Config::load_from_directory(&temp_dir).unwrap();
+        let default_config = Config::default();
+        assert_eq!(
+            config.resolver.ipv4[0].url,
+            default_config.resolver.ipv4[0].url
+        );
+        assert!(!config.providers.is_empty());
+
+        let error = Config::load_from_directory_strict(&temp_dir).unwrap_err();
+        assert!(error.downcast_ref::<MissingConfigError>().is_some());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_log_config_module_levels() {
+        let temp_dir = std::env::temp_dir().join("dnrs_log_config_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        fs::create_dir_all(&temp_dir).unwrap();
+        assert!(
+            Config::load_log_config(&temp_dir, Parsing::Lenient)
+                .unwrap()
+                .module_levels()
+                .is_empty()
+        );
+
+        fs::write(
+            temp_dir.join("log.yaml"),
+            "module_levels:\n  reqwest: warn\n  dnrs::resolver: debug\n",
+        )
+        .unwrap();
+        let module_levels = Config::load_log_config(&temp_dir, Parsing::Lenient)
+            .unwrap()
+            .module_levels();
+        assert_eq!(
+            module_levels,
+            [
+                (
+                    "dnrs::resolver".to_string(),
+                    lum_log::log::LevelFilter::Debug
+                ),
+                ("reqwest".to_string(), lum_log::log::LevelFilter::Warn),
+            ]
+        );
+        let config = Config::load_from_directory(&temp_dir).unwrap();
+        assert_eq!(config.log.module_levels(), module_levels);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_or_create_leaves_existing_config_untouched() {
+        let temp_dir = std::env::temp_dir().join("dnrs_untouched_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        Config::create_example_structure(&temp_dir).unwrap();
+        let resolver_path = temp_dir.join("resolver.yaml");
+        let content = "# My resolvers\nipv4:\n  url: https://ip.example.com\n  type: Raw\nipv6:\n    url:   https://ipv6.example.com\n    type: Raw\n";
+        fs::write(&resolver_path, content).unwrap();
+
+        let config =
+            Config::load_or_create(&temp_dir, FirstRun::Bootstrap, Parsing::Lenient).unwrap();
+        assert_eq!(config.resolver.ipv4[0].url, "https://ip.example.com");
+        assert_eq!(fs::read_to_string(&resolver_path).unwrap(), content);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_or_create_without_writing() {
+        let temp_dir = std::env::temp_dir().join("dnrs_no_write_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        let config =
+            Config::load_or_create(&temp_dir, FirstRun::Defaults, Parsing::Lenient).unwrap();
+        assert!(!config.providers.is_empty());
+        assert!(!temp_dir.exists());
+    }
+
+    #[test]
+    fn test_load_or_create_fails_without_bootstrap() {
+        let temp_dir = std::env::temp_dir().join("dnrs_no_bootstrap_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        let error =
+            Config::load_or_create(&temp_dir, FirstRun::Fail, Parsing::Lenient).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<MissingConfigError>(),
+            Some(MissingConfigError::Missing(path)) if *path == temp_dir
+        ));
+        assert!(!temp_dir.exists());
+    }
+
+    #[test]
+    fn test_load_or_create_bootstraps_missing_directory() {
+        let temp_dir = std::env::temp_dir().join("dnrs_bootstrap_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        Config::load_or_create(&temp_dir, FirstRun::Bootstrap, Parsing::Lenient).unwrap();
+        assert!(temp_dir.join("resolver.yaml").exists());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_load_or_create_keeps_config_valid() {
+        let temp_dir = std::env::temp_dir().join("dnrs_concurrent_bootstrap_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        let writers: Vec<_> = (0..4)
+            .map(|_| {
+                let temp_dir = temp_dir.clone();
+                std::thread::spawn(move || {
+                    Config::load_or_create(&temp_dir, FirstRun::Bootstrap, Parsing::Lenient)
+                        .unwrap()
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let config = Config::load_from_directory_strict(&temp_dir).unwrap();
+        assert_eq!(config.providers.len(), Config::default().providers.len());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
     #[test]
     fn test_load_from_directory_invalid_yaml() {
         let temp_dir = std::env::temp_dir().join("dnrs_invalid_yaml_test");
@@ -342,38 +1545,3 @@ mod tests {
         fs::remove_dir_all(&temp_dir).unwrap();
     }
 }
-
-impl MergeFrom<Self> for Config {
-    /// Merges another configuration into this one.
-    ///
-    /// Values from `other` will override values in `self`.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use dnrs::Config;
-    /// use lum_config::MergeFrom;
-    ///
-    /// let mut config = Config::default();
-    /// let mut other = Config::default();
-    /// other.resolver.ipv4.url = "https://example.com".to_string();
-    ///
-    /// let merged = config.merge_from(other);
-    /// assert_eq!(merged.resolver.ipv4.url, "https://example.com");
-    /// ```
-    fn merge_from(self, other: Self) -> Self {
-        Self {
-            resolver: other.resolver,
-            providers: if !other.providers.is_empty() {
-                other.providers
-            } else {
-                self.providers
-            },
-            dns: if !other.dns.is_empty() {
-                other.dns
-            } else {
-                self.dns
-            },
-        }
-    }
-}
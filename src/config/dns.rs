@@ -1,23 +1,139 @@
+use std::{net::Ipv6Addr, path::Path};
+
 use lum_libs::serde::{Deserialize, Serialize};
 
-use crate::provider::{hetzner, netcup, nitrado};
+use crate::provider::{desec, digitalocean, hetzner, namecheap, netcup, nitrado, njalla, route53};
 use crate::types;
 
+pub mod manual;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
 pub enum Type {
     Nitrado(nitrado::DnsConfig),
     Hetzner(hetzner::DnsConfig),
     Netcup(netcup::DnsConfig),
+    Route53(route53::DnsConfig),
+    Desec(desec::DnsConfig),
+    DigitalOcean(digitalocean::DnsConfig),
+    Namecheap(namecheap::DnsConfig),
+    Njalla(njalla::DnsConfig),
+}
+
+impl Type {
+    pub fn provider_name(&self) -> &str {
+        match self {
+            Type::Nitrado(config) => &config.provider_name,
+            Type::Hetzner(config) => &config.provider_name,
+            Type::Netcup(config) => &config.provider_name,
+            Type::Route53(config) => &config.provider_name,
+            Type::Desec(config) => &config.provider_name,
+            Type::DigitalOcean(config) => &config.provider_name,
+            Type::Namecheap(config) => &config.provider_name,
+            Type::Njalla(config) => &config.provider_name,
+        }
+    }
+
+    /// Returns the file this config was loaded from, `None` for built-in defaults.
+    pub fn source_path(&self) -> Option<&Path> {
+        match self {
+            Type::Nitrado(config) => config.source_path.as_deref(),
+            Type::Hetzner(config) => config.source_path.as_deref(),
+            Type::Netcup(config) => config.source_path.as_deref(),
+            Type::Route53(config) => config.source_path.as_deref(),
+            Type::Desec(config) => config.source_path.as_deref(),
+            Type::DigitalOcean(config) => config.source_path.as_deref(),
+            Type::Namecheap(config) => config.source_path.as_deref(),
+            Type::Njalla(config) => config.source_path.as_deref(),
+        }
+    }
+
+    /// Returns the configured domains as `(domain, records)` pairs.
+    pub fn domains(&self) -> Vec<(&str, &[RecordConfig])> {
+        match self {
+            Type::Nitrado(config) => config
+                .domains
+                .iter()
+                .map(|domain| (domain.domain.as_str(), domain.records.as_slice()))
+                .collect(),
+            Type::Hetzner(config) => config
+                .domains
+                .iter()
+                .map(|domain| (domain.domain.as_str(), domain.records.as_slice()))
+                .collect(),
+            Type::Netcup(config) => config
+                .domains
+                .iter()
+                .map(|domain| (domain.domain.as_str(), domain.records.as_slice()))
+                .collect(),
+            Type::Route53(config) => config
+                .domains
+                .iter()
+                .map(|domain| (domain.domain.as_str(), domain.records.as_slice()))
+                .collect(),
+            Type::Desec(config) => config
+                .domains
+                .iter()
+                .map(|domain| (domain.domain.as_str(), domain.records.as_slice()))
+                .collect(),
+            Type::DigitalOcean(config) => config
+                .domains
+                .iter()
+                .map(|domain| (domain.domain.as_str(), domain.records.as_slice()))
+                .collect(),
+            Type::Namecheap(config) => config
+                .domains
+                .iter()
+                .map(|domain| (domain.domain.as_str(), domain.records.as_slice()))
+                .collect(),
+            Type::Njalla(config) => config
+                .domains
+                .iter()
+                .map(|domain| (domain.domain.as_str(), domain.records.as_slice()))
+                .collect(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
 pub enum RecordConfig {
-    Manual(types::dns::Record),
+    /// A record with a fixed value, see [`manual::ManualRecord`] for how it is written.
+    Manual(#[serde(with = "manual")] types::dns::Record),
     Automatic(AutomaticRecordConfig),
 }
 
+impl RecordConfig {
+    /// Returns the names of the providers this record is published to: the providers listed by
+    /// an automatic record, otherwise `default_provider`, the provider of its DNS config.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::config::dns::{AutomaticRecordConfig, RecordConfig, ResolveType};
+    ///
+    /// let mut config = AutomaticRecordConfig {
+    ///     domain: "home.example.com".to_string(),
+    ///     ttl: None,
+    ///     resolve_type: ResolveType::IPv4,
+    ///     ipv6_suffix: None,
+    ///     providers: vec![],
+    /// };
+    /// assert_eq!(RecordConfig::Automatic(config.clone()).target_providers("Hetzner1"), vec!["Hetzner1"]);
+    ///
+    /// config.providers = vec!["Hetzner1".to_string(), "Desec1".to_string()];
+    /// assert_eq!(RecordConfig::Automatic(config).target_providers("Hetzner1"), vec!["Hetzner1", "Desec1"]);
+    /// ```
+    pub fn target_providers<'a>(&'a self, default_provider: &'a str) -> Vec<&'a str> {
+        match self {
+            RecordConfig::Automatic(config) if !config.providers.is_empty() => {
+                config.providers.iter().map(String::as_str).collect()
+            }
+            _ => vec![default_provider],
+        }
+    }
+}
+
 /// Configuration for an automatically updated DNS record.
 ///
 /// # Examples
@@ -29,6 +145,8 @@ pub enum RecordConfig {
 ///     domain: "home.example.com".to_string(),
 ///     ttl: Some(300),
 ///     resolve_type: ResolveType::IPv4,
+///     ipv6_suffix: None,
+///     providers: vec![],
 /// };
 ///
 /// assert_eq!(config.domain, "home.example.com");
@@ -40,6 +158,17 @@ pub struct AutomaticRecordConfig {
     pub domain: String,
     pub ttl: Option<u32>,
     pub resolve_type: ResolveType,
+
+    /// Interface identifier of the host for IPv6 records, e.g. `::1234`. With prefix
+    /// delegation the resolvers see the address of the router, so the published address is
+    /// formed by the /64 prefix of the resolved address and the low 64 bits of this suffix.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipv6_suffix: Option<Ipv6Addr>,
+
+    /// Publish the record to these providers instead of the provider of its DNS config, e.g.
+    /// to two providers for redundancy.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub providers: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,426 @@
+use std::{
+    collections::BTreeMap,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+use lum_libs::serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use thiserror::Error;
+
+use crate::types::dns::{MxRecord, Record, RecordType, RecordValue, SvcbRecord};
+
+#[derive(Debug, Error)]
+pub enum ManualRecordError {
+    #[error("Record {0} needs a type or a value")]
+    MissingType(String),
+
+    #[error("{0:?} record {1} is missing the field {2}")]
+    MissingField(RecordType, String, &'static str),
+
+    #[error("{0:?} record {1} has an invalid {2}: {3}")]
+    InvalidField(RecordType, String, &'static str, String),
+
+    #[error("Invalid record value of {0}: {1}")]
+    InvalidValue(String, serde_yaml_ng::Error),
+}
+
+/// A manual record as written in a DNS config.
+///
+/// The fields of the record type are written next to its `type`, named like in zone file
+/// documentation. Records written with an explicit `value`, the representation of earlier
+/// versions, are still accepted.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::{config::dns::manual::ManualRecord, types::dns::{Record, RecordValue}};
+///
+/// let manual: ManualRecord = serde_yaml_ng::from_str(r#"
+///     domain: _sip._tcp.example.com
+///     type: SRV
+///     priority: 0
+///     weight: 5
+///     port: 5060
+///     target: sip.example.com
+/// "#).unwrap();
+///
+/// let record = Record::try_from(manual).unwrap();
+/// assert!(matches!(record.value, RecordValue::SRV(0, 5, 5060, _)));
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct ManualRecord {
+    pub domain: String,
+
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub record_type: Option<RecordType>,
+
+    /// Address of A and AAAA records, target of CNAME and NS records, text of TXT and SPF
+    /// records and the whole SOA.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<BTreeMap<String, String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matching_type: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+
+    /// Flags of CAA and DNSKEY records.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flags: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    /// The CAA value, or the whole record value without a `type`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_yaml_ng::Value>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_tag: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest_type: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+fn required<T>(
+    field: Option<T>,
+    record_type: RecordType,
+    domain: &str,
+    name: &'static str,
+) -> Result<T, ManualRecordError> {
+    field.ok_or_else(|| ManualRecordError::MissingField(record_type, domain.to_string(), name))
+}
+
+impl ManualRecord {
+    fn to_value(&self, record_type: RecordType) -> Result<RecordValue, ManualRecordError> {
+        let domain = self.domain.as_str();
+        let field =
+            |value: Option<&String>, name| required(value.cloned(), record_type, domain, name);
+        let invalid = |name, error: String| {
+            ManualRecordError::InvalidField(record_type, domain.to_string(), name, error)
+        };
+
+        let value = match record_type {
+            RecordType::A => RecordValue::A(
+                field(self.content.as_ref(), "content")?
+                    .parse::<Ipv4Addr>()
+                    .map_err(|e| invalid("content", e.to_string()))?,
+            ),
+            RecordType::AAAA => RecordValue::AAAA(
+                field(self.content.as_ref(), "content")?
+                    .parse::<Ipv6Addr>()
+                    .map_err(|e| invalid("content", e.to_string()))?,
+            ),
+            RecordType::CNAME => RecordValue::CNAME(field(self.content.as_ref(), "content")?),
+            RecordType::TXT => RecordValue::TXT(field(self.content.as_ref(), "content")?),
+            RecordType::SPF => RecordValue::SPF(field(self.content.as_ref(), "content")?),
+            RecordType::NS => RecordValue::NS(field(self.content.as_ref(), "content")?),
+            RecordType::SOA => RecordValue::SOA(field(self.content.as_ref(), "content")?),
+            RecordType::MX => RecordValue::MX(MxRecord {
+                priority: required(self.priority, record_type, domain, "priority")?,
+                target: field(self.target.as_ref(), "target")?,
+            }),
+            RecordType::SRV => RecordValue::SRV(
+                required(self.priority, record_type, domain, "priority")?,
+                required(self.weight, record_type, domain, "weight")?,
+                required(self.port, record_type, domain, "port")?,
+                field(self.target.as_ref(), "target")?,
+            ),
+            RecordType::TLSA => RecordValue::TLSA(
+                required(self.usage, record_type, domain, "usage")?,
+                required(self.selector, record_type, domain, "selector")?,
+                required(self.matching_type, record_type, domain, "matching_type")?,
+                field(self.data.as_ref(), "data")?,
+            ),
+            RecordType::CAA => {
+                let flags = self.flags.unwrap_or(0);
+                let value = match required(self.value.as_ref(), record_type, domain, "value")? {
+                    serde_yaml_ng::Value::String(value) => value.clone(),
+                    _ => return Err(invalid("value", "expected a string".to_string())),
+                };
+
+                RecordValue::CAA(
+                    u8::try_from(flags).map_err(|e| invalid("flags", e.to_string()))?,
+                    field(self.tag.as_ref(), "tag")?,
+                    value,
+                )
+            }
+            RecordType::SVCB | RecordType::HTTPS => {
+                let svcb = SvcbRecord {
+                    priority: required(self.priority, record_type, domain, "priority")?,
+                    target: field(self.target.as_ref(), "target")?,
+                    params: self.params.clone().unwrap_or_default(),
+                };
+
+                match record_type {
+                    RecordType::SVCB => RecordValue::SVCB(svcb),
+                    _ => RecordValue::HTTPS(svcb),
+                }
+            }
+            RecordType::DS => RecordValue::DS(
+                required(self.key_tag, record_type, domain, "key_tag")?,
+                required(self.algorithm, record_type, domain, "algorithm")?,
+                required(self.digest_type, record_type, domain, "digest_type")?,
+                field(self.digest.as_ref(), "digest")?,
+            ),
+            RecordType::DNSKEY => RecordValue::DNSKEY(
+                required(self.flags, record_type, domain, "flags")?,
+                required(self.protocol, record_type, domain, "protocol")?,
+                required(self.algorithm, record_type, domain, "algorithm")?,
+                field(self.public_key.as_ref(), "public_key")?,
+            ),
+        };
+
+        Ok(value)
+    }
+}
+
+impl TryFrom<ManualRecord> for Record {
+    type Error = ManualRecordError;
+
+    fn try_from(manual: ManualRecord) -> Result<Self, Self::Error> {
+        let value = match (manual.record_type, &manual.value) {
+            (Some(record_type), _) => manual.to_value(record_type)?,
+            (None, Some(value)) => serde_yaml_ng::from_value(value.clone())
+                .map_err(|e| ManualRecordError::InvalidValue(manual.domain.clone(), e))?,
+            (None, None) => return Err(ManualRecordError::MissingType(manual.domain)),
+        };
+
+        Ok(Record {
+            domain: manual.domain,
+            value,
+            ttl: manual.ttl,
+        })
+    }
+}
+
+impl From<&Record> for ManualRecord {
+    fn from(record: &Record) -> Self {
+        let manual = ManualRecord {
+            domain: record.domain.clone(),
+            record_type: Some(record.value.record_type()),
+            ttl: record.ttl,
+            ..Default::default()
+        };
+
+        match &record.value {
+            RecordValue::A(ip) => ManualRecord {
+                content: Some(ip.to_string()),
+                ..manual
+            },
+            RecordValue::AAAA(ip) => ManualRecord {
+                content: Some(ip.to_string()),
+                ..manual
+            },
+            RecordValue::CNAME(content)
+            | RecordValue::TXT(content)
+            | RecordValue::SPF(content)
+            | RecordValue::NS(content)
+            | RecordValue::SOA(content) => ManualRecord {
+                content: Some(content.clone()),
+                ..manual
+            },
+            RecordValue::MX(mx) => ManualRecord {
+                priority: Some(mx.priority),
+                target: Some(mx.target.clone()),
+                ..manual
+            },
+            RecordValue::SRV(priority, weight, port, target) => ManualRecord {
+                priority: Some(*priority),
+                weight: Some(*weight),
+                port: Some(*port),
+                target: Some(target.clone()),
+                ..manual
+            },
+            RecordValue::TLSA(usage, selector, matching_type, data) => ManualRecord {
+                usage: Some(*usage),
+                selector: Some(*selector),
+                matching_type: Some(*matching_type),
+                data: Some(data.clone()),
+                ..manual
+            },
+            RecordValue::CAA(flags, tag, value) => ManualRecord {
+                flags: Some(u16::from(*flags)),
+                tag: Some(tag.clone()),
+                value: Some(serde_yaml_ng::Value::String(value.clone())),
+                ..manual
+            },
+            RecordValue::SVCB(svcb) | RecordValue::HTTPS(svcb) => ManualRecord {
+                priority: Some(svcb.priority),
+                target: Some(svcb.target.clone()),
+                params: (!svcb.params.is_empty()).then(|| svcb.params.clone()),
+                ..manual
+            },
+            RecordValue::DS(key_tag, algorithm, digest_type, digest) => ManualRecord {
+                key_tag: Some(*key_tag),
+                algorithm: Some(*algorithm),
+                digest_type: Some(*digest_type),
+                digest: Some(digest.clone()),
+                ..manual
+            },
+            RecordValue::DNSKEY(flags, protocol, algorithm, public_key) => ManualRecord {
+                flags: Some(*flags),
+                protocol: Some(*protocol),
+                algorithm: Some(*algorithm),
+                public_key: Some(public_key.clone()),
+                ..manual
+            },
+        }
+    }
+}
+
+/// Serializes a [`Record`] as a [`ManualRecord`], for use with `#[serde(with)]`.
+pub fn serialize<S: Serializer>(record: &Record, serializer: S) -> Result<S::Ok, S::Error> {
+    ManualRecord::from(record).serialize(serializer)
+}
+
+/// Deserializes a [`Record`] from a [`ManualRecord`], for use with `#[serde(with)]`.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Record, D::Error> {
+    let manual = ManualRecord::deserialize(deserializer)?;
+    Record::try_from(manual).map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Record {
+        let manual: ManualRecord = serde_yaml_ng::from_str(yaml).unwrap();
+        Record::try_from(manual).unwrap()
+    }
+
+    #[test]
+    fn test_friendly_yaml_of_every_record_type() {
+        let cases = [
+            ("type: A\ncontent: 1.2.3.4", "1.2.3.4"),
+            ("type: AAAA\ncontent: 2001:db8::1", "2001:db8::1"),
+            ("type: CNAME\ncontent: example.com", "example.com"),
+            ("type: TXT\ncontent: hello", "hello"),
+            ("type: SPF\ncontent: v=spf1 -all", "v=spf1 -all"),
+            ("type: NS\ncontent: ns1.example.com", "ns1.example.com"),
+            (
+                "type: SOA\ncontent: ns1.example.com. admin.example.com. 1 7200 3600 1209600 3600",
+                "ns1.example.com. admin.example.com. 1 7200 3600 1209600 3600",
+            ),
+            (
+                "type: MX\npriority: 10\ntarget: mail.example.com",
+                "10 mail.example.com",
+            ),
+            (
+                "type: SRV\npriority: 0\nweight: 5\nport: 5060\ntarget: sip.example.com",
+                "0 5 5060 sip.example.com",
+            ),
+            (
+                "type: TLSA\nusage: 3\nselector: 1\nmatching_type: 1\ndata: abcd",
+                "3 1 1 abcd",
+            ),
+            (
+                "type: CAA\nflags: 0\ntag: issue\nvalue: letsencrypt.org",
+                "0 issue letsencrypt.org",
+            ),
+            (
+                "type: SVCB\npriority: 1\ntarget: .\nparams:\n  alpn: h2,h3",
+                "1 . alpn=h2,h3",
+            ),
+            (
+                "type: HTTPS\npriority: 0\ntarget: cdn.example.net",
+                "0 cdn.example.net",
+            ),
+            (
+                "type: DS\nkey_tag: 12345\nalgorithm: 13\ndigest_type: 2\ndigest: abcd",
+                "12345 13 2 abcd",
+            ),
+            (
+                "type: DNSKEY\nflags: 257\nprotocol: 3\nalgorithm: 13\npublic_key: AwEAAQ==",
+                "257 3 13 AwEAAQ==",
+            ),
+        ];
+
+        assert_eq!(cases.len(), RecordType::ALL.len());
+        for (fields, content) in cases {
+            let record = parse(&format!("domain: example.com\nttl: 300\n{}", fields));
+            assert_eq!(record.value.content(), content, "{}", fields);
+            assert_eq!(record.ttl, Some(300));
+        }
+    }
+
+    #[test]
+    fn test_legacy_value_is_accepted() {
+        let record = parse(
+            r#"
+            domain: _sip._tcp.example.com
+            value: !SRV [0, 5, 5060, sip.example.com]
+            ttl: null
+            "#,
+        );
+
+        assert!(matches!(record.value, RecordValue::SRV(0, 5, 5060, _)));
+    }
+
+    #[test]
+    fn test_round_trip_uses_friendly_yaml() {
+        let record = parse("domain: example.com\ntype: CAA\ntag: issue\nvalue: letsencrypt.org");
+
+        let yaml = serde_yaml_ng::to_string(&ManualRecord::from(&record)).unwrap();
+        assert!(yaml.contains("type: CAA"));
+        assert!(yaml.contains("tag: issue"));
+
+        let round_tripped = parse(&yaml);
+        assert_eq!(round_tripped.value.content(), record.value.content());
+    }
+
+    #[test]
+    fn test_missing_field_is_reported() {
+        let manual: ManualRecord =
+            serde_yaml_ng::from_str("domain: example.com\ntype: SRV\npriority: 0").unwrap();
+
+        let error = Record::try_from(manual).unwrap_err();
+        assert!(matches!(
+            error,
+            ManualRecordError::MissingField(RecordType::SRV, _, "weight")
+        ));
+    }
+
+    #[test]
+    fn test_record_config_uses_friendly_yaml() {
+        let records: Vec<crate::config::dns::RecordConfig> = serde_yaml_ng::from_str(
+            r#"
+            - !Manual
+              domain: example.com
+              type: MX
+              priority: 10
+              target: mail.example.com
+            "#,
+        )
+        .unwrap();
+
+        let crate::config::dns::RecordConfig::Manual(record) = &records[0] else {
+            panic!("Expected a manual record");
+        };
+        assert_eq!(record.value.content(), "10 mail.example.com");
+    }
+}
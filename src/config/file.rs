@@ -0,0 +1,199 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use lum_config::MergeFrom;
+use lum_libs::serde::{Deserialize, Serialize};
+
+use crate::config::{
+    Config, Parsing, dns, http, log, merge_by_key, notification, parse_yaml, provider::Provider,
+    resolver,
+};
+
+/// A single config file given with `--config-file`, holding any part of a [`Config`].
+///
+/// Sections missing from the file are left as they are when it is merged into a config.
+/// Providers are merged by name and DNS configs by provider name, like in
+/// [`Config::merge_from`], so a file only has to contain the providers it adds or replaces.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::Config;
+/// use dnrs::config::file::ConfigFile;
+/// use lum_config::MergeFrom;
+///
+/// let file: ConfigFile = serde_yaml_ng::from_str(r#"
+///     providers:
+///       - !Hetzner
+///         name: Hetzner1
+///         api_key: production-key
+///         api_base_url: https://dns.hetzner.com/api/v1
+///         requests_per_second: null
+/// "#).unwrap();
+///
+/// let config = Config::default().merge_from(file);
+/// assert_eq!(config.providers.len(), Config::default().providers.len());
+/// assert_eq!(config.resolver.ipv4.len(), Config::default().resolver.ipv4.len());
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+#[serde(default)]
+pub struct ConfigFile {
+    pub resolver: Option<resolver::Config>,
+    pub http: Option<http::Config>,
+    pub notifications: Option<notification::Config>,
+    pub log: Option<log::Config>,
+    pub audit_log: Option<PathBuf>,
+    pub providers: Vec<Provider>,
+    pub dns: Vec<dns::Type>,
+}
+
+impl ConfigFile {
+    pub fn load(path: impl AsRef<Path>, parsing: Parsing) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        parse_yaml(&content, path, parsing)
+    }
+
+    /// Returns the log settings of the last of `files` that has them.
+    pub fn last_log_config(files: &[ConfigFile]) -> Option<&log::Config> {
+        files.iter().rev().find_map(|file| file.log.as_ref())
+    }
+}
+
+impl MergeFrom<ConfigFile> for Config {
+    /// Merges a config file into this configuration, see [`ConfigFile`].
+    fn merge_from(self, other: ConfigFile) -> Self {
+        Self {
+            resolver: other.resolver.unwrap_or(self.resolver),
+            http: other.http.unwrap_or(self.http),
+            notifications: other.notifications.unwrap_or(self.notifications),
+            log: other.log.unwrap_or(self.log),
+            audit_log: other.audit_log.or(self.audit_log),
+            providers: merge_by_key(self.providers, other.providers, Provider::name),
+            dns: merge_by_key(self.dns, other.dns, dns::Type::provider_name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::secret::Secret;
+
+    fn hetzner_key(config: &Config, name: &str) -> String {
+        config
+            .providers
+            .iter()
+            .find_map(|provider| match provider {
+                Provider::Hetzner(hetzner) if hetzner.name == name => {
+                    Some(hetzner.api_key.as_str().to_string())
+                }
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_files_are_merged_in_order() {
+        let dir = std::env::temp_dir().join("dnrs_test_config_files");
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+
+        let base = dir.join("base.yaml");
+        fs::write(
+            &base,
+            r#"
+resolver:
+  ipv4:
+    - url: https://base.example.com/ipv4
+      type: Raw
+  ipv6: []
+providers:
+  - !Hetzner
+    name: Hetzner1
+    api_key: base-key
+    api_base_url: https://dns.hetzner.com/api/v1
+    requests_per_second: null
+  - !Hetzner
+    name: Hetzner2
+    api_key: base-key-2
+    api_base_url: https://dns.hetzner.com/api/v1
+    requests_per_second: null
+"#,
+        )
+        .unwrap();
+
+        let secret = dir.join("secret.yaml");
+        let key_file = dir.join("hetzner.key");
+        fs::write(&key_file, "secret-key\n").unwrap();
+        fs::write(
+            &secret,
+            format!(
+                r#"
+providers:
+  - !Hetzner
+    name: Hetzner1
+    api_key:
+      file: {}
+    api_base_url: https://dns.hetzner.com/api/v1
+    requests_per_second: null
+"#,
+                key_file.display()
+            ),
+        )
+        .unwrap();
+
+        let override_file = dir.join("override.yaml");
+        fs::write(
+            &override_file,
+            r#"
+providers:
+  - !Hetzner
+    name: Hetzner2
+    api_key: override-key
+    api_base_url: https://staging.example.com/api/v1
+    requests_per_second: 2.0
+"#,
+        )
+        .unwrap();
+
+        let files: Vec<ConfigFile> = [&base, &secret, &override_file]
+            .into_iter()
+            .map(|path| ConfigFile::load(path, Parsing::Strict).unwrap())
+            .collect();
+        let config = Config::default().with_files(files).unwrap();
+
+        assert_eq!(config.resolver.ipv4[0].url, "https://base.example.com/ipv4");
+        assert!(config.resolver.ipv6.is_empty());
+        assert_eq!(hetzner_key(&config, "Hetzner1"), "secret-key");
+        assert_eq!(hetzner_key(&config, "Hetzner2"), "override-key");
+
+        let hetzners: Vec<_> = config
+            .providers
+            .iter()
+            .filter_map(|provider| match provider {
+                Provider::Hetzner(hetzner) => Some(hetzner),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(hetzners.len(), 2);
+        assert!(matches!(hetzners[0].api_key, Secret::File { .. }));
+        assert_eq!(
+            hetzners[1].api_base_url,
+            "https://staging.example.com/api/v1"
+        );
+        assert_eq!(hetzners[1].requests_per_second, Some(2.0));
+        assert_eq!(
+            config.providers.len(),
+            Config::default().providers.len() + 1
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,315 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug, Formatter},
+    fs, io,
+    path::PathBuf,
+};
+
+use lum_libs::serde::{Deserialize, Serialize};
+use lum_log::warn;
+use reqwest::{
+    Certificate,
+    header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT},
+};
+use thiserror::Error;
+
+use crate::{PROGRAM_NAME, PROGRAM_VERSION};
+
+#[derive(Debug, Error)]
+pub enum HttpConfigError {
+    #[error("Invalid HTTP header name: {0}")]
+    InvalidHeaderName(String),
+
+    #[error("Invalid value for HTTP header {0}")]
+    InvalidHeaderValue(String),
+
+    #[error("Failed to read CA certificate {path:?}: {source}")]
+    ReadCaCert { path: PathBuf, source: io::Error },
+
+    #[error("Invalid CA certificate {path:?}: {source}")]
+    InvalidCaCert {
+        path: PathBuf,
+        source: reqwest::Error,
+    },
+
+    #[error("Failed to build HTTP client: {0}")]
+    Reqwest(#[from] reqwest::Error),
+}
+
+/// TLS settings of the HTTP client.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+#[serde(default)]
+pub struct TlsConfig {
+    /// PEM file with root certificates trusted in addition to the system ones, e.g. of a
+    /// corporate proxy or a self-hosted endpoint with a private CA.
+    pub ca_cert_path: Option<PathBuf>,
+
+    /// Accepts any server certificate, even expired or self-signed ones. This disables the
+    /// protection against intercepted connections, credentials can be stolen with it.
+    pub danger_accept_invalid_certs: bool,
+}
+
+impl TlsConfig {
+    /// Reads the certificates of `ca_cert_path`, if set.
+    fn ca_certs(&self) -> Result<Vec<Certificate>, HttpConfigError> {
+        let Some(path) = &self.ca_cert_path else {
+            return Ok(Vec::new());
+        };
+
+        let pem = fs::read(path).map_err(|source| HttpConfigError::ReadCaCert {
+            path: path.clone(),
+            source,
+        })?;
+        Certificate::from_pem_bundle(&pem).map_err(|source| HttpConfigError::InvalidCaCert {
+            path: path.clone(),
+            source,
+        })
+    }
+}
+
+/// Configuration of the HTTP client shared by all providers and resolvers.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use dnrs::config::http::Config;
+///
+/// let config: Config = serde_yaml_ng::from_str(r#"
+///     user_agent: "my-updater/1.0"
+///     headers:
+///       Proxy-Authorization: "Bearer token"
+///     tls:
+///       ca_cert_path: /etc/ssl/corporate-root.pem
+/// "#).unwrap();
+///
+/// assert_eq!(config.user_agent(), "my-updater/1.0");
+/// assert_eq!(config.headers["Proxy-Authorization"], "Bearer token");
+/// assert_eq!(config.tls.ca_cert_path.as_deref(), Some(Path::new("/etc/ssl/corporate-root.pem")));
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+#[serde(default)]
+pub struct Config {
+    /// Overrides the default `dnrs/<version>` User-Agent.
+    pub user_agent: Option<String>,
+
+    /// Headers sent with every request, e.g. to authenticate at a proxy.
+    pub headers: BTreeMap<String, String>,
+
+    pub tls: TlsConfig,
+}
+
+/// Header values often hold credentials for proxies, so only their names are printed.
+impl Debug for Config {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let headers: BTreeMap<&str, &str> = self
+            .headers
+            .keys()
+            .map(|name| (name.as_str(), "***"))
+            .collect();
+
+        f.debug_struct("Config")
+            .field("user_agent", &self.user_agent)
+            .field("headers", &headers)
+            .field("tls", &self.tls)
+            .finish()
+    }
+}
+
+impl Config {
+    /// Returns the configured User-Agent, `dnrs/<version>` by default.
+    pub fn user_agent(&self) -> String {
+        match &self.user_agent {
+            Some(user_agent) => user_agent.clone(),
+            None => format!("{}/{}", PROGRAM_NAME, PROGRAM_VERSION),
+        }
+    }
+
+    /// Builds the shared HTTP client sending the User-Agent and headers with every request and
+    /// verifying servers as configured in `tls`.
+    pub fn build_client(&self) -> Result<reqwest::Client, HttpConfigError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in self.headers.iter() {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| HttpConfigError::InvalidHeaderName(name.clone()))?;
+            let header_value = HeaderValue::from_str(value)
+                .map_err(|_| HttpConfigError::InvalidHeaderValue(name.clone()))?;
+            headers.insert(header_name, header_value);
+        }
+
+        let user_agent = self.user_agent();
+        let user_agent = HeaderValue::from_str(&user_agent)
+            .map_err(|_| HttpConfigError::InvalidHeaderValue(USER_AGENT.to_string()))?;
+
+        let mut builder = reqwest::Client::builder()
+            .user_agent(user_agent)
+            .default_headers(headers);
+        for certificate in self.tls.ca_certs()? {
+            builder = builder.add_root_certificate(certificate);
+        }
+        if self.tls.danger_accept_invalid_certs {
+            warn!(
+                "TLS certificate verification is DISABLED (danger_accept_invalid_certs), connections to providers can be intercepted"
+            );
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, Issuer, KeyPair};
+    use std::net::SocketAddr;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use tokio_native_tls::{TlsAcceptor, native_tls};
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{header, method},
+    };
+
+    #[tokio::test]
+    async fn test_client_sends_default_user_agent() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header(
+                "user-agent",
+                format!("dnrs/{}", PROGRAM_VERSION).as_str(),
+            ))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Config::default().build_client().unwrap();
+        let response = client.get(server.uri()).send().await.unwrap();
+        assert!(response.status().is_success());
+    }
+
+    #[tokio::test]
+    async fn test_client_sends_configured_headers() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("user-agent", "custom/1.0"))
+            .and(header("x-proxy-token", "secret"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = Config {
+            user_agent: Some("custom/1.0".to_string()),
+            headers: BTreeMap::from([("X-Proxy-Token".to_string(), "secret".to_string())]),
+            ..Default::default()
+        };
+        let client = config.build_client().unwrap();
+        let response = client.get(server.uri()).send().await.unwrap();
+        assert!(response.status().is_success());
+    }
+
+    #[test]
+    fn test_invalid_header_name() {
+        let config = Config {
+            headers: BTreeMap::from([("bad header".to_string(), "value".to_string())]),
+            ..Default::default()
+        };
+
+        let result = config.build_client();
+        assert!(
+            matches!(result, Err(HttpConfigError::InvalidHeaderName(name)) if name == "bad header")
+        );
+    }
+
+    /// Generates a CA and a certificate for 127.0.0.1 signed by it, returned as the PEM of the
+    /// CA and the PEM of the certificate and its key.
+    fn private_ca() -> (String, String, String) {
+        let mut ca_params = CertificateParams::new(Vec::new()).unwrap();
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        ca_params
+            .distinguished_name
+            .push(DnType::CommonName, "dnrs test CA");
+        let ca_key = KeyPair::generate().unwrap();
+        let ca_cert = ca_params.self_signed(&ca_key).unwrap();
+        let issuer = Issuer::new(ca_params, ca_key);
+
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(vec!["127.0.0.1".to_string()]).unwrap();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "127.0.0.1");
+        let cert = params.signed_by(&key, &issuer).unwrap();
+
+        (ca_cert.pem(), cert.pem(), key.serialize_pem())
+    }
+
+    /// Serves `200 OK` over TLS with the given certificate until the test ends.
+    async fn tls_server(cert_pem: &str, key_pem: &str) -> SocketAddr {
+        let identity =
+            native_tls::Identity::from_pkcs8(cert_pem.as_bytes(), key_pem.as_bytes()).unwrap();
+        let acceptor = TlsAcceptor::from(native_tls::TlsAcceptor::new(identity).unwrap());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let Ok(mut stream) = acceptor.accept(stream).await else {
+                    continue;
+                };
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .await;
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        address
+    }
+
+    #[tokio::test]
+    async fn test_client_trusts_configured_ca() {
+        let (ca_pem, cert_pem, key_pem) = private_ca();
+        let address = tls_server(&cert_pem, &key_pem).await;
+        let url = format!("https://{}/", address);
+
+        let untrusting = Config::default().build_client().unwrap();
+        assert!(untrusting.get(&url).send().await.is_err());
+
+        let ca_path = std::env::temp_dir().join("dnrs_test_ca.pem");
+        fs::write(&ca_path, ca_pem).unwrap();
+        let config = Config {
+            tls: TlsConfig {
+                ca_cert_path: Some(ca_path.clone()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let client = config.build_client().unwrap();
+        let response = client.get(&url).send().await.unwrap();
+        assert!(response.status().is_success());
+
+        fs::remove_file(ca_path).unwrap();
+    }
+
+    #[test]
+    fn test_missing_ca_cert() {
+        let config = Config {
+            tls: TlsConfig {
+                ca_cert_path: Some(PathBuf::from("/nonexistent/dnrs-ca.pem")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = config.build_client();
+        assert!(matches!(result, Err(HttpConfigError::ReadCaCert { .. })));
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+
+use lum_libs::serde::{Deserialize, Serialize};
+use lum_log::log::LevelFilter;
+
+/// A log level, written in lowercase like in `RUST_LOG`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename_all = "lowercase")]
+pub enum Level {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<Level> for LevelFilter {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Off => LevelFilter::Off,
+            Level::Error => LevelFilter::Error,
+            Level::Warn => LevelFilter::Warn,
+            Level::Info => LevelFilter::Info,
+            Level::Debug => LevelFilter::Debug,
+            Level::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+/// Logging settings, loaded from `log.yaml`. The global level is set with `--verbose` and
+/// `--quiet`.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::config::log::Config;
+/// use lum_log::log::LevelFilter;
+///
+/// let config: Config = serde_yaml_ng::from_str(r#"
+///     module_levels:
+///       reqwest: warn
+///       dnrs::resolver: debug
+/// "#).unwrap();
+///
+/// assert_eq!(
+///     config.module_levels(),
+///     [
+///         ("dnrs::resolver".to_string(), LevelFilter::Debug),
+///         ("reqwest".to_string(), LevelFilter::Warn),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+#[serde(default)]
+pub struct Config {
+    /// Levels of single modules and their submodules, overriding the global level, e.g. to
+    /// silence a noisy dependency while debugging dnrs.
+    pub module_levels: BTreeMap<String, Level>,
+}
+
+impl Config {
+    /// Returns the module levels in the form the logger takes them.
+    pub fn module_levels(&self) -> Vec<(String, LevelFilter)> {
+        self.module_levels
+            .iter()
+            .map(|(module, level)| (module.clone(), (*level).into()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_level_is_rejected() {
+        let result = serde_yaml_ng::from_str::<Config>("module_levels:\n  reqwest: loud\n");
+        assert!(result.is_err());
+
+        let config: Config = serde_yaml_ng::from_str("{}").unwrap();
+        assert!(config.module_levels().is_empty());
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+
+use lum_libs::serde::{Deserialize, Serialize};
+
+use crate::config::secret::Secret;
+
+/// Events of the `auto` and `watch` flow that can be notified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub enum Event {
+    /// The resolved public address differs from the one of the previous run.
+    IpChange,
+    /// At least one record was updated.
+    UpdateSuccess,
+    /// Addresses could not be resolved or a record could not be updated.
+    UpdateFailure,
+}
+
+impl Event {
+    pub const ALL: [Event; 3] = [Event::IpChange, Event::UpdateSuccess, Event::UpdateFailure];
+}
+
+fn all_events() -> Vec<Event> {
+    Event::ALL.to_vec()
+}
+
+/// A webhook receiving notifications as a JSON `POST`, e.g. an ntfy topic or a chat bot relay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct WebhookConfig {
+    /// URL the notifications are posted to. A secret, as some services carry a token in it.
+    pub url: Secret,
+
+    /// Events sent to this webhook, all of them by default.
+    #[serde(default = "all_events")]
+    pub events: Vec<Event>,
+
+    /// Headers sent with every notification, e.g. `Authorization`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, Secret>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub enum NotifierConfig {
+    Webhook(WebhookConfig),
+}
+
+impl NotifierConfig {
+    /// Returns the events this notifier is interested in.
+    pub fn events(&self) -> &[Event] {
+        match self {
+            NotifierConfig::Webhook(config) => &config.events,
+        }
+    }
+
+    pub fn secrets_mut(&mut self) -> Vec<&mut Secret> {
+        match self {
+            NotifierConfig::Webhook(config) => std::iter::once(&mut config.url)
+                .chain(config.headers.values_mut())
+                .collect(),
+        }
+    }
+}
+
+/// Notifications sent from the `auto` and `watch` flow, loaded from `notifications.yaml`.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::config::notification::{Config, Event, NotifierConfig};
+///
+/// let config: Config = serde_yaml_ng::from_str(r#"
+///     notifiers:
+///       - !Webhook
+///         url: https://ntfy.sh/my-dnrs
+///         events: [IpChange, UpdateFailure]
+/// "#).unwrap();
+///
+/// assert_eq!(config.notifiers[0].events(), [Event::IpChange, Event::UpdateFailure]);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+#[serde(default)]
+pub struct Config {
+    pub notifiers: Vec<NotifierConfig>,
+
+    /// URL requested with a `GET` after every fully successful run, for dead man's switch
+    /// monitoring like healthchecks.io. A secret, as such URLs identify the check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heartbeat_url: Option<Secret>,
+
+    /// Also request `<heartbeat_url>/fail` after a run that failed, in part or entirely.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub heartbeat_on_failure: bool,
+}
+
+impl Config {
+    pub fn secrets_mut(&mut self) -> Vec<&mut Secret> {
+        self.notifiers
+            .iter_mut()
+            .flat_map(NotifierConfig::secrets_mut)
+            .chain(self.heartbeat_url.as_mut())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_defaults_to_all_events() {
+        let config: Config = serde_yaml_ng::from_str(
+            r#"
+            notifiers:
+              - !Webhook
+                url:
+                  env: DNRS_WEBHOOK_URL
+            "#,
+        )
+        .unwrap();
+
+        let NotifierConfig::Webhook(webhook) = &config.notifiers[0];
+        assert_eq!(webhook.events, Event::ALL);
+        assert!(matches!(webhook.url, Secret::Env { .. }));
+        assert!(config.heartbeat_url.is_none());
+    }
+}
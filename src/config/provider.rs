@@ -1,6 +1,11 @@
+use std::path::Path;
+
 use lum_libs::serde::{Deserialize, Serialize};
 
-use crate::provider::{hetzner, netcup, nitrado};
+use crate::{
+    config::secret::Secret,
+    provider::{axfr, desec, digitalocean, hetzner, namecheap, netcup, nitrado, njalla, route53},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
@@ -8,4 +13,106 @@ pub enum Provider {
     Nitrado(nitrado::Config),
     Hetzner(hetzner::Config),
     Netcup(netcup::Config),
+    Route53(route53::Config),
+    Desec(desec::Config),
+    DigitalOcean(digitalocean::Config),
+    Namecheap(namecheap::Config),
+    Njalla(njalla::Config),
+    Axfr(axfr::Config),
+}
+
+impl Provider {
+    pub fn name(&self) -> &str {
+        match self {
+            Provider::Nitrado(config) => &config.name,
+            Provider::Hetzner(config) => &config.name,
+            Provider::Netcup(config) => &config.name,
+            Provider::Route53(config) => &config.name,
+            Provider::Desec(config) => &config.name,
+            Provider::DigitalOcean(config) => &config.name,
+            Provider::Namecheap(config) => &config.name,
+            Provider::Njalla(config) => &config.name,
+            Provider::Axfr(config) => &config.name,
+        }
+    }
+
+    /// Returns the type of this provider, one of [`available_providers`].
+    ///
+    /// [`available_providers`]: crate::provider::available_providers
+    pub fn provider_type(&self) -> &'static str {
+        match self {
+            Provider::Nitrado(_) => "Nitrado",
+            Provider::Hetzner(_) => "Hetzner",
+            Provider::Netcup(_) => "Netcup",
+            Provider::Route53(_) => "Route53",
+            Provider::Desec(_) => "Desec",
+            Provider::DigitalOcean(_) => "DigitalOcean",
+            Provider::Namecheap(_) => "Namecheap",
+            Provider::Njalla(_) => "Njalla",
+            Provider::Axfr(_) => "Axfr",
+        }
+    }
+
+    /// Returns the credentials of this provider, for resolving file secrets.
+    pub fn secrets_mut(&mut self) -> Vec<&mut Secret> {
+        match self {
+            Provider::Nitrado(config) => vec![&mut config.api_key],
+            Provider::Hetzner(config) => vec![&mut config.api_key],
+            Provider::Netcup(config) => vec![&mut config.api_key, &mut config.api_password],
+            Provider::Route53(config) => vec![&mut config.secret_access_key],
+            Provider::Desec(config) => vec![&mut config.token],
+            Provider::DigitalOcean(config) => vec![&mut config.token],
+            Provider::Namecheap(config) => vec![&mut config.ddns_password],
+            Provider::Njalla(config) => vec![&mut config.token],
+            Provider::Axfr(config) => config.tsig_secret.iter_mut().collect(),
+        }
+    }
+
+    /// Sets the zone id of `domain`, so that the provider does not look it up. Returns `false` for
+    /// providers that do not address zones by id.
+    pub fn set_zone_id(&mut self, domain: &str, zone_id: &str) -> bool {
+        match self {
+            Provider::Hetzner(config) => {
+                config
+                    .zone_ids
+                    .insert(domain.to_string(), zone_id.to_string());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Sets the URL the API of this provider is reached at. Returns `false` for providers without
+    /// an HTTP API.
+    pub fn set_api_base_url(&mut self, api_base_url: &str) -> bool {
+        let api_base_url = api_base_url.to_string();
+        match self {
+            Provider::Nitrado(config) => config.api_base_url = api_base_url,
+            Provider::Hetzner(config) => config.api_base_url = api_base_url,
+            Provider::Netcup(config) => config.api_base_url = api_base_url,
+            Provider::Route53(config) => config.api_base_url = api_base_url,
+            Provider::Desec(config) => config.api_base_url = api_base_url,
+            Provider::DigitalOcean(config) => config.api_base_url = api_base_url,
+            Provider::Namecheap(config) => config.api_base_url = api_base_url,
+            Provider::Njalla(config) => config.api_base_url = api_base_url,
+            Provider::Axfr(_) => return false,
+        }
+
+        true
+    }
+
+    /// Returns the file this provider was loaded from, `None` for built-in defaults.
+    pub fn source_path(&self) -> Option<&Path> {
+        match self {
+            Provider::Nitrado(config) => config.source_path.as_deref(),
+            Provider::Hetzner(config) => config.source_path.as_deref(),
+            Provider::Netcup(config) => config.source_path.as_deref(),
+            Provider::Route53(config) => config.source_path.as_deref(),
+            Provider::Desec(config) => config.source_path.as_deref(),
+            Provider::DigitalOcean(config) => config.source_path.as_deref(),
+            Provider::Namecheap(config) => config.source_path.as_deref(),
+            Provider::Njalla(config) => config.source_path.as_deref(),
+            Provider::Axfr(config) => config.source_path.as_deref(),
+        }
+    }
 }
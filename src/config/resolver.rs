@@ -1,4 +1,11 @@
-use lum_libs::serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+use lum_libs::serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{MapAccess, SeqAccess, Visitor, value},
+};
+
+use crate::{config::secret::Secret, types::dns::RecordType};
 
 /// Represents the type of an IP resolver.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,6 +15,44 @@ pub enum IpResolverType {
     Raw,
     /// The response is a JSON object, and the IP address is at the specified path.
     JSON(String),
+    /// The response is text, e.g. an HTML page, and the IP address is the first capture group
+    /// of the specified regular expression, or its whole match if it has no group.
+    Regex(String),
+    /// The resolver URL is a DNS-over-HTTPS endpoint queried with the JSON API
+    /// (`application/dns-json`) for a record of the specified name and type, e.g. the `TXT`
+    /// record of `o-o.myaddr.l.google.com` at `https://dns.google/resolve`.
+    Doh {
+        query_name: String,
+        record_type: RecordType,
+    },
+    /// The address is read from the Internet Gateway Device of the local network over UPnP
+    /// (`GetExternalIPAddress`). The gateway is discovered with SSDP, the URL is not used. IGD
+    /// only reports IPv4 addresses.
+    Upnp,
+}
+
+/// Credentials sent to an IP resolver behind authentication.
+///
+/// In YAML, e.g. `auth: !Bearer { token: { env: IP_ECHO_TOKEN } }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub enum ResolverAuth {
+    /// HTTP Basic authentication.
+    Basic { username: String, password: Secret },
+    /// An `Authorization: Bearer` token.
+    Bearer { token: Secret },
+    /// An arbitrary header, e.g. an API key header.
+    Header { name: String, value: Secret },
+}
+
+impl ResolverAuth {
+    pub fn secrets_mut(&mut self) -> Vec<&mut Secret> {
+        match self {
+            ResolverAuth::Basic { password, .. } => vec![password],
+            ResolverAuth::Bearer { token } => vec![token],
+            ResolverAuth::Header { value, .. } => vec![value],
+        }
+    }
 }
 
 /// Configuration for an IP resolver.
@@ -20,6 +65,9 @@ pub enum IpResolverType {
 /// let resolver = IpResolver {
 ///     url: "https://ip.cancom.io".to_string(),
 ///     type_: IpResolverType::Raw,
+///     priority: None,
+///     weight: 1,
+///     auth: None,
 /// };
 ///
 /// assert_eq!(resolver.url, "https://ip.cancom.io");
@@ -28,30 +76,108 @@ pub enum IpResolverType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
 pub struct IpResolver {
+    /// Not used by [`IpResolverType::Upnp`] resolvers, which may leave it out.
+    #[serde(default)]
     pub url: String,
 
     #[serde(rename = "type")]
     pub type_: IpResolverType,
+
+    /// Failover order, lower values are tried first. Resolvers without a priority are tried
+    /// last, in the order they are configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+
+    /// Vote of this resolver when a quorum is configured.
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+
+    /// Credentials sent with every request to this resolver.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<ResolverAuth>,
+}
+
+fn default_weight() -> u32 {
+    1
 }
 
+struct OneOrManyVisitor;
+
+impl<'de> Visitor<'de> for OneOrManyVisitor {
+    type Value = Vec<IpResolver>;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("a resolver or a list of resolvers")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        let resolver = IpResolver::deserialize(value::MapAccessDeserializer::new(map))?;
+        Ok(vec![resolver])
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        Vec::deserialize(value::SeqAccessDeserializer::new(seq))
+    }
+}
+
+/// Accepts a list of resolvers as well as the single resolver of older configs.
+///
+/// Not an untagged enum, as those lose the YAML tags of [`IpResolverType`].
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<IpResolver>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(OneOrManyVisitor)
+}
+
+/// Configuration of the resolvers for the public IPv4 and IPv6 addresses.
+///
+/// Without a `quorum`, the resolvers are tried in order of their priority until one succeeds.
+/// With a `quorum`, all resolvers are queried and an address is only accepted once resolvers
+/// with at least this total weight returned it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
 pub struct Config {
-    pub ipv4: IpResolver,
-    pub ipv6: IpResolver,
+    #[serde(deserialize_with = "one_or_many")]
+    pub ipv4: Vec<IpResolver>,
+
+    #[serde(deserialize_with = "one_or_many")]
+    pub ipv6: Vec<IpResolver>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum: Option<u32>,
+}
+
+impl Config {
+    /// Returns the credentials of all resolvers, for resolving file and environment secrets.
+    pub fn secrets_mut(&mut self) -> Vec<&mut Secret> {
+        self.ipv4
+            .iter_mut()
+            .chain(self.ipv6.iter_mut())
+            .filter_map(|resolver| resolver.auth.as_mut())
+            .flat_map(|auth| auth.secrets_mut())
+            .collect()
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
-            ipv4: IpResolver {
+            ipv4: vec![IpResolver {
                 url: "https://ip.cancom.io".to_string(),
                 type_: IpResolverType::Raw,
-            },
-            ipv6: IpResolver {
+                priority: None,
+                weight: 1,
+                auth: None,
+            }],
+            ipv6: vec![IpResolver {
                 url: "https://ipv6.cancom.io".to_string(),
                 type_: IpResolverType::Raw,
-            },
+                priority: None,
+                weight: 1,
+                auth: None,
+            }],
+            quorum: None,
         }
     }
 }
@@ -89,12 +215,87 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_deserialize_ip_resolver_doh() {
+        let yaml = r#"
+            url: "https://dns.google/resolve"
+            type: !Doh
+              query_name: "o-o.myaddr.l.google.com"
+              record_type: TXT
+        "#;
+        let resolver: IpResolver = serde_yaml_ng::from_str(yaml).unwrap();
+        match resolver.type_ {
+            IpResolverType::Doh {
+                query_name,
+                record_type,
+            } => {
+                assert_eq!(query_name, "o-o.myaddr.l.google.com");
+                assert_eq!(record_type, RecordType::TXT);
+            }
+            _ => panic!("Expected Doh type"),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_ip_resolver_upnp_without_url() {
+        let resolver: IpResolver = serde_yaml_ng::from_str("type: Upnp").unwrap();
+        assert!(matches!(resolver.type_, IpResolverType::Upnp));
+        assert!(resolver.url.is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_ip_resolver_auth() {
+        let yaml = r#"
+            url: "https://ip.internal.example.com"
+            type: Raw
+            auth: !Basic
+              username: dnrs
+              password:
+                file: /run/secrets/ip_echo
+        "#;
+        let resolver: IpResolver = serde_yaml_ng::from_str(yaml).unwrap();
+        match resolver.auth {
+            Some(ResolverAuth::Basic { username, password }) => {
+                assert_eq!(username, "dnrs");
+                assert!(matches!(password, Secret::File { .. }));
+            }
+            _ => panic!("Expected Basic auth"),
+        }
+    }
+
     #[test]
     fn test_deserialize_config_default() {
         let config = Config::default();
         let yaml = serde_yaml_ng::to_string(&config).unwrap();
         let deserialized: Config = serde_yaml_ng::from_str(&yaml).unwrap();
-        assert_eq!(deserialized.ipv4.url, config.ipv4.url);
-        assert_eq!(deserialized.ipv6.url, config.ipv6.url);
+        assert_eq!(deserialized.ipv4[0].url, config.ipv4[0].url);
+        assert_eq!(deserialized.ipv6[0].url, config.ipv6[0].url);
+    }
+
+    #[test]
+    fn test_deserialize_single_and_multiple_resolvers() {
+        let yaml = r#"
+            ipv4:
+              url: "https://ipv4.example.com"
+              type: Raw
+            ipv6:
+              - url: "https://a.example.com"
+                type: !JSON "ip"
+                priority: 2
+                weight: 3
+              - url: "https://b.example.com"
+                type: Raw
+            quorum: 4
+        "#;
+        let config: Config = serde_yaml_ng::from_str(yaml).unwrap();
+
+        assert_eq!(config.ipv4.len(), 1);
+        assert_eq!(config.ipv4[0].priority, None);
+        assert_eq!(config.ipv4[0].weight, 1);
+        assert_eq!(config.ipv6.len(), 2);
+        assert_eq!(config.ipv6[0].priority, Some(2));
+        assert_eq!(config.ipv6[0].weight, 3);
+        assert!(matches!(&config.ipv6[0].type_, IpResolverType::JSON(path) if path == "ip"));
+        assert_eq!(config.quorum, Some(4));
     }
 }
@@ -0,0 +1,197 @@
+use std::{
+    env,
+    fmt::{self, Debug, Formatter},
+    fs, io,
+    path::PathBuf,
+};
+
+use lum_libs::serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error("Failed to read secret file {path:?}: {source}")]
+    Read { path: PathBuf, source: io::Error },
+
+    #[error("Failed to read secret from environment variable {name}: {source}")]
+    Env { name: String, source: env::VarError },
+}
+
+/// A credential in a config, given inline, as a path to a file holding it or as the name of an
+/// environment variable.
+///
+/// In YAML, an inline secret is a plain string, a file secret is `{ file: /run/secrets/name }`
+/// and an environment secret is `{ env: DNRS_TOKEN }`. File and environment secrets are read by
+/// [`Secret::resolve`] when the config is loaded. The value read is never serialized, so writing
+/// a loaded config back keeps the reference.
+/// The [`Debug`] output redacts the value, so configs can be logged safely.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::config::secret::Secret;
+///
+/// let secret: Secret = "my_api_key".into();
+/// assert_eq!(secret.as_str(), "my_api_key");
+/// assert_eq!(format!("{:?}", secret), "\"***\"");
+/// ```
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", untagged)]
+pub enum Secret {
+    Inline(String),
+    File {
+        file: PathBuf,
+        #[serde(skip)]
+        value: String,
+    },
+    Env {
+        env: String,
+        #[serde(skip)]
+        value: String,
+    },
+}
+
+impl Secret {
+    /// Returns the secret value. File and environment secrets are empty until resolved.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Secret::Inline(value) => value,
+            Secret::File { value, .. } => value,
+            Secret::Env { value, .. } => value,
+        }
+    }
+
+    /// Reads the secret from its file or environment variable, trimming surrounding whitespace.
+    /// Inline secrets are left unchanged.
+    pub fn resolve(&mut self) -> Result<(), SecretError> {
+        match self {
+            Secret::Inline(_) => {}
+            Secret::File { file, value } => {
+                let content = fs::read_to_string(&*file).map_err(|source| SecretError::Read {
+                    path: file.clone(),
+                    source,
+                })?;
+                *value = content.trim().to_string();
+            }
+            Secret::Env { env: name, value } => {
+                let content = env::var(&*name).map_err(|source| SecretError::Env {
+                    name: name.clone(),
+                    source,
+                })?;
+                *value = content.trim().to_string();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Secret::Inline(_) => write!(f, "{:?}", "***"),
+            Secret::File { file, .. } => f
+                .debug_struct("File")
+                .field("file", file)
+                .field("value", &"***")
+                .finish(),
+            Secret::Env { env, .. } => f
+                .debug_struct("Env")
+                .field("env", env)
+                .field("value", &"***")
+                .finish(),
+        }
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Secret::Inline(value.to_string())
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret::Inline(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_redacts_file_secret_value() {
+        let secret = Secret::File {
+            file: PathBuf::from("/run/secrets/api_key"),
+            value: "file-secret-value".to_string(),
+        };
+
+        let debug = format!("{:?}", secret);
+        assert!(!debug.contains("file-secret-value"));
+        assert!(debug.contains("/run/secrets/api_key"));
+    }
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_inline_secret() {
+        let mut secret: Secret = serde_yaml_ng::from_str("my_api_key").unwrap();
+        secret.resolve().unwrap();
+
+        assert_eq!(secret, Secret::Inline("my_api_key".to_string()));
+        assert_eq!(serde_yaml_ng::to_string(&secret).unwrap(), "my_api_key\n");
+    }
+
+    #[test]
+    fn test_file_secret_is_read_and_not_written_back() {
+        let path = temp_file("dnrs_test_secret", "file_api_key\n");
+
+        let yaml = format!("file: {}", path.display());
+        let mut secret: Secret = serde_yaml_ng::from_str(&yaml).unwrap();
+        assert_eq!(secret.as_str(), "");
+
+        secret.resolve().unwrap();
+        assert_eq!(secret.as_str(), "file_api_key");
+
+        let written = serde_yaml_ng::to_string(&secret).unwrap();
+        assert!(!written.contains("file_api_key"));
+        assert_eq!(written.trim(), yaml);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_env_secret_is_read_and_not_written_back() {
+        // SAFETY: the variable is unique to this test, no other thread reads or writes it
+        unsafe { env::set_var("DNRS_TEST_ENV_SECRET", "env_api_key\n") };
+
+        let mut secret: Secret = serde_yaml_ng::from_str("env: DNRS_TEST_ENV_SECRET").unwrap();
+        secret.resolve().unwrap();
+        assert_eq!(secret.as_str(), "env_api_key");
+        assert!(!format!("{:?}", secret).contains("env_api_key"));
+        assert_eq!(
+            serde_yaml_ng::to_string(&secret).unwrap().trim(),
+            "env: DNRS_TEST_ENV_SECRET"
+        );
+
+        let mut missing: Secret = serde_yaml_ng::from_str("env: DNRS_TEST_UNSET_SECRET").unwrap();
+        assert!(matches!(missing.resolve(), Err(SecretError::Env { .. })));
+    }
+
+    #[test]
+    fn test_missing_secret_file() {
+        let path = std::env::temp_dir().join("dnrs_test_missing_secret");
+        let mut secret = Secret::File {
+            file: path.clone(),
+            value: String::new(),
+        };
+
+        let error = secret.resolve().unwrap_err();
+        assert!(matches!(error, SecretError::Read { path: error_path, .. } if error_path == path));
+    }
+}
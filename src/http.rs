@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use reqwest::{
+    Method, StatusCode,
+    header::{HeaderMap, HeaderName, HeaderValue},
+};
+
+use crate::{provider, retry::SendWithRetry};
+
+/// An HTTP request as providers send it, with the whole body in memory.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        HttpRequest {
+            method,
+            url: url.into(),
+            headers: HeaderMap::new(),
+            body: None,
+        }
+    }
+
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::new(Method::GET, url)
+    }
+
+    pub fn post(url: impl Into<String>) -> Self {
+        Self::new(Method::POST, url)
+    }
+
+    /// Sets the header `name`, replacing one of the same name.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Adds `headers`, replacing headers of the same name.
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+}
+
+/// The response to an [`HttpRequest`], with the body read.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        HttpResponse {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+
+    /// Returns the body for error messages, see [`provider::truncate_error_body`].
+    pub fn error_body(&self) -> String {
+        provider::truncate_error_body(&self.body)
+    }
+}
+
+/// Sends HTTP requests. Implemented by [`reqwest::Client`], tests can implement it to answer
+/// requests with canned responses instead of a server.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::http::{HttpClient, HttpRequest};
+///
+/// async fn zones(http: &dyn HttpClient) -> reqwest::Result<String> {
+///     let response = http.send(HttpRequest::get("https://dns.example.com/zones")).await?;
+///     Ok(response.body)
+/// }
+///
+/// let _ = zones(&reqwest::Client::new());
+/// ```
+#[async_trait]
+pub trait HttpClient: Send + Sync {
+    async fn send(&self, request: HttpRequest) -> reqwest::Result<HttpResponse>;
+}
+
+/// Sends the request with the default [`RetryPolicy`](crate::retry::RetryPolicy).
+#[async_trait]
+impl HttpClient for reqwest::Client {
+    async fn send(&self, request: HttpRequest) -> reqwest::Result<HttpResponse> {
+        let mut builder = self
+            .request(request.method, &request.url)
+            .headers(request.headers);
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        let response = builder.send_with_retry().await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.text().await?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// An [`HttpClient`] answering requests with canned responses, in order, and keeping the
+/// requests it was sent.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub(crate) struct FakeHttpClient {
+    responses: std::sync::Mutex<std::collections::VecDeque<HttpResponse>>,
+    requests: std::sync::Mutex<Vec<HttpRequest>>,
+}
+
+#[cfg(test)]
+impl FakeHttpClient {
+    pub(crate) fn new(responses: impl IntoIterator<Item = HttpResponse>) -> Self {
+        FakeHttpClient {
+            responses: std::sync::Mutex::new(responses.into_iter().collect()),
+            requests: Default::default(),
+        }
+    }
+
+    /// Returns the requests sent so far.
+    pub(crate) fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl HttpClient for FakeHttpClient {
+    /// Returns the next canned response, panicking if there is none left.
+    async fn send(&self, request: HttpRequest) -> reqwest::Result<HttpResponse> {
+        let response = self
+            .responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| panic!("No response left for {} {}", request.method, request.url));
+        self.requests.lock().unwrap().push(request);
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_string, header, method, path},
+    };
+
+    #[tokio::test]
+    async fn test_reqwest_client_sends_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/records"))
+            .and(header("Auth-API-Token", "test_key"))
+            .and(body_string("{}"))
+            .respond_with(ResponseTemplate::new(201).set_body_string("created"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut headers = HeaderMap::new();
+        headers.insert("Auth-API-Token", "test_key".parse().unwrap());
+        let request = HttpRequest::post(format!("{}/records", server.uri()))
+            .headers(headers)
+            .body("{}");
+
+        let response = reqwest::Client::new().send(request).await.unwrap();
+        assert_eq!(response.status, StatusCode::CREATED);
+        assert_eq!(response.body, "created");
+    }
+}
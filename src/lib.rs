@@ -1,16 +1,32 @@
-use clap::Parser;
 use lum_log::debug;
-use std::time::Instant;
+use std::{path::Path, time::Instant};
 use thiserror::Error;
 
-use crate::cli::{Command, ExecutableCommand, command::Input};
+use crate::{
+    cli::{Command, ExecutableCommand, command::Input},
+    update::DomainFailure,
+};
 
+pub mod acme;
+pub mod audit;
 pub mod cli;
 pub mod config;
+pub mod http;
+pub mod lock;
 pub mod logger;
+pub mod metrics;
+pub mod notification;
+pub mod propagation;
 pub mod provider;
+pub mod rate_limit;
 pub mod resolver;
+pub mod retry;
+pub mod state;
+pub mod timing;
+pub mod totp;
 pub mod types;
+pub mod update;
+pub mod zone;
 
 #[cfg(test)]
 mod cli_tests;
@@ -24,18 +40,106 @@ pub const PROGRAM_VERSION: &str = env!("CARGO_PKG_VERSION");
 #[derive(Debug, Error)]
 pub enum RuntimeError {
     #[error("Error while executing command: {0}")]
-    Command(#[from] cli::command::Error),
+    Command(cli::command::Error),
+
+    #[error(
+        "Updated {succeeded} domains, but failed to update {}: {}",
+        .failed.len(),
+        cli::auto::join_failures(.failed)
+    )]
+    PartialFailure {
+        succeeded: usize,
+        failed: Vec<DomainFailure>,
+    },
+}
+
+impl RuntimeError {
+    /// The process exit code for this error: 2 if only some domains failed to update,
+    /// otherwise 1.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            RuntimeError::PartialFailure { .. } => 2,
+            RuntimeError::Command(_) => 1,
+        }
+    }
 }
 
-pub async fn run(config: Config) -> Result<(), RuntimeError> {
+impl From<cli::command::Error> for RuntimeError {
+    /// Partially failed updates of `auto` and `watch --once` alike become
+    /// [`RuntimeError::PartialFailure`].
+    fn from(error: cli::command::Error) -> Self {
+        match error {
+            cli::command::Error::Auto(cli::auto::Error::UpdateFailed {
+                succeeded,
+                failures,
+            })
+            | cli::command::Error::Watch(cli::watch::Error::Auto(
+                cli::auto::Error::UpdateFailed {
+                    succeeded,
+                    failures,
+                },
+            )) if succeeded > 0 => RuntimeError::PartialFailure {
+                succeeded,
+                failed: failures,
+            },
+            error => RuntimeError::Command(error),
+        }
+    }
+}
+
+/// Executes `command` and returns the process exit code, which is 0 unless `auto` is run with
+/// `--exit-code-on-change` and changed a record. `state_path` is where the state between runs is
+/// persisted, if anywhere.
+pub async fn run(
+    command: &Command<'_>,
+    config: Config,
+    state_path: Option<&Path>,
+) -> Result<i32, RuntimeError> {
     let start = Instant::now();
 
-    let command = Command::parse();
-    let input = Input { config: &config };
-    command.execute(&input).await?;
+    let input = Input {
+        config: &config,
+        state_path,
+    };
+    let exit_code = command.execute(&input).await?;
 
     let elapsed = start.elapsed();
     debug!("Done in {}ms", elapsed.as_millis());
 
-    Ok(())
+    Ok(exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{auto, command, watch};
+
+    fn update_failed(succeeded: usize) -> auto::Error {
+        auto::Error::UpdateFailed {
+            succeeded,
+            failures: vec![DomainFailure {
+                provider_name: "Nitrado1".to_string(),
+                domain: "b.com".to_string(),
+                error: "Domain 'b.com' not found".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_watch_once_partial_failure_exits_like_auto() {
+        for succeeded in [0, 1] {
+            let auto = RuntimeError::from(command::Error::Auto(update_failed(succeeded)));
+            let watch = RuntimeError::from(command::Error::Watch(watch::Error::Auto(
+                update_failed(succeeded),
+            )));
+            assert_eq!(watch.exit_code(), auto.exit_code());
+        }
+
+        let watch = RuntimeError::from(command::Error::Watch(watch::Error::Auto(update_failed(1))));
+        assert!(matches!(
+            watch,
+            RuntimeError::PartialFailure { succeeded: 1, .. }
+        ));
+        assert_eq!(watch.exit_code(), 2);
+    }
 }
@@ -0,0 +1,107 @@
+use std::{
+    ffi::OsString,
+    fs::{File, OpenOptions, TryLockError},
+    io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+
+/// How long [`FileLock::acquire`] waits for another dnrs instance by default.
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Error)]
+pub enum LockError {
+    #[error("Failed to open lock file {0:?}: {1}")]
+    Io(PathBuf, io::Error),
+
+    #[error("Another dnrs instance is running, lock file {0:?} is still held")]
+    Contended(PathBuf),
+}
+
+/// Returns the lock file guarding `path`, `path` with `.lock` appended.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::lock::lock_path;
+/// use std::path::Path;
+///
+/// assert_eq!(lock_path(Path::new("/etc/dnrs/state.json")), Path::new("/etc/dnrs/state.json.lock"));
+/// ```
+pub fn lock_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+    file_name.push(".lock");
+    path.with_file_name(file_name)
+}
+
+/// An exclusive advisory lock on a file, released when dropped.
+///
+/// The lock only serializes dnrs instances with each other, other programs can still write the
+/// guarded files.
+#[derive(Debug)]
+pub struct FileLock {
+    file: File,
+}
+
+impl FileLock {
+    /// Locks the file at `path`, creating it if necessary.
+    ///
+    /// If another process holds the lock, this retries until `timeout` has passed and then
+    /// returns [`LockError::Contended`].
+    pub fn acquire(path: &Path, timeout: Duration) -> Result<FileLock, LockError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .map_err(|e| LockError::Io(path.to_path_buf(), e))?;
+
+        let start = Instant::now();
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(FileLock { file }),
+                Err(TryLockError::WouldBlock) if start.elapsed() < timeout => {
+                    thread::sleep(RETRY_INTERVAL);
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(LockError::Contended(path.to_path_buf()));
+                }
+                Err(TryLockError::Error(e)) => return Err(LockError::Io(path.to_path_buf(), e)),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // Closing the file releases the lock as well, unlocking just makes it explicit
+        let _ = self.file.unlock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_second_lock_is_contended_until_released() {
+        let dir = std::env::temp_dir().join("dnrs_test_lock_contended");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.lock");
+
+        let lock = FileLock::acquire(&path, Duration::ZERO).unwrap();
+        let result = FileLock::acquire(&path, Duration::from_millis(100));
+        assert!(matches!(result, Err(LockError::Contended(_))));
+
+        drop(lock);
+        assert!(FileLock::acquire(&path, Duration::ZERO).is_ok());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -5,7 +5,14 @@ use lum_log::{
     log::{LevelFilter, SetLoggerError},
 };
 
-pub fn setup_logger() -> Result<(), SetLoggerError> {
+/// Sets up the global logger, logging messages at `min_log_level` and above. Modules listed in
+/// `module_levels` log at their own level instead, see [`log::Config`].
+///
+/// [`log::Config`]: crate::config::log::Config
+pub fn setup_logger(
+    min_log_level: LevelFilter,
+    module_levels: &[(String, LevelFilter)],
+) -> Result<(), SetLoggerError> {
     let mut colors = HashMap::new();
     colors.insert(LevelFilter::Info, "Green".into());
     colors.insert(LevelFilter::Error, "Red".into());
@@ -15,15 +22,13 @@ pub fn setup_logger() -> Result<(), SetLoggerError> {
 
     let config = Config {
         colors,
-        min_log_level: LevelFilter::Info,
+        min_log_level,
     };
 
-    let module_levels = [];
-
     Builder::new(defaults::format())
         .config(&config)
         .chain(io::stdout())
         .is_debug_build(cfg!(debug_assertions))
-        .module_levels(&module_levels)
+        .module_levels(module_levels)
         .apply()
 }
@@ -1,9 +1,17 @@
 use std::fmt::{self, Debug};
-use std::fs;
 
-use dnrs::{Config, RuntimeError, run, setup_logger};
+use clap::Parser;
+use std::{
+    path::{Path, PathBuf},
+    process,
+};
+
+use dnrs::{
+    Config, RuntimeError, cli::Command, config::file::ConfigFile, run, setup_logger,
+    state::STATE_FILE_NAME,
+};
 use lum_config::{ConfigPathError, EnvironmentConfigParseError, FileConfigParseError};
-use lum_log::{info, log::SetLoggerError};
+use lum_log::log::SetLoggerError;
 use thiserror::Error;
 
 /*
@@ -64,9 +72,6 @@ enum Error {
     #[error("Unable to determine config directory")]
     NoConfigDirectory,
 
-    #[error("Config path exists but is not a directory")]
-    ConfigIsNotDirectory,
-
     #[error("Runtime error: {0}")]
     Runtime(#[from] RuntimeError),
 }
@@ -78,41 +83,73 @@ impl Debug for Error {
     }
 }
 
-fn read_config() -> Result<Config, Error> {
+fn default_config_dir() -> Result<PathBuf, Error> {
     let config_dir = dirs::config_dir()
         .ok_or(Error::NoConfigDirectory)?
         .join(APP_NAME);
 
-    if config_dir.exists() && !config_dir.is_dir() {
-        return Err(Error::ConfigIsNotDirectory);
-    }
-
-    let config = if config_dir.exists() {
-        Config::load_from_directory(&config_dir)?
-    } else {
-        info!("Config directory does not exist, creating default structure...");
-        fs::create_dir_all(&config_dir)?;
-
-        Config::create_example_structure(&config_dir)?;
-        info!(
-            "Created default config structure at: {}",
-            config_dir.display()
-        );
-        info!("Please configure your providers and DNS settings, then run again.");
-
-        //TODO: Handle first run better (signal to caller that config was created)
-        Config::default()
-    };
+    Ok(config_dir)
+}
 
+fn read_config(command: &Command, config_dir: &Path) -> Result<Config, Error> {
+    let config = Config::load_or_create(config_dir, command.first_run(), command.parsing())?;
     Ok(config)
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
-    setup_logger()?;
+    let command = Command::parse();
+    let config_dir = match &command.config_dir {
+        Some(config_dir) => config_dir.clone(),
+        None => default_config_dir()?,
+    };
+
+    let config_files = command
+        .config_file
+        .iter()
+        .map(|path| ConfigFile::load(path, command.parsing()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // A single config file holds the log settings too, so it is read before the logger is set up
+    let single_file_config = config_dir
+        .is_file()
+        .then(|| Config::load_from_file(&config_dir, command.parsing()))
+        .transpose()?;
+
+    // Set up before the rest of the config is loaded, so that loading it is logged
+    let log_config = match (
+        ConfigFile::last_log_config(&config_files),
+        &single_file_config,
+    ) {
+        (Some(log_config), _) => log_config.clone(),
+        (None, Some(config)) => config.log.clone(),
+        (None, None) => Config::load_log_config(&config_dir, command.parsing())?,
+    };
+    setup_logger(command.log_level(), &log_config.module_levels())?;
 
-    let config = read_config()?;
-    run(config).await?;
+    let config = match single_file_config {
+        Some(config) => config,
+        None => read_config(&command, &config_dir)?,
+    };
+    let config = config.with_files(config_files)?;
 
-    Ok(())
+    // The state is only persisted if the config directory exists, see --no-write-config. It is
+    // kept next to a single config file.
+    let state_dir = if config_dir.is_file() {
+        config_dir.parent()
+    } else {
+        config_dir.is_dir().then_some(config_dir.as_path())
+    };
+    let state_path = state_dir.map(|state_dir| state_dir.join(STATE_FILE_NAME));
+    match run(&command, config, state_path.as_deref()).await {
+        Ok(0) => Ok(()),
+        Ok(exit_code) => process::exit(exit_code),
+        Err(e) => {
+            // Partial failures get their own exit code, so they are reported here instead of
+            // being returned from main()
+            let exit_code = e.exit_code();
+            eprintln!("Error: {}", Error::from(e));
+            process::exit(exit_code);
+        }
+    }
 }
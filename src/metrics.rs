@@ -0,0 +1,230 @@
+use std::{collections::BTreeMap, fmt::Write as _, fs, io, path::Path};
+
+/// Per-provider counters of a single run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProviderMetrics {
+    pub records_updated: u64,
+    pub update_errors: u64,
+}
+
+/// Metrics of an update run in the Prometheus text format, for the node_exporter textfile
+/// collector.
+///
+/// Counters describe the run that wrote the file. The last success timestamp is carried over
+/// from the previous file if the run had errors, see [`Metrics::write`].
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::metrics::Metrics;
+///
+/// let mut metrics = Metrics::default();
+/// metrics.record_success("Hetzner1", 2);
+/// metrics.record_error("Netcup1");
+///
+/// let text = metrics.render(None);
+/// assert!(text.contains("dnrs_records_updated_total{provider=\"Hetzner1\"} 2"));
+/// assert!(text.contains("dnrs_update_errors_total{provider=\"Netcup1\"} 1"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metrics {
+    pub providers: BTreeMap<String, ProviderMetrics>,
+}
+
+pub const LAST_SUCCESS_TIMESTAMP: &str = "dnrs_last_success_timestamp";
+pub const RECORDS_UPDATED_TOTAL: &str = "dnrs_records_updated_total";
+pub const UPDATE_ERRORS_TOTAL: &str = "dnrs_update_errors_total";
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl Metrics {
+    pub fn record_success(&mut self, provider_name: &str, records: usize) {
+        self.providers
+            .entry(provider_name.to_string())
+            .or_default()
+            .records_updated += records as u64;
+    }
+
+    pub fn record_error(&mut self, provider_name: &str) {
+        self.providers
+            .entry(provider_name.to_string())
+            .or_default()
+            .update_errors += 1;
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.providers
+            .values()
+            .any(|provider| provider.update_errors > 0)
+    }
+
+    /// Renders the metrics. The timestamp line is omitted if there never was a successful run.
+    pub fn render(&self, last_success_timestamp: Option<i64>) -> String {
+        let mut text = String::new();
+
+        if let Some(timestamp) = last_success_timestamp {
+            let _ = writeln!(
+                text,
+                "# HELP {} Unix time of the last run without update errors.",
+                LAST_SUCCESS_TIMESTAMP
+            );
+            let _ = writeln!(text, "# TYPE {} gauge", LAST_SUCCESS_TIMESTAMP);
+            let _ = writeln!(text, "{} {}", LAST_SUCCESS_TIMESTAMP, timestamp);
+        }
+
+        self.write_counter(
+            &mut text,
+            RECORDS_UPDATED_TOTAL,
+            "Records updated by the last run.",
+            |provider| provider.records_updated,
+        );
+        self.write_counter(
+            &mut text,
+            UPDATE_ERRORS_TOTAL,
+            "Domain updates that failed in the last run.",
+            |provider| provider.update_errors,
+        );
+
+        text
+    }
+
+    fn write_counter(
+        &self,
+        text: &mut String,
+        name: &str,
+        help: &str,
+        value: impl Fn(&ProviderMetrics) -> u64,
+    ) {
+        let _ = writeln!(text, "# HELP {} {}", name, help);
+        let _ = writeln!(text, "# TYPE {} counter", name);
+        for (provider_name, provider) in self.providers.iter() {
+            let _ = writeln!(
+                text,
+                "{}{{provider=\"{}\"}} {}",
+                name,
+                escape_label_value(provider_name),
+                value(provider)
+            );
+        }
+    }
+
+    /// Writes the metrics to `path` atomically via a temporary file and a rename.
+    ///
+    /// If this run had errors, the last success timestamp of the existing file is kept,
+    /// otherwise `now` is written.
+    pub fn write(&self, path: &Path, now: i64) -> io::Result<()> {
+        let last_success_timestamp = if self.has_errors() {
+            read_last_success_timestamp(path)
+        } else {
+            Some(now)
+        };
+
+        let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(".tmp");
+        let temp_path = path.with_file_name(temp_name);
+
+        fs::write(&temp_path, self.render(last_success_timestamp))?;
+        fs::rename(&temp_path, path)
+    }
+}
+
+/// Reads the last success timestamp from a previously written metrics file.
+pub fn read_last_success_timestamp(path: &Path) -> Option<i64> {
+    let content = fs::read_to_string(path).ok()?;
+    content.lines().find_map(|line| {
+        let value = line
+            .strip_prefix(LAST_SUCCESS_TIMESTAMP)?
+            .strip_prefix(' ')?;
+        value.trim().parse().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_metric_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == ':')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+    }
+
+    /// Checks a sample line of the form `name{label="value",...} value`.
+    fn is_valid_sample(line: &str) -> bool {
+        let Some((series, value)) = line.rsplit_once(' ') else {
+            return false;
+        };
+        if value.parse::<f64>().is_err() {
+            return false;
+        }
+
+        match series.split_once('{') {
+            None => is_metric_name(series),
+            Some((name, labels)) => {
+                let Some(labels) = labels.strip_suffix('}') else {
+                    return false;
+                };
+                is_metric_name(name)
+                    && labels.split(',').all(|label| {
+                        label.split_once('=').is_some_and(|(key, value)| {
+                            is_metric_name(key)
+                                && value.len() >= 2
+                                && value.starts_with('"')
+                                && value.ends_with('"')
+                        })
+                    })
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_emits_valid_metric_lines() {
+        let mut metrics = Metrics::default();
+        metrics.record_success("Hetzner1", 3);
+        metrics.record_success("Netcup \"main\"", 1);
+        metrics.record_error("Netcup \"main\"");
+
+        let text = metrics.render(Some(1_700_000_000));
+
+        let samples: Vec<&str> = text.lines().filter(|line| !line.starts_with('#')).collect();
+        assert_eq!(samples.len(), 5);
+        for line in text.lines() {
+            if let Some(comment) = line.strip_prefix("# ") {
+                assert!(comment.starts_with("HELP ") || comment.starts_with("TYPE "));
+            } else {
+                assert!(is_valid_sample(line), "invalid metric line: {}", line);
+            }
+        }
+        assert!(text.contains("dnrs_last_success_timestamp 1700000000"));
+        assert!(text.contains("dnrs_update_errors_total{provider=\"Netcup \\\"main\\\"\"} 1"));
+    }
+
+    #[test]
+    fn test_write_keeps_last_success_on_errors() {
+        let dir = std::env::temp_dir().join("dnrs_test_metrics");
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("dnrs.prom");
+
+        let mut metrics = Metrics::default();
+        metrics.record_success("Hetzner1", 1);
+        metrics.write(&path, 100).unwrap();
+        assert_eq!(read_last_success_timestamp(&path), Some(100));
+
+        metrics.record_error("Hetzner1");
+        metrics.write(&path, 200).unwrap();
+        assert_eq!(read_last_success_timestamp(&path), Some(100));
+        assert!(!dir.join("dnrs.prom.tmp").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
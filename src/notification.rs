@@ -0,0 +1,333 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use async_trait::async_trait;
+use lum_libs::{serde::Serialize, serde_json};
+use lum_log::{debug, warn};
+use reqwest::header::CONTENT_TYPE;
+use thiserror::Error;
+
+use crate::config::notification::{self, Event, NotifierConfig, WebhookConfig};
+
+#[derive(Debug, Error)]
+pub enum NotifyError {
+    #[error("HTTP request failed: {0}")]
+    Reqwest(#[from] reqwest::Error),
+
+    #[error("Failed to serialize notification: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Notification was rejected with HTTP status {0}")]
+    Unsuccessful(u16),
+}
+
+/// Something worth telling the user about during unattended operation.
+///
+/// Serialized with the name of its [`Event`] in `event`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "lum_libs::serde", tag = "event")]
+pub enum Notification {
+    IpChange {
+        previous_ipv4: Option<Ipv4Addr>,
+        ipv4: Option<Ipv4Addr>,
+        previous_ipv6: Option<Ipv6Addr>,
+        ipv6: Option<Ipv6Addr>,
+    },
+    UpdateSuccess {
+        records: Vec<String>,
+    },
+    UpdateFailure {
+        errors: Vec<String>,
+    },
+}
+
+impl Notification {
+    pub fn event(&self) -> Event {
+        match self {
+            Notification::IpChange { .. } => Event::IpChange,
+            Notification::UpdateSuccess { .. } => Event::UpdateSuccess,
+            Notification::UpdateFailure { .. } => Event::UpdateFailure,
+        }
+    }
+
+    /// Returns a short human-readable summary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::notification::Notification;
+    ///
+    /// let notification = Notification::IpChange {
+    ///     previous_ipv4: Some("1.2.3.4".parse().unwrap()),
+    ///     ipv4: Some("5.6.7.8".parse().unwrap()),
+    ///     previous_ipv6: None,
+    ///     ipv6: None,
+    /// };
+    /// assert_eq!(notification.message(), "Public address changed: IPv4 1.2.3.4 -> 5.6.7.8");
+    /// ```
+    pub fn message(&self) -> String {
+        match self {
+            Notification::IpChange {
+                previous_ipv4,
+                ipv4,
+                previous_ipv6,
+                ipv6,
+            } => {
+                let mut changes = Vec::new();
+                if previous_ipv4 != ipv4 {
+                    changes.push(format!(
+                        "IPv4 {} -> {}",
+                        display(previous_ipv4),
+                        display(ipv4)
+                    ));
+                }
+                if previous_ipv6 != ipv6 {
+                    changes.push(format!(
+                        "IPv6 {} -> {}",
+                        display(previous_ipv6),
+                        display(ipv6)
+                    ));
+                }
+                format!("Public address changed: {}", changes.join(", "))
+            }
+            Notification::UpdateSuccess { records } => {
+                format!("Updated {} records", records.len())
+            }
+            Notification::UpdateFailure { errors } => {
+                format!("Update failed: {}", errors.join("; "))
+            }
+        }
+    }
+}
+
+fn display<T: ToString>(address: &Option<T>) -> String {
+    address
+        .as_ref()
+        .map_or_else(|| "none".to_string(), ToString::to_string)
+}
+
+/// Delivers notifications to one destination.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Returns the events this notifier should be called for.
+    fn events(&self) -> &[Event];
+
+    async fn notify(
+        &self,
+        reqwest: &reqwest::Client,
+        notification: &Notification,
+    ) -> Result<(), NotifyError>;
+}
+
+/// The JSON body posted by [`WebhookNotifier`].
+#[derive(Debug, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+struct WebhookPayload<'notification> {
+    #[serde(flatten)]
+    notification: &'notification Notification,
+    message: String,
+}
+
+/// Posts notifications as JSON to a URL.
+pub struct WebhookNotifier<'config> {
+    pub config: &'config WebhookConfig,
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier<'_> {
+    fn events(&self) -> &[Event] {
+        &self.config.events
+    }
+
+    async fn notify(
+        &self,
+        reqwest: &reqwest::Client,
+        notification: &Notification,
+    ) -> Result<(), NotifyError> {
+        let payload = WebhookPayload {
+            notification,
+            message: notification.message(),
+        };
+
+        let body = serde_json::to_string(&payload)?;
+
+        let mut request = reqwest
+            .post(self.config.url.as_str())
+            .header(CONTENT_TYPE, "application/json")
+            .body(body);
+        for (name, value) in self.config.headers.iter() {
+            request = request.header(name, value.as_str());
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(NotifyError::Unsuccessful(response.status().as_u16()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates the notifiers of the configuration.
+pub fn notifiers(config: &notification::Config) -> Vec<Box<dyn Notifier + '_>> {
+    config
+        .notifiers
+        .iter()
+        .map(|notifier_config| match notifier_config {
+            NotifierConfig::Webhook(config) => {
+                Box::new(WebhookNotifier { config }) as Box<dyn Notifier>
+            }
+        })
+        .collect()
+}
+
+/// Sends `notification` to every notifier interested in its event.
+///
+/// Failures are logged, a notification that can not be delivered never fails the run.
+pub async fn notify_all(
+    notifiers: &[Box<dyn Notifier + '_>],
+    reqwest: &reqwest::Client,
+    notification: &Notification,
+) {
+    let event = notification.event();
+    for notifier in notifiers
+        .iter()
+        .filter(|notifier| notifier.events().contains(&event))
+    {
+        match notifier.notify(reqwest, notification).await {
+            Ok(()) => debug!("Sent {:?} notification", event),
+            Err(e) => warn!("Failed to send {:?} notification: {}", event, e),
+        }
+    }
+}
+
+/// Returns the URL signalling a failed run to a heartbeat monitor, `<url>/fail`.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::notification::heartbeat_failure_url;
+///
+/// assert_eq!(heartbeat_failure_url("https://hc-ping.com/abc/"), "https://hc-ping.com/abc/fail");
+/// ```
+pub fn heartbeat_failure_url(url: &str) -> String {
+    format!("{}/fail", url.trim_end_matches('/'))
+}
+
+/// Pings the configured heartbeat URL after a run. Failed runs are only reported if
+/// `heartbeat_on_failure` is set, otherwise the missing ping lets the monitor alert.
+///
+/// Failures are logged, a heartbeat that can not be sent never fails the run.
+pub async fn heartbeat(config: &notification::Config, reqwest: &reqwest::Client, success: bool) {
+    let Some(url) = &config.heartbeat_url else {
+        return;
+    };
+
+    let url = match (success, config.heartbeat_on_failure) {
+        (true, _) => url.as_str().to_string(),
+        (false, true) => heartbeat_failure_url(url.as_str()),
+        (false, false) => return,
+    };
+
+    match reqwest.get(&url).send().await {
+        Ok(response) if response.status().is_success() => debug!("Sent heartbeat"),
+        Ok(response) => warn!(
+            "Heartbeat was rejected with HTTP status {}",
+            response.status().as_u16()
+        ),
+        Err(e) => warn!("Failed to send heartbeat: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lum_libs::serde_json::json;
+    use std::collections::BTreeMap;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_json, header, method, path},
+    };
+
+    #[tokio::test]
+    async fn test_webhook_only_receives_configured_events() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("authorization", "Bearer token"))
+            .and(body_json(json!({
+                "event": "UpdateFailure",
+                "errors": ["Hetzner1/example.com: timeout"],
+                "message": "Update failed: Hetzner1/example.com: timeout",
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = notification::Config {
+            notifiers: vec![NotifierConfig::Webhook(WebhookConfig {
+                url: server.uri().into(),
+                events: vec![Event::UpdateFailure],
+                headers: BTreeMap::from([("Authorization".to_string(), "Bearer token".into())]),
+            })],
+            ..Default::default()
+        };
+        let notifiers = notifiers(&config);
+        let reqwest = reqwest::Client::new();
+
+        let success = Notification::UpdateSuccess {
+            records: vec!["home.example.com".to_string()],
+        };
+        notify_all(&notifiers, &reqwest, &success).await;
+
+        let failure = Notification::UpdateFailure {
+            errors: vec!["Hetzner1/example.com: timeout".to_string()],
+        };
+        notify_all(&notifiers, &reqwest, &failure).await;
+    }
+
+    async fn heartbeat_server(success: u64, failure: u64) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ping"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(success)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/ping/fail"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(failure)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_is_pinged_on_success() {
+        let server = heartbeat_server(1, 0).await;
+        let config = notification::Config {
+            heartbeat_url: Some(format!("{}/ping", server.uri()).into()),
+            heartbeat_on_failure: true,
+            ..Default::default()
+        };
+
+        heartbeat(&config, &reqwest::Client::new(), true).await;
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_fail_endpoint_on_failure() {
+        let server = heartbeat_server(0, 1).await;
+        let mut config = notification::Config {
+            heartbeat_url: Some(format!("{}/ping", server.uri()).into()),
+            heartbeat_on_failure: true,
+            ..Default::default()
+        };
+        let reqwest = reqwest::Client::new();
+
+        heartbeat(&config, &reqwest, false).await;
+
+        // Without heartbeat_on_failure a failed run sends nothing
+        config.heartbeat_on_failure = false;
+        heartbeat(&config, &reqwest, false).await;
+    }
+}
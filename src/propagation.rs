@@ -0,0 +1,349 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use hickory_resolver::{
+    Resolver,
+    config::{NameServerConfigGroup, ResolverConfig, ResolverOpts},
+    name_server::TokioConnectionProvider,
+    proto::rr::{RData, RecordType as HickoryRecordType},
+};
+use lum_log::debug;
+use thiserror::Error;
+use tokio::time::Instant;
+
+use crate::types::dns::{Record, RecordValue};
+
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error)]
+pub enum PropagationError {
+    #[error("Invalid resolver address: {0}")]
+    InvalidResolver(String),
+
+    #[error("Propagation checks are not supported for {0:?} records")]
+    UnsupportedRecordType(RecordValue),
+
+    #[error("DNS lookup failed: {0}")]
+    Lookup(String),
+}
+
+/// Looks up the values a DNS server currently serves for a record.
+#[async_trait]
+pub trait DnsLookup: Send + Sync {
+    async fn lookup(
+        &self,
+        server: SocketAddr,
+        record: &Record,
+    ) -> Result<Vec<String>, PropagationError>;
+}
+
+/// [`DnsLookup`] querying the servers directly over UDP/TCP, bypassing any cache.
+pub struct DirectLookup;
+
+fn record_type(value: &RecordValue) -> Result<HickoryRecordType, PropagationError> {
+    match value {
+        RecordValue::A(_) => Ok(HickoryRecordType::A),
+        RecordValue::AAAA(_) => Ok(HickoryRecordType::AAAA),
+        RecordValue::CNAME(_) => Ok(HickoryRecordType::CNAME),
+        RecordValue::TXT(_) => Ok(HickoryRecordType::TXT),
+        RecordValue::MX(_) => Ok(HickoryRecordType::MX),
+        RecordValue::NS(_) => Ok(HickoryRecordType::NS),
+        _ => Err(PropagationError::UnsupportedRecordType(value.clone())),
+    }
+}
+
+fn rdata_to_string(rdata: &RData) -> Option<String> {
+    match rdata {
+        RData::A(a) => Some(a.0.to_string()),
+        RData::AAAA(aaaa) => Some(aaaa.0.to_string()),
+        RData::CNAME(cname) => Some(cname.0.to_utf8()),
+        RData::NS(ns) => Some(ns.0.to_utf8()),
+        RData::MX(mx) => Some(format!("{} {}", mx.preference(), mx.exchange().to_utf8())),
+        RData::TXT(txt) => Some(
+            txt.txt_data()
+                .iter()
+                .map(|data| String::from_utf8_lossy(data))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl DnsLookup for DirectLookup {
+    async fn lookup(
+        &self,
+        server: SocketAddr,
+        record: &Record,
+    ) -> Result<Vec<String>, PropagationError> {
+        let record_type = record_type(&record.value)?;
+
+        let name_servers =
+            NameServerConfigGroup::from_ips_clear(&[server.ip()], server.port(), true);
+        let config = ResolverConfig::from_parts(None, vec![], name_servers);
+        let mut options = ResolverOpts::default();
+        options.cache_size = 0;
+        options.attempts = 1;
+
+        let resolver = Resolver::builder_with_config(config, TokioConnectionProvider::default())
+            .with_options(options)
+            .build();
+
+        let name = format!("{}.", record.domain.trim_end_matches('.'));
+        match resolver.lookup(name, record_type).await {
+            Ok(lookup) => Ok(lookup.iter().filter_map(rdata_to_string).collect()),
+            Err(e) if e.is_no_records_found() => Ok(vec![]),
+            Err(e) => Err(PropagationError::Lookup(e.to_string())),
+        }
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
+
+/// Returns whether a value served by DNS matches the record's expected value.
+pub fn matches_value(value: &RecordValue, served: &str) -> bool {
+    match value {
+        RecordValue::A(ip) => served
+            .parse::<IpAddr>()
+            .is_ok_and(|served| served == IpAddr::V4(*ip)),
+        RecordValue::AAAA(ip) => served
+            .parse::<IpAddr>()
+            .is_ok_and(|served| served == IpAddr::V6(*ip)),
+        RecordValue::CNAME(target) | RecordValue::NS(target) => {
+            normalize_name(target) == normalize_name(served)
+        }
+        RecordValue::TXT(text) => text.trim_matches('"') == served,
+        RecordValue::MX(mx) => match served.split_once(' ') {
+            Some((priority, target)) => {
+                priority == mx.priority.to_string()
+                    && normalize_name(target) == normalize_name(&mx.target)
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn parse_resolver(resolver: &str) -> Result<SocketAddr, PropagationError> {
+    if let Ok(addr) = resolver.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+
+    resolver
+        .parse::<IpAddr>()
+        .map(|ip| SocketAddr::new(ip, 53))
+        .map_err(|_| PropagationError::InvalidResolver(resolver.to_string()))
+}
+
+/// Which resolvers served the expected value before the deadline.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropagationReport {
+    pub agreed: Vec<String>,
+    pub pending: Vec<String>,
+}
+
+impl PropagationReport {
+    pub fn is_complete(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Polls the given DNS servers until all of them serve the record's value or `timeout` elapses.
+///
+/// Resolvers are given as IP addresses, optionally with a port (`1.1.1.1`, `[::1]:5353`).
+/// Lookup errors are treated like a not-yet-propagated value, so a flaky server does not end the
+/// wait early. The returned report lists which resolvers agreed and which did not.
+pub async fn wait_for_propagation(
+    record: &Record,
+    resolvers: &[&str],
+    timeout: Duration,
+) -> Result<PropagationReport, PropagationError> {
+    wait_for_propagation_with(
+        &DirectLookup,
+        record,
+        resolvers,
+        timeout,
+        DEFAULT_POLL_INTERVAL,
+    )
+    .await
+}
+
+/// Like [`wait_for_propagation`], but with a custom [`DnsLookup`] and poll interval.
+pub async fn wait_for_propagation_with(
+    lookup: &dyn DnsLookup,
+    record: &Record,
+    resolvers: &[&str],
+    timeout: Duration,
+    poll_interval: Duration,
+) -> Result<PropagationReport, PropagationError> {
+    record_type(&record.value)?;
+
+    let mut pending = resolvers
+        .iter()
+        .map(|resolver| Ok((resolver.to_string(), parse_resolver(resolver)?)))
+        .collect::<Result<Vec<_>, PropagationError>>()?;
+    let mut agreed = Vec::new();
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let mut still_pending = Vec::new();
+        for (resolver, addr) in pending {
+            match lookup.lookup(addr, record).await {
+                Ok(values)
+                    if values
+                        .iter()
+                        .any(|value| matches_value(&record.value, value)) =>
+                {
+                    debug!(
+                        "{} serves the expected value for {}",
+                        resolver, record.domain
+                    );
+                    agreed.push(resolver);
+                }
+                Ok(values) => {
+                    debug!(
+                        "{} still serves {:?} for {}",
+                        resolver, values, record.domain
+                    );
+                    still_pending.push((resolver, addr));
+                }
+                Err(e) => {
+                    debug!("Lookup of {} at {} failed: {}", record.domain, resolver, e);
+                    still_pending.push((resolver, addr));
+                }
+            }
+        }
+        pending = still_pending;
+
+        if pending.is_empty() || Instant::now() + poll_interval > deadline {
+            break;
+        }
+
+        tokio::time::sleep(poll_interval).await;
+    }
+
+    Ok(PropagationReport {
+        agreed,
+        pending: pending.into_iter().map(|(resolver, _)| resolver).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::HashMap, net::Ipv4Addr, sync::Mutex};
+
+    /// Serves the old value for the first `stale_lookups` queries per server, then the new one.
+    struct StubLookup {
+        old_value: String,
+        new_value: String,
+        stale_lookups: HashMap<SocketAddr, usize>,
+        calls: Mutex<HashMap<SocketAddr, usize>>,
+    }
+
+    #[async_trait]
+    impl DnsLookup for StubLookup {
+        async fn lookup(
+            &self,
+            server: SocketAddr,
+            _record: &Record,
+        ) -> Result<Vec<String>, PropagationError> {
+            let mut calls = self.calls.lock().unwrap();
+            let count = calls.entry(server).or_default();
+            *count += 1;
+
+            let stale_lookups = self
+                .stale_lookups
+                .get(&server)
+                .copied()
+                .unwrap_or(usize::MAX);
+            if *count > stale_lookups {
+                Ok(vec![self.new_value.clone()])
+            } else {
+                Ok(vec![self.old_value.clone()])
+            }
+        }
+    }
+
+    fn a_record() -> Record {
+        Record {
+            domain: "home.example.com".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(5, 6, 7, 8)),
+            ttl: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_propagation_old_then_new_value() {
+        let lookup = StubLookup {
+            old_value: "1.2.3.4".to_string(),
+            new_value: "5.6.7.8".to_string(),
+            stale_lookups: HashMap::from([
+                ("1.1.1.1:53".parse().unwrap(), 2),
+                ("8.8.8.8:53".parse().unwrap(), 0),
+            ]),
+            calls: Mutex::new(HashMap::new()),
+        };
+
+        let report = wait_for_propagation_with(
+            &lookup,
+            &a_record(),
+            &["1.1.1.1", "8.8.8.8"],
+            Duration::from_secs(5),
+            Duration::from_millis(10),
+        )
+        .await
+        .unwrap();
+
+        assert!(report.is_complete());
+        assert_eq!(report.agreed, vec!["8.8.8.8", "1.1.1.1"]);
+        let calls = lookup.calls.lock().unwrap();
+        assert_eq!(calls[&"1.1.1.1:53".parse().unwrap()], 3);
+        assert_eq!(calls[&"8.8.8.8:53".parse().unwrap()], 1);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_propagation_times_out() {
+        let lookup = StubLookup {
+            old_value: "1.2.3.4".to_string(),
+            new_value: "5.6.7.8".to_string(),
+            stale_lookups: HashMap::from([("9.9.9.9:53".parse().unwrap(), 0)]),
+            calls: Mutex::new(HashMap::new()),
+        };
+
+        let report = wait_for_propagation_with(
+            &lookup,
+            &a_record(),
+            &["1.1.1.1", "9.9.9.9"],
+            Duration::from_millis(50),
+            Duration::from_millis(10),
+        )
+        .await
+        .unwrap();
+
+        assert!(!report.is_complete());
+        assert_eq!(report.agreed, vec!["9.9.9.9"]);
+        assert_eq!(report.pending, vec!["1.1.1.1"]);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_propagation_invalid_resolver() {
+        let result =
+            wait_for_propagation(&a_record(), &["not-an-ip"], Duration::from_secs(1)).await;
+        assert!(matches!(result, Err(PropagationError::InvalidResolver(_))));
+    }
+
+    #[test]
+    fn test_matches_value_normalizes_names() {
+        let value = RecordValue::CNAME("Target.Example.com".to_string());
+        assert!(matches_value(&value, "target.example.com."));
+
+        let value = RecordValue::TXT("\"token\"".to_string());
+        assert!(matches_value(&value, "token"));
+    }
+}
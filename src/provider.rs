@@ -1,11 +1,35 @@
-use anyhow::Result;
+use std::time::Duration;
+
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use lum_libs::serde_json;
+use lum_log::{info, warn};
+use thiserror::Error;
 
-use crate::types::dns::Record;
+use crate::{
+    Config,
+    config::{dns, provider::Provider as ProviderConfig},
+    http::HttpClient,
+    provider::{
+        axfr::AxfrProvider, desec::DesecProvider, digitalocean::DigitalOceanProvider,
+        hetzner::HetznerProvider, idn::IdnProvider, namecheap::NamecheapProvider,
+        naming::RecordNaming, netcup::NetcupProvider, nitrado::NitradoProvider,
+        njalla::NjallaProvider, route53::Route53Provider,
+    },
+    types::dns::{Record, RecordType, RecordValidationError, RecordValue},
+};
 
+pub mod axfr;
+pub mod desec;
+pub mod digitalocean;
 pub mod hetzner;
+pub mod idn;
+pub mod namecheap;
+pub mod naming;
 pub mod netcup;
 pub mod nitrado;
+pub mod njalla;
+pub mod route53;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Feature {
@@ -14,11 +38,170 @@ pub enum Feature {
     AddRecord,
     UpdateRecord,
     DeleteRecord,
+    /// Several records can be written with a single API call.
+    BatchWrite,
+    /// The API reports when records were last modified, see
+    /// [`Provider::get_records_modified_since`].
+    ModificationTimes,
+}
+
+/// Errors returned by [`Provider`] implementations and the provider registry.
+#[derive(Debug, Error)]
+pub enum ProviderError {
+    #[error("HTTP request failed: {0}")]
+    Reqwest(#[from] reqwest::Error),
+
+    #[error("JSON parsing error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("The given provider is not configured: {0}")]
+    NotConfigured(String),
+
+    #[error("Domain '{0}' not found")]
+    DomainNotFound(String),
+
+    #[error("Record '{0}' not found")]
+    RecordNotFound(String),
+
+    #[error("Invalid internationalized domain name '{0}'")]
+    InvalidDomainName(String),
+
+    #[error("Invalid record: {0}")]
+    InvalidRecord(#[from] RecordValidationError),
+
+    #[error("Record type {0:?} is not supported by provider {1}")]
+    UnsupportedRecordType(RecordType, &'static str),
+
+    #[error("'{0}' is a public suffix and can not be managed as a zone")]
+    NoZone(String),
+
+    #[error("Record '{record}' is outside of zone '{zone}'")]
+    RecordOutsideZone { record: String, zone: String },
+
+    #[error(
+        "'{0}' is the apex of its zone and can not be a CNAME, {hint}",
+        hint = naming::CNAME_AT_APEX_HINT
+    )]
+    CnameAtApex(String),
+
+    #[error("Feature {0:?} is not supported by provider {1}")]
+    UnsupportedFeature(Feature, &'static str),
+
+    #[error("Provider returned no result for record '{0}'")]
+    MissingResult(String),
+
+    #[error("Timed out after {0:?}")]
+    Timeout(Duration),
+
+    #[error("Nitrado error: {0}")]
+    Nitrado(Box<nitrado::Error>),
+
+    #[error("Hetzner error: {0}")]
+    Hetzner(Box<hetzner::Error>),
+
+    #[error("Netcup error: {0}")]
+    Netcup(Box<netcup::Error>),
+
+    #[error("Route53 error: {0}")]
+    Route53(Box<route53::Error>),
+
+    #[error("deSEC error: {0}")]
+    Desec(Box<desec::Error>),
+
+    #[error("DigitalOcean error: {0}")]
+    DigitalOcean(Box<digitalocean::Error>),
+
+    #[error("Namecheap error: {0}")]
+    Namecheap(Box<namecheap::Error>),
+
+    #[error("Njalla error: {0}")]
+    Njalla(Box<njalla::Error>),
+
+    #[error("AXFR error: {0}")]
+    Axfr(Box<axfr::Error>),
+}
+
+impl From<nitrado::Error> for ProviderError {
+    fn from(error: nitrado::Error) -> Self {
+        ProviderError::Nitrado(Box::new(error))
+    }
+}
+
+impl From<hetzner::Error> for ProviderError {
+    fn from(error: hetzner::Error) -> Self {
+        ProviderError::Hetzner(Box::new(error))
+    }
+}
+
+impl From<netcup::Error> for ProviderError {
+    fn from(error: netcup::Error) -> Self {
+        ProviderError::Netcup(Box::new(error))
+    }
+}
+
+impl From<route53::Error> for ProviderError {
+    fn from(error: route53::Error) -> Self {
+        ProviderError::Route53(Box::new(error))
+    }
+}
+
+impl From<desec::Error> for ProviderError {
+    fn from(error: desec::Error) -> Self {
+        ProviderError::Desec(Box::new(error))
+    }
+}
+
+impl From<digitalocean::Error> for ProviderError {
+    fn from(error: digitalocean::Error) -> Self {
+        ProviderError::DigitalOcean(Box::new(error))
+    }
+}
+
+impl From<namecheap::Error> for ProviderError {
+    fn from(error: namecheap::Error) -> Self {
+        ProviderError::Namecheap(Box::new(error))
+    }
+}
+
+impl From<njalla::Error> for ProviderError {
+    fn from(error: njalla::Error) -> Self {
+        ProviderError::Njalla(Box::new(error))
+    }
+}
+
+impl From<axfr::Error> for ProviderError {
+    fn from(error: axfr::Error) -> Self {
+        ProviderError::Axfr(Box::new(error))
+    }
+}
+
+pub type Result<T, E = ProviderError> = std::result::Result<T, E>;
+
+/// Longest part of an error response body kept in errors, HTML error pages can be large.
+const MAX_ERROR_BODY_LENGTH: usize = 512;
+
+/// Trims the `body` of an unsuccessful response for error messages and cuts it to
+/// [`MAX_ERROR_BODY_LENGTH`] characters.
+pub(crate) fn truncate_error_body(body: &str) -> String {
+    let body = body.trim();
+    match body.char_indices().nth(MAX_ERROR_BODY_LENGTH) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body.to_string(),
+    }
+}
+
+/// Reports a record that was to be deleted as already absent, which counts as deleted, see
+/// [`Provider::delete_record`].
+pub(crate) fn already_absent(provider_name: &str, record: &Record) -> Result<()> {
+    info!("{} is already absent at {}", record, provider_name);
+    Ok(())
 }
 
 pub struct GetRecordsInput<'input> {
     pub domain: &'input str,
     pub subdomains: Vec<&'input str>,
+    /// Only get records of this type, filtered by the API if the provider supports it.
+    pub record_type: Option<RecordType>,
 }
 
 pub struct GetAllRecordsInput<'input> {
@@ -41,6 +224,41 @@ impl<'input> From<&'input GetRecordsInput<'input>> for GetAllRecordsInput<'input
     }
 }
 
+pub struct AddRecordInput<'input> {
+    pub domain: &'input str,
+    pub record: &'input Record,
+}
+
+pub struct AddRecordsInput<'input> {
+    pub domain: &'input str,
+    pub records: &'input [Record],
+}
+
+pub struct UpdateRecordInput<'input> {
+    pub domain: &'input str,
+    pub record: &'input Record,
+}
+
+pub struct UpdateRecordsInput<'input> {
+    pub domain: &'input str,
+    pub records: &'input [Record],
+}
+
+pub struct UpsertRecordInput<'input> {
+    pub domain: &'input str,
+    pub record: &'input Record,
+}
+
+pub struct UpsertRecordsInput<'input> {
+    pub domain: &'input str,
+    pub records: &'input [Record],
+}
+
+pub struct DeleteRecordInput<'input> {
+    pub domain: &'input str,
+    pub record: &'input Record,
+}
+
 /// Trait for DNS providers.
 ///
 /// This trait defines the interface for interacting with various DNS providers
@@ -66,18 +284,118 @@ pub trait Provider: Send + Sync {
         self.get_supported_features().contains(feature)
     }
 
+    /// Record types this provider can write. SPF records are written as TXT, see
+    /// [`RecordType::wire_type`].
+    fn supported_record_types(&self) -> &'static [RecordType] {
+        &RecordType::ALL
+    }
+
+    /// How this provider's API names records, in particular the zone apex.
+    fn record_naming(&self) -> RecordNaming {
+        RecordNaming::FullDomain
+    }
+
+    /// Smallest and largest TTL this provider accepts, `None` where it has no bound. Writes
+    /// clamp TTLs to these bounds, see [`Provider::clamp_ttl`].
+    fn ttl_bounds(&self) -> (Option<u32>, Option<u32>) {
+        (None, None)
+    }
+
+    /// Returns `record` with its TTL clamped to [`Provider::ttl_bounds`], warning if it had to
+    /// be changed. A record without a TTL keeps the provider's default.
+    fn clamp_ttl(&self, record: &Record) -> Record {
+        let Some(ttl) = record.ttl else {
+            return record.clone();
+        };
+
+        let (min, max) = self.ttl_bounds();
+        let clamped = ttl.clamp(min.unwrap_or(u32::MIN), max.unwrap_or(u32::MAX));
+        if clamped != ttl {
+            warn!(
+                "TTL {} of {} is outside the range {} accepts, using {}",
+                ttl,
+                record.domain,
+                self.get_provider_name(),
+                clamped
+            );
+        }
+
+        Record {
+            ttl: Some(clamped),
+            ..record.clone()
+        }
+    }
+
+    /// Returns the zone `fqdn` belongs to, the registrable domain according to the public suffix
+    /// list by default. Public suffixes themselves belong to no zone this provider can manage.
+    fn zone_of(&self, fqdn: &str) -> Option<String> {
+        naming::registrable_zone(fqdn)
+    }
+
+    /// Checks that `records` can be written to the configured `domain`: the domain has to belong
+    /// to a zone, see [`Provider::zone_of`], every record has to lie within the domain and none
+    /// may be a CNAME at its apex.
+    fn validate_zone(&self, domain: &str, records: &[Record]) -> Result<()> {
+        if self.zone_of(domain).is_none() {
+            return Err(ProviderError::NoZone(domain.to_string()));
+        }
+
+        for record in records {
+            if !naming::is_within(&record.domain, domain) {
+                return Err(ProviderError::RecordOutsideZone {
+                    record: record.domain.clone(),
+                    zone: domain.to_string(),
+                });
+            }
+
+            if matches!(record.value, RecordValue::CNAME(_))
+                && naming::is_apex(&record.domain, domain)
+            {
+                return Err(ProviderError::CnameAtApex(record.domain.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that this provider can write `records` before any request is sent: their type
+    /// has to be supported and their values valid.
+    fn validate_records(&self, records: &[Record]) -> Result<()> {
+        for record in records {
+            let record_type = record.value.wire_type();
+            if !self.supported_record_types().contains(&record_type) {
+                return Err(ProviderError::UnsupportedRecordType(
+                    record_type,
+                    self.get_provider_name(),
+                ));
+            }
+            record.value.validate()?;
+        }
+
+        Ok(())
+    }
+
     async fn get_records(
         &self,
-        reqwest: reqwest::Client,
+        http: &dyn HttpClient,
         input: &GetRecordsInput,
     ) -> Result<Vec<Record>> {
         let get_all_records_input = GetAllRecordsInput::from(input);
-        let records = self
-            .get_all_records(reqwest, &get_all_records_input)
-            .await?;
+        let records = match input.record_type {
+            Some(record_type) => {
+                self.get_records_of_type(http, &get_all_records_input, record_type)
+                    .await?
+            }
+            None => self.get_all_records(http, &get_all_records_input).await?,
+        };
         let records = records
             .into_iter()
-            .filter(|record| input.subdomains.contains(&record.domain.as_str()))
+            .filter(|record| {
+                input
+                    .subdomains
+                    .iter()
+                    .any(|subdomain| naming::same_name(subdomain, &record.domain, input.domain))
+            })
             .collect();
 
         Ok(records)
@@ -85,21 +403,342 @@ pub trait Provider: Send + Sync {
 
     async fn get_all_records(
         &self,
-        reqwest: reqwest::Client,
+        http: &dyn HttpClient,
         input: &GetAllRecordsInput,
     ) -> Result<Vec<Record>>;
 
-    async fn add_record(&self, reqwest: reqwest::Client, record: &Record) -> Result<()>;
-    async fn update_record(&self, reqwest: reqwest::Client, record: &Record) -> Result<()>;
-    async fn delete_record(&self, reqwest: reqwest::Client, record: &Record) -> Result<()>;
+    /// Checks that the provider is reachable and accepts the configured credentials.
+    ///
+    /// The default implementation gets all records of `domain`, a domain managed at the
+    /// provider.
+    async fn check(&self, http: &dyn HttpClient, domain: &str) -> Result<()> {
+        let input = GetAllRecordsInput { domain };
+        self.get_all_records(http, &input).await?;
+
+        Ok(())
+    }
+
+    /// Gets all records of `record_type` in a domain.
+    ///
+    /// The default implementation filters the result of [`Provider::get_all_records`];
+    /// providers whose API can filter by type override it with a narrower query.
+    async fn get_records_of_type(
+        &self,
+        http: &dyn HttpClient,
+        input: &GetAllRecordsInput,
+        record_type: RecordType,
+    ) -> Result<Vec<Record>> {
+        let records = self.get_all_records(http, input).await?;
+        let records = records
+            .into_iter()
+            .filter(|record| record.value.record_type() == record_type)
+            .collect();
+
+        Ok(records)
+    }
+
+    /// Gets all records of a domain that were modified after `since`.
+    ///
+    /// Only providers supporting [`Feature::ModificationTimes`] implement this, the default
+    /// implementation returns [`ProviderError::UnsupportedFeature`].
+    async fn get_records_modified_since(
+        &self,
+        _http: &dyn HttpClient,
+        _input: &GetAllRecordsInput,
+        _since: DateTime<Utc>,
+    ) -> Result<Vec<Record>> {
+        Err(ProviderError::UnsupportedFeature(
+            Feature::ModificationTimes,
+            self.get_provider_name(),
+        ))
+    }
+
+    async fn add_record(&self, http: &dyn HttpClient, input: &AddRecordInput) -> Result<()>;
+
+    /// Adds several records to the same domain.
+    ///
+    /// The outer error is for failures affecting the whole batch, the returned vector holds one
+    /// result per record in input order. The default implementation calls
+    /// [`Provider::add_record`] for each record; providers supporting [`Feature::BatchWrite`]
+    /// override it with a single API call.
+    async fn add_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &AddRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        let mut results = Vec::with_capacity(input.records.len());
+        for record in input.records {
+            let add_record_input = AddRecordInput {
+                domain: input.domain,
+                record,
+            };
+            results.push(self.add_record(http, &add_record_input).await);
+        }
+
+        Ok(results)
+    }
+
+    async fn update_record(&self, http: &dyn HttpClient, input: &UpdateRecordInput) -> Result<()>;
+
+    /// Updates several records of the same domain, like [`Provider::add_records`].
+    async fn update_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &UpdateRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        let mut results = Vec::with_capacity(input.records.len());
+        for record in input.records {
+            let update_record_input = UpdateRecordInput {
+                domain: input.domain,
+                record,
+            };
+            results.push(self.update_record(http, &update_record_input).await);
+        }
+
+        Ok(results)
+    }
+
+    /// Updates the record if one of its name and type exists, adds it otherwise.
+    ///
+    /// The default implementation gets the records of the type to decide between
+    /// [`Provider::update_record`] and [`Provider::add_record`]; providers whose API can upsert
+    /// natively override it with a single call.
+    async fn upsert_record(&self, http: &dyn HttpClient, input: &UpsertRecordInput) -> Result<()> {
+        let record = input.record;
+        let get_all_records_input = GetAllRecordsInput {
+            domain: input.domain,
+        };
+        let existing = self
+            .get_records_of_type(http, &get_all_records_input, record.value.wire_type())
+            .await?;
+
+        let exists = existing
+            .iter()
+            .any(|existing| naming::same_name(&existing.domain, &record.domain, input.domain));
+        if exists {
+            let update_record_input = UpdateRecordInput {
+                domain: input.domain,
+                record,
+            };
+            self.update_record(http, &update_record_input).await
+        } else {
+            let add_record_input = AddRecordInput {
+                domain: input.domain,
+                record,
+            };
+            self.add_record(http, &add_record_input).await
+        }
+    }
+
+    /// Upserts several records of the same domain, like [`Provider::add_records`].
+    ///
+    /// The default implementation gets the records of the domain once, then updates those whose
+    /// name and type exist with [`Provider::update_records`] and adds the others with
+    /// [`Provider::add_records`], so batch writes are kept.
+    async fn upsert_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &UpsertRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        let get_all_records_input = GetAllRecordsInput {
+            domain: input.domain,
+        };
+        let existing = self.get_all_records(http, &get_all_records_input).await?;
+
+        let (updates, additions): (Vec<_>, Vec<_>) =
+            input.records.iter().enumerate().partition(|(_, record)| {
+                existing.iter().any(|existing| {
+                    existing.value.wire_type() == record.value.wire_type()
+                        && naming::same_name(&existing.domain, &record.domain, input.domain)
+                })
+            });
+
+        let mut results: Vec<Option<Result<()>>> = input.records.iter().map(|_| None).collect();
+        if !updates.is_empty() {
+            let records: Vec<Record> = updates
+                .iter()
+                .map(|(_, record)| (*record).clone())
+                .collect();
+            let update_records_input = UpdateRecordsInput {
+                domain: input.domain,
+                records: &records,
+            };
+            let update_results = self.update_records(http, &update_records_input).await?;
+            for ((index, _), result) in updates.iter().zip(update_results) {
+                results[*index] = Some(result);
+            }
+        }
+        if !additions.is_empty() {
+            let records: Vec<Record> = additions
+                .iter()
+                .map(|(_, record)| (*record).clone())
+                .collect();
+            let add_records_input = AddRecordsInput {
+                domain: input.domain,
+                records: &records,
+            };
+            let add_results = self.add_records(http, &add_records_input).await?;
+            for ((index, _), result) in additions.iter().zip(add_results) {
+                results[*index] = Some(result);
+            }
+        }
+
+        // A batch that answers fewer records than it was given fails the rest
+        Ok(results
+            .into_iter()
+            .zip(input.records)
+            .map(|(result, record)| {
+                result.unwrap_or_else(|| Err(ProviderError::MissingResult(record.domain.clone())))
+            })
+            .collect())
+    }
+
+    /// Deletes the record with the name, type and value of `input.record`.
+    ///
+    /// Deleting is idempotent: a record that does not exist, e.g. because a retried run already
+    /// deleted it, is logged as already absent and counts as deleted.
+    async fn delete_record(&self, http: &dyn HttpClient, input: &DeleteRecordInput) -> Result<()>;
+}
+
+/// Returns the provider types dnrs supports, as they are tagged in the configuration, e.g.
+/// `!Hetzner`.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::available_providers;
+///
+/// assert!(available_providers().contains(&"Hetzner"));
+/// ```
+pub fn available_providers() -> &'static [&'static str] {
+    &[
+        "Nitrado",
+        "Hetzner",
+        "Netcup",
+        "Route53",
+        "Desec",
+        "DigitalOcean",
+        "Namecheap",
+        "Njalla",
+        "Axfr",
+    ]
+}
+
+/// Creates the provider instance for the configured provider with the given name.
+///
+/// Returns [`ProviderError::NotConfigured`] if no provider with that name is configured.
+/// The provider is wrapped in an [`IdnProvider`], so internationalized domain names are sent
+/// in their ASCII-compatible form.
+pub fn get_provider<'config>(
+    name: &str,
+    config: &'config Config,
+) -> Result<Box<dyn Provider + 'config>> {
+    let provider = create_provider(name, config)?;
+    Ok(Box::new(IdnProvider::new(provider)))
+}
+
+fn create_provider<'config>(
+    name: &str,
+    config: &'config Config,
+) -> Result<Box<dyn Provider + 'config>> {
+    for provider in config.providers.iter() {
+        match provider {
+            ProviderConfig::Nitrado(nitrado_config) => {
+                if name == nitrado_config.name {
+                    return Ok(Box::new(NitradoProvider::new(nitrado_config)));
+                }
+            }
+            ProviderConfig::Hetzner(hetzner_config) => {
+                if name == hetzner_config.name {
+                    let zone_ids = config
+                        .dns
+                        .iter()
+                        .filter_map(|dns_config| match dns_config {
+                            dns::Type::Hetzner(dns_config) if dns_config.provider_name == name => {
+                                Some(dns_config)
+                            }
+                            _ => None,
+                        })
+                        .flat_map(hetzner::DnsConfig::zone_ids);
+                    let provider = HetznerProvider::new(hetzner_config).with_zone_ids(zone_ids);
+                    return Ok(Box::new(provider));
+                }
+            }
+            ProviderConfig::Netcup(netcup_config) => {
+                if name == netcup_config.name {
+                    return Ok(Box::new(NetcupProvider::new(netcup_config)));
+                }
+            }
+            ProviderConfig::Route53(route53_config) => {
+                if name == route53_config.name {
+                    return Ok(Box::new(Route53Provider::new(route53_config)));
+                }
+            }
+            ProviderConfig::Desec(desec_config) => {
+                if name == desec_config.name {
+                    return Ok(Box::new(DesecProvider::new(desec_config)));
+                }
+            }
+            ProviderConfig::DigitalOcean(digitalocean_config) => {
+                if name == digitalocean_config.name {
+                    return Ok(Box::new(DigitalOceanProvider::new(digitalocean_config)));
+                }
+            }
+            ProviderConfig::Namecheap(namecheap_config) => {
+                if name == namecheap_config.name {
+                    return Ok(Box::new(NamecheapProvider::new(namecheap_config)));
+                }
+            }
+            ProviderConfig::Njalla(njalla_config) => {
+                if name == njalla_config.name {
+                    return Ok(Box::new(NjallaProvider::new(njalla_config)));
+                }
+            }
+            ProviderConfig::Axfr(axfr_config) => {
+                if name == axfr_config.name {
+                    return Ok(Box::new(AxfrProvider::new(axfr_config)));
+                }
+            }
+        }
+    }
+
+    Err(ProviderError::NotConfigured(name.to_string()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::dns::RecordValue;
+    use crate::{
+        cli::generate_config::provider_type, http::FakeHttpClient, types::dns::RecordValue,
+    };
     use std::net::Ipv4Addr;
 
+    #[test]
+    fn test_available_providers_lists_every_wired_provider() {
+        let config = Config::default();
+        for provider_config in config.providers.iter() {
+            assert!(
+                available_providers().contains(&provider_config.provider_type()),
+                "{} is missing",
+                provider_config.provider_type()
+            );
+            assert!(get_provider(provider_config.name(), &config).is_ok());
+        }
+        assert_eq!(config.providers.len(), available_providers().len());
+
+        for (path, _) in Config::example_files().unwrap() {
+            if let Some(example_type) = provider_type(&path) {
+                assert!(
+                    available_providers()
+                        .iter()
+                        .any(|known| known.eq_ignore_ascii_case(example_type)),
+                    "{} is missing",
+                    example_type
+                );
+            }
+        }
+    }
+
     struct MockProvider {
         name: &'static str,
         records: Vec<Record>,
@@ -117,25 +756,63 @@ mod tests {
 
         async fn get_all_records(
             &self,
-            _reqwest: reqwest::Client,
+            _http: &dyn HttpClient,
             _input: &GetAllRecordsInput,
         ) -> Result<Vec<Record>> {
             Ok(self.records.clone())
         }
 
-        async fn add_record(&self, _reqwest: reqwest::Client, _record: &Record) -> Result<()> {
+        async fn add_record(&self, _http: &dyn HttpClient, _input: &AddRecordInput) -> Result<()> {
             unimplemented!()
         }
 
-        async fn update_record(&self, _reqwest: reqwest::Client, _record: &Record) -> Result<()> {
+        async fn update_record(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &UpdateRecordInput,
+        ) -> Result<()> {
             unimplemented!()
         }
 
-        async fn delete_record(&self, _reqwest: reqwest::Client, _record: &Record) -> Result<()> {
-            unimplemented!()
+        async fn delete_record(
+            &self,
+            _http: &dyn HttpClient,
+            input: &DeleteRecordInput,
+        ) -> Result<()> {
+            let exists = self.records.iter().any(|record| {
+                record.domain == input.record.domain
+                    && record.value.content() == input.record.value.content()
+            });
+            if !exists {
+                return already_absent(self.name, input.record);
+            }
+
+            Ok(())
         }
     }
 
+    #[tokio::test]
+    async fn test_delete_of_absent_record_succeeds() {
+        let provider = MockProvider {
+            name: "Mock",
+            records: vec![],
+        };
+        let record = Record {
+            domain: "gone.example.com".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(1, 1, 1, 1)),
+            ttl: None,
+        };
+        let input = DeleteRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        let result = provider
+            .delete_record(&reqwest::Client::new(), &input)
+            .await;
+        assert!(result.is_ok());
+    }
+
     #[tokio::test]
     async fn test_provider_generic_get_records() {
         let records = vec![
@@ -161,13 +838,16 @@ mod tests {
             records,
         };
 
-        let reqwest = reqwest::Client::new();
         let input = GetRecordsInput {
             domain: "example.com",
             subdomains: vec!["a.example.com", "c.example.com"],
+            record_type: None,
         };
 
-        let filtered = provider.get_records(reqwest, &input).await.unwrap();
+        let filtered = provider
+            .get_records(&FakeHttpClient::default(), &input)
+            .await
+            .unwrap();
 
         assert_eq!(filtered.len(), 2);
         assert_eq!(filtered[0].domain, "a.example.com");
@@ -185,4 +865,394 @@ mod tests {
         assert!(provider.is_feature_supported(&Feature::GetAllRecords));
         assert!(!provider.is_feature_supported(&Feature::AddRecord));
     }
+
+    #[test]
+    fn test_get_provider_nitrado() {
+        let config = Config {
+            providers: vec![ProviderConfig::Nitrado(nitrado::Config {
+                name: "TestNitrado".to_string(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+
+        let provider = get_provider("TestNitrado", &config).unwrap();
+        assert_eq!(provider.get_provider_name(), "Nitrado");
+    }
+
+    #[test]
+    fn test_get_provider_hetzner() {
+        let config = Config {
+            providers: vec![ProviderConfig::Hetzner(hetzner::Config {
+                name: "TestHetzner".to_string(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+
+        let provider = get_provider("TestHetzner", &config).unwrap();
+        assert_eq!(provider.get_provider_name(), "Hetzner");
+    }
+
+    #[test]
+    fn test_get_provider_netcup() {
+        let config = Config {
+            providers: vec![ProviderConfig::Netcup(netcup::Config {
+                name: "TestNetcup".to_string(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+
+        let provider = get_provider("TestNetcup", &config).unwrap();
+        assert_eq!(provider.get_provider_name(), "Netcup");
+    }
+
+    fn sample_value(record_type: RecordType) -> RecordValue {
+        match record_type {
+            RecordType::A => RecordValue::A(Ipv4Addr::new(1, 1, 1, 1)),
+            RecordType::AAAA => RecordValue::AAAA("2001:db8::1".parse().unwrap()),
+            RecordType::CNAME => RecordValue::CNAME("target.example.com".to_string()),
+            RecordType::TXT => RecordValue::TXT("hello".to_string()),
+            RecordType::SPF => RecordValue::SPF("v=spf1 -all".to_string()),
+            RecordType::MX => RecordValue::MX(crate::types::dns::MxRecord {
+                priority: 10,
+                target: "mail.example.com".to_string(),
+            }),
+            RecordType::NS => RecordValue::NS("ns1.example.com".to_string()),
+            RecordType::SOA => RecordValue::SOA(
+                "ns1.example.com. admin.example.com. 1 7200 3600 1209600 3600".to_string(),
+            ),
+            RecordType::SRV => RecordValue::SRV(10, 5, 5060, "sip.example.com".to_string()),
+            RecordType::TLSA => RecordValue::TLSA(3, 1, 1, "ab".repeat(32)),
+            RecordType::CAA => RecordValue::CAA(0, "issue".to_string(), "ca.example".to_string()),
+            RecordType::SVCB => RecordValue::SVCB("1 svc.example.com port=8443".parse().unwrap()),
+            RecordType::HTTPS => RecordValue::HTTPS("1 . alpn=h2,h3".parse().unwrap()),
+            RecordType::DS => RecordValue::DS(2371, 13, 2, "ab".repeat(32)),
+            RecordType::DNSKEY => RecordValue::DNSKEY(257, 3, 13, "AAAA".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_providers_reject_unsupported_record_types() {
+        // Requests would fail to connect, so a passing write means validation was skipped
+        let api_base_url = "http://127.0.0.1:1".to_string();
+        let config = Config {
+            providers: vec![
+                ProviderConfig::Nitrado(nitrado::Config {
+                    api_base_url: api_base_url.clone(),
+                    ..Default::default()
+                }),
+                ProviderConfig::Hetzner(hetzner::Config {
+                    api_base_url: api_base_url.clone(),
+                    ..Default::default()
+                }),
+                ProviderConfig::Netcup(netcup::Config {
+                    api_base_url: api_base_url.clone(),
+                    ..Default::default()
+                }),
+                ProviderConfig::Route53(route53::Config {
+                    api_base_url: api_base_url.clone(),
+                    ..Default::default()
+                }),
+                ProviderConfig::Desec(desec::Config {
+                    api_base_url: api_base_url.clone(),
+                    ..Default::default()
+                }),
+                ProviderConfig::DigitalOcean(digitalocean::Config {
+                    api_base_url: api_base_url.clone(),
+                    ..Default::default()
+                }),
+                ProviderConfig::Namecheap(namecheap::Config {
+                    api_base_url: api_base_url.clone(),
+                    ..Default::default()
+                }),
+                ProviderConfig::Njalla(njalla::Config {
+                    api_base_url: api_base_url.clone(),
+                    ..Default::default()
+                }),
+            ],
+            ..Default::default()
+        };
+
+        let mut rejected = 0;
+        for provider_config in config.providers.iter() {
+            let provider = get_provider(provider_config.name(), &config).unwrap();
+            for record_type in RecordType::ALL {
+                if provider
+                    .supported_record_types()
+                    .contains(&record_type.wire_type())
+                {
+                    continue;
+                }
+
+                let record = Record {
+                    domain: "www.example.com".to_string(),
+                    value: sample_value(record_type),
+                    ttl: None,
+                };
+                let input = AddRecordInput {
+                    domain: "example.com",
+                    record: &record,
+                };
+                let result = provider.add_record(&reqwest::Client::new(), &input).await;
+                assert!(
+                    matches!(result, Err(ProviderError::UnsupportedRecordType(rejected_type, _)) if rejected_type == record_type.wire_type()),
+                    "{} did not reject {:?}",
+                    provider.get_provider_name(),
+                    record_type
+                );
+                rejected += 1;
+            }
+        }
+        assert!(rejected > 0);
+    }
+
+    #[test]
+    fn test_ttl_is_clamped_to_provider_bounds() {
+        let config = Config::default();
+        let provider = get_provider("Hetzner1", &config).unwrap();
+        assert_eq!(provider.ttl_bounds(), (Some(60), None));
+
+        let record = |ttl| Record {
+            domain: "www.example.com".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+            ttl,
+        };
+        assert_eq!(provider.clamp_ttl(&record(Some(30))).ttl, Some(60));
+        assert_eq!(provider.clamp_ttl(&record(Some(300))).ttl, Some(300));
+        assert_eq!(provider.clamp_ttl(&record(None)).ttl, None);
+
+        let provider = get_provider("Njalla1", &config).unwrap();
+        assert_eq!(provider.clamp_ttl(&record(Some(604800))).ttl, Some(86400));
+    }
+
+    #[test]
+    fn test_spf_is_checked_as_txt() {
+        let provider = MockProvider {
+            name: "Mock",
+            records: Vec::new(),
+        };
+        let record = Record {
+            domain: "example.com".to_string(),
+            value: sample_value(RecordType::SPF),
+            ttl: None,
+        };
+
+        assert!(provider.validate_records(&[record]).is_ok());
+        assert_eq!(RecordType::SPF.wire_type(), RecordType::TXT);
+    }
+
+    #[test]
+    fn test_cname_at_apex_is_rejected() {
+        let provider = MockProvider {
+            name: "Mock",
+            records: Vec::new(),
+        };
+        let cname = |domain: &str| Record {
+            domain: domain.to_string(),
+            value: RecordValue::CNAME("example.net".to_string()),
+            ttl: None,
+        };
+
+        assert!(matches!(
+            provider.validate_zone("example.com", &[cname("example.com.")]),
+            Err(ProviderError::CnameAtApex(name)) if name == "example.com."
+        ));
+        assert!(
+            provider
+                .validate_zone("example.com", &[cname("www.example.com")])
+                .is_ok()
+        );
+    }
+
+    /// Records which write method was called for which record.
+    struct UpsertProvider {
+        records: Vec<Record>,
+        calls: std::sync::Mutex<Vec<(&'static str, String)>>,
+    }
+
+    #[async_trait]
+    impl Provider for UpsertProvider {
+        fn get_provider_name(&self) -> &'static str {
+            "Upsert"
+        }
+
+        fn get_supported_features(&self) -> Vec<Feature> {
+            vec![Feature::AddRecord, Feature::UpdateRecord]
+        }
+
+        async fn get_all_records(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &GetAllRecordsInput,
+        ) -> Result<Vec<Record>> {
+            Ok(self.records.clone())
+        }
+
+        async fn add_record(&self, _http: &dyn HttpClient, input: &AddRecordInput) -> Result<()> {
+            let call = ("add", input.record.domain.clone());
+            self.calls.lock().unwrap().push(call);
+            Ok(())
+        }
+
+        async fn update_record(
+            &self,
+            _http: &dyn HttpClient,
+            input: &UpdateRecordInput,
+        ) -> Result<()> {
+            let call = ("update", input.record.domain.clone());
+            self.calls.lock().unwrap().push(call);
+            Ok(())
+        }
+
+        async fn delete_record(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &DeleteRecordInput,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upsert_record_updates_existing_and_adds_missing() {
+        let provider = UpsertProvider {
+            records: vec![
+                Record {
+                    domain: "home.example.com".to_string(),
+                    value: RecordValue::A(Ipv4Addr::new(1, 1, 1, 1)),
+                    ttl: None,
+                },
+                Record {
+                    domain: "mail.example.com".to_string(),
+                    value: sample_value(RecordType::MX),
+                    ttl: None,
+                },
+            ],
+            calls: Default::default(),
+        };
+
+        let upserts = [
+            ("HOME.example.com.", RecordType::A),
+            ("mail.example.com", RecordType::A),
+            ("new.example.com", RecordType::A),
+            ("mail.example.com", RecordType::MX),
+        ];
+        for (domain, record_type) in upserts {
+            let record = Record {
+                domain: domain.to_string(),
+                value: sample_value(record_type),
+                ttl: None,
+            };
+            let input = UpsertRecordInput {
+                domain: "example.com",
+                record: &record,
+            };
+            provider
+                .upsert_record(&reqwest::Client::new(), &input)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            *provider.calls.lock().unwrap(),
+            vec![
+                ("update", "HOME.example.com.".to_string()),
+                ("add", "mail.example.com".to_string()),
+                ("add", "new.example.com".to_string()),
+                ("update", "mail.example.com".to_string()),
+            ]
+        );
+    }
+
+    /// Answers a batch of additions with a result for its first record only.
+    struct ShortBatchProvider;
+
+    #[async_trait]
+    impl Provider for ShortBatchProvider {
+        fn get_provider_name(&self) -> &'static str {
+            "ShortBatch"
+        }
+
+        fn get_supported_features(&self) -> Vec<Feature> {
+            vec![Feature::AddRecord, Feature::BatchWrite]
+        }
+
+        async fn get_all_records(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &GetAllRecordsInput,
+        ) -> Result<Vec<Record>> {
+            Ok(vec![])
+        }
+
+        async fn add_record(&self, _http: &dyn HttpClient, _input: &AddRecordInput) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn add_records(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &AddRecordsInput,
+        ) -> Result<Vec<Result<()>>> {
+            Ok(vec![Ok(())])
+        }
+
+        async fn update_record(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &UpdateRecordInput,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn delete_record(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &DeleteRecordInput,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_short_batch_result_fails_missing_records() {
+        let records: Vec<Record> = ["one.example.com", "two.example.com"]
+            .into_iter()
+            .map(|domain| Record {
+                domain: domain.to_string(),
+                value: sample_value(RecordType::A),
+                ttl: None,
+            })
+            .collect();
+        let input = UpsertRecordsInput {
+            domain: "example.com",
+            records: &records,
+        };
+
+        let results = ShortBatchProvider
+            .upsert_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(
+            matches!(&results[1], Err(ProviderError::MissingResult(domain)) if domain == "two.example.com")
+        );
+    }
+
+    #[test]
+    fn test_error_body_is_trimmed_and_cut() {
+        let body = truncate_error_body(&format!("\n{}\n", "x".repeat(MAX_ERROR_BODY_LENGTH + 1)));
+        assert_eq!(body, format!("{}...", "x".repeat(MAX_ERROR_BODY_LENGTH)));
+    }
+
+    #[test]
+    fn test_get_provider_not_found() {
+        let config = Config::default();
+        let result = get_provider("NonExistent", &config);
+        assert!(matches!(result, Err(ProviderError::NotConfigured(name)) if name == "NonExistent"));
+    }
 }
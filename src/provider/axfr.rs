@@ -0,0 +1,544 @@
+use std::{
+    io,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use hickory_resolver::proto::{
+    ProtoError,
+    op::{Message, MessageType, OpCode, Query, ResponseCode},
+    rr::{Name, RData, Record as HickoryRecord, RecordType as HickoryRecordType, rdata::TXT},
+    serialize::binary::BinEncodable,
+};
+use hmac::{Hmac, Mac};
+use lum_log::debug;
+use sha2::Sha256;
+use thiserror::Error;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::{
+    http::HttpClient,
+    provider::{
+        AddRecordInput, DeleteRecordInput, Feature, GetAllRecordsInput, Provider, ProviderError,
+        Result, UpdateRecordInput, naming,
+    },
+    types::dns::{self, RecordType, RecordValue},
+    zone::{self, ZoneError},
+};
+
+pub mod config;
+
+pub use config::Config;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Port of the server if the configured one has none.
+pub const DEFAULT_PORT: u16 = 53;
+
+/// How long a whole zone transfer may take.
+pub const TRANSFER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Allowed clock difference of a TSIG signature in seconds, as recommended by RFC 8945.
+const TSIG_FUDGE: u16 = 300;
+
+const TSIG_ALGORITHM: &str = "hmac-sha256.";
+
+/// DNS class ANY, used for TSIG records.
+const CLASS_ANY: u16 = 255;
+
+/// Record type code of TSIG records.
+const TYPE_TSIG: u16 = 250;
+
+pub struct AxfrProvider<'provider_config> {
+    pub provider_config: &'provider_config Config,
+}
+
+impl<'provider_config> AxfrProvider<'provider_config> {
+    pub fn new(provider_config: &'provider_config Config) -> AxfrProvider<'provider_config> {
+        AxfrProvider { provider_config }
+    }
+
+    /// Returns the TSIG key to sign the transfer request with, if one is configured.
+    fn tsig_key(&self) -> std::result::Result<Option<TsigKey>, Error> {
+        let config = self.provider_config;
+        match (&config.tsig_key_name, &config.tsig_secret) {
+            (None, None) => Ok(None),
+            (Some(name), Some(secret)) => Ok(Some(TsigKey {
+                name: Name::from_ascii(dns::absolute_name(name))?,
+                secret: decode_base64(secret.as_str()).ok_or(Error::InvalidTsigSecret)?,
+            })),
+            _ => Err(Error::IncompleteTsig),
+        }
+    }
+
+    /// Transfers the configured zone and converts its records.
+    async fn fetch_records(&self) -> std::result::Result<Vec<dns::Record>, Error> {
+        let zone = &self.provider_config.zone;
+        let zone_name = Name::from_ascii(dns::absolute_name(zone))?;
+        let tsig_key = self.tsig_key()?;
+        let address = server_address(&self.provider_config.server);
+
+        let transfer = async {
+            let mut stream = TcpStream::connect(&address).await?;
+            transfer(&mut stream, &zone_name, tsig_key.as_ref()).await
+        };
+        let records = tokio::time::timeout(TRANSFER_TIMEOUT, transfer)
+            .await
+            .map_err(|_| Error::Timeout(TRANSFER_TIMEOUT))??;
+        debug!(
+            "Transferred {} records of {} from {}",
+            records.len(),
+            zone,
+            address
+        );
+
+        let mut converted = Vec::new();
+        for record in records.iter() {
+            if let Some(record) = to_record(record, zone)? {
+                converted.push(record);
+            }
+        }
+
+        Ok(converted)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Connection to the server failed: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Invalid DNS message: {0}")]
+    Proto(#[from] ProtoError),
+
+    #[error("Server refused the transfer: {0}")]
+    Refused(ResponseCode),
+
+    #[error("Transfer ended before the closing SOA record")]
+    Incomplete,
+
+    #[error("Transfer did not finish within {0:?}")]
+    Timeout(Duration),
+
+    #[error("TSIG secret is not valid base64")]
+    InvalidTsigSecret,
+
+    #[error("TSIG needs both tsig_key_name and tsig_secret")]
+    IncompleteTsig,
+
+    #[error("Invalid record '{record}': {error}")]
+    InvalidRecord { record: String, error: ZoneError },
+
+    #[error("Invalid record: {0}")]
+    InvalidValue(#[from] dns::RecordValidationError),
+}
+
+/// A TSIG key to sign requests with, see RFC 8945.
+#[derive(Debug, Clone)]
+pub struct TsigKey {
+    pub name: Name,
+    pub secret: Vec<u8>,
+}
+
+impl TsigKey {
+    /// Appends a TSIG record signed at `time_signed`, in seconds since the Unix epoch, to the
+    /// encoded `message`. Only HMAC-SHA256 is supported.
+    pub fn sign(
+        &self,
+        message: &mut Vec<u8>,
+        time_signed: u64,
+    ) -> std::result::Result<(), ProtoError> {
+        let key_name = self.name.to_lowercase().to_bytes()?;
+        let algorithm = Name::from_ascii(TSIG_ALGORITHM)?.to_bytes()?;
+        let time_signed = &time_signed.to_be_bytes()[2..];
+
+        let mut variables = key_name.clone();
+        variables.extend(CLASS_ANY.to_be_bytes());
+        variables.extend(0u32.to_be_bytes());
+        variables.extend(&algorithm);
+        variables.extend(time_signed);
+        variables.extend(TSIG_FUDGE.to_be_bytes());
+        // Error and other data length
+        variables.extend([0, 0, 0, 0]);
+
+        let mut mac =
+            HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(message);
+        mac.update(&variables);
+        let mac = mac.finalize().into_bytes();
+
+        let mut rdata = algorithm;
+        rdata.extend(time_signed);
+        rdata.extend(TSIG_FUDGE.to_be_bytes());
+        rdata.extend((mac.len() as u16).to_be_bytes());
+        rdata.extend(mac);
+        // Original id, error and other data length
+        rdata.extend(&message[..2]);
+        rdata.extend([0, 0, 0, 0]);
+
+        message.extend(key_name);
+        message.extend(TYPE_TSIG.to_be_bytes());
+        message.extend(CLASS_ANY.to_be_bytes());
+        message.extend(0u32.to_be_bytes());
+        message.extend((rdata.len() as u16).to_be_bytes());
+        message.extend(rdata);
+
+        let additional_count = u16::from_be_bytes([message[10], message[11]]) + 1;
+        message[10..12].copy_from_slice(&additional_count.to_be_bytes());
+
+        Ok(())
+    }
+}
+
+/// Decodes standard base64 with padding, as TSIG secrets are given. Returns `None` for invalid
+/// input.
+fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut bytes = Vec::with_capacity(encoded.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for character in encoded.trim().trim_end_matches('=').bytes() {
+        let value = ALPHABET.iter().position(|&symbol| symbol == character)?;
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    (!bytes.is_empty()).then_some(bytes)
+}
+
+/// Returns the address to connect to for `server`, adding the [`DEFAULT_PORT`] if it has no
+/// port.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::axfr::server_address;
+///
+/// assert_eq!(server_address("ns1.example.com"), "ns1.example.com:53");
+/// assert_eq!(server_address("192.0.2.1:5353"), "192.0.2.1:5353");
+/// assert_eq!(server_address("2001:db8::1"), "[2001:db8::1]:53");
+/// ```
+pub fn server_address(server: &str) -> String {
+    match server.parse::<IpAddr>() {
+        Ok(ip) => SocketAddr::new(ip, DEFAULT_PORT).to_string(),
+        Err(_) if server.contains(':') => server.to_string(),
+        Err(_) => format!("{}:{}", server, DEFAULT_PORT),
+    }
+}
+
+/// Requests the transfer of `zone` on `stream`, signed with `tsig_key` if given, and returns the
+/// transferred records. The SOA record closing the transfer is not included.
+pub async fn transfer<S>(
+    stream: &mut S,
+    zone: &Name,
+    tsig_key: Option<&TsigKey>,
+) -> std::result::Result<Vec<HickoryRecord>, Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut query = Message::new();
+    query
+        .set_id(fastrand::u16(..))
+        .set_message_type(MessageType::Query)
+        .set_op_code(OpCode::Query)
+        .add_query(Query::query(zone.clone(), HickoryRecordType::AXFR));
+    let mut query = query.to_vec()?;
+    if let Some(tsig_key) = tsig_key {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        tsig_key.sign(&mut query, now)?;
+    }
+
+    stream.write_u16(query.len() as u16).await?;
+    stream.write_all(&query).await?;
+
+    let mut records = Vec::new();
+    let mut soa_count = 0;
+    loop {
+        let length = match stream.read_u16().await {
+            Ok(length) => length,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Err(Error::Incomplete),
+            Err(e) => return Err(e.into()),
+        };
+        let mut buffer = vec![0; length as usize];
+        stream.read_exact(&mut buffer).await?;
+
+        let message = Message::from_vec(&buffer)?;
+        if message.response_code() != ResponseCode::NoError {
+            return Err(Error::Refused(message.response_code()));
+        }
+        if message.answers().is_empty() {
+            return Err(Error::Incomplete);
+        }
+
+        for record in message.answers() {
+            if record.record_type() == HickoryRecordType::SOA {
+                soa_count += 1;
+                if soa_count == 2 {
+                    return Ok(records);
+                }
+            }
+            records.push(record.clone());
+        }
+    }
+}
+
+/// Returns TXT data as one string, or as quoted strings (`"a" "b"`) if it consists of several.
+fn text_value(txt: &TXT) -> String {
+    let chunks: Vec<String> = txt
+        .iter()
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect();
+    if let [chunk] = chunks.as_slice() {
+        return chunk.clone();
+    }
+
+    chunks
+        .iter()
+        .map(|chunk| format!("\"{}\"", chunk.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Converts a transferred record of `zone`. Records of types dnrs does not handle, like DNSSEC
+/// signatures, are skipped.
+pub fn to_record(
+    record: &HickoryRecord,
+    zone: &str,
+) -> std::result::Result<Option<dns::Record>, Error> {
+    if RecordType::from_str(&record.record_type().to_string()).is_err() {
+        debug!("Skipping unsupported record {}", record);
+        return Ok(None);
+    }
+
+    if let RData::TXT(txt) = record.data() {
+        let value = RecordValue::TXT(text_value(txt));
+        let record = dns::Record::builder(record.name().to_string(), value)
+            .ttl(Some(record.ttl()))
+            .build()?;
+        return Ok(Some(record));
+    }
+
+    let line = record.to_string();
+    let parsed = zone::parse(&line, zone).map_err(|error| Error::InvalidRecord {
+        record: line.clone(),
+        error,
+    })?;
+
+    Ok(parsed.into_iter().next())
+}
+
+#[async_trait]
+impl Provider for AxfrProvider<'_> {
+    fn get_provider_name(&self) -> &'static str {
+        "AXFR"
+    }
+
+    fn get_supported_features(&self) -> Vec<Feature> {
+        vec![Feature::GetRecords, Feature::GetAllRecords]
+    }
+
+    /// Gets the records of `input.domain` from a transfer of the configured zone.
+    async fn get_all_records(
+        &self,
+        _http: &dyn HttpClient,
+        input: &GetAllRecordsInput,
+    ) -> Result<Vec<dns::Record>> {
+        if !naming::is_within(input.domain, &self.provider_config.zone) {
+            return Err(ProviderError::DomainNotFound(input.domain.to_string()));
+        }
+
+        let mut records = self.fetch_records().await?;
+        records.retain(|record| naming::is_within(&record.domain, input.domain));
+
+        Ok(records)
+    }
+
+    async fn add_record(&self, _http: &dyn HttpClient, _input: &AddRecordInput) -> Result<()> {
+        Err(ProviderError::UnsupportedFeature(
+            Feature::AddRecord,
+            self.get_provider_name(),
+        ))
+    }
+
+    async fn update_record(
+        &self,
+        _http: &dyn HttpClient,
+        _input: &UpdateRecordInput,
+    ) -> Result<()> {
+        Err(ProviderError::UnsupportedFeature(
+            Feature::UpdateRecord,
+            self.get_provider_name(),
+        ))
+    }
+
+    async fn delete_record(
+        &self,
+        _http: &dyn HttpClient,
+        _input: &DeleteRecordInput,
+    ) -> Result<()> {
+        Err(ProviderError::UnsupportedFeature(
+            Feature::DeleteRecord,
+            self.get_provider_name(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hickory_resolver::proto::rr::rdata::{A, MX, SOA};
+    use std::net::Ipv4Addr;
+
+    fn name(name: &str) -> Name {
+        Name::from_ascii(name).unwrap()
+    }
+
+    fn soa() -> HickoryRecord {
+        let soa = SOA::new(
+            name("ns1.example.com."),
+            name("admin.example.com."),
+            2024010101,
+            7200,
+            3600,
+            1209600,
+            300,
+        );
+        HickoryRecord::from_rdata(name("example.com."), 3600, RData::SOA(soa))
+    }
+
+    fn response(id: u16, answers: Vec<HickoryRecord>) -> Vec<u8> {
+        let mut message = Message::new();
+        message
+            .set_id(id)
+            .set_message_type(MessageType::Response)
+            .add_answers(answers);
+        message.to_vec().unwrap()
+    }
+
+    async fn write_message<S: AsyncWrite + Unpin>(stream: &mut S, message: &[u8]) {
+        stream.write_u16(message.len() as u16).await.unwrap();
+        stream.write_all(message).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_transfer_reads_records_of_every_message() {
+        let (mut client, mut server) = tokio::io::duplex(64 * 1024);
+
+        let server = tokio::spawn(async move {
+            let length = server.read_u16().await.unwrap();
+            let mut query = vec![0; length as usize];
+            server.read_exact(&mut query).await.unwrap();
+            let query = Message::from_vec(&query).unwrap();
+            assert_eq!(query.queries()[0].query_type(), HickoryRecordType::AXFR);
+            assert_eq!(query.queries()[0].name(), &name("example.com."));
+
+            let www = HickoryRecord::from_rdata(
+                name("www.example.com."),
+                300,
+                RData::A(A(Ipv4Addr::new(1, 2, 3, 4))),
+            );
+            let txt = HickoryRecord::from_rdata(
+                name("example.com."),
+                300,
+                RData::TXT(TXT::new(vec![
+                    "v=DKIM1; ".to_string(),
+                    "p=MIGf".to_string(),
+                ])),
+            );
+            let mx = HickoryRecord::from_rdata(
+                name("example.com."),
+                3600,
+                RData::MX(MX::new(10, name("mail.example.com."))),
+            );
+            write_message(&mut server, &response(query.id(), vec![soa(), www, txt])).await;
+            write_message(&mut server, &response(query.id(), vec![mx, soa()])).await;
+        });
+
+        let records = transfer(&mut client, &name("example.com."), None)
+            .await
+            .unwrap();
+        server.await.unwrap();
+
+        let records: Vec<String> = records
+            .iter()
+            .filter_map(|record| to_record(record, "example.com").unwrap())
+            .map(|record| record.to_string())
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                "example.com. 3600 IN SOA ns1.example.com. admin.example.com. 2024010101 7200 3600 1209600 300",
+                "www.example.com. 300 IN A 1.2.3.4",
+                "example.com. 300 IN TXT \"v=DKIM1; \" \"p=MIGf\"",
+                "example.com. 3600 IN MX 10 mail.example.com.",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transfer_reports_refusal_and_early_end() {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+        tokio::spawn(async move {
+            let length = server.read_u16().await.unwrap();
+            let mut query = vec![0; length as usize];
+            server.read_exact(&mut query).await.unwrap();
+
+            let mut refused = Message::new();
+            refused
+                .set_message_type(MessageType::Response)
+                .set_response_code(ResponseCode::Refused);
+            write_message(&mut server, &refused.to_vec().unwrap()).await;
+        });
+        let result = transfer(&mut client, &name("example.com."), None).await;
+        assert!(matches!(result, Err(Error::Refused(ResponseCode::Refused))));
+
+        let (mut client, mut server) = tokio::io::duplex(1024);
+        tokio::spawn(async move {
+            let length = server.read_u16().await.unwrap();
+            let mut query = vec![0; length as usize];
+            server.read_exact(&mut query).await.unwrap();
+            write_message(&mut server, &response(0, vec![soa()])).await;
+        });
+        let result = transfer(&mut client, &name("example.com."), None).await;
+        assert!(matches!(result, Err(Error::Incomplete)));
+    }
+
+    #[test]
+    fn test_tsig_record_is_appended() {
+        let key = TsigKey {
+            name: name("transfer.example.com."),
+            secret: decode_base64("c2VjcmV0LWtleQ==").unwrap(),
+        };
+        assert_eq!(key.secret, b"secret-key");
+
+        let mut query = Message::new();
+        query
+            .set_id(42)
+            .add_query(Query::query(name("example.com."), HickoryRecordType::AXFR));
+        let mut bytes = query.to_vec().unwrap();
+        key.sign(&mut bytes, 1_700_000_000).unwrap();
+
+        let signed = Message::from_vec(&bytes).unwrap();
+        assert_eq!(signed.id(), 42);
+        assert_eq!(signed.queries().len(), 1);
+        assert_eq!(signed.header().additional_count(), 1);
+
+        let mut other = query.to_vec().unwrap();
+        key.sign(&mut other, 1_700_000_000).unwrap();
+        assert_eq!(bytes, other);
+        assert!(decode_base64("not base64!").is_none());
+    }
+}
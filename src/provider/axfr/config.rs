@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use lum_libs::serde::{Deserialize, Serialize};
+
+use crate::config::secret::Secret;
+
+/// A read-only provider transferring a zone from its authoritative server, to verify what is
+/// actually served.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Config {
+    pub name: String,
+
+    /// Authoritative server to transfer the zone from, `host` or `host:port`
+    pub server: String,
+
+    /// Zone to transfer
+    pub zone: String,
+
+    /// Name of the TSIG key the transfer request is signed with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tsig_key_name: Option<String>,
+
+    /// Base64 secret of the HMAC-SHA256 TSIG key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tsig_secret: Option<Secret>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            name: "Axfr1".to_string(),
+            server: "ns1.example.com".to_string(),
+            zone: "example.com".to_string(),
+            tsig_key_name: None,
+            tsig_secret: None,
+            source_path: None,
+        }
+    }
+}
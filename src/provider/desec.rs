@@ -0,0 +1,497 @@
+use async_trait::async_trait;
+use lum_libs::serde_json;
+use reqwest::{
+    Method, StatusCode,
+    header::{AUTHORIZATION, CONTENT_TYPE, HeaderValue},
+};
+use thiserror::Error;
+
+use crate::{
+    http::{HttpClient, HttpRequest},
+    provider::{
+        self, AddRecordInput, AddRecordsInput, DeleteRecordInput, Feature, GetAllRecordsInput,
+        Provider, ProviderError, Result, UpdateRecordInput, UpdateRecordsInput,
+        naming::RecordNaming,
+    },
+    rate_limit::RateLimiter,
+    types::dns::{self, RecordType},
+};
+
+pub mod config;
+pub mod model;
+
+pub use config::{Config, DnsConfig, DomainConfig};
+pub use model::{RRset, TryFromRecordError};
+
+pub struct DesecProvider<'provider_config> {
+    pub provider_config: &'provider_config Config,
+    pub rate_limiter: RateLimiter,
+}
+
+impl<'provider_config> DesecProvider<'provider_config> {
+    pub fn new(provider_config: &'provider_config Config) -> DesecProvider<'provider_config> {
+        DesecProvider {
+            provider_config,
+            rate_limiter: RateLimiter::new(provider_config.requests_per_second),
+        }
+    }
+
+    fn rrsets_url(&self, domain: &str) -> String {
+        format!(
+            "{}/domains/{}/rrsets/",
+            self.provider_config.api_base_url, domain
+        )
+    }
+
+    async fn send(
+        &self,
+        http: &dyn HttpClient,
+        method: Method,
+        domain: &str,
+        body: Option<String>,
+    ) -> Result<String> {
+        let token = format!("Token {}", self.provider_config.token.as_str());
+        let token = HeaderValue::from_str(&token).map_err(|_| Error::InvalidToken)?;
+        let mut request =
+            HttpRequest::new(method, self.rrsets_url(domain)).header(AUTHORIZATION, token);
+        if let Some(body) = body {
+            request = request
+                .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+                .body(body);
+        }
+
+        self.rate_limiter.acquire().await;
+        let response = http.send(request).await?;
+
+        if response.status == StatusCode::NOT_FOUND {
+            return Err(ProviderError::DomainNotFound(domain.to_string()));
+        }
+
+        if !response.is_success() {
+            return Err(Error::Unsuccessful {
+                status: response.status.as_u16(),
+                body: response.error_body(),
+            }
+            .into());
+        }
+
+        Ok(response.body)
+    }
+
+    async fn list_rrsets(&self, http: &dyn HttpClient, domain: &str) -> Result<Vec<RRset>> {
+        let text = self.send(http, Method::GET, domain, None).await?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Replaces the given RRsets in a single bulk request. Empty RRsets are deleted.
+    async fn patch_rrsets(
+        &self,
+        http: &dyn HttpClient,
+        domain: &str,
+        rrsets: &[RRset],
+    ) -> Result<()> {
+        let body = serde_json::to_string(rrsets)?;
+        self.send(http, Method::PATCH, domain, Some(body)).await?;
+
+        Ok(())
+    }
+
+    /// Adds `records` to their RRsets, keeping values that already exist.
+    async fn add_to_rrsets(
+        &self,
+        http: &dyn HttpClient,
+        domain: &str,
+        records: &[dns::Record],
+    ) -> Result<()> {
+        let existing = self.list_rrsets(http, domain).await?;
+
+        let mut rrsets = model::group_records(records, domain);
+        for rrset in rrsets.iter_mut() {
+            let current = existing
+                .iter()
+                .find(|existing| existing.matches(&rrset.subname, rrset.r#type));
+
+            if let Some(current) = current {
+                let mut values = current.records.clone();
+                for value in rrset.records.drain(..) {
+                    if !values.contains(&value) {
+                        values.push(value);
+                    }
+                }
+                rrset.records = values;
+                rrset.ttl = rrset.ttl.or(current.ttl);
+            }
+            rrset.ttl = rrset.ttl.or(Some(model::DEFAULT_TTL));
+        }
+
+        self.patch_rrsets(http, domain, &rrsets).await
+    }
+
+    /// Replaces the RRsets of `records` with exactly the given values, so updating one of several
+    /// TXT records of a name drops the others unless they are part of the same update.
+    async fn replace_rrsets(
+        &self,
+        http: &dyn HttpClient,
+        domain: &str,
+        records: &[dns::Record],
+    ) -> Result<()> {
+        let mut rrsets = model::group_records(records, domain);
+        for rrset in rrsets.iter_mut() {
+            rrset.ttl = rrset.ttl.or(Some(model::DEFAULT_TTL));
+        }
+
+        self.patch_rrsets(http, domain, &rrsets).await
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("HTTP request failed: {0}")]
+    Reqwest(#[from] reqwest::Error),
+
+    #[error("HTTP response is not successful: {status}, body: {body:?}")]
+    Unsuccessful { status: u16, body: String },
+
+    #[error("JSON parsing error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid record in response: {0}")]
+    TryFromRecord(#[from] TryFromRecordError),
+
+    #[error("Invalid token: contains characters that are not allowed in HTTP headers")]
+    InvalidToken,
+}
+
+#[async_trait]
+impl Provider for DesecProvider<'_> {
+    fn get_provider_name(&self) -> &'static str {
+        "deSEC"
+    }
+
+    fn record_naming(&self) -> RecordNaming {
+        RecordNaming::Relative { apex: "" }
+    }
+
+    /// deSEC accepts TTLs of up to a week. The minimum is set per domain, so it is left to the
+    /// API.
+    fn ttl_bounds(&self) -> (Option<u32>, Option<u32>) {
+        (None, Some(604800))
+    }
+
+    fn get_supported_features(&self) -> Vec<Feature> {
+        vec![
+            Feature::GetRecords,
+            Feature::GetAllRecords,
+            Feature::AddRecord,
+            Feature::UpdateRecord,
+            Feature::DeleteRecord,
+            Feature::BatchWrite,
+        ]
+    }
+
+    /// The SOA record is managed by deSEC.
+    fn supported_record_types(&self) -> &'static [RecordType] {
+        &[
+            RecordType::A,
+            RecordType::AAAA,
+            RecordType::CNAME,
+            RecordType::TXT,
+            RecordType::MX,
+            RecordType::NS,
+            RecordType::SRV,
+            RecordType::TLSA,
+            RecordType::CAA,
+            RecordType::SVCB,
+            RecordType::HTTPS,
+            RecordType::DS,
+            RecordType::DNSKEY,
+        ]
+    }
+
+    async fn get_all_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &GetAllRecordsInput,
+    ) -> Result<Vec<dns::Record>> {
+        let rrsets = self.list_rrsets(http, input.domain).await?;
+        let records = model::records_of(&rrsets, input.domain).map_err(Error::from)?;
+
+        Ok(records)
+    }
+
+    async fn add_record(&self, http: &dyn HttpClient, input: &AddRecordInput) -> Result<()> {
+        self.validate_records(std::slice::from_ref(input.record))?;
+        let records = std::slice::from_ref(input.record);
+        self.add_to_rrsets(http, input.domain, records).await
+    }
+
+    async fn add_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &AddRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        self.validate_records(input.records)?;
+        self.add_to_rrsets(http, input.domain, input.records)
+            .await?;
+        Ok(input.records.iter().map(|_| Ok(())).collect())
+    }
+
+    async fn update_record(&self, http: &dyn HttpClient, input: &UpdateRecordInput) -> Result<()> {
+        self.validate_records(std::slice::from_ref(input.record))?;
+        let records = std::slice::from_ref(input.record);
+        self.replace_rrsets(http, input.domain, records).await
+    }
+
+    async fn update_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &UpdateRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        self.validate_records(input.records)?;
+        self.replace_rrsets(http, input.domain, input.records)
+            .await?;
+        Ok(input.records.iter().map(|_| Ok(())).collect())
+    }
+
+    /// Removes the value from its RRset, which deSEC deletes once it is empty.
+    async fn delete_record(&self, http: &dyn HttpClient, input: &DeleteRecordInput) -> Result<()> {
+        let record = input.record;
+        let subname = model::subname(&record.domain, input.domain);
+        let value = model::value_of(&record.value);
+
+        let existing = self.list_rrsets(http, input.domain).await?;
+        let mut rrset = match existing.into_iter().find(|rrset| {
+            rrset.matches(&subname, record.value.wire_type()) && rrset.records.contains(&value)
+        }) {
+            Some(rrset) => rrset,
+            None => return provider::already_absent(self.get_provider_name(), record),
+        };
+
+        rrset.records.retain(|existing| *existing != value);
+        self.patch_rrsets(http, input.domain, &[rrset]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dns::RecordValue;
+    use lum_libs::serde_json::json;
+    use std::net::Ipv4Addr;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_json, header, method, path},
+    };
+
+    fn config(server: &MockServer) -> Config {
+        Config {
+            token: "test_token".into(),
+            api_base_url: server.uri(),
+            ..Default::default()
+        }
+    }
+
+    fn a_record(ip: Ipv4Addr) -> dns::Record {
+        dns::Record {
+            domain: "www.example.com".to_string(),
+            value: RecordValue::A(ip),
+            ttl: None,
+        }
+    }
+
+    fn existing_rrsets() -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(json!([{
+            "domain": "example.com",
+            "subname": "www",
+            "name": "www.example.com.",
+            "type": "A",
+            "records": ["1.1.1.1"],
+            "ttl": 3600,
+        }]))
+    }
+
+    #[tokio::test]
+    async fn test_add_records_groups_into_one_rrset() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/domains/example.com/rrsets/"))
+            .and(header("Authorization", "Token test_token"))
+            .respond_with(existing_rrsets())
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/domains/example.com/rrsets/"))
+            .and(body_json(json!([{
+                "subname": "www",
+                "type": "A",
+                "records": ["1.1.1.1", "2.2.2.2", "3.3.3.3"],
+                "ttl": 3600,
+            }])))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = DesecProvider::new(&config);
+        let records = vec![
+            a_record(Ipv4Addr::new(2, 2, 2, 2)),
+            a_record(Ipv4Addr::new(3, 3, 3, 3)),
+        ];
+        let input = AddRecordsInput {
+            domain: "example.com",
+            records: &records,
+        };
+
+        let results = provider
+            .add_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| result.is_ok()));
+    }
+
+    fn txt_record(domain: &str, text: &str) -> dns::Record {
+        dns::Record {
+            domain: domain.to_string(),
+            value: RecordValue::TXT(text.to_string()),
+            ttl: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_two_txt_records_on_apex_share_one_rrset() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/domains/example.com/rrsets/"))
+            .respond_with(existing_rrsets())
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/domains/example.com/rrsets/"))
+            .and(body_json(json!([{
+                "subname": "",
+                "type": "TXT",
+                "records": ["\"v=spf1 -all\"", "\"google-site-verification=abc\""],
+                "ttl": 3600,
+            }])))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = DesecProvider::new(&config);
+        let records = vec![
+            txt_record("@", "v=spf1 -all"),
+            txt_record("example.com", "google-site-verification=abc"),
+        ];
+        let input = AddRecordsInput {
+            domain: "example.com",
+            records: &records,
+        };
+
+        let results = provider
+            .add_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+        assert!(results.iter().all(|result| result.is_ok()));
+    }
+
+    #[tokio::test]
+    async fn test_second_txt_record_on_apex_keeps_first() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/domains/example.com/rrsets/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "domain": "example.com",
+                "subname": "",
+                "name": "example.com.",
+                "type": "TXT",
+                "records": ["\"v=spf1 -all\""],
+                "ttl": 3600,
+            }])))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/domains/example.com/rrsets/"))
+            .and(body_json(json!([{
+                "subname": "",
+                "type": "TXT",
+                "records": ["\"v=spf1 -all\"", "\"part one\" \"part two\""],
+                "ttl": 3600,
+            }])))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = DesecProvider::new(&config);
+        let record = txt_record("@", "\"part one\" \"part two\"");
+        let input = AddRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        provider
+            .add_record(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete_last_value_empties_rrset() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/domains/example.com/rrsets/"))
+            .respond_with(existing_rrsets())
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/domains/example.com/rrsets/"))
+            .and(body_json(json!([{
+                "subname": "www",
+                "type": "A",
+                "records": [],
+                "ttl": 3600,
+            }])))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = DesecProvider::new(&config);
+        let record = a_record(Ipv4Addr::new(1, 1, 1, 1));
+        let input = DeleteRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        provider
+            .delete_record(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unknown_domain_is_domain_not_found() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = DesecProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let result = provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await;
+        assert!(matches!(result, Err(ProviderError::DomainNotFound(_))));
+    }
+}
@@ -0,0 +1,396 @@
+use core::num;
+use std::{
+    net::{self, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use lum_libs::serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    provider::naming::RecordNaming,
+    types::dns::{
+        self, DnssecParseError, MxRecord, RecordType, RecordValue, SvcbParseError, SvcbRecord,
+        txt_chunks,
+    },
+};
+
+/// deSEC's minimum TTL, used for new RRsets if the record does not specify one.
+pub const DEFAULT_TTL: u32 = 3600;
+
+/// A deSEC RRset: all records of one subname and type.
+///
+/// `subname` is relative to the domain, the apex is the empty string.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct RRset {
+    pub subname: String,
+    pub r#type: RecordType,
+    pub records: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+impl RRset {
+    /// Returns whether this RRset holds the records of `subname` and `r#type`.
+    pub fn matches(&self, subname: &str, r#type: RecordType) -> bool {
+        self.subname == subname && self.r#type == r#type
+    }
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum TryFromRecordError {
+    #[error("Invalid IP address: {0}")]
+    InvalidIp(#[from] net::AddrParseError),
+
+    #[error("Invalid MX record format: {0}")]
+    InvalidMxFormat(String),
+
+    #[error("Invalid priority in MX record: {0}")]
+    InvalidMxPriority(num::ParseIntError),
+
+    #[error("Invalid SRV record format: {0}")]
+    InvalidSrvFormat(String),
+
+    #[error("Invalid SRV record priority/weight/port: {0}")]
+    InvalidSrvValue(num::ParseIntError),
+
+    #[error("Invalid TLSA record format: {0}")]
+    InvalidTlsaFormat(String),
+
+    #[error("Invalid TLSA record usage/selector/matching type: {0}")]
+    InvalidTlsaValue(num::ParseIntError),
+
+    #[error("Invalid CAA record format: {0}")]
+    InvalidCaaFormat(String),
+
+    #[error("Invalid CAA record flag: {0}")]
+    InvalidCaaFlag(num::ParseIntError),
+
+    #[error("Invalid SVCB/HTTPS record: {0}")]
+    InvalidSvcb(#[from] SvcbParseError),
+    #[error("Invalid DNSSEC record: {0}")]
+    InvalidDnssec(#[from] DnssecParseError),
+
+    #[error("Invalid record: {0}")]
+    InvalidRecord(#[from] dns::RecordValidationError),
+}
+
+/// Returns the subname of `name` within `domain`, the empty string for the apex.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::desec::model::subname;
+///
+/// assert_eq!(subname("www.example.com", "example.com"), "www");
+/// assert_eq!(subname("example.com", "example.com"), "");
+/// ```
+pub fn subname(name: &str, domain: &str) -> String {
+    RecordNaming::Relative { apex: "" }.to_provider_name(name, domain)
+}
+
+/// Returns the fully qualified name of `subname` within `domain`.
+pub fn full_name(subname: &str, domain: &str) -> String {
+    RecordNaming::Relative { apex: "" }.to_full_name(subname, domain)
+}
+
+fn absolute(name: &str) -> String {
+    format!("{}.", name.trim_end_matches('.'))
+}
+
+fn quote(text: &str) -> String {
+    if text.starts_with('"') && text.ends_with('"') && text.len() >= 2 {
+        text.to_string()
+    } else {
+        format!("\"{}\"", text.replace('"', "\\\""))
+    }
+}
+
+/// Removes the quotes of a single quoted string. Content of several quoted strings is kept as it
+/// is, that is how [`RecordValue::TXT`] holds it.
+fn unquote(text: &str) -> String {
+    if txt_chunks(text).len() > 1 {
+        return text.to_string();
+    }
+
+    match text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+    {
+        Some(text) => text.replace("\\\"", "\""),
+        None => text.to_string(),
+    }
+}
+
+/// Formats a record value as an entry of an RRset's `records`.
+///
+/// deSEC expects quoted TXT content and absolute target names.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::desec::model::value_of;
+/// use dnrs::types::dns::RecordValue;
+///
+/// assert_eq!(value_of(&RecordValue::TXT("hello".to_string())), "\"hello\"");
+/// assert_eq!(value_of(&RecordValue::CNAME("target.example.com".to_string())), "target.example.com.");
+/// ```
+pub fn value_of(value: &RecordValue) -> String {
+    match value {
+        RecordValue::TXT(text) | RecordValue::SPF(text) => quote(text),
+        RecordValue::CNAME(target) | RecordValue::NS(target) => absolute(target),
+        RecordValue::MX(mx) => format!("{} {}", mx.priority, absolute(&mx.target)),
+        RecordValue::SRV(priority, weight, port, target) => {
+            format!("{} {} {} {}", priority, weight, port, absolute(target))
+        }
+        RecordValue::CAA(flag, tag, value) => format!("{} {} {}", flag, tag, quote(value)),
+        RecordValue::SVCB(_) | RecordValue::HTTPS(_) => value.to_string(),
+        _ => value.content(),
+    }
+}
+
+fn relative(name: &str) -> String {
+    name.trim_end_matches('.').to_string()
+}
+
+fn split_parts(value: &str, count: usize) -> Option<Vec<&str>> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    (parts.len() == count).then_some(parts)
+}
+
+/// Parses a single entry of an RRset's `records`.
+pub fn parse_value(r#type: RecordType, value: &str) -> Result<RecordValue, TryFromRecordError> {
+    let value = match r#type {
+        RecordType::A => RecordValue::A(Ipv4Addr::from_str(value)?),
+        RecordType::AAAA => RecordValue::AAAA(Ipv6Addr::from_str(value)?),
+        RecordType::CNAME => RecordValue::CNAME(relative(value)),
+        RecordType::TXT => RecordValue::TXT(unquote(value)),
+        RecordType::SPF => RecordValue::SPF(unquote(value)),
+        RecordType::NS => RecordValue::NS(relative(value)),
+        RecordType::SOA => RecordValue::SOA(value.to_string()),
+        RecordType::MX => {
+            let parts = split_parts(value, 2)
+                .ok_or_else(|| TryFromRecordError::InvalidMxFormat(value.to_string()))?;
+            let priority = parts[0]
+                .parse::<u16>()
+                .map_err(TryFromRecordError::InvalidMxPriority)?;
+
+            RecordValue::MX(MxRecord {
+                priority,
+                target: relative(parts[1]),
+            })
+        }
+        RecordType::SRV => {
+            let parts = split_parts(value, 4)
+                .ok_or_else(|| TryFromRecordError::InvalidSrvFormat(value.to_string()))?;
+            let numbers = parts[..3]
+                .iter()
+                .map(|part| part.parse::<u16>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(TryFromRecordError::InvalidSrvValue)?;
+
+            RecordValue::SRV(numbers[0], numbers[1], numbers[2], relative(parts[3]))
+        }
+        RecordType::TLSA => {
+            let parts = split_parts(value, 4)
+                .ok_or_else(|| TryFromRecordError::InvalidTlsaFormat(value.to_string()))?;
+            let numbers = parts[..3]
+                .iter()
+                .map(|part| part.parse::<u16>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(TryFromRecordError::InvalidTlsaValue)?;
+
+            RecordValue::TLSA(numbers[0], numbers[1], numbers[2], parts[3].to_string())
+        }
+        RecordType::CAA => {
+            let mut parts = value.splitn(3, ' ');
+            let (Some(flag), Some(tag), Some(caa_value)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                return Err(TryFromRecordError::InvalidCaaFormat(value.to_string()));
+            };
+            let flag = flag
+                .parse::<u8>()
+                .map_err(TryFromRecordError::InvalidCaaFlag)?;
+
+            RecordValue::CAA(flag, tag.to_string(), unquote(caa_value))
+        }
+        RecordType::DS => RecordValue::parse_ds(value)?,
+        RecordType::DNSKEY => RecordValue::parse_dnskey(value)?,
+        RecordType::SVCB | RecordType::HTTPS => {
+            let mut svcb = SvcbRecord::from_str(value)?;
+            if svcb.target != "." {
+                svcb.target = relative(&svcb.target);
+            }
+
+            match r#type {
+                RecordType::SVCB => RecordValue::SVCB(svcb),
+                _ => RecordValue::HTTPS(svcb),
+            }
+        }
+    };
+
+    Ok(value)
+}
+
+/// Converts the RRsets of `domain` into one [`dns::Record`] per RRset entry.
+pub fn records_of(rrsets: &[RRset], domain: &str) -> Result<Vec<dns::Record>, TryFromRecordError> {
+    let mut records = Vec::new();
+    for rrset in rrsets {
+        for value in rrset.records.iter() {
+            let value = parse_value(rrset.r#type, value)?;
+            let record = dns::Record::builder(full_name(&rrset.subname, domain), value)
+                .ttl(rrset.ttl)
+                .build()?;
+            records.push(record);
+        }
+    }
+
+    Ok(records)
+}
+
+/// Groups records of `domain` into RRsets by subname and type, keeping the input order.
+///
+/// The TTL of an RRset is the first TTL given by one of its records. Duplicate values are
+/// only included once.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::desec::model::group_records;
+/// use dnrs::types::dns::{Record, RecordValue};
+/// use std::net::Ipv4Addr;
+///
+/// let records = vec![
+///     Record { domain: "www.example.com".to_string(), value: RecordValue::A(Ipv4Addr::new(1, 1, 1, 1)), ttl: None },
+///     Record { domain: "www.example.com".to_string(), value: RecordValue::A(Ipv4Addr::new(2, 2, 2, 2)), ttl: None },
+/// ];
+///
+/// let rrsets = group_records(&records, "example.com");
+/// assert_eq!(rrsets.len(), 1);
+/// assert_eq!(rrsets[0].records, vec!["1.1.1.1", "2.2.2.2"]);
+/// ```
+pub fn group_records(records: &[dns::Record], domain: &str) -> Vec<RRset> {
+    let mut rrsets: Vec<RRset> = Vec::new();
+    for record in records {
+        let subname = subname(&record.domain, domain);
+        let r#type = record.value.wire_type();
+        let value = value_of(&record.value);
+
+        match rrsets
+            .iter_mut()
+            .find(|rrset| rrset.matches(&subname, r#type))
+        {
+            Some(rrset) => {
+                if !rrset.records.contains(&value) {
+                    rrset.records.push(value);
+                }
+                rrset.ttl = rrset.ttl.or(record.ttl);
+            }
+            None => rrsets.push(RRset {
+                subname,
+                r#type,
+                records: vec![value],
+                ttl: record.ttl,
+            }),
+        }
+    }
+
+    rrsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn a_record(name: &str, ip: Ipv4Addr, ttl: Option<u32>) -> dns::Record {
+        dns::Record {
+            domain: name.to_string(),
+            value: RecordValue::A(ip),
+            ttl,
+        }
+    }
+
+    #[test]
+    fn test_group_two_a_records_on_same_name() {
+        let records = vec![
+            a_record("www.example.com", Ipv4Addr::new(1, 1, 1, 1), None),
+            a_record("mail.example.com", Ipv4Addr::new(3, 3, 3, 3), None),
+            a_record("www.example.com", Ipv4Addr::new(2, 2, 2, 2), Some(7200)),
+        ];
+
+        let rrsets = group_records(&records, "example.com");
+
+        assert_eq!(
+            rrsets,
+            vec![
+                RRset {
+                    subname: "www".to_string(),
+                    r#type: RecordType::A,
+                    records: vec!["1.1.1.1".to_string(), "2.2.2.2".to_string()],
+                    ttl: Some(7200),
+                },
+                RRset {
+                    subname: "mail".to_string(),
+                    r#type: RecordType::A,
+                    records: vec!["3.3.3.3".to_string()],
+                    ttl: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_records_of_flattens_rrsets() {
+        let rrsets = vec![
+            RRset {
+                subname: String::new(),
+                r#type: RecordType::MX,
+                records: vec!["10 mail.example.com.".to_string()],
+                ttl: Some(3600),
+            },
+            RRset {
+                subname: "www".to_string(),
+                r#type: RecordType::A,
+                records: vec!["1.1.1.1".to_string(), "2.2.2.2".to_string()],
+                ttl: Some(3600),
+            },
+        ];
+
+        let records = records_of(&rrsets, "example.com").unwrap();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].domain, "example.com");
+        assert!(
+            matches!(&records[0].value, RecordValue::MX(mx) if mx.target == "mail.example.com")
+        );
+        assert_eq!(records[2].domain, "www.example.com");
+    }
+
+    #[test]
+    fn test_multi_string_txt_round_trips() {
+        let value = RecordValue::TXT(r#""v=DKIM1; k=rsa; " "p=MIGf""#.to_string());
+        let content = value_of(&value);
+        assert_eq!(content, r#""v=DKIM1; k=rsa; " "p=MIGf""#);
+
+        let parsed = parse_value(RecordType::TXT, &content).unwrap();
+        assert_eq!(value_of(&parsed), content);
+
+        let parsed = parse_value(RecordType::TXT, r#""say \"hi\"""#).unwrap();
+        assert!(matches!(parsed, RecordValue::TXT(text) if text == r#"say "hi""#));
+    }
+
+    #[test]
+    fn test_srv_record_round_trips() {
+        let value = RecordValue::SRV(10, 5, 5060, "sip.example.com".to_string());
+        let content = value_of(&value);
+        assert_eq!(content, "10 5 5060 sip.example.com.");
+
+        let parsed = parse_value(RecordType::SRV, &content).unwrap();
+        assert!(matches!(
+            parsed,
+            RecordValue::SRV(10, 5, 5060, ref target) if target == "sip.example.com"
+        ));
+    }
+}
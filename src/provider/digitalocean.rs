@@ -0,0 +1,373 @@
+use async_trait::async_trait;
+use lum_libs::serde_json;
+use reqwest::{
+    Method, StatusCode,
+    header::{AUTHORIZATION, CONTENT_TYPE, HeaderValue},
+};
+use thiserror::Error;
+
+use crate::{
+    http::{HttpClient, HttpRequest},
+    provider::{
+        self, AddRecordInput, DeleteRecordInput, Feature, GetAllRecordsInput, Provider,
+        ProviderError, Result, UpdateRecordInput, naming::RecordNaming,
+    },
+    rate_limit::RateLimiter,
+    types::dns::{self, RecordType},
+};
+
+pub mod config;
+pub mod model;
+
+pub use config::{Config, DnsConfig, DomainConfig};
+pub use model::{ListRecordsResponse, RECORDS_PER_PAGE, Record, TryFromRecordError};
+
+pub struct DigitalOceanProvider<'provider_config> {
+    pub provider_config: &'provider_config Config,
+    pub rate_limiter: RateLimiter,
+}
+
+impl<'provider_config> DigitalOceanProvider<'provider_config> {
+    pub fn new(
+        provider_config: &'provider_config Config,
+    ) -> DigitalOceanProvider<'provider_config> {
+        DigitalOceanProvider {
+            provider_config,
+            rate_limiter: RateLimiter::new(provider_config.requests_per_second),
+        }
+    }
+
+    fn records_url(&self, domain: &str) -> String {
+        format!(
+            "{}/domains/{}/records",
+            self.provider_config.api_base_url, domain
+        )
+    }
+
+    async fn send(
+        &self,
+        http: &dyn HttpClient,
+        method: Method,
+        url: &str,
+        domain: &str,
+        body: Option<String>,
+    ) -> Result<String> {
+        let token = format!("Bearer {}", self.provider_config.token.as_str());
+        let token = HeaderValue::from_str(&token).map_err(|_| Error::InvalidToken)?;
+        let mut request = HttpRequest::new(method, url).header(AUTHORIZATION, token);
+        if let Some(body) = body {
+            request = request
+                .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+                .body(body);
+        }
+
+        self.rate_limiter.acquire().await;
+        let response = http.send(request).await?;
+
+        if response.status == StatusCode::NOT_FOUND {
+            return Err(ProviderError::DomainNotFound(domain.to_string()));
+        }
+
+        if !response.is_success() {
+            return Err(Error::Unsuccessful {
+                status: response.status.as_u16(),
+                body: response.error_body(),
+            }
+            .into());
+        }
+
+        Ok(response.body)
+    }
+
+    /// Gets the API records of `domain`, following the pagination of the API.
+    async fn list_api_records(&self, http: &dyn HttpClient, domain: &str) -> Result<Vec<Record>> {
+        let mut api_records = Vec::new();
+        let mut page = 1;
+        loop {
+            let url = format!(
+                "{}?page={}&per_page={}",
+                self.records_url(domain),
+                page,
+                RECORDS_PER_PAGE
+            );
+            let text = self.send(http, Method::GET, &url, domain, None).await?;
+            let response: ListRecordsResponse = serde_json::from_str(&text)?;
+            let has_next_page = response.has_next_page();
+            api_records.extend(response.domain_records);
+
+            if !has_next_page {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(api_records)
+    }
+
+    /// Converts `record` into the DigitalOcean representation with a name relative to `domain`.
+    fn to_api_record(&self, record: &dns::Record, domain: &str) -> Record {
+        let mut api_record = Record::from(record);
+        api_record.name = self
+            .record_naming()
+            .to_provider_name(&record.domain, domain);
+        api_record
+    }
+
+    /// Finds the existing record `api_record` refers to. Updates match by name and type,
+    /// deletes also by value.
+    async fn find_record_id(
+        &self,
+        http: &dyn HttpClient,
+        domain: &str,
+        api_record: &Record,
+        match_value: bool,
+    ) -> Result<Option<u64>> {
+        let existing = self.list_api_records(http, domain).await?;
+        let id = existing
+            .iter()
+            .find(|existing| {
+                existing.name == api_record.name
+                    && existing.r#type == api_record.r#type
+                    && (!match_value || existing.same_value(api_record))
+            })
+            .and_then(|existing| existing.id);
+
+        Ok(id)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("HTTP request failed: {0}")]
+    Reqwest(#[from] reqwest::Error),
+
+    #[error("HTTP response is not successful: {status}, body: {body:?}")]
+    Unsuccessful { status: u16, body: String },
+
+    #[error("JSON parsing error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid record in response: {0}")]
+    TryFromRecord(#[from] TryFromRecordError),
+
+    #[error("Invalid token: contains characters that are not allowed in HTTP headers")]
+    InvalidToken,
+}
+
+#[async_trait]
+impl Provider for DigitalOceanProvider<'_> {
+    fn get_provider_name(&self) -> &'static str {
+        "DigitalOcean"
+    }
+
+    fn get_supported_features(&self) -> Vec<Feature> {
+        vec![
+            Feature::GetRecords,
+            Feature::GetAllRecords,
+            Feature::AddRecord,
+            Feature::UpdateRecord,
+            Feature::DeleteRecord,
+        ]
+    }
+
+    /// The SOA record is managed by DigitalOcean.
+    fn supported_record_types(&self) -> &'static [RecordType] {
+        &[
+            RecordType::A,
+            RecordType::AAAA,
+            RecordType::CNAME,
+            RecordType::TXT,
+            RecordType::MX,
+            RecordType::NS,
+            RecordType::SRV,
+            RecordType::CAA,
+        ]
+    }
+
+    fn record_naming(&self) -> RecordNaming {
+        RecordNaming::Relative { apex: "@" }
+    }
+
+    /// DigitalOcean rejects TTLs below 30 seconds.
+    fn ttl_bounds(&self) -> (Option<u32>, Option<u32>) {
+        (Some(30), None)
+    }
+
+    async fn get_all_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &GetAllRecordsInput,
+    ) -> Result<Vec<dns::Record>> {
+        let api_records = self.list_api_records(http, input.domain).await?;
+
+        let naming = self.record_naming();
+        let mut records = Vec::with_capacity(api_records.len());
+        for api_record in api_records {
+            let mut record = dns::Record::try_from(api_record).map_err(Error::from)?;
+            record.domain = naming.to_full_name(&record.domain, input.domain);
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+
+    async fn add_record(&self, http: &dyn HttpClient, input: &AddRecordInput) -> Result<()> {
+        self.validate_records(std::slice::from_ref(input.record))?;
+        let api_record = self.to_api_record(input.record, input.domain);
+
+        let body = serde_json::to_string(&api_record)?;
+        let url = self.records_url(input.domain);
+        self.send(http, Method::POST, &url, input.domain, Some(body))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Replaces the first record with the same name and type.
+    async fn update_record(&self, http: &dyn HttpClient, input: &UpdateRecordInput) -> Result<()> {
+        self.validate_records(std::slice::from_ref(input.record))?;
+        let api_record = self.to_api_record(input.record, input.domain);
+
+        let Some(id) = self
+            .find_record_id(http, input.domain, &api_record, false)
+            .await?
+        else {
+            return Err(ProviderError::RecordNotFound(input.record.domain.clone()));
+        };
+
+        let body = serde_json::to_string(&api_record)?;
+        let url = format!("{}/{}", self.records_url(input.domain), id);
+        self.send(http, Method::PUT, &url, input.domain, Some(body))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_record(&self, http: &dyn HttpClient, input: &DeleteRecordInput) -> Result<()> {
+        let api_record = self.to_api_record(input.record, input.domain);
+
+        let Some(id) = self
+            .find_record_id(http, input.domain, &api_record, true)
+            .await?
+        else {
+            return provider::already_absent(self.get_provider_name(), input.record);
+        };
+
+        // The domain was just listed, a 404 means the record was deleted in the meantime
+        let url = format!("{}/{}", self.records_url(input.domain), id);
+        match self
+            .send(http, Method::DELETE, &url, input.domain, None)
+            .await
+        {
+            Err(ProviderError::DomainNotFound(_)) => {
+                provider::already_absent(self.get_provider_name(), input.record)
+            }
+            result => result.map(|_| ()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dns::RecordValue;
+    use lum_libs::serde_json::json;
+    use std::net::Ipv4Addr;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{header, method, path, query_param},
+    };
+
+    fn config(server: &MockServer) -> Config {
+        Config {
+            token: "test_token".into(),
+            api_base_url: server.uri(),
+            ..Default::default()
+        }
+    }
+
+    fn a_record(id: u64, name: &str, ip: &str) -> lum_libs::serde_json::Value {
+        json!({"id": id, "type": "A", "name": name, "data": ip, "priority": null, "port": null,
+               "ttl": 1800, "weight": null, "flags": null, "tag": null})
+    }
+
+    #[tokio::test]
+    async fn test_get_all_records_follows_pages() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/domains/example.com/records"))
+            .and(header("Authorization", "Bearer test_token"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "domain_records": [a_record(1, "@", "1.1.1.1")],
+                "links": {"pages": {"next": "https://api.digitalocean.com/v2/domains/example.com/records?page=2"}},
+                "meta": {"total": 2},
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/domains/example.com/records"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "domain_records": [a_record(2, "www", "2.2.2.2")],
+                "links": {"pages": {"prev": "https://api.digitalocean.com/v2/domains/example.com/records?page=1"}},
+                "meta": {"total": 2},
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = DigitalOceanProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let records = provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+        let names: Vec<&str> = records
+            .iter()
+            .map(|record| record.domain.as_str())
+            .collect();
+        assert_eq!(names, vec!["example.com", "www.example.com"]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_record_uses_id_of_matching_value() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/domains/example.com/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "domain_records": [a_record(1, "www", "1.1.1.1"), a_record(2, "www", "2.2.2.2")],
+                "links": {},
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/domains/example.com/records/2"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = DigitalOceanProvider::new(&config);
+        let record = dns::Record {
+            domain: "www.example.com".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(2, 2, 2, 2)),
+            ttl: None,
+        };
+        let input = DeleteRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        provider
+            .delete_record(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+    }
+}
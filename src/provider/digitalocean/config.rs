@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use lum_libs::serde::{Deserialize, Serialize};
+
+use crate::config::{dns::RecordConfig, secret::Secret};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Config {
+    pub name: String,
+    pub token: Secret,
+    pub api_base_url: String,
+    pub requests_per_second: Option<f64>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            name: "DigitalOcean1".to_string(),
+            token: "your_token".into(),
+            api_base_url: "https://api.digitalocean.com/v2".to_string(),
+            requests_per_second: None,
+            source_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct DomainConfig {
+    pub domain: String,
+    pub records: Vec<RecordConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct DnsConfig {
+    pub provider_name: String,
+    pub domains: Vec<DomainConfig>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
+}
+
+impl Default for DnsConfig {
+    fn default() -> Self {
+        DnsConfig {
+            provider_name: "DigitalOcean1".to_string(),
+            domains: vec![],
+            source_path: None,
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use std::{
+    net::{self, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use lum_libs::serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::dns::{self, MxRecord, RecordType, RecordValue};
+
+/// Number of records requested per page when listing records, the maximum of the API.
+pub const RECORDS_PER_PAGE: u32 = 200;
+
+/// A DigitalOcean domain record.
+///
+/// `data` holds the address, target or text of the record. MX, SRV and CAA keep their other
+/// fields (`priority`, `port`, `weight`, `flags` and `tag`) separately.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Record {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    pub r#type: RecordType,
+    pub name: String,
+    pub data: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flags: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+impl Record {
+    /// Returns whether `other` holds the same value, ignoring id and TTL.
+    pub fn same_value(&self, other: &Record) -> bool {
+        self.r#type == other.r#type
+            && self.data.trim_end_matches('.') == other.data.trim_end_matches('.')
+            && self.priority == other.priority
+            && self.port == other.port
+            && self.weight == other.weight
+            && self.flags == other.flags
+            && self.tag == other.tag
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Pages {
+    pub next: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Links {
+    #[serde(default)]
+    pub pages: Option<Pages>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct ListRecordsResponse {
+    pub domain_records: Vec<Record>,
+    #[serde(default)]
+    pub links: Option<Links>,
+}
+
+impl ListRecordsResponse {
+    /// Returns whether the API links a next page.
+    pub fn has_next_page(&self) -> bool {
+        self.links
+            .as_ref()
+            .and_then(|links| links.pages.as_ref())
+            .is_some_and(|pages| pages.next.is_some())
+    }
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum TryFromRecordError {
+    #[error("Invalid IP address: {0}")]
+    InvalidIp(#[from] net::AddrParseError),
+
+    #[error("{record_type:?} record is missing its {field}")]
+    MissingField {
+        record_type: RecordType,
+        field: &'static str,
+    },
+
+    #[error("Record type {0:?} is not supported by DigitalOcean")]
+    UnsupportedRecordType(RecordType),
+
+    #[error("Invalid record: {0}")]
+    InvalidRecord(#[from] dns::RecordValidationError),
+}
+
+fn required<T>(
+    value: Option<T>,
+    record_type: RecordType,
+    field: &'static str,
+) -> Result<T, TryFromRecordError> {
+    value.ok_or(TryFromRecordError::MissingField { record_type, field })
+}
+
+fn relative(name: &str) -> String {
+    name.trim_end_matches('.').to_string()
+}
+
+/// Converts a DigitalOcean record into the internal [`dns::Record`] type.
+///
+/// The name stays as DigitalOcean returns it, relative to the domain with `@` for the apex.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::digitalocean::model::Record;
+/// use dnrs::types::dns::{RecordType, RecordValue};
+///
+/// let api_record = Record {
+///     id: Some(1),
+///     r#type: RecordType::MX,
+///     name: "@".to_string(),
+///     data: "mail.example.com.".to_string(),
+///     priority: Some(10),
+///     port: None,
+///     ttl: Some(1800),
+///     weight: None,
+///     flags: None,
+///     tag: None,
+/// };
+///
+/// let record = dnrs::types::dns::Record::try_from(api_record).unwrap();
+/// assert_eq!(record.value.content(), "10 mail.example.com");
+/// ```
+impl TryFrom<Record> for dns::Record {
+    type Error = TryFromRecordError;
+
+    fn try_from(api_record: Record) -> Result<Self, Self::Error> {
+        let record_type = api_record.r#type;
+        let value = match record_type {
+            RecordType::A => RecordValue::A(Ipv4Addr::from_str(&api_record.data)?),
+            RecordType::AAAA => RecordValue::AAAA(Ipv6Addr::from_str(&api_record.data)?),
+            RecordType::CNAME => RecordValue::CNAME(relative(&api_record.data)),
+            RecordType::NS => RecordValue::NS(relative(&api_record.data)),
+            RecordType::TXT => RecordValue::TXT(api_record.data),
+            RecordType::SPF => RecordValue::SPF(api_record.data),
+            RecordType::SOA => RecordValue::SOA(api_record.data),
+            RecordType::MX => RecordValue::MX(MxRecord {
+                priority: required(api_record.priority, record_type, "priority")?,
+                target: relative(&api_record.data),
+            }),
+            RecordType::SRV => RecordValue::SRV(
+                required(api_record.priority, record_type, "priority")?,
+                required(api_record.weight, record_type, "weight")?,
+                required(api_record.port, record_type, "port")?,
+                relative(&api_record.data),
+            ),
+            RecordType::CAA => RecordValue::CAA(
+                required(api_record.flags, record_type, "flags")?,
+                required(api_record.tag, record_type, "tag")?,
+                api_record.data,
+            ),
+            RecordType::TLSA
+            | RecordType::SVCB
+            | RecordType::HTTPS
+            | RecordType::DS
+            | RecordType::DNSKEY => {
+                return Err(TryFromRecordError::UnsupportedRecordType(record_type));
+            }
+        };
+
+        Ok(dns::Record::builder(api_record.name, value)
+            .ttl(api_record.ttl)
+            .build()?)
+    }
+}
+
+/// Converts an internal [`dns::Record`] into the DigitalOcean representation.
+///
+/// Targets are written as absolute names, DigitalOcean would otherwise append the domain. The
+/// name is copied as is and has to be made relative by the caller.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::digitalocean::model::Record;
+/// use dnrs::types::dns::{self, RecordValue};
+///
+/// let record = dns::Record {
+///     domain: "_sip._tcp".to_string(),
+///     value: RecordValue::SRV(10, 5, 5060, "sip.example.com".to_string()),
+///     ttl: Some(600),
+/// };
+///
+/// let api_record = Record::from(&record);
+/// assert_eq!(api_record.data, "sip.example.com.");
+/// assert_eq!((api_record.priority, api_record.weight, api_record.port), (Some(10), Some(5), Some(5060)));
+/// ```
+impl From<&dns::Record> for Record {
+    fn from(record: &dns::Record) -> Self {
+        let mut api_record = Record {
+            id: None,
+            r#type: record.value.wire_type(),
+            name: record.domain.clone(),
+            data: record.value.content(),
+            priority: None,
+            port: None,
+            ttl: record.ttl,
+            weight: None,
+            flags: None,
+            tag: None,
+        };
+
+        match &record.value {
+            RecordValue::CNAME(target) | RecordValue::NS(target) => {
+                api_record.data = dns::absolute_name(target);
+            }
+            RecordValue::MX(mx) => {
+                api_record.data = dns::absolute_name(&mx.target);
+                api_record.priority = Some(mx.priority);
+            }
+            RecordValue::SRV(priority, weight, port, target) => {
+                api_record.data = dns::absolute_name(target);
+                api_record.priority = Some(*priority);
+                api_record.weight = Some(*weight);
+                api_record.port = Some(*port);
+            }
+            RecordValue::CAA(flags, tag, value) => {
+                api_record.data = value.clone();
+                api_record.flags = Some(*flags);
+                api_record.tag = Some(tag.clone());
+            }
+            _ => {}
+        }
+
+        api_record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lum_libs::serde_json;
+
+    #[test]
+    fn test_srv_record_uses_separate_fields() {
+        let api_record: Record = serde_json::from_str(
+            r#"{"id": 3, "type": "SRV", "name": "_sip._tcp", "data": "sip.example.com.",
+                "priority": 10, "port": 5060, "ttl": 1800, "weight": 5, "flags": null,
+                "tag": null}"#,
+        )
+        .unwrap();
+
+        let record = dns::Record::try_from(api_record.clone()).unwrap();
+        assert_eq!(record.domain, "_sip._tcp");
+        assert_eq!(record.ttl, Some(1800));
+        match &record.value {
+            RecordValue::SRV(priority, weight, port, target) => {
+                assert_eq!((*priority, *weight, *port), (10, 5, 5060));
+                assert_eq!(target, "sip.example.com");
+            }
+            other => panic!("Expected SRV record, got {:?}", other),
+        }
+
+        let round_trip = Record::from(&record);
+        assert!(round_trip.same_value(&api_record));
+
+        let without_port = Record {
+            port: None,
+            ..api_record
+        };
+        assert!(matches!(
+            dns::Record::try_from(without_port),
+            Err(TryFromRecordError::MissingField { field: "port", .. })
+        ));
+    }
+
+    #[test]
+    fn test_caa_record_uses_flags_and_tag() {
+        let api_record: Record = serde_json::from_str(
+            r#"{"id": 4, "type": "CAA", "name": "@", "data": "letsencrypt.org", "priority": null,
+                "port": null, "ttl": 3600, "weight": null, "flags": 0, "tag": "issue"}"#,
+        )
+        .unwrap();
+
+        let record = dns::Record::try_from(api_record.clone()).unwrap();
+        match &record.value {
+            RecordValue::CAA(flags, tag, value) => {
+                assert_eq!(*flags, 0);
+                assert_eq!(tag, "issue");
+                assert_eq!(value, "letsencrypt.org");
+            }
+            other => panic!("Expected CAA record, got {:?}", other),
+        }
+
+        let round_trip = Record::from(&record);
+        assert_eq!(round_trip.flags, Some(0));
+        assert_eq!(round_trip.tag.as_deref(), Some("issue"));
+        assert_eq!(round_trip.data, "letsencrypt.org");
+        assert!(round_trip.same_value(&api_record));
+
+        let serialized = serde_json::to_value(&round_trip).unwrap();
+        assert!(serialized.get("id").is_none());
+        assert!(serialized.get("port").is_none());
+    }
+}
@@ -1,45 +1,113 @@
-use anyhow::Result;
+use std::{collections::HashMap, sync::Mutex};
+
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use lum_libs::serde_json;
-use reqwest::header::HeaderMap;
+use reqwest::{
+    Method,
+    header::{CONTENT_TYPE, HeaderMap, HeaderValue},
+};
 use thiserror::Error;
 
 use crate::{
-    provider::{Feature, GetAllRecordsInput, Provider},
-    types::dns::{self},
+    http::{HttpClient, HttpRequest},
+    provider::{
+        self, AddRecordInput, AddRecordsInput, DeleteRecordInput, Feature, GetAllRecordsInput,
+        Provider, ProviderError, Result, UpdateRecordInput, naming::RecordNaming,
+    },
+    rate_limit::RateLimiter,
+    types::dns::{self, RecordType},
 };
 
 pub mod config;
 pub mod model;
 
 pub use config::{Config, DnsConfig, DomainConfig};
-pub use model::{GetRecordsResponse, Record, TryFromRecordError};
+pub use model::{
+    BulkCreateRequest, BulkCreateResponse, GetRecordsResponse, NewRecord, RECORDS_PER_PAGE, Record,
+    TryFromRecordError,
+};
 
 pub struct HetznerProvider<'provider_config> {
     pub provider_config: &'provider_config Config,
+    pub rate_limiter: RateLimiter,
+
+    /// Zone ids by domain, the configured ones and those looked up so far.
+    zone_ids: Mutex<HashMap<String, String>>,
 }
 
 impl<'provider_config> HetznerProvider<'provider_config> {
     pub fn new(provider_config: &'provider_config Config) -> HetznerProvider<'provider_config> {
-        HetznerProvider { provider_config }
+        let zone_ids = provider_config.zone_ids.clone().into_iter().collect();
+
+        HetznerProvider {
+            provider_config,
+            rate_limiter: RateLimiter::new(provider_config.requests_per_second),
+            zone_ids: Mutex::new(zone_ids),
+        }
     }
 
-    async fn get_zone_id(&self, reqwest: reqwest::Client, domain: &str) -> Result<String> {
+    /// Adds known zone ids of domains, e.g. those of the DNS config. Ids of the provider config
+    /// take precedence.
+    pub fn with_zone_ids<'a>(self, zone_ids: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        {
+            let mut known = self.zone_ids.lock().unwrap();
+            for (domain, zone_id) in zone_ids {
+                known
+                    .entry(domain.to_string())
+                    .or_insert_with(|| zone_id.to_string());
+            }
+        }
+
+        self
+    }
+
+    fn auth_headers(&self) -> Result<HeaderMap> {
+        let api_key = self
+            .provider_config
+            .api_key
+            .as_str()
+            .parse()
+            .map_err(|_| Error::InvalidApiKey)?;
+
         let mut headers = HeaderMap::new();
-        headers.insert(
-            "Auth-API-Token",
-            self.provider_config.api_key.parse().expect("Invalid Hetzner API key: contains characters that are not allowed in HTTP headers"),
-        );
+        headers.insert("Auth-API-Token", api_key);
+
+        Ok(headers)
+    }
+
+    /// Returns the id of the zone of `domain`. Unless it is known, the zone is looked up once and
+    /// its id kept for the lifetime of the provider.
+    async fn get_zone_id(&self, http: &dyn HttpClient, domain: &str) -> Result<String> {
+        if let Some(zone_id) = self.zone_ids.lock().unwrap().get(domain) {
+            return Ok(zone_id.clone());
+        }
+
+        let zone_id = self.lookup_zone_id(http, domain).await?;
+        self.zone_ids
+            .lock()
+            .unwrap()
+            .insert(domain.to_string(), zone_id.clone());
+
+        Ok(zone_id)
+    }
+
+    async fn lookup_zone_id(&self, http: &dyn HttpClient, domain: &str) -> Result<String> {
+        let headers = self.auth_headers()?;
 
         let url = format!("{}/zones", self.provider_config.api_base_url);
-        let response = reqwest.get(&url).headers(headers).send().await?;
+        self.rate_limiter.acquire().await;
+        let response = http.send(HttpRequest::get(url).headers(headers)).await?;
 
-        if !response.status().is_success() {
-            return Err(Error::Unsuccessful(response.status().as_u16(), response).into());
+        if !response.is_success() {
+            return Err(Error::Unsuccessful {
+                status: response.status.as_u16(),
+                body: response.error_body(),
+            }
+            .into());
         }
 
-        let text = response.text().await?;
-        let json_value: serde_json::Value = serde_json::from_str(&text)?;
+        let json_value: serde_json::Value = serde_json::from_str(&response.body)?;
 
         match json_value
             .get("zones")
@@ -56,8 +124,149 @@ impl<'provider_config> HetznerProvider<'provider_config> {
                 })
             }) {
             Some(zone_id) => Ok(zone_id),
-            None => Err(Error::DomainNotFound(domain.to_string()).into()),
+            None => Err(ProviderError::DomainNotFound(domain.to_string())),
+        }
+    }
+
+    /// Gets the API records of `domain`, only those of `record_type` if given, following the
+    /// pagination of the API.
+    async fn fetch_api_records(
+        &self,
+        http: &dyn HttpClient,
+        domain: &str,
+        record_type: Option<RecordType>,
+    ) -> Result<Vec<Record>> {
+        let headers = self.auth_headers()?;
+
+        let zone_id = self.get_zone_id(http, domain).await?;
+
+        let mut api_records = Vec::new();
+        let mut page = 1;
+        loop {
+            let mut url = format!(
+                "{}/records?zone_id={}&page={}&per_page={}",
+                self.provider_config.api_base_url, zone_id, page, RECORDS_PER_PAGE
+            );
+            if let Some(record_type) = record_type {
+                url.push_str(&format!("&type={:?}", record_type));
+            }
+
+            self.rate_limiter.acquire().await;
+            let response = http
+                .send(HttpRequest::get(url).headers(headers.clone()))
+                .await?;
+
+            if !response.is_success() {
+                return Err(Error::Unsuccessful {
+                    status: response.status.as_u16(),
+                    body: response.error_body(),
+                }
+                .into());
+            }
+
+            let response: GetRecordsResponse = serde_json::from_str(&response.body)?;
+            let has_next_page = response.has_next_page();
+            api_records.extend(response.records);
+
+            if !has_next_page {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(api_records)
+    }
+
+    /// Converts API records of `domain` into [`dns::Record`]s with fully qualified names.
+    fn to_records(&self, api_records: Vec<Record>, domain: &str) -> Result<Vec<dns::Record>> {
+        let response = GetRecordsResponse {
+            records: api_records,
+            meta: None,
+        };
+        let mut records: Vec<dns::Record> = response.try_into().map_err(Error::from)?;
+        let naming = self.record_naming();
+        for record in records.iter_mut() {
+            record.domain = naming.to_full_name(&record.domain, domain);
+        }
+
+        Ok(records)
+    }
+
+    /// Converts `record` into a record to create in the zone `zone_id` of `domain`.
+    fn to_new_record(&self, record: &dns::Record, domain: &str, zone_id: &str) -> NewRecord {
+        NewRecord {
+            zone_id: zone_id.to_string(),
+            r#type: record.value.wire_type(),
+            name: self
+                .record_naming()
+                .to_provider_name(&record.domain, domain),
+            value: model::value_of(&record.value),
+            ttl: record.ttl,
+        }
+    }
+
+    /// Finds the id of the existing record `new_record` refers to. Updates match by name and
+    /// type, deletes also by value.
+    async fn find_record_id(
+        &self,
+        http: &dyn HttpClient,
+        domain: &str,
+        new_record: &NewRecord,
+        match_value: bool,
+    ) -> Result<Option<String>> {
+        let existing = self
+            .fetch_api_records(http, domain, Some(new_record.r#type))
+            .await?;
+        let id = existing
+            .into_iter()
+            .find(|existing| {
+                existing.r#type == new_record.r#type
+                    && existing.name.eq_ignore_ascii_case(&new_record.name)
+                    && (!match_value || existing.value == new_record.value)
+            })
+            .and_then(|existing| existing.id);
+
+        Ok(id)
+    }
+
+    /// Sends a request with `body` to the record `id`, failing on unsuccessful responses.
+    async fn send_to_record(
+        &self,
+        http: &dyn HttpClient,
+        method: Method,
+        id: &str,
+        body: Option<String>,
+    ) -> Result<()> {
+        let url = format!("{}/records/{}", self.provider_config.api_base_url, id);
+        let mut headers = self.auth_headers()?;
+        let mut request = HttpRequest::new(method, url);
+        if let Some(body) = body {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            request = request.body(body);
+        }
+
+        self.rate_limiter.acquire().await;
+        let response = http.send(request.headers(headers)).await?;
+        if !response.is_success() {
+            return Err(Error::Unsuccessful {
+                status: response.status.as_u16(),
+                body: response.error_body(),
+            }
+            .into());
         }
+
+        Ok(())
+    }
+
+    /// Gets the records of `domain`, only those of `record_type` if given.
+    async fn fetch_records(
+        &self,
+        http: &dyn HttpClient,
+        domain: &str,
+        record_type: Option<RecordType>,
+    ) -> Result<Vec<dns::Record>> {
+        let api_records = self.fetch_api_records(http, domain, record_type).await?;
+        self.to_records(api_records, domain)
     }
 }
 
@@ -66,14 +275,20 @@ pub enum Error {
     #[error("HTTP request failed: {0}")]
     Reqwest(#[from] reqwest::Error),
 
-    #[error("HTTP response is not successful: {0}")]
-    Unsuccessful(u16, reqwest::Response),
+    #[error("HTTP response is not successful: {status}, body: {body:?}")]
+    Unsuccessful { status: u16, body: String },
 
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
 
-    #[error("Domain '{0}' not found in Hetzner zones")]
-    DomainNotFound(String),
+    #[error("Invalid record in response: {0}")]
+    TryFromRecord(#[from] TryFromRecordError),
+
+    #[error("Invalid API key: contains characters that are not allowed in HTTP headers")]
+    InvalidApiKey,
+
+    #[error("Record {0} was rejected as invalid")]
+    InvalidRecord(String),
 }
 
 #[async_trait]
@@ -89,51 +304,1011 @@ impl Provider for HetznerProvider<'_> {
             Feature::AddRecord,
             Feature::UpdateRecord,
             Feature::DeleteRecord,
+            Feature::BatchWrite,
+            Feature::ModificationTimes,
         ]
     }
 
+    fn supported_record_types(&self) -> &'static [RecordType] {
+        &[
+            RecordType::A,
+            RecordType::AAAA,
+            RecordType::CNAME,
+            RecordType::TXT,
+            RecordType::MX,
+            RecordType::NS,
+            RecordType::SOA,
+            RecordType::SRV,
+            RecordType::TLSA,
+            RecordType::CAA,
+            RecordType::DS,
+        ]
+    }
+
+    fn record_naming(&self) -> RecordNaming {
+        RecordNaming::Relative { apex: "@" }
+    }
+
+    /// Hetzner rejects TTLs below one minute.
+    fn ttl_bounds(&self) -> (Option<u32>, Option<u32>) {
+        (Some(60), None)
+    }
 
     async fn get_all_records(
         &self,
-        reqwest: reqwest::Client,
+        http: &dyn HttpClient,
         input: &GetAllRecordsInput,
     ) -> Result<Vec<dns::Record>> {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            "Auth-API-Token",
-            self.provider_config.api_key.parse().expect("Invalid Hetzner API key: contains characters that are not allowed in HTTP headers"),
+        self.fetch_records(http, input.domain, None).await
+    }
+
+    async fn get_records_of_type(
+        &self,
+        http: &dyn HttpClient,
+        input: &GetAllRecordsInput,
+        record_type: RecordType,
+    ) -> Result<Vec<dns::Record>> {
+        self.fetch_records(http, input.domain, Some(record_type))
+            .await
+    }
+
+    /// Records without a modification time are left out, they cannot be shown to be newer.
+    async fn get_records_modified_since(
+        &self,
+        http: &dyn HttpClient,
+        input: &GetAllRecordsInput,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<dns::Record>> {
+        let api_records = self.fetch_api_records(http, input.domain, None).await?;
+        let api_records = api_records
+            .into_iter()
+            .filter(|record| record.modified.is_some_and(|modified| modified > since))
+            .collect();
+
+        self.to_records(api_records, input.domain)
+    }
+
+    async fn add_record(&self, http: &dyn HttpClient, input: &AddRecordInput) -> Result<()> {
+        let input = AddRecordsInput {
+            domain: input.domain,
+            records: std::slice::from_ref(input.record),
+        };
+
+        let mut results = self.add_records(http, &input).await?;
+        results.remove(0)
+    }
+
+    /// Creates all records with a single request to the bulk endpoint. Records the API rejects
+    /// fail on their own, the others are created.
+    async fn add_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &AddRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        self.validate_records(input.records)?;
+        let zone_id = self.get_zone_id(http, input.domain).await?;
+
+        let new_records: Vec<NewRecord> = input
+            .records
+            .iter()
+            .map(|record| self.to_new_record(record, input.domain, &zone_id))
+            .collect();
+        let body = serde_json::to_string(&BulkCreateRequest {
+            records: new_records.clone(),
+        })?;
+
+        let url = format!("{}/records/bulk", self.provider_config.api_base_url);
+        self.rate_limiter.acquire().await;
+        let mut headers = self.auth_headers()?;
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        let request = HttpRequest::post(url).headers(headers).body(body);
+        let response = http.send(request).await?;
+
+        if !response.is_success() {
+            return Err(Error::Unsuccessful {
+                status: response.status.as_u16(),
+                body: response.error_body(),
+            }
+            .into());
+        }
+
+        let response: BulkCreateResponse = serde_json::from_str(&response.body)?;
+
+        let results = input
+            .records
+            .iter()
+            .zip(new_records.iter())
+            .map(|(record, new_record)| {
+                let rejected = response
+                    .invalid_records
+                    .iter()
+                    .any(|invalid| invalid.same_record(new_record));
+                if rejected {
+                    return Err(Error::InvalidRecord(record.domain.clone()).into());
+                }
+
+                Ok(())
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Replaces the first record with the same name and type.
+    async fn update_record(&self, http: &dyn HttpClient, input: &UpdateRecordInput) -> Result<()> {
+        self.validate_records(std::slice::from_ref(input.record))?;
+        let zone_id = self.get_zone_id(http, input.domain).await?;
+        let new_record = self.to_new_record(input.record, input.domain, &zone_id);
+
+        let Some(id) = self
+            .find_record_id(http, input.domain, &new_record, false)
+            .await?
+        else {
+            return Err(ProviderError::RecordNotFound(input.record.domain.clone()));
+        };
+
+        let body = serde_json::to_string(&new_record)?;
+        self.send_to_record(http, Method::PUT, &id, Some(body))
+            .await
+    }
+
+    async fn delete_record(&self, http: &dyn HttpClient, input: &DeleteRecordInput) -> Result<()> {
+        let zone_id = self.get_zone_id(http, input.domain).await?;
+        let new_record = self.to_new_record(input.record, input.domain, &zone_id);
+
+        let Some(id) = self
+            .find_record_id(http, input.domain, &new_record, true)
+            .await?
+        else {
+            return provider::already_absent(self.get_provider_name(), input.record);
+        };
+
+        // The record was just listed, a 404 means it was deleted in the meantime
+        match self.send_to_record(http, Method::DELETE, &id, None).await {
+            Err(ProviderError::Hetzner(error))
+                if matches!(*error, Error::Unsuccessful { status: 404, .. }) =>
+            {
+                provider::already_absent(self.get_provider_name(), input.record)
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        http::{FakeHttpClient, HttpResponse},
+        provider::GetRecordsInput,
+    };
+    use reqwest::StatusCode;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_json, header, method, path, query_param},
+    };
+
+    fn config(server: &MockServer) -> Config {
+        Config {
+            api_key: "test_key".into(),
+            api_base_url: server.uri(),
+            ..Default::default()
+        }
+    }
+
+    /// Config of a provider talking to a [`FakeHttpClient`].
+    fn fake_config() -> Config {
+        Config {
+            api_key: "test_key".into(),
+            api_base_url: "https://dns.example.net/api/v1".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn ok(body: impl ToString) -> HttpResponse {
+        HttpResponse::new(StatusCode::OK, body.to_string())
+    }
+
+    fn zones() -> HttpResponse {
+        ok(
+            r#"{"zones": [{"id": "zone2", "name": "other.com"}, {"id": "zone1", "name": "example.com"}]}"#,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_get_all_records_unknown_zone_is_domain_not_found() {
+        let http =
+            FakeHttpClient::new([ok(r#"{"zones": [{"id": "zone1", "name": "other.com"}]}"#)]);
+        let config = fake_config();
+        let provider = HetznerProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let result = provider.get_all_records(&http, &input).await;
+        assert!(
+            matches!(result, Err(ProviderError::DomainNotFound(domain)) if domain == "example.com")
         );
 
-        let domain = &input.domain;
-        let zone_id = self.get_zone_id(reqwest.clone(), domain).await?;
+        let requests = http.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url, "https://dns.example.net/api/v1/zones");
+        assert_eq!(requests[0].headers["Auth-API-Token"], "test_key");
+    }
+
+    fn records_page(ips: &[&str], page: u32, last_page: u32) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(records_page_json(ips, page, last_page))
+    }
+
+    fn records_page_json(ips: &[&str], page: u32, last_page: u32) -> serde_json::Value {
+        let records: Vec<_> = ips
+            .iter()
+            .map(|ip| {
+                serde_json::json!({
+                    "type": "A",
+                    "id": format!("record-{}", ip),
+                    "created": "2024-01-01 00:00:00 +0000 UTC",
+                    "modified": "2024-01-01 00:00:00 +0000 UTC",
+                    "zone_id": "zone1",
+                    "name": "www",
+                    "value": ip,
+                    "ttl": 300,
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "records": records,
+            "meta": {
+                "pagination": {
+                    "page": page,
+                    "per_page": 2,
+                    "last_page": last_page,
+                    "total_entries": 3,
+                }
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_get_all_records_collects_all_pages() {
+        let http = FakeHttpClient::new([
+            zones(),
+            ok(records_page_json(&["1.1.1.1", "2.2.2.2"], 1, 2)),
+            ok(records_page_json(&["3.3.3.3"], 2, 2)),
+        ]);
+        let config = fake_config();
+        let provider = HetznerProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
 
-        let url = format!(
-            "{}/records?zone_id={}",
-            self.provider_config.api_base_url, zone_id
+        let records = provider.get_all_records(&http, &input).await.unwrap();
+        let contents: Vec<String> = records
+            .iter()
+            .map(|record| record.value.content())
+            .collect();
+        assert_eq!(contents, vec!["1.1.1.1", "2.2.2.2", "3.3.3.3"]);
+
+        let urls: Vec<String> = http
+            .requests()
+            .into_iter()
+            .map(|request| request.url)
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "https://dns.example.net/api/v1/zones".to_string(),
+                format!(
+                    "https://dns.example.net/api/v1/records?zone_id=zone1&page=1&per_page={}",
+                    RECORDS_PER_PAGE
+                ),
+                format!(
+                    "https://dns.example.net/api/v1/records?zone_id=zone1&page=2&per_page={}",
+                    RECORDS_PER_PAGE
+                ),
+            ]
         );
+    }
 
-        let response = reqwest.get(&url).headers(headers).send().await?;
+    #[tokio::test]
+    async fn test_get_records_maps_apex() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/zones"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"zones": [{"id": "zone1", "name": "example.com"}]}"#),
+            )
+            .mount(&server)
+            .await;
+        let page = serde_json::json!({
+            "records": [
+                {"type": "A", "id": "1", "created": "", "modified": "", "zone_id": "zone1",
+                 "name": "@", "value": "1.1.1.1", "ttl": 300},
+                {"type": "A", "id": "2", "created": "", "modified": "", "zone_id": "zone1",
+                 "name": "www", "value": "2.2.2.2", "ttl": 300},
+            ]
+        });
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page))
+            .mount(&server)
+            .await;
 
-        if !response.status().is_success() {
-            return Err(Error::Unsuccessful(response.status().as_u16(), response).into());
+        let config = config(&server);
+        let provider = HetznerProvider::new(&config);
+        assert_eq!(
+            provider
+                .record_naming()
+                .to_provider_name("example.com", "example.com"),
+            "@"
+        );
+
+        let input = GetRecordsInput {
+            domain: "example.com",
+            subdomains: vec!["example.com"],
+            record_type: None,
+        };
+        let records = provider
+            .get_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].domain, "example.com");
+        assert_eq!(records[0].value.content(), "1.1.1.1");
+
+        let input = GetRecordsInput {
+            domain: "example.com",
+            subdomains: vec!["www.example.com"],
+            record_type: None,
+        };
+        let records = provider
+            .get_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+        assert_eq!(records[0].domain, "www.example.com");
+    }
+
+    #[tokio::test]
+    async fn test_get_records_filters_type_in_url() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/zones"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"zones": [{"id": "zone1", "name": "example.com"}]}"#),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("zone_id", "zone1"))
+            .and(query_param("type", "A"))
+            .respond_with(records_page(&["1.1.1.1"], 1, 1))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = HetznerProvider::new(&config);
+        let input = GetRecordsInput {
+            domain: "example.com",
+            subdomains: vec!["www.example.com"],
+            record_type: Some(RecordType::A),
+        };
+
+        let records = provider
+            .get_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].value.content(), "1.1.1.1");
+    }
+
+    #[tokio::test]
+    async fn test_get_records_modified_since_filters_by_modified() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/zones"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"zones": [{"id": "zone1", "name": "example.com"}]}"#),
+            )
+            .mount(&server)
+            .await;
+        let page = serde_json::json!({
+            "records": [
+                {"type": "A", "id": "1", "created": "2024-01-01 00:00:00 +0000 UTC",
+                 "modified": "2024-01-01 00:00:00 +0000 UTC", "zone_id": "zone1",
+                 "name": "old", "value": "1.1.1.1", "ttl": 300},
+                {"type": "A", "id": "2", "created": "2024-01-01 00:00:00 +0000 UTC",
+                 "modified": "2024-06-01 12:30:00.250 +0000 UTC", "zone_id": "zone1",
+                 "name": "new", "value": "2.2.2.2", "ttl": 300},
+                {"type": "A", "id": "3", "created": "", "modified": "", "zone_id": "zone1",
+                 "name": "unknown", "value": "3.3.3.3", "ttl": 300},
+            ]
+        });
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page))
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = HetznerProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+        let since = model::parse_timestamp("2024-03-01 00:00:00 +0000 UTC").unwrap();
+
+        let records = provider
+            .get_records_modified_since(&reqwest::Client::new(), &input, since)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].domain, "new.example.com");
+    }
+
+    #[tokio::test]
+    async fn test_api_key_with_newline_is_invalid_api_key() {
+        let config = Config {
+            api_key: "test\nkey".into(),
+            api_base_url: "http://127.0.0.1:1".to_string(),
+            ..Default::default()
+        };
+        let provider = HetznerProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let result = provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await;
+        match result {
+            Err(ProviderError::Hetzner(error)) => assert!(matches!(*error, Error::InvalidApiKey)),
+            _ => panic!("Expected Hetzner error"),
         }
+    }
 
-        let text = response.text().await?;
-        let response: GetRecordsResponse = serde_json::from_str(&text)?;
-        let records: Vec<dns::Record> = response.try_into()?;
+    #[tokio::test]
+    async fn test_get_all_records_unsuccessful_is_hetzner_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/zones"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
 
-        Ok(records)
+        let config = config(&server);
+        let provider = HetznerProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let result = provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await;
+        match result {
+            Err(ProviderError::Hetzner(error)) => {
+                assert!(matches!(*error, Error::Unsuccessful { status: 401, .. }))
+            }
+            _ => panic!("Expected Hetzner error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_html_error_page_is_status_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/zones"))
+            .respond_with(ResponseTemplate::new(500).set_body_raw(
+                "<html><body><h1>500 Internal Server Error</h1></body></html>\n",
+                "text/html",
+            ))
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = HetznerProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let result = provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await;
+        match result {
+            Err(ProviderError::Hetzner(error)) => match *error {
+                Error::Unsuccessful { status, body } => {
+                    assert_eq!(status, 500);
+                    assert_eq!(
+                        body,
+                        "<html><body><h1>500 Internal Server Error</h1></body></html>"
+                    );
+                }
+                error => panic!("Expected status error, got {}", error),
+            },
+            _ => panic!("Expected Hetzner error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_records_sends_one_bulk_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/zones"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"zones": [{"id": "zone1", "name": "example.com"}]}"#),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/records/bulk"))
+            .and(header("Auth-API-Token", "test_key"))
+            .and(body_json(serde_json::json!({
+                "records": [
+                    {"zone_id": "zone1", "type": "A", "name": "@", "value": "1.2.3.4", "ttl": 300},
+                    {"zone_id": "zone1", "type": "AAAA", "name": "www", "value": "::1"},
+                    {"zone_id": "zone1", "type": "TXT", "name": "www", "value": "hello"},
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "records": [],
+                "valid_records": [
+                    {"zone_id": "zone1", "type": "A", "name": "@", "value": "1.2.3.4", "ttl": 300},
+                    {"zone_id": "zone1", "type": "AAAA", "name": "www", "value": "::1"},
+                ],
+                "invalid_records": [
+                    {"zone_id": "zone1", "type": "TXT", "name": "www", "value": "hello", "ttl": 86400},
+                ],
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = HetznerProvider::new(&config);
+        let records = [
+            dns::Record {
+                domain: "example.com".to_string(),
+                value: dns::RecordValue::A("1.2.3.4".parse().unwrap()),
+                ttl: Some(300),
+            },
+            dns::Record {
+                domain: "www.example.com".to_string(),
+                value: dns::RecordValue::AAAA("::1".parse().unwrap()),
+                ttl: None,
+            },
+            dns::Record {
+                domain: "www.example.com".to_string(),
+                value: dns::RecordValue::TXT("hello".to_string()),
+                ttl: None,
+            },
+        ];
+        let input = AddRecordsInput {
+            domain: "example.com",
+            records: &records,
+        };
+
+        let results = provider
+            .add_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+        assert!(results[0].is_ok());
+        assert!(results[1].is_ok());
+        match &results[2] {
+            Err(ProviderError::Hetzner(error)) => {
+                assert!(
+                    matches!(**error, Error::InvalidRecord(ref domain) if domain == "www.example.com")
+                );
+            }
+            other => panic!("Expected rejected record, got {:?}", other),
+        }
     }
 
-    async fn add_record(&self, _reqwest: reqwest::Client, _input: &dns::Record) -> Result<()> {
-        unimplemented!("Hetzner add_record not yet implemented")
+    async fn mount_zone(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/zones"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"zones": [{"id": "zone1", "name": "example.com"}]}"#),
+            )
+            .mount(server)
+            .await;
     }
 
-    async fn update_record(&self, _reqwest: reqwest::Client, _input: &dns::Record) -> Result<()> {
-        unimplemented!("Hetzner update_record not yet implemented")
+    fn www_record(ip: &str) -> dns::Record {
+        dns::Record {
+            domain: "www.example.com".to_string(),
+            value: dns::RecordValue::A(ip.parse().unwrap()),
+            ttl: Some(300),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_record_puts_to_record_id() {
+        let server = MockServer::start().await;
+        mount_zone(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("zone_id", "zone1"))
+            .and(query_param("type", "A"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "records": [
+                    {"type": "A", "id": "1", "zone_id": "zone1", "name": "mail",
+                     "value": "1.2.3.4", "ttl": 300},
+                    {"type": "A", "id": "2", "zone_id": "zone1", "name": "www",
+                     "value": "1.2.3.4", "ttl": 300},
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/records/2"))
+            .and(header("Auth-API-Token", "test_key"))
+            .and(body_json(serde_json::json!({
+                "zone_id": "zone1", "type": "A", "name": "www", "value": "5.6.7.8", "ttl": 300,
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = HetznerProvider::new(&config);
+        let record = www_record("5.6.7.8");
+        let input = UpdateRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+        provider
+            .update_record(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
     }
 
-    async fn delete_record(&self, _reqwest: reqwest::Client, _input: &dns::Record) -> Result<()> {
-        unimplemented!("Hetzner delete_record not yet implemented")
+    #[tokio::test]
+    async fn test_update_of_missing_record_is_record_not_found() {
+        let server = MockServer::start().await;
+        mount_zone(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"records": []})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = HetznerProvider::new(&config);
+        let record = www_record("5.6.7.8");
+        let input = UpdateRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+        let result = provider
+            .update_record(&reqwest::Client::new(), &input)
+            .await;
+        assert!(
+            matches!(result, Err(ProviderError::RecordNotFound(domain)) if domain == "www.example.com")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_of_absent_record_succeeds() {
+        let server = MockServer::start().await;
+        mount_zone(&server).await;
+        let listed = |ids: &[(&str, &str)]| {
+            let records: Vec<_> = ids
+                .iter()
+                .map(|(id, ip)| {
+                    serde_json::json!({"type": "A", "id": id, "zone_id": "zone1", "name": "www",
+                                       "value": ip, "ttl": 300})
+                })
+                .collect();
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "records": records }))
+        };
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(listed(&[("1", "1.1.1.1"), ("2", "1.2.3.4")]))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(listed(&[("1", "1.1.1.1")]))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/records/2"))
+            .and(header("Auth-API-Token", "test_key"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = HetznerProvider::new(&config);
+        let record = www_record("1.2.3.4");
+        let input = DeleteRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        // Deleting twice succeeds, the second time the record is not listed anymore
+        for _ in 0..2 {
+            provider
+                .delete_record(&reqwest::Client::new(), &input)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_not_found_is_already_absent() {
+        let server = MockServer::start().await;
+        mount_zone(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "records": [
+                    {"type": "A", "id": "2", "zone_id": "zone1", "name": "www",
+                     "value": "1.2.3.4", "ttl": 300},
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/records/2"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = HetznerProvider::new(&config);
+        let record = www_record("1.2.3.4");
+        let input = DeleteRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+        provider
+            .delete_record(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wildcard_record_is_created_and_filtered_by_literal_name() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/zones"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"zones": [{"id": "zone1", "name": "example.com"}]}"#),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/records/bulk"))
+            .and(body_json(serde_json::json!({
+                "records": [
+                    {"zone_id": "zone1", "type": "A", "name": "*", "value": "1.2.3.4", "ttl": 300},
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "records": [],
+                "valid_records": [
+                    {"zone_id": "zone1", "type": "A", "name": "*", "value": "1.2.3.4", "ttl": 300},
+                ],
+                "invalid_records": [],
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let page = serde_json::json!({
+            "records": [
+                {"type": "A", "id": "1", "created": "", "modified": "", "zone_id": "zone1",
+                 "name": "*", "value": "1.2.3.4", "ttl": 300},
+                {"type": "A", "id": "2", "created": "", "modified": "", "zone_id": "zone1",
+                 "name": "www", "value": "2.2.2.2", "ttl": 300},
+            ]
+        });
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page))
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = HetznerProvider::new(&config);
+        let record = dns::Record {
+            domain: "*.example.com".to_string(),
+            value: dns::RecordValue::A("1.2.3.4".parse().unwrap()),
+            ttl: Some(300),
+        };
+        let input = AddRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+        provider
+            .add_record(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+
+        let input = GetRecordsInput {
+            domain: "example.com",
+            subdomains: vec!["*.example.com"],
+            record_type: None,
+        };
+        let records = provider
+            .get_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].domain, "*.example.com");
+        assert_eq!(records[0].value.content(), "1.2.3.4");
+    }
+
+    async fn mount_zone_records(server: &MockServer, zone_id: &str, zone_lookups: u64) {
+        Mock::given(method("GET"))
+            .and(path("/zones"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"zones": [{"id": "zone1", "name": "example.com"}]}"#),
+            )
+            .expect(zone_lookups)
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("zone_id", zone_id))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "records": [
+                    {"type": "A", "id": "1", "created": "", "modified": "", "zone_id": zone_id,
+                     "name": "www", "value": "1.2.3.4", "ttl": 300},
+                ]
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_configured_zone_id_skips_lookup() {
+        let server = MockServer::start().await;
+        mount_zone_records(&server, "zone9", 0).await;
+
+        let mut config = config(&server);
+        config
+            .zone_ids
+            .insert("example.com".to_string(), "zone9".to_string());
+        let provider = HetznerProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let records = provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dns_config_zone_id_skips_lookup() {
+        let server = MockServer::start().await;
+        mount_zone_records(&server, "zone9", 0).await;
+
+        let config = config(&server);
+        let dns_config = DnsConfig {
+            domains: vec![DomainConfig {
+                domain: "example.com".to_string(),
+                zone_id: Some("zone9".to_string()),
+                records: vec![],
+            }],
+            ..Default::default()
+        };
+        let provider = HetznerProvider::new(&config).with_zone_ids(dns_config.zone_ids());
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_zone_lookup_is_cached() {
+        let server = MockServer::start().await;
+        mount_zone_records(&server, "zone1", 1).await;
+
+        let config = config(&server);
+        let provider = HetznerProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        for _ in 0..2 {
+            provider
+                .get_all_records(&reqwest::Client::new(), &input)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_records_of_type_follows_pages_with_fake_client() {
+        let http = FakeHttpClient::new([
+            zones(),
+            ok(records_page_json(&["1.1.1.1", "2.2.2.2"], 1, 2)),
+            ok(records_page_json(&["3.3.3.3"], 2, 2)),
+        ]);
+        let config = fake_config();
+        let provider = HetznerProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let records = provider
+            .get_records_of_type(&http, &input, RecordType::A)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2].domain, "www.example.com");
+
+        let requests = http.requests();
+        let urls: Vec<&str> = requests
+            .iter()
+            .map(|request| request.url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "https://dns.example.net/api/v1/zones",
+                "https://dns.example.net/api/v1/records?zone_id=zone1&page=1&per_page=100&type=A",
+                "https://dns.example.net/api/v1/records?zone_id=zone1&page=2&per_page=100&type=A",
+            ]
+        );
+        assert!(
+            requests
+                .iter()
+                .all(|request| request.headers["Auth-API-Token"] == "test_key")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_zone_lookup_with_fake_client() {
+        let http = FakeHttpClient::new([
+            zones(),
+            HttpResponse::new(StatusCode::UNAUTHORIZED, "  invalid token\n"),
+        ]);
+        let config = Config {
+            api_key: "test_key".into(),
+            ..Default::default()
+        };
+        let provider = HetznerProvider::new(&config);
+
+        for _ in 0..2 {
+            let zone_id = provider.get_zone_id(&http, "example.com").await.unwrap();
+            assert_eq!(zone_id, "zone1");
+        }
+        assert_eq!(http.requests().len(), 1);
+
+        let result = provider.get_zone_id(&http, "example.org").await;
+        match result {
+            Err(ProviderError::Hetzner(error)) => assert!(
+                matches!(*error, Error::Unsuccessful { status: 401, body } if body == "invalid token")
+            ),
+            _ => panic!("Expected Hetzner error"),
+        }
     }
 }
@@ -1,21 +1,35 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
 use lum_libs::serde::{Deserialize, Serialize};
 
-use crate::config::dns::RecordConfig;
+use crate::config::{dns::RecordConfig, secret::Secret};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
 pub struct Config {
     pub name: String,
-    pub api_key: String,
+    pub api_key: Secret,
     pub api_base_url: String,
+    pub requests_per_second: Option<f64>,
+
+    /// Zone ids of domains, used instead of looking the zones up by name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub zone_ids: BTreeMap<String, String>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             name: "Hetzner1".to_string(),
-            api_key: "your_api_key".to_string(),
+            api_key: "your_api_key".into(),
             api_base_url: "https://dns.hetzner.com/api/v1".to_string(),
+            requests_per_second: None,
+            zone_ids: BTreeMap::new(),
+            source_path: None,
         }
     }
 }
@@ -24,6 +38,11 @@ impl Default for Config {
 #[serde(crate = "lum_libs::serde")]
 pub struct DomainConfig {
     pub domain: String,
+
+    /// Zone id of the domain, used instead of looking the zone up by name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone_id: Option<String>,
+
     pub records: Vec<RecordConfig>,
 }
 
@@ -32,6 +51,20 @@ pub struct DomainConfig {
 pub struct DnsConfig {
     pub provider_name: String,
     pub domains: Vec<DomainConfig>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
+}
+
+impl DnsConfig {
+    /// Returns the domains with a configured zone id and their ids.
+    pub fn zone_ids(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.domains.iter().filter_map(|domain_config| {
+            let zone_id = domain_config.zone_id.as_deref()?;
+            Some((domain_config.domain.as_str(), zone_id))
+        })
+    }
 }
 
 impl Default for DnsConfig {
@@ -39,6 +72,7 @@ impl Default for DnsConfig {
         DnsConfig {
             provider_name: "Hetzner1".to_string(),
             domains: vec![],
+            source_path: None,
         }
     }
 }
@@ -4,24 +4,81 @@ use std::{
     str::FromStr,
 };
 
+use chrono::{DateTime, Utc};
 use lum_libs::serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::types::dns::{self, MxRecord, RecordType, RecordValue};
+use crate::types::dns::{self, DnssecParseError, MxRecord, RecordType, RecordValue};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
 pub struct Record {
     pub r#type: RecordType,
-    pub id: String,
-    pub created: String,
-    pub modified: String,
-    pub zone_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(with = "timestamp", default)]
+    pub created: Option<DateTime<Utc>>,
+    #[serde(with = "timestamp", default)]
+    pub modified: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone_id: Option<String>,
     pub name: String,
     pub value: String,
+    #[serde(default)]
     pub ttl: Option<u32>,
 }
 
+/// Parses a Hetzner timestamp like `2019-08-17 16:04:27.581 +0000 UTC`.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::hetzner::model::parse_timestamp;
+///
+/// let timestamp = parse_timestamp("2019-08-17 16:04:27.581 +0000 UTC").unwrap();
+/// assert_eq!(timestamp.to_rfc3339(), "2019-08-17T16:04:27.581+00:00");
+/// ```
+pub fn parse_timestamp(text: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+    let text = text.strip_suffix(" UTC").unwrap_or(text);
+    let timestamp = DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f %z")?;
+
+    Ok(timestamp.with_timezone(&Utc))
+}
+
+/// (De)serializes optional Hetzner timestamps, the API sends empty strings or `null` for missing
+/// ones.
+mod timestamp {
+    use chrono::{DateTime, Utc};
+    use lum_libs::serde::{Deserialize, Deserializer, Serializer, de};
+
+    pub fn serialize<S: Serializer>(
+        timestamp: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match timestamp {
+            Some(timestamp) => serializer.serialize_str(
+                &timestamp
+                    .format("%Y-%m-%d %H:%M:%S%.3f +0000 UTC")
+                    .to_string(),
+            ),
+            None => serializer.serialize_str(""),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        let text = Option::<String>::deserialize(deserializer)?.unwrap_or_default();
+        if text.is_empty() {
+            return Ok(None);
+        }
+
+        super::parse_timestamp(&text)
+            .map(Some)
+            .map_err(|error| de::Error::custom(format!("invalid timestamp '{}': {}", text, error)))
+    }
+}
+
 #[derive(Debug, Clone, Error)]
 pub enum TryFromRecordError {
     #[error("Invalid IP address: {0}")]
@@ -50,6 +107,14 @@ pub enum TryFromRecordError {
 
     #[error("Invalid CAA record flag: {0}")]
     InvalidCaaFlag(num::ParseIntError),
+
+    #[error("Record type {0:?} is not supported by this provider")]
+    UnsupportedRecordType(RecordType),
+    #[error("Invalid DNSSEC record: {0}")]
+    InvalidDnssec(#[from] DnssecParseError),
+
+    #[error("Invalid record: {0}")]
+    InvalidRecord(#[from] dns::RecordValidationError),
 }
 
 /// Converts a Hetzner API record into the internal [`dns::Record`] type.
@@ -63,10 +128,10 @@ pub enum TryFromRecordError {
 ///
 /// let api_record = Record {
 ///     r#type: RecordType::A,
-///     id: "1".to_string(),
-///     created: "2023-01-01".to_string(),
-///     modified: "2023-01-01".to_string(),
-///     zone_id: "zone1".to_string(),
+///     id: Some("1".to_string()),
+///     created: None,
+///     modified: None,
+///     zone_id: Some("zone1".to_string()),
 ///     name: "example.com".to_string(),
 ///     value: "1.2.3.4".to_string(),
 ///     ttl: Some(3600),
@@ -129,7 +194,7 @@ impl TryFrom<Record> for dns::Record {
                     .parse::<u16>()
                     .map_err(TryFromRecordError::InvalidSrvValue)?;
 
-                let target = parts[3].to_string();
+                let target = relative(parts[3]);
                 RecordValue::SRV(priority, weight, port, target)
             }
             RecordType::TLSA => {
@@ -167,16 +232,51 @@ impl TryFrom<Record> for dns::Record {
                 let value = parts[2].to_string();
                 RecordValue::CAA(flag, tag, value)
             }
+            RecordType::DS => RecordValue::parse_ds(&api_record.value)?,
+            RecordType::SVCB | RecordType::HTTPS | RecordType::DNSKEY => {
+                return Err(TryFromRecordError::UnsupportedRecordType(api_record.r#type));
+            }
         };
 
-        Ok(dns::Record {
-            domain: api_record.name,
-            value,
-            ttl: api_record.ttl,
-        })
+        Ok(dns::Record::builder(api_record.name, value)
+            .ttl(api_record.ttl)
+            .build()?)
     }
 }
 
+/// Returns the value Hetzner expects for `value`, in zone file syntax.
+///
+/// Hetzner reads names without a trailing dot as relative to the zone, so the SRV target is
+/// written as an absolute name.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::hetzner::model::value_of;
+/// use dnrs::types::dns::RecordValue;
+///
+/// let value = RecordValue::SRV(10, 5, 5060, "sip.example.com".to_string());
+/// assert_eq!(value_of(&value), "10 5 5060 sip.example.com.");
+/// ```
+pub fn value_of(value: &RecordValue) -> String {
+    match value {
+        RecordValue::SRV(priority, weight, port, target) => {
+            format!(
+                "{} {} {} {}",
+                priority,
+                weight,
+                port,
+                dns::absolute_name(target)
+            )
+        }
+        _ => value.content(),
+    }
+}
+
+fn relative(name: &str) -> String {
+    name.trim_end_matches('.').to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,10 +286,10 @@ mod tests {
     fn test_hetzner_record_to_dns_record_a() {
         let api_record = Record {
             r#type: RecordType::A,
-            id: "1".to_string(),
-            created: "2023-01-01".to_string(),
-            modified: "2023-01-01".to_string(),
-            zone_id: "zone1".to_string(),
+            id: Some("1".to_string()),
+            created: None,
+            modified: None,
+            zone_id: Some("zone1".to_string()),
             name: "example.com".to_string(),
             value: "1.2.3.4".to_string(),
             ttl: Some(3600),
@@ -209,10 +309,10 @@ mod tests {
     fn test_hetzner_record_to_dns_record_mx() {
         let api_record = Record {
             r#type: RecordType::MX,
-            id: "2".to_string(),
-            created: "2023-01-01".to_string(),
-            modified: "2023-01-01".to_string(),
-            zone_id: "zone1".to_string(),
+            id: Some("2".to_string()),
+            created: None,
+            modified: None,
+            zone_id: Some("zone1".to_string()),
             name: "example.com".to_string(),
             value: "10 mail.example.com".to_string(),
             ttl: None,
@@ -226,12 +326,179 @@ mod tests {
             panic!("Expected MX record");
         }
     }
+
+    #[test]
+    fn test_srv_record_round_trips() {
+        let value = RecordValue::SRV(10, 5, 5060, "sip.example.com".to_string());
+        assert_eq!(value_of(&value), "10 5 5060 sip.example.com.");
+
+        let api_record = Record {
+            r#type: RecordType::SRV,
+            id: Some("3".to_string()),
+            created: None,
+            modified: None,
+            zone_id: Some("zone1".to_string()),
+            name: "_sip._tcp".to_string(),
+            value: value_of(&value),
+            ttl: None,
+        };
+
+        let dns_record = dns::Record::try_from(api_record).unwrap();
+        assert!(matches!(
+            dns_record.value,
+            RecordValue::SRV(10, 5, 5060, ref target) if target == "sip.example.com"
+        ));
+    }
+
+    #[test]
+    fn test_record_timestamps_are_parsed() {
+        let json = r#"{"type": "A", "id": "1", "created": "2019-08-17 16:04:27.581 +0000 UTC",
+            "modified": "2024-01-01 00:00:00 +0000 UTC", "zone_id": "zone1", "name": "www",
+            "value": "1.2.3.4", "ttl": 300}"#;
+        let record: Record = lum_libs::serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            record.created.unwrap().to_rfc3339(),
+            "2019-08-17T16:04:27.581+00:00"
+        );
+        assert_eq!(
+            record.modified.unwrap().to_rfc3339(),
+            "2024-01-01T00:00:00+00:00"
+        );
+
+        let json = json.replace("2024-01-01 00:00:00 +0000 UTC", "");
+        let record: Record = lum_libs::serde_json::from_str(&json).unwrap();
+        assert!(record.modified.is_none());
+
+        let json = json.replace("2019-08-17 16:04:27.581 +0000 UTC", "yesterday");
+        assert!(lum_libs::serde_json::from_str::<Record>(&json).is_err());
+    }
+
+    #[test]
+    fn test_records_response_tolerates_api_drift() {
+        // A real response with fields the model does not know, plus a record missing its id,
+        // zone and TTL and one with a null timestamp.
+        let json = r#"{
+            "records": [
+                {
+                    "type": "A",
+                    "id": "7a0e1d3c9b",
+                    "created": "2024-03-02 10:15:00.123 +0000 UTC",
+                    "modified": null,
+                    "zone_id": "zone1",
+                    "name": "@",
+                    "value": "1.2.3.4",
+                    "ttl": 300,
+                    "locked": false,
+                    "comment": "managed by dnrs"
+                },
+                {
+                    "type": "TXT",
+                    "name": "www",
+                    "value": "v=spf1 -all"
+                }
+            ],
+            "meta": {
+                "pagination": {
+                    "page": 1,
+                    "previous_page": null,
+                    "next_page": null,
+                    "last_page": 1
+                }
+            },
+            "links": {}
+        }"#;
+
+        let response: GetRecordsResponse = lum_libs::serde_json::from_str(json).unwrap();
+        assert!(!response.has_next_page());
+        assert!(response.records[0].modified.is_none());
+        assert!(response.records[1].id.is_none());
+
+        let records: Vec<dns::Record> = response.try_into().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].ttl, Some(300));
+        assert_eq!(records[1].ttl, None);
+        assert!(matches!(records[1].value, RecordValue::TXT(ref text) if text == "v=spf1 -all"));
+    }
+}
+
+/// A record to create, as sent to `POST /records/bulk`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct NewRecord {
+    pub zone_id: String,
+    pub r#type: RecordType,
+    pub name: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+impl NewRecord {
+    /// Returns whether `other` describes the same record, ignoring the TTL the API may have
+    /// filled in.
+    pub fn same_record(&self, other: &NewRecord) -> bool {
+        self.r#type == other.r#type
+            && self.name.eq_ignore_ascii_case(&other.name)
+            && self.value == other.value
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct BulkCreateRequest {
+    pub records: Vec<NewRecord>,
+}
+
+/// Response of `POST /records/bulk`. Records failing validation are returned in
+/// `invalid_records`, all others are created.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct BulkCreateResponse {
+    #[serde(default)]
+    pub records: Vec<Record>,
+    #[serde(default)]
+    pub valid_records: Vec<NewRecord>,
+    #[serde(default)]
+    pub invalid_records: Vec<NewRecord>,
+}
+
+/// Number of records requested per page when listing records.
+pub const RECORDS_PER_PAGE: u32 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Pagination {
+    pub page: u32,
+    #[serde(default)]
+    pub per_page: Option<u32>,
+    pub last_page: u32,
+    #[serde(default)]
+    pub total_entries: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Meta {
+    pub pagination: Pagination,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
 pub struct GetRecordsResponse {
     pub records: Vec<Record>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+}
+
+impl GetRecordsResponse {
+    /// Returns whether there are more pages after this one. Responses without pagination
+    /// information are complete.
+    pub fn has_next_page(&self) -> bool {
+        self.meta
+            .as_ref()
+            .is_some_and(|meta| meta.pagination.page < meta.pagination.last_page)
+    }
 }
 
 impl TryFrom<GetRecordsResponse> for Vec<dns::Record> {
@@ -0,0 +1,372 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    http::HttpClient,
+    provider::{
+        AddRecordInput, AddRecordsInput, DeleteRecordInput, Feature, GetAllRecordsInput, Provider,
+        ProviderError, Result, UpdateRecordInput, UpdateRecordsInput, UpsertRecordInput,
+        UpsertRecordsInput, naming::RecordNaming,
+    },
+    types::dns::{Record, RecordType},
+};
+
+/// Converts a possibly internationalized domain name to its ASCII-compatible (punycode) form.
+///
+/// ASCII names are returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::idn::to_ascii;
+///
+/// assert_eq!(to_ascii("müller.de").unwrap(), "xn--mller-kva.de");
+/// assert_eq!(to_ascii("www.example.com").unwrap(), "www.example.com");
+/// ```
+pub fn to_ascii(domain: &str) -> Result<String> {
+    if domain.is_ascii() {
+        return Ok(domain.to_string());
+    }
+
+    idna::domain_to_ascii(domain).map_err(|_| ProviderError::InvalidDomainName(domain.to_string()))
+}
+
+/// Converts an ASCII-compatible domain name back to its Unicode form for display.
+///
+/// Names that cannot be decoded are returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::idn::to_unicode;
+///
+/// assert_eq!(to_unicode("xn--mller-kva.de"), "müller.de");
+/// ```
+pub fn to_unicode(domain: &str) -> String {
+    if !domain.contains("xn--") {
+        return domain.to_string();
+    }
+
+    match idna::domain_to_unicode(domain) {
+        (unicode, Ok(())) => unicode,
+        (_, Err(_)) => domain.to_string(),
+    }
+}
+
+fn ascii_records(records: &[Record]) -> Result<Vec<Record>> {
+    records
+        .iter()
+        .map(|record| {
+            Ok(Record {
+                domain: to_ascii(&record.domain)?,
+                ..record.clone()
+            })
+        })
+        .collect()
+}
+
+/// Wraps a provider so that domain names reach it in their ASCII-compatible form.
+///
+/// Records returned by the provider have their names converted back to Unicode, so users see
+/// the domains the way they configured them.
+pub struct IdnProvider<'provider> {
+    pub inner: Box<dyn Provider + 'provider>,
+}
+
+impl<'provider> IdnProvider<'provider> {
+    pub fn new(inner: Box<dyn Provider + 'provider>) -> IdnProvider<'provider> {
+        IdnProvider { inner }
+    }
+}
+
+#[async_trait]
+impl Provider for IdnProvider<'_> {
+    fn get_provider_name(&self) -> &'static str {
+        self.inner.get_provider_name()
+    }
+
+    fn get_supported_features(&self) -> Vec<Feature> {
+        self.inner.get_supported_features()
+    }
+
+    fn supported_record_types(&self) -> &'static [RecordType] {
+        self.inner.supported_record_types()
+    }
+
+    fn record_naming(&self) -> RecordNaming {
+        self.inner.record_naming()
+    }
+
+    fn ttl_bounds(&self) -> (Option<u32>, Option<u32>) {
+        self.inner.ttl_bounds()
+    }
+
+    fn zone_of(&self, fqdn: &str) -> Option<String> {
+        self.inner.zone_of(&to_ascii(fqdn).ok()?)
+    }
+
+    async fn get_all_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &GetAllRecordsInput,
+    ) -> Result<Vec<Record>> {
+        let domain = to_ascii(input.domain)?;
+        let input = GetAllRecordsInput { domain: &domain };
+
+        let mut records = self.inner.get_all_records(http, &input).await?;
+        for record in records.iter_mut() {
+            record.domain = to_unicode(&record.domain);
+        }
+
+        Ok(records)
+    }
+
+    async fn get_records_of_type(
+        &self,
+        http: &dyn HttpClient,
+        input: &GetAllRecordsInput,
+        record_type: RecordType,
+    ) -> Result<Vec<Record>> {
+        let domain = to_ascii(input.domain)?;
+        let input = GetAllRecordsInput { domain: &domain };
+
+        let mut records = self
+            .inner
+            .get_records_of_type(http, &input, record_type)
+            .await?;
+        for record in records.iter_mut() {
+            record.domain = to_unicode(&record.domain);
+        }
+
+        Ok(records)
+    }
+
+    async fn get_records_modified_since(
+        &self,
+        http: &dyn HttpClient,
+        input: &GetAllRecordsInput,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Record>> {
+        let domain = to_ascii(input.domain)?;
+        let input = GetAllRecordsInput { domain: &domain };
+
+        let mut records = self
+            .inner
+            .get_records_modified_since(http, &input, since)
+            .await?;
+        for record in records.iter_mut() {
+            record.domain = to_unicode(&record.domain);
+        }
+
+        Ok(records)
+    }
+
+    async fn add_record(&self, http: &dyn HttpClient, input: &AddRecordInput) -> Result<()> {
+        let domain = to_ascii(input.domain)?;
+        let records = ascii_records(std::slice::from_ref(input.record))?;
+        let input = AddRecordInput {
+            domain: &domain,
+            record: &records[0],
+        };
+
+        self.inner.add_record(http, &input).await
+    }
+
+    async fn add_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &AddRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        let domain = to_ascii(input.domain)?;
+        let records = ascii_records(input.records)?;
+        let input = AddRecordsInput {
+            domain: &domain,
+            records: &records,
+        };
+
+        self.inner.add_records(http, &input).await
+    }
+
+    async fn update_record(&self, http: &dyn HttpClient, input: &UpdateRecordInput) -> Result<()> {
+        let domain = to_ascii(input.domain)?;
+        let records = ascii_records(std::slice::from_ref(input.record))?;
+        let input = UpdateRecordInput {
+            domain: &domain,
+            record: &records[0],
+        };
+
+        self.inner.update_record(http, &input).await
+    }
+
+    async fn update_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &UpdateRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        let domain = to_ascii(input.domain)?;
+        let records = ascii_records(input.records)?;
+        let input = UpdateRecordsInput {
+            domain: &domain,
+            records: &records,
+        };
+
+        self.inner.update_records(http, &input).await
+    }
+
+    async fn upsert_record(&self, http: &dyn HttpClient, input: &UpsertRecordInput) -> Result<()> {
+        let domain = to_ascii(input.domain)?;
+        let records = ascii_records(std::slice::from_ref(input.record))?;
+        let input = UpsertRecordInput {
+            domain: &domain,
+            record: &records[0],
+        };
+
+        self.inner.upsert_record(http, &input).await
+    }
+
+    async fn upsert_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &UpsertRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        let domain = to_ascii(input.domain)?;
+        let records = ascii_records(input.records)?;
+        let input = UpsertRecordsInput {
+            domain: &domain,
+            records: &records,
+        };
+
+        self.inner.upsert_records(http, &input).await
+    }
+
+    async fn delete_record(&self, http: &dyn HttpClient, input: &DeleteRecordInput) -> Result<()> {
+        let domain = to_ascii(input.domain)?;
+        let records = ascii_records(std::slice::from_ref(input.record))?;
+        let input = DeleteRecordInput {
+            domain: &domain,
+            record: &records[0],
+        };
+
+        self.inner.delete_record(http, &input).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http::FakeHttpClient, types::dns::RecordValue};
+    use std::{
+        net::Ipv4Addr,
+        sync::{Arc, Mutex},
+    };
+
+    #[test]
+    fn test_unicode_domain_round_trip() {
+        let ascii = to_ascii("www.müller.de").unwrap();
+        assert_eq!(ascii, "www.xn--mller-kva.de");
+        assert_eq!(to_unicode(&ascii), "www.müller.de");
+    }
+
+    #[test]
+    fn test_wildcard_label_survives_conversion() {
+        let ascii = to_ascii("*.müller.de").unwrap();
+        assert_eq!(ascii, "*.xn--mller-kva.de");
+        assert_eq!(to_unicode(&ascii), "*.müller.de");
+    }
+
+    struct RecordingProvider {
+        domains: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Provider for RecordingProvider {
+        fn get_provider_name(&self) -> &'static str {
+            "Recording"
+        }
+
+        fn get_supported_features(&self) -> Vec<Feature> {
+            vec![Feature::GetAllRecords, Feature::UpdateRecord]
+        }
+
+        async fn get_all_records(
+            &self,
+            _http: &dyn HttpClient,
+            input: &GetAllRecordsInput,
+        ) -> Result<Vec<Record>> {
+            self.domains.lock().unwrap().push(input.domain.to_string());
+            Ok(vec![Record {
+                domain: format!("www.{}", input.domain),
+                value: RecordValue::A(Ipv4Addr::new(1, 1, 1, 1)),
+                ttl: None,
+            }])
+        }
+
+        async fn add_record(&self, _http: &dyn HttpClient, _input: &AddRecordInput) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn update_record(
+            &self,
+            _http: &dyn HttpClient,
+            input: &UpdateRecordInput,
+        ) -> Result<()> {
+            let mut domains = self.domains.lock().unwrap();
+            domains.push(input.domain.to_string());
+            domains.push(input.record.domain.clone());
+            Ok(())
+        }
+
+        async fn delete_record(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &DeleteRecordInput,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_receives_punycode_and_returns_unicode() {
+        let domains = Arc::new(Mutex::new(Vec::new()));
+        let provider = IdnProvider::new(Box::new(RecordingProvider {
+            domains: domains.clone(),
+        }));
+        let http = FakeHttpClient::default();
+
+        let records = provider
+            .get_all_records(
+                &http,
+                &GetAllRecordsInput {
+                    domain: "müller.de",
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(records[0].domain, "www.müller.de");
+
+        let record = Record {
+            domain: "www.müller.de".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(2, 2, 2, 2)),
+            ttl: None,
+        };
+        provider
+            .update_record(
+                &http,
+                &UpdateRecordInput {
+                    domain: "müller.de",
+                    record: &record,
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *domains.lock().unwrap(),
+            vec![
+                "xn--mller-kva.de",
+                "xn--mller-kva.de",
+                "www.xn--mller-kva.de"
+            ]
+        );
+    }
+}
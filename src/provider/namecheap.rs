@@ -0,0 +1,339 @@
+use async_trait::async_trait;
+use thiserror::Error;
+use url::Url;
+
+use crate::{
+    http::{HttpClient, HttpRequest},
+    provider::{
+        AddRecordInput, DeleteRecordInput, Feature, GetAllRecordsInput, Provider, ProviderError,
+        Result, UpdateRecordInput, UpsertRecordInput, UpsertRecordsInput,
+        naming::{self, RecordNaming},
+    },
+    rate_limit::RateLimiter,
+    types::dns::{self, RecordType, RecordValue},
+};
+
+pub mod config;
+pub mod model;
+
+pub use config::{Config, DnsConfig, DomainConfig};
+pub use model::InterfaceResponse;
+
+/// Provider for Namecheap's dynamic DNS endpoint.
+///
+/// The endpoint can only set the address of existing A records of hosts with dynamic DNS
+/// enabled, it cannot list, create or delete records.
+pub struct NamecheapProvider<'provider_config> {
+    pub provider_config: &'provider_config Config,
+    pub rate_limiter: RateLimiter,
+}
+
+impl<'provider_config> NamecheapProvider<'provider_config> {
+    pub fn new(provider_config: &'provider_config Config) -> NamecheapProvider<'provider_config> {
+        NamecheapProvider {
+            provider_config,
+            rate_limiter: RateLimiter::new(provider_config.requests_per_second),
+        }
+    }
+
+    /// Builds the URL setting `host` of the configured domain to `ip`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::provider::namecheap::{Config, NamecheapProvider};
+    ///
+    /// let config = Config {
+    ///     ddns_password: "secret".into(),
+    ///     ..Default::default()
+    /// };
+    /// let provider = NamecheapProvider::new(&config);
+    ///
+    /// let url = provider.update_url("www", "1.2.3.4").unwrap();
+    /// assert_eq!(
+    ///     url.as_str(),
+    ///     "https://dynamicdns.park-your-domain.com/update?host=www&domain=example.com&password=secret&ip=1.2.3.4"
+    /// );
+    /// ```
+    pub fn update_url(&self, host: &str, ip: &str) -> Result<Url, url::ParseError> {
+        let mut url = Url::parse(&self.provider_config.api_base_url)?;
+        url.path_segments_mut()
+            .map_err(|_| url::ParseError::RelativeUrlWithCannotBeABaseBase)?
+            .pop_if_empty()
+            .push("update");
+        url.query_pairs_mut()
+            .append_pair("host", host)
+            .append_pair("domain", &self.provider_config.domain)
+            .append_pair("password", self.provider_config.ddns_password.as_str())
+            .append_pair("ip", ip);
+
+        Ok(url)
+    }
+
+    /// Returns whether dynamic DNS is enabled for the record `name` in `domain`.
+    fn is_configured_host(&self, name: &str, domain: &str) -> bool {
+        let record_naming = self.record_naming();
+        self.provider_config
+            .hosts
+            .iter()
+            .any(|host| naming::same_name(&record_naming.to_full_name(host, domain), name, domain))
+    }
+
+    async fn set_host(
+        &self,
+        http: &dyn HttpClient,
+        domain: &str,
+        record: &dns::Record,
+    ) -> Result<()> {
+        self.validate_records(std::slice::from_ref(record))?;
+
+        if !naming::is_apex(domain, &self.provider_config.domain) {
+            return Err(ProviderError::DomainNotFound(domain.to_string()));
+        }
+        if !self.is_configured_host(&record.domain, domain) {
+            return Err(ProviderError::RecordNotFound(record.domain.clone()));
+        }
+        let RecordValue::A(ip) = &record.value else {
+            return Err(ProviderError::UnsupportedRecordType(
+                record.value.record_type(),
+                self.get_provider_name(),
+            ));
+        };
+
+        let host = self
+            .record_naming()
+            .to_provider_name(&record.domain, domain);
+        let url = self
+            .update_url(&host, &ip.to_string())
+            .map_err(Error::from)?;
+
+        self.rate_limiter.acquire().await;
+        let response = http.send(HttpRequest::get(url)).await?;
+
+        if !response.is_success() {
+            return Err(Error::Unsuccessful {
+                status: response.status.as_u16(),
+                body: response.error_body(),
+            }
+            .into());
+        }
+
+        let response = InterfaceResponse::parse(&response.body).map_err(Error::from)?;
+        if !response.is_success() {
+            return Err(Error::Rejected(response.error_messages().join("; ")).into());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("HTTP request failed: {0}")]
+    Reqwest(#[from] reqwest::Error),
+
+    #[error("HTTP response is not successful: {status}, body: {body:?}")]
+    Unsuccessful { status: u16, body: String },
+
+    #[error("Invalid API base URL: {0}")]
+    Url(#[from] url::ParseError),
+
+    #[error("XML parsing error: {0}")]
+    Xml(#[from] quick_xml::DeError),
+
+    #[error("Update rejected: {0}")]
+    Rejected(String),
+}
+
+#[async_trait]
+impl Provider for NamecheapProvider<'_> {
+    fn get_provider_name(&self) -> &'static str {
+        "Namecheap"
+    }
+
+    fn get_supported_features(&self) -> Vec<Feature> {
+        vec![Feature::AddRecord, Feature::UpdateRecord]
+    }
+
+    /// The dynamic DNS endpoint only sets IPv4 addresses.
+    fn supported_record_types(&self) -> &'static [RecordType] {
+        &[RecordType::A]
+    }
+
+    fn record_naming(&self) -> RecordNaming {
+        RecordNaming::Relative { apex: "@" }
+    }
+
+    async fn get_all_records(
+        &self,
+        _http: &dyn HttpClient,
+        _input: &GetAllRecordsInput,
+    ) -> Result<Vec<dns::Record>> {
+        Err(ProviderError::UnsupportedFeature(
+            Feature::GetAllRecords,
+            self.get_provider_name(),
+        ))
+    }
+
+    /// Sets the address of the host, the A record has to exist at Namecheap already.
+    async fn add_record(&self, http: &dyn HttpClient, input: &AddRecordInput) -> Result<()> {
+        self.set_host(http, input.domain, input.record).await
+    }
+
+    async fn update_record(&self, http: &dyn HttpClient, input: &UpdateRecordInput) -> Result<()> {
+        self.set_host(http, input.domain, input.record).await
+    }
+
+    /// Setting a host adds or updates it alike, the records are not listed first.
+    async fn upsert_record(&self, http: &dyn HttpClient, input: &UpsertRecordInput) -> Result<()> {
+        self.set_host(http, input.domain, input.record).await
+    }
+
+    async fn upsert_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &UpsertRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        let mut results = Vec::with_capacity(input.records.len());
+        for record in input.records {
+            results.push(self.set_host(http, input.domain, record).await);
+        }
+
+        Ok(results)
+    }
+
+    async fn delete_record(
+        &self,
+        _http: &dyn HttpClient,
+        _input: &DeleteRecordInput,
+    ) -> Result<()> {
+        Err(ProviderError::UnsupportedFeature(
+            Feature::DeleteRecord,
+            self.get_provider_name(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path, query_param},
+    };
+
+    fn config(server: &MockServer) -> Config {
+        Config {
+            ddns_password: "test_password".into(),
+            hosts: vec!["@".to_string(), "home".to_string()],
+            api_base_url: server.uri(),
+            ..Default::default()
+        }
+    }
+
+    fn a_record(domain: &str) -> dns::Record {
+        dns::Record {
+            domain: domain.to_string(),
+            value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+            ttl: None,
+        }
+    }
+
+    #[test]
+    fn test_update_url_encodes_query() {
+        let config = Config {
+            ddns_password: "p&ss word".into(),
+            api_base_url: "https://ddns.example.net/".to_string(),
+            ..Default::default()
+        };
+        let provider = NamecheapProvider::new(&config);
+
+        let url = provider.update_url("@", "1.2.3.4").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://ddns.example.net/update?host=%40&domain=example.com&password=p%26ss+word&ip=1.2.3.4"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_record_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/update"))
+            .and(query_param("host", "home"))
+            .and(query_param("domain", "example.com"))
+            .and(query_param("password", "test_password"))
+            .and(query_param("ip", "1.2.3.4"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "<interface-response><IP>1.2.3.4</IP><ErrCount>0</ErrCount><errors /></interface-response>",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = NamecheapProvider::new(&config);
+        let record = a_record("home.example.com");
+        let input = UpdateRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        provider
+            .update_record(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_record_reports_xml_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/update"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "<interface-response><ErrCount>1</ErrCount><errors><Err1>Passwords do not match</Err1></errors></interface-response>",
+            ))
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = NamecheapProvider::new(&config);
+        let record = a_record("example.com");
+        let input = UpdateRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        let result = provider
+            .update_record(&reqwest::Client::new(), &input)
+            .await;
+        match result {
+            Err(ProviderError::Namecheap(error)) => {
+                assert!(
+                    matches!(*error, Error::Rejected(ref message) if message == "Passwords do not match")
+                );
+            }
+            other => panic!("Expected rejected update, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_record_of_unconfigured_host() {
+        let server = MockServer::start().await;
+
+        let config = config(&server);
+        let provider = NamecheapProvider::new(&config);
+        let record = a_record("www.example.com");
+        let input = UpdateRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        let result = provider
+            .update_record(&reqwest::Client::new(), &input)
+            .await;
+        assert!(matches!(result, Err(ProviderError::RecordNotFound(_))));
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+}
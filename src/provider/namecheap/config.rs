@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use lum_libs::serde::{Deserialize, Serialize};
+
+use crate::config::{dns::RecordConfig, secret::Secret};
+
+/// Namecheap only offers a dynamic DNS endpoint, authenticated by a password per domain.
+///
+/// `hosts` lists the hosts dynamic DNS is enabled for at Namecheap, `@` for the apex. Updates of
+/// other hosts are rejected before any request is sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Config {
+    pub name: String,
+    pub domain: String,
+    pub ddns_password: Secret,
+    pub hosts: Vec<String>,
+    pub api_base_url: String,
+    pub requests_per_second: Option<f64>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            name: "Namecheap1".to_string(),
+            domain: "example.com".to_string(),
+            ddns_password: "your_ddns_password".into(),
+            hosts: vec!["@".to_string()],
+            api_base_url: "https://dynamicdns.park-your-domain.com".to_string(),
+            requests_per_second: None,
+            source_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct DomainConfig {
+    pub domain: String,
+    pub records: Vec<RecordConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct DnsConfig {
+    pub provider_name: String,
+    pub domains: Vec<DomainConfig>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
+}
+
+impl Default for DnsConfig {
+    fn default() -> Self {
+        DnsConfig {
+            provider_name: "Namecheap1".to_string(),
+            domains: vec![],
+            source_path: None,
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use lum_libs::serde::Deserialize;
+
+/// The XML response of the Namecheap dynamic DNS endpoint.
+///
+/// Namecheap answers with HTTP 200 even if the update failed, failures are only reported by a
+/// non-zero `ErrCount` and the messages in `errors` (`Err1`, `Err2`, ...).
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::namecheap::model::InterfaceResponse;
+///
+/// let xml = r#"<?xml version="1.0" encoding="utf-16"?>
+/// <interface-response>
+///   <Command>SETDNSHOST</Command>
+///   <ErrCount>1</ErrCount>
+///   <errors><Err1>Passwords do not match</Err1></errors>
+///   <Done>true</Done>
+/// </interface-response>"#;
+///
+/// let response = InterfaceResponse::parse(xml).unwrap();
+/// assert_eq!(response.error_messages(), vec!["Passwords do not match"]);
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct InterfaceResponse {
+    #[serde(rename = "ErrCount")]
+    pub err_count: u32,
+    #[serde(rename = "IP", default)]
+    pub ip: Option<String>,
+    #[serde(default)]
+    pub errors: Option<BTreeMap<String, String>>,
+}
+
+impl InterfaceResponse {
+    /// Parses the response body. Namecheap declares UTF-16 in the XML header, but sends UTF-8.
+    pub fn parse(xml: &str) -> Result<Self, quick_xml::DeError> {
+        quick_xml::de::from_str(xml)
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.err_count == 0
+    }
+
+    /// Returns the error messages in the order Namecheap numbered them.
+    pub fn error_messages(&self) -> Vec<&str> {
+        let Some(errors) = &self.errors else {
+            return Vec::new();
+        };
+
+        let mut errors: Vec<(u32, &str)> = errors
+            .iter()
+            .filter_map(|(key, message)| {
+                let index = key.strip_prefix("Err")?.parse().ok()?;
+                Some((index, message.as_str()))
+            })
+            .collect();
+        errors.sort_by_key(|(index, _)| *index);
+
+        errors.into_iter().map(|(_, message)| message).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_successful_response() {
+        let xml = r#"<?xml version="1.0" encoding="utf-16"?>
+<interface-response>
+  <Command>SETDNSHOST</Command>
+  <Language>eng</Language>
+  <IP>1.2.3.4</IP>
+  <ErrCount>0</ErrCount>
+  <errors />
+  <ResponseCount>0</ResponseCount>
+  <responses />
+  <Done>true</Done>
+  <debug><![CDATA[]]></debug>
+</interface-response>"#;
+
+        let response = InterfaceResponse::parse(xml).unwrap();
+        assert!(response.is_success());
+        assert_eq!(response.ip.as_deref(), Some("1.2.3.4"));
+        assert!(response.error_messages().is_empty());
+    }
+
+    #[test]
+    fn test_parse_error_response() {
+        let xml = r#"<?xml version="1.0" encoding="utf-16"?>
+<interface-response>
+  <Command>SETDNSHOST</Command>
+  <Language>eng</Language>
+  <ErrCount>2</ErrCount>
+  <errors>
+    <Err1>Passwords do not match</Err1>
+    <Err2>No Records updated. A record not Found;</Err2>
+  </errors>
+  <ResponseCount>2</ResponseCount>
+  <Done>true</Done>
+</interface-response>"#;
+
+        let response = InterfaceResponse::parse(xml).unwrap();
+        assert!(!response.is_success());
+        assert_eq!(
+            response.error_messages(),
+            vec![
+                "Passwords do not match",
+                "No Records updated. A record not Found;"
+            ]
+        );
+    }
+}
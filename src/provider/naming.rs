@@ -0,0 +1,371 @@
+use std::sync::LazyLock;
+
+use publicsuffix::{List, Psl};
+
+/// Suggestion for a CNAME at a zone apex, which RFC 1034 forbids next to the zone's SOA and NS
+/// records. Of the supported providers only Route53 (alias records) and Namecheap (ALIAS
+/// records) can point an apex at another name. dnrs manages neither, so they have to be set up
+/// at the provider.
+pub const CNAME_AT_APEX_HINT: &str =
+    "use A/AAAA records or an ALIAS/ANAME record if the provider supports one";
+
+/// Returns whether `name` is the apex of `zone`.
+///
+/// Besides the bare zone name, the placeholders `@` and the empty string used by some providers
+/// and in configs are recognized. A trailing dot and case are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::naming::is_apex;
+///
+/// assert!(is_apex("example.com", "example.com"));
+/// assert!(is_apex("Example.COM.", "example.com"));
+/// assert!(is_apex("@", "example.com"));
+/// assert!(is_apex("", "example.com"));
+/// assert!(!is_apex("www.example.com", "example.com"));
+/// ```
+pub fn is_apex(name: &str, zone: &str) -> bool {
+    let name = name.trim_end_matches('.');
+    name.is_empty() || name == "@" || name.eq_ignore_ascii_case(zone.trim_end_matches('.'))
+}
+
+/// How a provider API names the records of a zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordNaming {
+    /// Fully qualified names, the apex is the bare domain.
+    FullDomain,
+    /// Names relative to the zone, the apex is `apex`, e.g. `@` or the empty string.
+    Relative { apex: &'static str },
+}
+
+impl RecordNaming {
+    /// Converts the fully qualified `name` into the name the provider uses within `zone`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::provider::naming::RecordNaming;
+    ///
+    /// let relative = RecordNaming::Relative { apex: "@" };
+    /// assert_eq!(relative.to_provider_name("example.com", "example.com"), "@");
+    /// assert_eq!(relative.to_provider_name("www.example.com", "example.com"), "www");
+    ///
+    /// let full = RecordNaming::FullDomain;
+    /// assert_eq!(full.to_provider_name("@", "example.com"), "example.com");
+    /// assert_eq!(full.to_provider_name("www.example.com.", "example.com"), "www.example.com");
+    /// ```
+    pub fn to_provider_name(&self, name: &str, zone: &str) -> String {
+        let name = name.trim_end_matches('.');
+        let zone = zone.trim_end_matches('.');
+
+        match self {
+            RecordNaming::FullDomain if is_apex(name, zone) => zone.to_string(),
+            RecordNaming::FullDomain => name.to_string(),
+            RecordNaming::Relative { apex } if is_apex(name, zone) => apex.to_string(),
+            RecordNaming::Relative { .. } => match name.len().checked_sub(zone.len() + 1) {
+                Some(split) if name[split..].eq_ignore_ascii_case(&format!(".{}", zone)) => {
+                    name[..split].to_string()
+                }
+                _ => name.to_string(),
+            },
+        }
+    }
+
+    /// Converts a name the provider returned for a record in `zone` into the fully qualified
+    /// name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::provider::naming::RecordNaming;
+    ///
+    /// let relative = RecordNaming::Relative { apex: "@" };
+    /// assert_eq!(relative.to_full_name("@", "example.com"), "example.com");
+    /// assert_eq!(relative.to_full_name("www", "example.com"), "www.example.com");
+    /// ```
+    pub fn to_full_name(&self, name: &str, zone: &str) -> String {
+        let name = name.trim_end_matches('.');
+        let zone = zone.trim_end_matches('.');
+
+        if is_apex(name, zone) {
+            return zone.to_string();
+        }
+
+        let is_qualified = name.len() > zone.len()
+            && name[name.len() - zone.len() - 1..].eq_ignore_ascii_case(&format!(".{}", zone));
+        match self {
+            RecordNaming::Relative { .. } if !is_qualified => format!("{}.{}", name, zone),
+            _ => name.to_string(),
+        }
+    }
+}
+
+/// Returns the fully qualified form of `name`, a record name in `zone` that is either fully
+/// qualified already or relative to it. `@` is the apex.
+///
+/// A `*` label is kept as it is, it names the wildcard record of its parent.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::naming::qualify;
+///
+/// assert_eq!(qualify("www", "example.com"), "www.example.com");
+/// assert_eq!(qualify("*", "example.com"), "*.example.com");
+/// assert_eq!(qualify("WWW.example.com.", "example.com"), "WWW.example.com");
+/// assert_eq!(qualify("@", "example.com"), "example.com");
+/// ```
+pub fn qualify(name: &str, zone: &str) -> String {
+    RecordNaming::Relative { apex: "@" }.to_full_name(name, zone)
+}
+
+/// Returns whether `name` and `other` are the same record name within `zone`, treating all
+/// spellings of the apex alike. Names are compared literally, `*` only equals the wildcard label,
+/// see [`name_matches`] for patterns.
+pub fn same_name(name: &str, other: &str, zone: &str) -> bool {
+    let naming = RecordNaming::FullDomain;
+    naming
+        .to_provider_name(name, zone)
+        .eq_ignore_ascii_case(&naming.to_provider_name(other, zone))
+}
+
+/// Returns whether `text` matches the glob `pattern`, ignoring case. `*` matches any sequence of
+/// characters, including dots, and `?` matches a single character.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::naming::glob_matches;
+///
+/// assert!(glob_matches("api-*", "api-eu"));
+/// assert!(glob_matches("*.example.com", "WWW.example.com"));
+/// assert!(glob_matches("node?", "node1"));
+/// assert!(!glob_matches("api-*", "www"));
+/// ```
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    // Greedy matching, backtracking to the last `*` on a mismatch
+    let (mut p, mut t) = (0, 0);
+    let mut last_star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                last_star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match last_star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    last_star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Returns whether the record `name` within `zone` matches the glob `pattern`, written either
+/// fully qualified (`*.example.com`) or relative to the zone (`api-*`, `@` for the apex).
+pub fn name_matches(pattern: &str, name: &str, zone: &str) -> bool {
+    let pattern = pattern.trim_end_matches('.');
+    let full_name = RecordNaming::FullDomain.to_provider_name(name, zone);
+    let relative_name = RecordNaming::Relative { apex: "@" }.to_provider_name(name, zone);
+
+    glob_matches(pattern, &full_name) || glob_matches(pattern, &relative_name)
+}
+
+/// The public suffix list, parsed on first use. Embedded so lookups work offline.
+static PUBLIC_SUFFIX_LIST: LazyLock<List> = LazyLock::new(|| {
+    include_str!("../../data/public_suffix_list.dat")
+        .parse()
+        .expect("embedded public suffix list is valid")
+});
+
+/// Returns the registrable zone of `fqdn`: its public suffix plus one label.
+///
+/// Multi-label suffixes like `co.uk` are taken into account. Public suffixes themselves have no
+/// registrable zone. A trailing dot and case are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::naming::registrable_zone;
+///
+/// assert_eq!(registrable_zone("www.example.com").as_deref(), Some("example.com"));
+/// assert_eq!(registrable_zone("www.example.co.uk").as_deref(), Some("example.co.uk"));
+/// assert_eq!(registrable_zone("co.uk"), None);
+/// ```
+pub fn registrable_zone(fqdn: &str) -> Option<String> {
+    let fqdn = fqdn.trim_end_matches('.').to_ascii_lowercase();
+    let domain = PUBLIC_SUFFIX_LIST.domain(fqdn.as_bytes())?;
+
+    String::from_utf8(domain.as_bytes().to_vec()).ok()
+}
+
+/// Returns whether `name` is `zone` itself or one of its subdomains.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::naming::is_within;
+///
+/// assert!(is_within("www.example.com", "example.com"));
+/// assert!(is_within("@", "example.com"));
+/// assert!(!is_within("www.example.org", "example.com"));
+/// assert!(!is_within("badexample.com", "example.com"));
+/// ```
+pub fn is_within(name: &str, zone: &str) -> bool {
+    if is_apex(name, zone) {
+        return true;
+    }
+
+    let name = name.trim_end_matches('.').to_ascii_lowercase();
+    let zone = zone.trim_end_matches('.').to_ascii_lowercase();
+    name.strip_suffix(&zone)
+        .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apex_mapping_round_trip() {
+        let namings = [
+            RecordNaming::FullDomain,
+            RecordNaming::Relative { apex: "@" },
+            RecordNaming::Relative { apex: "" },
+        ];
+
+        for naming in namings {
+            for apex in ["example.com", "example.com.", "@", ""] {
+                let provider_name = naming.to_provider_name(apex, "example.com");
+                assert_eq!(
+                    naming.to_full_name(&provider_name, "example.com"),
+                    "example.com"
+                );
+            }
+
+            let provider_name = naming.to_provider_name("a.b.example.com", "example.com");
+            assert_eq!(
+                naming.to_full_name(&provider_name, "example.com"),
+                "a.b.example.com"
+            );
+        }
+    }
+
+    #[test]
+    fn test_same_name_ignores_apex_spelling() {
+        assert!(same_name("@", "example.com.", "example.com"));
+        assert!(same_name(
+            "WWW.example.com",
+            "www.example.com",
+            "example.com"
+        ));
+        assert!(!same_name("www.example.com", "example.com", "example.com"));
+    }
+
+    #[test]
+    fn test_name_matches_star_pattern() {
+        assert!(name_matches(
+            "*.example.com",
+            "www.example.com",
+            "example.com"
+        ));
+        assert!(name_matches(
+            "*.example.com",
+            "a.b.example.com",
+            "example.com"
+        ));
+        assert!(!name_matches("*.example.com", "example.com", "example.com"));
+        assert!(name_matches("*", "example.com", "example.com"));
+        assert!(name_matches("@", "example.com", "example.com"));
+    }
+
+    #[test]
+    fn test_name_matches_prefix_pattern() {
+        assert!(name_matches("api-*", "api-eu.example.com", "example.com"));
+        assert!(name_matches("api-*", "API-us.example.com.", "example.com"));
+        assert!(!name_matches("api-*", "www.example.com", "example.com"));
+        assert!(!name_matches(
+            "api-*",
+            "my-api-eu.example.com",
+            "example.com"
+        ));
+    }
+
+    #[test]
+    fn test_wildcard_label_is_literal() {
+        let relative = RecordNaming::Relative { apex: "@" };
+        assert_eq!(
+            relative.to_provider_name("*.example.com", "example.com"),
+            "*"
+        );
+        assert_eq!(relative.to_full_name("*", "example.com"), "*.example.com");
+        assert_eq!(
+            relative.to_provider_name("*.sub.example.com", "example.com"),
+            "*.sub"
+        );
+        assert_eq!(
+            RecordNaming::FullDomain.to_full_name("*.example.com.", "example.com"),
+            "*.example.com"
+        );
+
+        assert!(same_name("*.example.com", "*.EXAMPLE.com.", "example.com"));
+        assert!(!same_name(
+            "*.example.com",
+            "www.example.com",
+            "example.com"
+        ));
+        assert!(!same_name("*.example.com", "example.com", "example.com"));
+        assert!(is_within("*.example.com", "example.com"));
+        assert_eq!(
+            registrable_zone("*.example.com").as_deref(),
+            Some("example.com")
+        );
+    }
+
+    #[test]
+    fn test_name_matches_wildcard_record_as_pattern() {
+        // As a pattern `*` matches any name, the wildcard record included
+        assert!(name_matches(
+            "*.example.com",
+            "*.example.com",
+            "example.com"
+        ));
+        assert!(name_matches("*", "*.example.com", "example.com"));
+        assert!(!name_matches("www", "*.example.com", "example.com"));
+    }
+
+    #[test]
+    fn test_registrable_zone_of_multi_label_suffixes() {
+        assert_eq!(
+            registrable_zone("www.example.co.uk").as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(
+            registrable_zone("a.b.example.com.au").as_deref(),
+            Some("example.com.au")
+        );
+        assert_eq!(
+            registrable_zone("WWW.Example.CO.UK.").as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(
+            registrable_zone("example.co.uk").as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(registrable_zone("co.uk"), None);
+        assert_eq!(registrable_zone("com.au"), None);
+    }
+}
@@ -1,42 +1,278 @@
-use anyhow::Result;
 use async_trait::async_trait;
+use lum_libs::serde::{Serialize, de::DeserializeOwned};
 use lum_libs::serde_json;
+use lum_log::error;
+use reqwest::header::{CONTENT_TYPE, HeaderValue};
 use thiserror::Error;
 
 use crate::{
-    provider::{Feature, GetAllRecordsInput, Provider},
-    types::dns::{self},
+    http::{HttpClient, HttpRequest},
+    provider::{
+        self, AddRecordInput, AddRecordsInput, DeleteRecordInput, Feature, GetAllRecordsInput,
+        Provider, ProviderError, Result, UpdateRecordInput, UpdateRecordsInput,
+        naming::RecordNaming,
+    },
+    rate_limit::RateLimiter,
+    types::dns::{self, RecordType},
 };
 
 pub mod config;
 pub mod model;
 
 pub use config::{Config, DnsConfig, DomainConfig};
-pub use model::{GetRecordsResponse, Record, TryFromRecordError};
+pub use model::{
+    DnsRecordSet, GetRecordsResponse, InfoDnsRecordsParam, LoginParam, LoginResponseData, Record,
+    Request, Response, SessionParam, TryFromRecordError, UpdateDnsRecordsParam, relative_hostname,
+};
 
 pub struct NetcupProvider<'provider_config> {
     pub provider_config: &'provider_config Config,
+    pub rate_limiter: RateLimiter,
 }
 
 impl<'provider_config> NetcupProvider<'provider_config> {
     pub fn new(provider_config: &'provider_config Config) -> NetcupProvider<'provider_config> {
-        NetcupProvider { provider_config }
+        NetcupProvider {
+            provider_config,
+            rate_limiter: RateLimiter::new(provider_config.requests_per_second),
+        }
+    }
+
+    fn session_param(&self, session_id: &str) -> SessionParam {
+        SessionParam {
+            customernumber: self.provider_config.customer_number,
+            apikey: self.provider_config.api_key.as_str().to_string(),
+            apisessionid: session_id.to_string(),
+        }
+    }
+
+    /// Builds the `updateDnsRecords` request writing `records` to `domain`.
+    pub fn update_dns_records_request(
+        &self,
+        session_id: &str,
+        domain: &str,
+        records: Vec<Record>,
+    ) -> Request<UpdateDnsRecordsParam> {
+        Request {
+            action: "updateDnsRecords",
+            param: UpdateDnsRecordsParam {
+                session: self.session_param(session_id),
+                domainname: domain.to_string(),
+                dnsrecordset: DnsRecordSet {
+                    dnsrecords: records,
+                },
+            },
+        }
+    }
+
+    async fn call<P, D>(&self, http: &dyn HttpClient, request: &Request<P>) -> Result<D>
+    where
+        P: Serialize,
+        D: DeserializeOwned,
+    {
+        let request = HttpRequest::post(&self.provider_config.api_base_url)
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .body(serde_json::to_string(request)?);
+
+        self.rate_limiter.acquire().await;
+        let response = http.send(request).await?;
+
+        if !response.is_success() {
+            return Err(Error::Unsuccessful {
+                status: response.status.as_u16(),
+                body: response.error_body(),
+            }
+            .into());
+        }
+
+        let response: Response = serde_json::from_str(&response.body)?;
+        if response.status != "success" {
+            return Err(Error::ApiError {
+                status: response.status,
+                message: response.longmessage.unwrap_or(response.shortmessage),
+            }
+            .into());
+        }
+
+        Ok(serde_json::from_value(response.responsedata)?)
+    }
+
+    async fn login(&self, http: &dyn HttpClient) -> Result<String> {
+        let request = Request {
+            action: "login",
+            param: LoginParam {
+                customernumber: self.provider_config.customer_number,
+                apikey: self.provider_config.api_key.as_str().to_string(),
+                apipassword: self.provider_config.api_password.as_str().to_string(),
+            },
+        };
+
+        let data: LoginResponseData = self.call(http, &request).await?;
+        Ok(data.apisessionid)
+    }
+
+    async fn logout(&self, http: &dyn HttpClient, session_id: &str) {
+        let request = Request {
+            action: "logout",
+            param: self.session_param(session_id),
+        };
+
+        if let Err(e) = self.call::<_, serde_json::Value>(http, &request).await {
+            error!("Failed to log out of Netcup session: {}", e);
+        }
+    }
+
+    async fn info_dns_records(
+        &self,
+        http: &dyn HttpClient,
+        session_id: &str,
+        domain: &str,
+    ) -> Result<Vec<Record>> {
+        let request = Request {
+            action: "infoDnsRecords",
+            param: InfoDnsRecordsParam {
+                session: self.session_param(session_id),
+                domainname: domain.to_string(),
+            },
+        };
+
+        let response: GetRecordsResponse = self.call(http, &request).await?;
+        Ok(response.records)
+    }
+
+    async fn update_dns_records(
+        &self,
+        http: &dyn HttpClient,
+        session_id: &str,
+        domain: &str,
+        records: Vec<Record>,
+    ) -> Result<()> {
+        let request = self.update_dns_records_request(session_id, domain, records);
+        self.call::<_, serde_json::Value>(http, &request).await?;
+        Ok(())
+    }
+
+    /// Converts `record` into the Netcup representation with a hostname relative to `domain`.
+    fn to_api_record(record: &dns::Record, domain: &str) -> Record {
+        let mut api_record = Record::from(record);
+        api_record.hostname = relative_hostname(&record.domain, domain);
+        api_record
+    }
+
+    /// Writes all `records` to `domain` in a single session and `updateDnsRecords` call.
+    ///
+    /// Updated records that are not found get an individual [`ProviderError::RecordNotFound`],
+    /// deleted ones that are not found are already absent. All others share the outcome of the
+    /// batch call.
+    async fn write_records(
+        &self,
+        http: &dyn HttpClient,
+        domain: &str,
+        records: &[dns::Record],
+        write: WriteMode,
+    ) -> Result<Vec<Result<()>>> {
+        let session_id = self.login(http).await?;
+        let result = self
+            .write_records_in_session(http, &session_id, domain, records, write)
+            .await;
+        self.logout(http, &session_id).await;
+
+        result
+    }
+
+    async fn write_records_in_session(
+        &self,
+        http: &dyn HttpClient,
+        session_id: &str,
+        domain: &str,
+        records: &[dns::Record],
+        write: WriteMode,
+    ) -> Result<Vec<Result<()>>> {
+        let existing = match write {
+            WriteMode::Add => Vec::new(),
+            _ => self.info_dns_records(http, session_id, domain).await?,
+        };
+
+        let mut results = Vec::with_capacity(records.len());
+        let mut api_records = Vec::with_capacity(records.len());
+        for record in records {
+            let mut api_record = Self::to_api_record(record, domain);
+            if write != WriteMode::Add {
+                let matched = existing.iter().find(|existing| {
+                    existing.hostname == api_record.hostname
+                        && existing.r#type == api_record.r#type
+                        && (write == WriteMode::Update
+                            || existing.destination == api_record.destination)
+                });
+
+                let Some(matched) = matched else {
+                    let result = match write {
+                        WriteMode::Delete => {
+                            provider::already_absent(self.get_provider_name(), record)
+                        }
+                        _ => Err(ProviderError::RecordNotFound(record.domain.clone())),
+                    };
+                    results.push(result);
+                    continue;
+                };
+
+                api_record.id = matched.id.clone();
+                if write == WriteMode::Delete {
+                    api_record.deleterecord = Some(true);
+                }
+            }
+
+            results.push(Ok(()));
+            api_records.push(api_record);
+        }
+
+        if !api_records.is_empty() {
+            self.update_dns_records(http, session_id, domain, api_records)
+                .await?;
+        }
+
+        Ok(results)
+    }
+
+    async fn write_record(
+        &self,
+        http: &dyn HttpClient,
+        domain: &str,
+        record: &dns::Record,
+        write: WriteMode,
+    ) -> Result<()> {
+        let records = std::slice::from_ref(record);
+        self.write_records(http, domain, records, write)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or(Ok(()))
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WriteMode {
+    Add,
+    Update,
+    Delete,
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("HTTP request failed: {0}")]
     Reqwest(#[from] reqwest::Error),
 
-    #[error("HTTP response is not successful: {0}")]
-    Unsuccessful(u16, reqwest::Response),
+    #[error("HTTP response is not successful: {status}, body: {body:?}")]
+    Unsuccessful { status: u16, body: String },
 
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
 
-    #[error("Domain '{0}' not found in Netcup zones")]
-    DomainNotFound(String),
+    #[error("Invalid record in response: {0}")]
+    TryFromRecord(#[from] TryFromRecordError),
+
+    #[error("Netcup API returned status {status}: {message}")]
+    ApiError { status: String, message: String },
 }
 
 #[async_trait]
@@ -45,6 +281,10 @@ impl Provider for NetcupProvider<'_> {
         "Netcup"
     }
 
+    fn record_naming(&self) -> RecordNaming {
+        RecordNaming::Relative { apex: "@" }
+    }
+
     fn get_supported_features(&self) -> Vec<Feature> {
         vec![
             Feature::GetRecords,
@@ -52,27 +292,311 @@ impl Provider for NetcupProvider<'_> {
             Feature::AddRecord,
             Feature::UpdateRecord,
             Feature::DeleteRecord,
+            Feature::BatchWrite,
         ]
     }
 
+    /// The SOA record is managed by Netcup.
+    fn supported_record_types(&self) -> &'static [RecordType] {
+        &[
+            RecordType::A,
+            RecordType::AAAA,
+            RecordType::CNAME,
+            RecordType::TXT,
+            RecordType::MX,
+            RecordType::NS,
+            RecordType::SRV,
+            RecordType::TLSA,
+            RecordType::CAA,
+            RecordType::DS,
+        ]
+    }
 
     async fn get_all_records(
         &self,
-        _reqwest: reqwest::Client,
-        _input: &GetAllRecordsInput,
+        http: &dyn HttpClient,
+        input: &GetAllRecordsInput,
     ) -> Result<Vec<dns::Record>> {
-        unimplemented!("Netcup get_all_records not yet implemented")
+        let session_id = self.login(http).await?;
+        let result = self.info_dns_records(http, &session_id, input.domain).await;
+        self.logout(http, &session_id).await;
+
+        let response = GetRecordsResponse { records: result? };
+        let mut records: Vec<dns::Record> = response.try_into().map_err(Error::from)?;
+        let naming = self.record_naming();
+        for record in records.iter_mut() {
+            record.domain = naming.to_full_name(&record.domain, input.domain);
+        }
+
+        Ok(records)
+    }
+
+    async fn add_record(&self, http: &dyn HttpClient, input: &AddRecordInput) -> Result<()> {
+        self.validate_records(std::slice::from_ref(input.record))?;
+        self.write_record(http, input.domain, input.record, WriteMode::Add)
+            .await
+    }
+
+    async fn add_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &AddRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        self.validate_records(input.records)?;
+        self.write_records(http, input.domain, input.records, WriteMode::Add)
+            .await
+    }
+
+    async fn update_record(&self, http: &dyn HttpClient, input: &UpdateRecordInput) -> Result<()> {
+        self.validate_records(std::slice::from_ref(input.record))?;
+        self.write_record(http, input.domain, input.record, WriteMode::Update)
+            .await
+    }
+
+    async fn update_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &UpdateRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        self.validate_records(input.records)?;
+        self.write_records(http, input.domain, input.records, WriteMode::Update)
+            .await
+    }
+
+    async fn delete_record(&self, http: &dyn HttpClient, input: &DeleteRecordInput) -> Result<()> {
+        self.write_record(http, input.domain, input.record, WriteMode::Delete)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dns::{MxRecord, RecordValue};
+    use lum_libs::serde_json::json;
+    use std::net::Ipv4Addr;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_partial_json, method},
+    };
+
+    fn config() -> Config {
+        Config {
+            customer_number: 12345,
+            api_key: "key".into(),
+            api_password: "password".into(),
+            ..Default::default()
+        }
     }
 
-    async fn add_record(&self, _reqwest: reqwest::Client, _input: &dns::Record) -> Result<()> {
-        unimplemented!("Netcup add_record not yet implemented")
+    #[test]
+    fn test_update_dns_records_payload_add() {
+        let config = config();
+        let provider = NetcupProvider::new(&config);
+        let record = dns::Record {
+            domain: "home.example.com".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+            ttl: None,
+        };
+
+        let api_record = NetcupProvider::to_api_record(&record, "example.com");
+        let request =
+            provider.update_dns_records_request("session", "example.com", vec![api_record]);
+
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "action": "updateDnsRecords",
+                "param": {
+                    "customernumber": 12345,
+                    "apikey": "key",
+                    "apisessionid": "session",
+                    "domainname": "example.com",
+                    "dnsrecordset": {
+                        "dnsrecords": [{
+                            "id": null,
+                            "hostname": "home",
+                            "type": "A",
+                            "priority": null,
+                            "destination": "1.2.3.4",
+                            "deleterecord": null,
+                            "state": null,
+                        }]
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_update_dns_records_payload_delete() {
+        let config = config();
+        let provider = NetcupProvider::new(&config);
+        let record = dns::Record {
+            domain: "example.com".to_string(),
+            value: RecordValue::MX(MxRecord {
+                priority: 10,
+                target: "mail.example.com".to_string(),
+            }),
+            ttl: None,
+        };
+
+        let mut api_record = NetcupProvider::to_api_record(&record, "example.com");
+        api_record.id = Some("42".to_string());
+        api_record.deleterecord = Some(true);
+        let request =
+            provider.update_dns_records_request("session", "example.com", vec![api_record]);
+
+        let value = serde_json::to_value(&request).unwrap();
+        let dns_record = &value["param"]["dnsrecordset"]["dnsrecords"][0];
+        assert_eq!(dns_record["id"], "42");
+        assert_eq!(dns_record["hostname"], "@");
+        assert_eq!(dns_record["type"], "MX");
+        assert_eq!(dns_record["priority"], "10");
+        assert_eq!(dns_record["destination"], "mail.example.com");
+        assert_eq!(dns_record["deleterecord"], true);
     }
 
-    async fn update_record(&self, _reqwest: reqwest::Client, _input: &dns::Record) -> Result<()> {
-        unimplemented!("Netcup update_record not yet implemented")
+    fn success(data: serde_json::Value) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(json!({
+            "status": "success",
+            "statuscode": 2000,
+            "shortmessage": "ok",
+            "longmessage": null,
+            "responsedata": data,
+        }))
     }
 
-    async fn delete_record(&self, _reqwest: reqwest::Client, _input: &dns::Record) -> Result<()> {
-        unimplemented!("Netcup delete_record not yet implemented")
+    #[tokio::test]
+    async fn test_delete_record_marks_existing_record() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"action": "login"})))
+            .respond_with(success(json!({"apisessionid": "session"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"action": "infoDnsRecords"})))
+            .respond_with(success(json!({"dnsrecords": [{
+                "id": "7",
+                "hostname": "home",
+                "type": "A",
+                "priority": "0",
+                "destination": "1.2.3.4",
+                "deleterecord": false,
+                "state": "yes",
+            }]})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({
+                "action": "updateDnsRecords",
+                "param": {"dnsrecordset": {"dnsrecords": [{"id": "7", "deleterecord": true}]}},
+            })))
+            .respond_with(success(json!({"dnsrecords": []})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"action": "logout"})))
+            .respond_with(success(json!("")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = Config {
+            api_base_url: server.uri(),
+            ..config()
+        };
+        let provider = NetcupProvider::new(&config);
+        let record = dns::Record {
+            domain: "home.example.com".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+            ttl: None,
+        };
+        let input = DeleteRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        provider
+            .delete_record(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_api_error_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "status": "error",
+                "statuscode": 4013,
+                "shortmessage": "Validation Error.",
+                "longmessage": "Invalid API key",
+                "responsedata": "",
+            })))
+            .mount(&server)
+            .await;
+
+        let config = Config {
+            api_base_url: server.uri(),
+            ..config()
+        };
+        let provider = NetcupProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let result = provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await;
+        match result {
+            Err(ProviderError::Netcup(error)) => match *error {
+                Error::ApiError { status, message } => {
+                    assert_eq!(status, "error");
+                    assert_eq!(message, "Invalid API key");
+                }
+                other => panic!("Expected API error, got {:?}", other),
+            },
+            _ => panic!("Expected Netcup error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_html_error_page_is_status_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(502)
+                    .set_body_raw("<html><h1>502 Bad Gateway</h1></html>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        let config = Config {
+            api_base_url: server.uri(),
+            ..config()
+        };
+        let provider = NetcupProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let result = provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await;
+        match result {
+            Err(ProviderError::Netcup(error)) => match *error {
+                Error::Unsuccessful { status, body } => {
+                    assert_eq!(status, 502);
+                    assert_eq!(body, "<html><h1>502 Bad Gateway</h1></html>");
+                }
+                other => panic!("Expected status error, got {:?}", other),
+            },
+            _ => panic!("Expected Netcup error"),
+        }
     }
 }
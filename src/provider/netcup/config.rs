@@ -1,15 +1,22 @@
+use std::path::PathBuf;
+
 use lum_libs::serde::{Deserialize, Serialize};
 
-use crate::config::dns::RecordConfig;
+use crate::config::{dns::RecordConfig, secret::Secret};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
 pub struct Config {
     pub name: String,
     pub customer_number: u32,
-    pub api_key: String,
-    pub api_password: String,
+    pub api_key: Secret,
+    pub api_password: Secret,
     pub api_base_url: String,
+    pub requests_per_second: Option<f64>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -17,9 +24,11 @@ impl Default for Config {
         Config {
             name: "Netcup1".to_string(),
             customer_number: 0,
-            api_key: "your_api_key".to_string(),
-            api_password: "your_api_password".to_string(),
+            api_key: "your_api_key".into(),
+            api_password: "your_api_password".into(),
             api_base_url: "https://ccp.netcup.net/run/webservice/servers/endpoint.php".to_string(),
+            requests_per_second: None,
+            source_path: None,
         }
     }
 }
@@ -36,6 +45,10 @@ pub struct DomainConfig {
 pub struct DnsConfig {
     pub provider_name: String,
     pub domains: Vec<DomainConfig>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
 }
 
 impl Default for DnsConfig {
@@ -43,6 +56,7 @@ impl Default for DnsConfig {
         DnsConfig {
             provider_name: "Netcup1".to_string(),
             domains: vec![],
+            source_path: None,
         }
     }
 }
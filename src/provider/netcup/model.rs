@@ -4,10 +4,16 @@ use std::{
     str::FromStr,
 };
 
-use lum_libs::serde::{Deserialize, Serialize};
+use lum_libs::{
+    serde::{Deserialize, Serialize},
+    serde_json,
+};
 use thiserror::Error;
 
-use crate::types::dns::{self, MxRecord, RecordType, RecordValue};
+use crate::{
+    provider::naming::RecordNaming,
+    types::dns::{self, DnssecParseError, MxRecord, RecordType, RecordValue},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
@@ -49,6 +55,14 @@ pub enum TryFromRecordError {
 
     #[error("Invalid CAA record flag: {0}")]
     InvalidCaaFlag(num::ParseIntError),
+
+    #[error("Record type {0:?} is not supported by this provider")]
+    UnsupportedRecordType(RecordType),
+    #[error("Invalid DNSSEC record: {0}")]
+    InvalidDnssec(#[from] DnssecParseError),
+
+    #[error("Invalid record: {0}")]
+    InvalidRecord(#[from] dns::RecordValidationError),
 }
 
 /// Converts a Netcup API record into the internal [`dns::Record`] type.
@@ -133,7 +147,7 @@ impl TryFrom<Record> for dns::Record {
                     let port = parts[1]
                         .parse::<u16>()
                         .map_err(TryFromRecordError::InvalidSrvValue)?;
-                    let target = parts[2].to_string();
+                    let target = parts[2].trim_end_matches('.').to_string();
 
                     RecordValue::SRV(priority, weight, port, target)
                 } else {
@@ -175,16 +189,146 @@ impl TryFrom<Record> for dns::Record {
 
                 RecordValue::CAA(flag, tag, value)
             }
+            RecordType::DS => RecordValue::parse_ds(&api_record.destination)?,
+            RecordType::SVCB | RecordType::HTTPS | RecordType::DNSKEY => {
+                return Err(TryFromRecordError::UnsupportedRecordType(api_record.r#type));
+            }
         };
 
-        Ok(dns::Record {
-            domain: api_record.hostname,
-            value,
-            ttl: None,
-        })
+        Ok(dns::Record::builder(api_record.hostname, value)
+            .ttl(None)
+            .build()?)
+    }
+}
+
+/// Converts an internal [`dns::Record`] into the Netcup API representation.
+///
+/// Netcup keeps the priority of MX and SRV records in a separate field, so it is split off the
+/// destination. The SRV target is written as an absolute name, Netcup would otherwise append the
+/// zone. The hostname is taken as is; see [`relative_hostname`] for making it relative to
+/// the zone.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::netcup::model::Record;
+/// use dnrs::types::dns::{self, RecordValue};
+///
+/// let record = dns::Record {
+///     domain: "_sip._tcp".to_string(),
+///     value: RecordValue::SRV(10, 5, 5060, "sip.example.com".to_string()),
+///     ttl: None,
+/// };
+///
+/// let api_record = Record::from(&record);
+/// assert_eq!(api_record.priority.as_deref(), Some("10"));
+/// assert_eq!(api_record.destination, "5 5060 sip.example.com.");
+/// ```
+impl From<&dns::Record> for Record {
+    fn from(record: &dns::Record) -> Self {
+        let (priority, destination) = match &record.value {
+            RecordValue::MX(mx) => (Some(mx.priority.to_string()), mx.target.clone()),
+            RecordValue::SRV(priority, weight, port, target) => (
+                Some(priority.to_string()),
+                format!("{} {} {}", weight, port, dns::absolute_name(target)),
+            ),
+            value => (None, value.content()),
+        };
+
+        Record {
+            id: None,
+            hostname: record.domain.clone(),
+            r#type: record.value.wire_type(),
+            priority,
+            destination,
+            deleterecord: None,
+            state: None,
+        }
     }
 }
 
+/// Returns `name` relative to `zone` as Netcup expects it, using `@` for the zone apex.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::netcup::model::relative_hostname;
+///
+/// assert_eq!(relative_hostname("www.example.com", "example.com"), "www");
+/// assert_eq!(relative_hostname("example.com", "example.com"), "@");
+/// assert_eq!(relative_hostname("www", "example.com"), "www");
+/// ```
+pub fn relative_hostname(name: &str, zone: &str) -> String {
+    RecordNaming::Relative { apex: "@" }.to_provider_name(name, zone)
+}
+
+/// Envelope of every Netcup CCP API request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Request<P> {
+    pub action: &'static str,
+    pub param: P,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct LoginParam {
+    pub customernumber: u32,
+    pub apikey: String,
+    pub apipassword: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct SessionParam {
+    pub customernumber: u32,
+    pub apikey: String,
+    pub apisessionid: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct InfoDnsRecordsParam {
+    #[serde(flatten)]
+    pub session: SessionParam,
+    pub domainname: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct DnsRecordSet {
+    pub dnsrecords: Vec<Record>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct UpdateDnsRecordsParam {
+    #[serde(flatten)]
+    pub session: SessionParam,
+    pub domainname: String,
+    pub dnsrecordset: DnsRecordSet,
+}
+
+/// Envelope of every Netcup CCP API response.
+///
+/// `responsedata` is kept untyped because Netcup sends an empty string instead of an object
+/// when a request fails.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Response {
+    pub status: String,
+    pub statuscode: u32,
+    pub shortmessage: String,
+    pub longmessage: Option<String>,
+    pub responsedata: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct LoginResponseData {
+    pub apisessionid: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -231,11 +375,29 @@ mod tests {
             panic!("Expected MX record");
         }
     }
+
+    #[test]
+    fn test_srv_record_round_trips() {
+        let record = dns::Record {
+            domain: "_sip._tcp".to_string(),
+            value: RecordValue::SRV(10, 5, 5060, "sip.example.com".to_string()),
+            ttl: None,
+        };
+
+        let api_record = Record::from(&record);
+        let dns_record = dns::Record::try_from(api_record).unwrap();
+        assert_eq!(dns_record.domain, "_sip._tcp");
+        assert!(matches!(
+            dns_record.value,
+            RecordValue::SRV(10, 5, 5060, ref target) if target == "sip.example.com"
+        ));
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
 pub struct GetRecordsResponse {
+    #[serde(rename = "dnsrecords")]
     pub records: Vec<Record>,
 }
 
@@ -1,28 +1,109 @@
-use anyhow::Result;
 use async_trait::async_trait;
 use lum_libs::serde_json;
-use reqwest::header::HeaderMap;
+use reqwest::{
+    Method,
+    header::{CONTENT_TYPE, HeaderMap, HeaderValue},
+};
 use thiserror::Error;
 
 use crate::{
-    provider::{Feature, GetAllRecordsInput, Provider},
-    types::dns::{self},
+    http::{HttpClient, HttpRequest},
+    provider::{
+        self, AddRecordInput, DeleteRecordInput, Feature, GetAllRecordsInput, Provider,
+        ProviderError, Result, UpdateRecordInput,
+    },
+    rate_limit::RateLimiter,
+    types::dns::{self, RecordType},
 };
 
 pub mod config;
 pub mod model;
 
 pub use config::{Config, DnsConfig, DomainConfig};
-pub use model::{GetRecordsResponse, Record, RecordMode, TryFromRecordError};
+pub use model::{
+    GetRecordsResponse, Record, RecordMode, StatusResponse, TryFromRecordError, WriteRecordRequest,
+};
 
 pub struct NitradoProvider<'provider_config> {
     pub provider_config: &'provider_config Config,
+    pub rate_limiter: RateLimiter,
 }
 
 impl<'provider_config> NitradoProvider<'provider_config> {
     pub fn new(provider_config: &'provider_config Config) -> NitradoProvider<'provider_config> {
-        NitradoProvider { provider_config }
+        NitradoProvider {
+            provider_config,
+            rate_limiter: RateLimiter::new(provider_config.requests_per_second),
+        }
+    }
+
+    fn auth_headers(&self) -> Result<HeaderMap> {
+        let authorization = format!("Bearer {}", self.provider_config.api_key.as_str())
+            .parse()
+            .map_err(|_| Error::InvalidApiKey)?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert("Authorization", authorization);
+
+        Ok(headers)
+    }
+
+    fn records_url(&self, domain: &str) -> String {
+        format!(
+            "{}/domain/{}/records",
+            self.provider_config.api_base_url, domain
+        )
+    }
+
+    async fn send_record_request(
+        &self,
+        http: &dyn HttpClient,
+        method: Method,
+        domain: &str,
+        record: &dns::Record,
+    ) -> Result<()> {
+        let mut body = WriteRecordRequest::from(record);
+        body.record.name = self
+            .record_naming()
+            .to_provider_name(&record.domain, domain);
+        let url = self.records_url(domain);
+
+        let request = HttpRequest::new(method, url)
+            .headers(self.auth_headers()?)
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .body(serde_json::to_string(&body)?);
+
+        self.rate_limiter.acquire().await;
+        let response = http.send(request).await?;
+
+        if !response.is_success() {
+            return Err(Error::Unsuccessful {
+                status: response.status.as_u16(),
+                body: response.error_body(),
+            }
+            .into());
+        }
+
+        if !response.body.trim().is_empty() {
+            check_status(&response.body)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Fails with [`Error::ApiError`] if the `status` of a response body is not `success`, as
+/// Nitrado also reports errors in successful HTTP responses.
+fn check_status(text: &str) -> Result<(), Error> {
+    let response: StatusResponse = serde_json::from_str(text)?;
+    if response.is_success() {
+        return Ok(());
     }
+
+    Err(Error::ApiError {
+        message: response.message_text(),
+        status: response.status,
+    })
 }
 
 #[derive(Debug, Error)]
@@ -30,11 +111,20 @@ pub enum Error {
     #[error("HTTP request failed: {0}")]
     Reqwest(#[from] reqwest::Error),
 
-    #[error("HTTP response is not successful: {0}")]
-    Unsuccessful(u16, reqwest::Response),
+    #[error("HTTP response is not successful: {status}, body: {body:?}")]
+    Unsuccessful { status: u16, body: String },
 
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
+
+    #[error("Invalid record in response: {0}")]
+    TryFromRecord(#[from] TryFromRecordError),
+
+    #[error("Invalid API key: contains characters that are not allowed in HTTP headers")]
+    InvalidApiKey,
+
+    #[error("Nitrado API returned status {status}: {message}")]
+    ApiError { status: String, message: String },
 }
 
 #[async_trait]
@@ -53,46 +143,369 @@ impl Provider for NitradoProvider<'_> {
         ]
     }
 
+    /// Nitrado does not manage NS and SOA records of its domains. DNSSEC records (DS, DNSKEY)
+    /// are not part of its record API either.
+    fn supported_record_types(&self) -> &'static [RecordType] {
+        &[
+            RecordType::A,
+            RecordType::AAAA,
+            RecordType::CNAME,
+            RecordType::TXT,
+            RecordType::MX,
+            RecordType::SRV,
+            RecordType::TLSA,
+            RecordType::CAA,
+        ]
+    }
+
     async fn get_all_records(
         &self,
-        reqwest: reqwest::Client,
+        http: &dyn HttpClient,
         input: &GetAllRecordsInput,
     ) -> Result<Vec<dns::Record>> {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            "Authorization",
-            format!("Bearer {}", self.provider_config.api_key)
-                .parse()
-                .unwrap(),
-        );
-
-        let domain = &input.domain;
-        let url = format!(
-            "{}/domain/{}/records",
-            self.provider_config.api_base_url, domain
-        );
-        let response = reqwest.get(&url).headers(headers).send().await?;
+        let url = self.records_url(input.domain);
+        self.rate_limiter.acquire().await;
+        let response = http
+            .send(HttpRequest::get(url).headers(self.auth_headers()?))
+            .await?;
 
-        if !response.status().is_success() {
-            return Err(Error::Unsuccessful(response.status().as_u16(), response).into());
+        if !response.is_success() {
+            return Err(Error::Unsuccessful {
+                status: response.status.as_u16(),
+                body: response.error_body(),
+            }
+            .into());
         }
 
-        let text = response.text().await?;
-        let response: GetRecordsResponse = serde_json::from_str(&text)?;
-        let records: Vec<dns::Record> = response.try_into()?;
+        check_status(&response.body)?;
+        let response: GetRecordsResponse = serde_json::from_str(&response.body)?;
+        let records: Vec<dns::Record> = response.try_into().map_err(Error::from)?;
 
         Ok(records)
     }
 
-    async fn add_record(&self, _reqwest: reqwest::Client, _input: &dns::Record) -> Result<()> {
-        unimplemented!()
+    async fn add_record(&self, http: &dyn HttpClient, input: &AddRecordInput) -> Result<()> {
+        self.validate_records(std::slice::from_ref(input.record))?;
+        self.send_record_request(http, Method::POST, input.domain, input.record)
+            .await
+    }
+
+    async fn update_record(&self, http: &dyn HttpClient, input: &UpdateRecordInput) -> Result<()> {
+        self.validate_records(std::slice::from_ref(input.record))?;
+        self.send_record_request(http, Method::PUT, input.domain, input.record)
+            .await
+    }
+
+    async fn delete_record(&self, http: &dyn HttpClient, input: &DeleteRecordInput) -> Result<()> {
+        let result = self
+            .send_record_request(http, Method::DELETE, input.domain, input.record)
+            .await;
+
+        match result {
+            Err(ProviderError::Nitrado(error))
+                if matches!(*error, Error::Unsuccessful { status: 404, .. }) =>
+            {
+                provider::already_absent(self.get_provider_name(), input.record)
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dns::{MxRecord, RecordValue};
+    use lum_libs::serde_json::json;
+    use std::net::Ipv4Addr;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_json, header, method, path},
+    };
+
+    fn config(server: &MockServer) -> Config {
+        Config {
+            api_key: "test_key".into(),
+            api_base_url: server.uri(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_of_absent_record_succeeds() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/domain/example.com/records"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "status": "error",
+                "message": "Record not found",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/domain/other.com/records"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = NitradoProvider::new(&config);
+        let record = dns::Record {
+            domain: "www.example.com".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+            ttl: None,
+        };
+        let input = DeleteRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+        let result = provider
+            .delete_record(&reqwest::Client::new(), &input)
+            .await;
+        assert!(result.is_ok());
+
+        // Other failures are still reported
+        let record = dns::Record {
+            domain: "www.other.com".to_string(),
+            ..record
+        };
+        let input = DeleteRecordInput {
+            domain: "other.com",
+            record: &record,
+        };
+        let result = provider
+            .delete_record(&reqwest::Client::new(), &input)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delete_record_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/domain/example.com/records"))
+            .and(header("Authorization", "Bearer test_key"))
+            .and(body_json(json!({
+                "type": "MX",
+                "content": "10 mail.example.com",
+                "name": "example.com",
+                "mode": "manual",
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = NitradoProvider::new(&config);
+        let record = dns::Record {
+            domain: "example.com".to_string(),
+            value: RecordValue::MX(MxRecord {
+                priority: 10,
+                target: "mail.example.com".to_string(),
+            }),
+            ttl: None,
+        };
+        let input = DeleteRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        provider
+            .delete_record(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_record_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/domain/example.com/records"))
+            .and(header("Authorization", "Bearer test_key"))
+            .and(body_json(json!({
+                "type": "A",
+                "content": "1.2.3.4",
+                "name": "home.example.com",
+                "mode": "manual",
+                "ttl": 300,
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = NitradoProvider::new(&config);
+        let record = dns::Record {
+            domain: "home.example.com".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+            ttl: Some(300),
+        };
+        let input = UpdateRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        provider
+            .update_record(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_apex_record_uses_full_domain() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/domain/example.com/records"))
+            .and(body_json(json!({
+                "type": "A",
+                "content": "1.2.3.4",
+                "name": "example.com",
+                "mode": "manual",
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = NitradoProvider::new(&config);
+        let record = dns::Record {
+            domain: "@".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+            ttl: None,
+        };
+        let input = UpdateRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        provider
+            .update_record(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_api_key_with_newline_is_invalid_api_key() {
+        let config = Config {
+            api_key: "test\nkey".into(),
+            api_base_url: "http://127.0.0.1:1".to_string(),
+            ..Default::default()
+        };
+        let provider = NitradoProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let result = provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await;
+        match result {
+            Err(ProviderError::Nitrado(error)) => assert!(matches!(*error, Error::InvalidApiKey)),
+            _ => panic!("Expected Nitrado error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_record_unsuccessful() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/domain/example.com/records"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = NitradoProvider::new(&config);
+        let record = dns::Record {
+            domain: "home.example.com".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+            ttl: None,
+        };
+        let input = UpdateRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        let result = provider
+            .update_record(&reqwest::Client::new(), &input)
+            .await;
+        match result {
+            Err(ProviderError::Nitrado(error)) => {
+                assert!(matches!(*error, Error::Unsuccessful { status: 404, .. }))
+            }
+            _ => panic!("Expected Nitrado error"),
+        }
+    }
+
+    fn api_error() -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(json!({
+            "status": "error",
+            "message": "Domain does not belong to this account",
+        }))
+    }
+
+    fn assert_api_error(result: Result<impl std::fmt::Debug>) {
+        match result {
+            Err(ProviderError::Nitrado(error)) => match *error {
+                Error::ApiError { status, message } => {
+                    assert_eq!(status, "error");
+                    assert_eq!(message, "Domain does not belong to this account");
+                }
+                other => panic!("Expected API error, got {:?}", other),
+            },
+            other => panic!("Expected Nitrado error, got {:?}", other),
+        }
     }
 
-    async fn update_record(&self, _reqwest: reqwest::Client, _input: &dns::Record) -> Result<()> {
-        unimplemented!()
+    #[tokio::test]
+    async fn test_get_all_records_error_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/domain/example.com/records"))
+            .respond_with(api_error())
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = NitradoProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let result = provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await;
+        assert_api_error(result);
     }
 
-    async fn delete_record(&self, _reqwest: reqwest::Client, _input: &dns::Record) -> Result<()> {
-        unimplemented!()
+    #[tokio::test]
+    async fn test_update_record_error_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/domain/example.com/records"))
+            .respond_with(api_error())
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = NitradoProvider::new(&config);
+        let record = dns::Record {
+            domain: "home.example.com".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+            ttl: None,
+        };
+        let input = UpdateRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        let result = provider
+            .update_record(&reqwest::Client::new(), &input)
+            .await;
+        assert_api_error(result);
     }
 }
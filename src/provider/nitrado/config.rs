@@ -1,21 +1,30 @@
+use std::path::PathBuf;
+
 use lum_libs::serde::{Deserialize, Serialize};
 
-use crate::config::dns::RecordConfig;
+use crate::config::{dns::RecordConfig, secret::Secret};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
 pub struct Config {
     pub name: String,
-    pub api_key: String,
+    pub api_key: Secret,
     pub api_base_url: String,
+    pub requests_per_second: Option<f64>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             name: "Nitrado1".to_string(),
-            api_key: "your_api_key".to_string(),
+            api_key: "your_api_key".into(),
             api_base_url: "https://api.nitrado.net".to_string(),
+            requests_per_second: None,
+            source_path: None,
         }
     }
 }
@@ -32,6 +41,10 @@ pub struct DomainConfig {
 pub struct DnsConfig {
     pub provider_name: String,
     pub domains: Vec<DomainConfig>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
 }
 
 impl Default for DnsConfig {
@@ -39,6 +52,7 @@ impl Default for DnsConfig {
         DnsConfig {
             provider_name: "Nitrado1".to_string(),
             domains: vec![],
+            source_path: None,
         }
     }
 }
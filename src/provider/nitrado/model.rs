@@ -4,7 +4,10 @@ use std::{
     str::FromStr,
 };
 
-use lum_libs::serde::{Deserialize, Serialize};
+use lum_libs::{
+    serde::{Deserialize, Serialize},
+    serde_json,
+};
 use thiserror::Error;
 
 use crate::types::dns::{self, MxRecord, RecordType, RecordValue};
@@ -25,7 +28,8 @@ pub struct Record {
     pub r#type: RecordType,
     pub content: String,
     pub name: String,
-    pub mode: RecordMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<RecordMode>,
 }
 
 #[derive(Debug, Clone, Error)]
@@ -57,8 +61,11 @@ pub enum TryFromRecordError {
     #[error("Invalid CAA record flag: {0}")]
     InvalidCaaFlag(num::ParseIntError),
 
-    #[error("Record type {0:?} is not supported by Nitrado provider")]
+    #[error("Record type {0:?} is not supported by this provider")]
     UnsupportedRecordType(RecordType),
+
+    #[error("Invalid record: {0}")]
+    InvalidRecord(#[from] dns::RecordValidationError),
 }
 
 /// Converts a Nitrado API record into the internal [`dns::Record`] type.
@@ -74,7 +81,7 @@ pub enum TryFromRecordError {
 ///     r#type: RecordType::A,
 ///     content: "1.2.3.4".to_string(),
 ///     name: "example.com".to_string(),
-///     mode: RecordMode::Manual,
+///     mode: Some(RecordMode::Manual),
 /// };
 ///
 /// let dns_record = dnrs::types::dns::Record::try_from(api_record).unwrap();
@@ -101,9 +108,8 @@ impl TryFrom<Record> for dns::Record {
             RecordType::CNAME => RecordValue::CNAME(api_record.content),
             RecordType::TXT => RecordValue::TXT(api_record.content),
             RecordType::SPF => RecordValue::SPF(api_record.content),
-            RecordType::NS | RecordType::SOA => {
-                return Err(TryFromRecordError::UnsupportedRecordType(api_record.r#type));
-            }
+            RecordType::NS => RecordValue::NS(api_record.content),
+            RecordType::SOA => RecordValue::SOA(api_record.content),
             RecordType::MX => {
                 let content = api_record.content;
                 let parts: Vec<&str> = content.split_whitespace().collect();
@@ -173,13 +179,100 @@ impl TryFrom<Record> for dns::Record {
                 let value = parts[2].to_string();
                 RecordValue::CAA(flag, tag, value)
             }
+            RecordType::SVCB | RecordType::HTTPS | RecordType::DS | RecordType::DNSKEY => {
+                return Err(TryFromRecordError::UnsupportedRecordType(api_record.r#type));
+            }
         };
 
-        Ok(dns::Record {
-            domain: api_record.name,
-            value,
-            ttl: None, // Nitrado API does not provide TTL on GET
-        })
+        // Nitrado API does not provide TTL on GET
+        Ok(dns::Record::builder(api_record.name, value).build()?)
+    }
+}
+
+/// Converts an internal [`dns::Record`] into the Nitrado API representation.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::nitrado::model::Record;
+/// use dnrs::types::dns::{self, RecordValue};
+/// use std::net::Ipv4Addr;
+///
+/// let record = dns::Record {
+///     domain: "home.example.com".to_string(),
+///     value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+///     ttl: Some(300),
+/// };
+///
+/// let api_record = Record::from(&record);
+/// assert_eq!(api_record.name, "home.example.com");
+/// assert_eq!(api_record.content, "1.2.3.4");
+/// ```
+impl From<&dns::Record> for Record {
+    fn from(record: &dns::Record) -> Self {
+        Record {
+            r#type: record.value.wire_type(),
+            content: record.value.content(),
+            name: record.domain.clone(),
+            mode: Some(RecordMode::Manual),
+        }
+    }
+}
+
+/// Request body for adding, updating and deleting records.
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct WriteRecordRequest {
+    #[serde(flatten)]
+    pub record: Record,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+impl From<&dns::Record> for WriteRecordRequest {
+    fn from(record: &dns::Record) -> Self {
+        WriteRecordRequest {
+            record: Record::from(record),
+            ttl: record.ttl,
+        }
+    }
+}
+
+/// The envelope of every Nitrado API response. `message` holds the payload if `status` is
+/// `success`, and a description of the problem otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::nitrado::model::StatusResponse;
+/// use lum_libs::serde_json;
+///
+/// let response: StatusResponse =
+///     serde_json::from_str(r#"{"status": "error", "message": "Domain not found"}"#).unwrap();
+/// assert!(!response.is_success());
+/// assert_eq!(response.message_text(), "Domain not found");
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct StatusResponse {
+    pub status: String,
+    #[serde(default)]
+    pub message: serde_json::Value,
+}
+
+impl StatusResponse {
+    pub fn is_success(&self) -> bool {
+        self.status == "success"
+    }
+
+    /// Returns `message` as text, e.g. the reason of an error.
+    pub fn message_text(&self) -> String {
+        match &self.message {
+            serde_json::Value::String(message) => message.clone(),
+            serde_json::Value::Null => String::new(),
+            message => message.to_string(),
+        }
     }
 }
 
@@ -205,7 +298,7 @@ impl TryFrom<GetRecordsResponse> for Vec<dns::Record> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::dns::{RecordValue, RecordType};
+    use crate::types::dns::{RecordType, RecordValue};
 
     #[test]
     fn test_nitrado_record_to_dns_record_a() {
@@ -213,7 +306,7 @@ mod tests {
             r#type: RecordType::A,
             content: "1.2.3.4".to_string(),
             name: "example.com".to_string(),
-            mode: RecordMode::Manual,
+            mode: Some(RecordMode::Manual),
         };
         let dns_record = dns::Record::try_from(api_record).unwrap();
         assert_eq!(dns_record.domain, "example.com");
@@ -229,7 +322,7 @@ mod tests {
             r#type: RecordType::AAAA,
             content: "::1".to_string(),
             name: "example.com".to_string(),
-            mode: RecordMode::Manual,
+            mode: Some(RecordMode::Manual),
         };
         let dns_record = dns::Record::try_from(api_record).unwrap();
         match dns_record.value {
@@ -244,7 +337,7 @@ mod tests {
             r#type: RecordType::MX,
             content: "10 mail.example.com".to_string(),
             name: "example.com".to_string(),
-            mode: RecordMode::Manual,
+            mode: Some(RecordMode::Manual),
         };
         let dns_record = dns::Record::try_from(api_record).unwrap();
         match dns_record.value {
@@ -262,7 +355,7 @@ mod tests {
             r#type: RecordType::SRV,
             content: "0 5 5060 sip.example.com".to_string(),
             name: "_sip._tcp.example.com".to_string(),
-            mode: RecordMode::Manual,
+            mode: Some(RecordMode::Manual),
         };
         let dns_record = dns::Record::try_from(api_record).unwrap();
         match dns_record.value {
@@ -276,27 +369,81 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_srv_record_round_trips() {
+        let record = dns::Record {
+            domain: "_sip._tcp.example.com".to_string(),
+            value: RecordValue::SRV(10, 5, 5060, "sip.example.com".to_string()),
+            ttl: None,
+        };
+
+        let api_record = Record::from(&record);
+        assert_eq!(api_record.content, "10 5 5060 sip.example.com");
+
+        let dns_record = dns::Record::try_from(api_record).unwrap();
+        assert_eq!(dns_record.domain, "_sip._tcp.example.com");
+        assert!(matches!(
+            dns_record.value,
+            RecordValue::SRV(10, 5, 5060, ref target) if target == "sip.example.com"
+        ));
+    }
+
     #[test]
     fn test_nitrado_record_to_dns_record_invalid_ip() {
         let api_record = Record {
             r#type: RecordType::A,
             content: "invalid".to_string(),
             name: "example.com".to_string(),
-            mode: RecordMode::Manual,
+            mode: Some(RecordMode::Manual),
         };
         let result = dns::Record::try_from(api_record);
         assert!(result.is_err());
     }
 
     #[test]
-    fn test_nitrado_record_to_dns_record_unsupported() {
+    fn test_nitrado_record_to_dns_record_ns() {
         let api_record = Record {
             r#type: RecordType::NS,
             content: "ns1.example.com".to_string(),
             name: "example.com".to_string(),
-            mode: RecordMode::Manual,
+            mode: Some(RecordMode::Manual),
         };
-        let result = dns::Record::try_from(api_record);
-        assert!(matches!(result, Err(TryFromRecordError::UnsupportedRecordType(RecordType::NS))));
+        let record = dns::Record::try_from(api_record).unwrap();
+        assert!(matches!(record.value, RecordValue::NS(target) if target == "ns1.example.com"));
+    }
+
+    #[test]
+    fn test_records_response_tolerates_api_drift() {
+        // A real response with fields the model does not know and a record without a mode.
+        let json = r#"{
+            "status": "success",
+            "message": [
+                {
+                    "id": 4711,
+                    "name": "example.com",
+                    "type": "A",
+                    "content": "1.2.3.4",
+                    "ttl": 3600,
+                    "mode": "auto",
+                    "editable": false
+                },
+                {
+                    "name": "www.example.com",
+                    "type": "CNAME",
+                    "content": "example.com"
+                }
+            ],
+            "data": {}
+        }"#;
+
+        let response: GetRecordsResponse = serde_json::from_str(json).unwrap();
+        assert!(matches!(response.message[0].mode, Some(RecordMode::Auto)));
+        assert!(response.message[1].mode.is_none());
+
+        let records: Vec<dns::Record> = response.try_into().unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(
+            matches!(records[1].value, RecordValue::CNAME(ref target) if target == "example.com")
+        );
     }
 }
@@ -0,0 +1,333 @@
+use async_trait::async_trait;
+use lum_libs::{
+    serde::{Serialize, de::DeserializeOwned},
+    serde_json,
+};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderValue};
+use thiserror::Error;
+
+use crate::{
+    http::{HttpClient, HttpRequest},
+    provider::{
+        self, AddRecordInput, DeleteRecordInput, Feature, GetAllRecordsInput, Provider,
+        ProviderError, Result, UpdateRecordInput, naming::RecordNaming,
+    },
+    rate_limit::RateLimiter,
+    types::dns::{self, RecordType},
+};
+
+pub mod config;
+pub mod model;
+
+pub use config::{Config, DnsConfig, DomainConfig};
+pub use model::{
+    DomainParams, ListRecordsResult, Record, RecordParams, RemoveRecordParams, Request, Response,
+    RpcError, TryFromRecordError,
+};
+
+pub struct NjallaProvider<'provider_config> {
+    pub provider_config: &'provider_config Config,
+    pub rate_limiter: RateLimiter,
+}
+
+impl<'provider_config> NjallaProvider<'provider_config> {
+    pub fn new(provider_config: &'provider_config Config) -> NjallaProvider<'provider_config> {
+        NjallaProvider {
+            provider_config,
+            rate_limiter: RateLimiter::new(provider_config.requests_per_second),
+        }
+    }
+
+    /// Calls the JSON-RPC `method` and returns its result.
+    async fn call<P, T>(&self, http: &dyn HttpClient, method: &'static str, params: P) -> Result<T>
+    where
+        P: Serialize + Send,
+        T: DeserializeOwned,
+    {
+        let body = serde_json::to_string(&Request::new(method, params))?;
+        let token = format!("Njalla {}", self.provider_config.token.as_str());
+        let token = HeaderValue::from_str(&token).map_err(|_| Error::InvalidToken)?;
+        let request = HttpRequest::post(&self.provider_config.api_base_url)
+            .header(AUTHORIZATION, token)
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .body(body);
+
+        self.rate_limiter.acquire().await;
+        let response = http.send(request).await?;
+
+        if !response.is_success() {
+            return Err(Error::Unsuccessful {
+                status: response.status.as_u16(),
+                body: response.error_body(),
+            }
+            .into());
+        }
+
+        let response: Response<T> = serde_json::from_str(&response.body)?;
+        let result = response.into_result().map_err(Error::from)?;
+
+        Ok(result)
+    }
+
+    async fn list_api_records(&self, http: &dyn HttpClient, domain: &str) -> Result<Vec<Record>> {
+        let params = DomainParams {
+            domain: domain.to_string(),
+        };
+        let result: ListRecordsResult = self.call(http, "list-records", params).await?;
+
+        Ok(result.records)
+    }
+
+    /// Converts `record` into the Njalla representation with a name relative to `domain`.
+    fn to_api_record(&self, record: &dns::Record, domain: &str) -> Record {
+        let mut api_record = Record::from(record);
+        api_record.name = self
+            .record_naming()
+            .to_provider_name(&record.domain, domain);
+        api_record
+    }
+
+    /// Finds the existing record `api_record` refers to. Updates match by name and type,
+    /// deletes also by value.
+    async fn find_record_id(
+        &self,
+        http: &dyn HttpClient,
+        domain: &str,
+        api_record: &Record,
+        match_value: bool,
+    ) -> Result<Option<u64>> {
+        let existing = self.list_api_records(http, domain).await?;
+        let id = existing
+            .iter()
+            .find(|existing| {
+                existing.name == api_record.name
+                    && existing.r#type == api_record.r#type
+                    && (!match_value || existing.same_value(api_record))
+            })
+            .and_then(|existing| existing.id);
+
+        Ok(id)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("HTTP request failed: {0}")]
+    Reqwest(#[from] reqwest::Error),
+
+    #[error("HTTP response is not successful: {status}, body: {body:?}")]
+    Unsuccessful { status: u16, body: String },
+
+    #[error("JSON parsing error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("API error: {0}")]
+    Rpc(#[from] RpcError),
+
+    #[error("Invalid record in response: {0}")]
+    TryFromRecord(#[from] TryFromRecordError),
+
+    #[error("Invalid token: contains characters that are not allowed in HTTP headers")]
+    InvalidToken,
+}
+
+#[async_trait]
+impl Provider for NjallaProvider<'_> {
+    fn get_provider_name(&self) -> &'static str {
+        "Njalla"
+    }
+
+    fn get_supported_features(&self) -> Vec<Feature> {
+        vec![
+            Feature::GetRecords,
+            Feature::GetAllRecords,
+            Feature::AddRecord,
+            Feature::UpdateRecord,
+            Feature::DeleteRecord,
+        ]
+    }
+
+    /// Njalla manages the NS and SOA records of its domains. Its TLSA, HTTPS and DS records use
+    /// formats of their own, which are not mapped.
+    fn supported_record_types(&self) -> &'static [RecordType] {
+        &[
+            RecordType::A,
+            RecordType::AAAA,
+            RecordType::CNAME,
+            RecordType::TXT,
+            RecordType::MX,
+            RecordType::SRV,
+            RecordType::CAA,
+        ]
+    }
+
+    fn record_naming(&self) -> RecordNaming {
+        RecordNaming::Relative { apex: "@" }
+    }
+
+    /// Njalla offers TTLs from one minute to one day.
+    fn ttl_bounds(&self) -> (Option<u32>, Option<u32>) {
+        (Some(60), Some(86400))
+    }
+
+    async fn get_all_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &GetAllRecordsInput,
+    ) -> Result<Vec<dns::Record>> {
+        let api_records = self.list_api_records(http, input.domain).await?;
+
+        let naming = self.record_naming();
+        let mut records = Vec::with_capacity(api_records.len());
+        for api_record in api_records {
+            let mut record = dns::Record::try_from(api_record).map_err(Error::from)?;
+            record.domain = naming.to_full_name(&record.domain, input.domain);
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+
+    async fn add_record(&self, http: &dyn HttpClient, input: &AddRecordInput) -> Result<()> {
+        self.validate_records(std::slice::from_ref(input.record))?;
+        let params = RecordParams {
+            domain: input.domain.to_string(),
+            record: self.to_api_record(input.record, input.domain),
+        };
+
+        let _: Record = self.call(http, "add-record", params).await?;
+
+        Ok(())
+    }
+
+    /// Replaces the first record with the same name and type.
+    async fn update_record(&self, http: &dyn HttpClient, input: &UpdateRecordInput) -> Result<()> {
+        self.validate_records(std::slice::from_ref(input.record))?;
+        let mut api_record = self.to_api_record(input.record, input.domain);
+
+        let Some(id) = self
+            .find_record_id(http, input.domain, &api_record, false)
+            .await?
+        else {
+            return Err(ProviderError::RecordNotFound(input.record.domain.clone()));
+        };
+
+        api_record.id = Some(id);
+        let params = RecordParams {
+            domain: input.domain.to_string(),
+            record: api_record,
+        };
+        let _: Record = self.call(http, "edit-record", params).await?;
+
+        Ok(())
+    }
+
+    async fn delete_record(&self, http: &dyn HttpClient, input: &DeleteRecordInput) -> Result<()> {
+        let api_record = self.to_api_record(input.record, input.domain);
+
+        let Some(id) = self
+            .find_record_id(http, input.domain, &api_record, true)
+            .await?
+        else {
+            return provider::already_absent(self.get_provider_name(), input.record);
+        };
+
+        let params = RemoveRecordParams {
+            domain: input.domain.to_string(),
+            id,
+        };
+        let _: serde_json::Value = self.call(http, "remove-record", params).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dns::RecordValue;
+    use lum_libs::serde_json::json;
+    use std::net::Ipv4Addr;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_json, header, method},
+    };
+
+    fn config(server: &MockServer) -> Config {
+        Config {
+            token: "test_token".into(),
+            api_base_url: server.uri(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_records_payload() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("Authorization", "Njalla test_token"))
+            .and(body_json(json!({
+                "jsonrpc": "2.0",
+                "method": "list-records",
+                "params": {"domain": "example.com"},
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "result": {"records": [
+                    {"id": 1, "name": "@", "type": "A", "content": "1.1.1.1", "ttl": 10800},
+                    {"id": 2, "name": "www", "type": "CNAME", "content": "example.com", "ttl": 10800},
+                ]},
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = NjallaProvider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let records = provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+        let names: Vec<&str> = records
+            .iter()
+            .map(|record| record.domain.as_str())
+            .collect();
+        assert_eq!(names, vec!["example.com", "www.example.com"]);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_error_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "error": {"code": 403, "message": "Permission denied"},
+            })))
+            .mount(&server)
+            .await;
+
+        let config = config(&server);
+        let provider = NjallaProvider::new(&config);
+        let record = dns::Record {
+            domain: "www.example.com".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+            ttl: None,
+        };
+        let input = AddRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        let result = provider.add_record(&reqwest::Client::new(), &input).await;
+        match result {
+            Err(ProviderError::Njalla(error)) => {
+                assert!(matches!(*error, Error::Rpc(RpcError { code: 403, .. })));
+            }
+            other => panic!("Expected API error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use std::{
+    net::{self, Ipv4Addr, Ipv6Addr},
+    num::ParseIntError,
+    str::FromStr,
+};
+
+use lum_libs::serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::dns::{self, MxRecord, RecordType, RecordValue};
+
+/// A JSON-RPC request to the Njalla API.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::njalla::model::{DomainParams, Request};
+/// use lum_libs::serde_json;
+///
+/// let request = Request::new("list-records", DomainParams { domain: "example.com".to_string() });
+/// assert_eq!(
+///     serde_json::to_string(&request).unwrap(),
+///     r#"{"jsonrpc":"2.0","method":"list-records","params":{"domain":"example.com"}}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Request<P> {
+    pub jsonrpc: &'static str,
+    pub method: &'static str,
+    pub params: P,
+}
+
+impl<P> Request<P> {
+    pub fn new(method: &'static str, params: P) -> Self {
+        Request {
+            jsonrpc: "2.0",
+            method,
+            params,
+        }
+    }
+}
+
+/// A JSON-RPC response of the Njalla API, holding either a `result` or an `error`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Response<T> {
+    pub result: Option<T>,
+    pub error: Option<RpcError>,
+}
+
+impl<T> Response<T> {
+    /// Returns the result, or the error reported by Njalla.
+    pub fn into_result(self) -> Result<T, RpcError> {
+        match (self.result, self.error) {
+            (_, Some(error)) => Err(error),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(RpcError {
+                code: 0,
+                message: "Response has neither a result nor an error".to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Error)]
+#[serde(crate = "lum_libs::serde")]
+#[error("{message} (code {code})")]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct DomainParams {
+    pub domain: String,
+}
+
+/// Parameters of `add-record` and `edit-record`, the latter with the `id` of the record.
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct RecordParams {
+    pub domain: String,
+    #[serde(flatten)]
+    pub record: Record,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct RemoveRecordParams {
+    pub domain: String,
+    pub id: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct ListRecordsResult {
+    pub records: Vec<Record>,
+}
+
+/// A Njalla record.
+///
+/// `content` holds the address, target or text of the record. MX and SRV records keep their
+/// priority, weight and port in separate fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Record {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    pub name: String,
+    pub r#type: RecordType,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prio: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+}
+
+impl Record {
+    /// Returns whether `other` holds the same value, ignoring id and TTL.
+    pub fn same_value(&self, other: &Record) -> bool {
+        self.r#type == other.r#type
+            && self.content.trim_end_matches('.') == other.content.trim_end_matches('.')
+            && self.prio == other.prio
+            && self.weight == other.weight
+            && self.port == other.port
+    }
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum TryFromRecordError {
+    #[error("Invalid IP address: {0}")]
+    InvalidIp(#[from] net::AddrParseError),
+
+    #[error("{record_type:?} record is missing its {field}")]
+    MissingField {
+        record_type: RecordType,
+        field: &'static str,
+    },
+
+    #[error("Invalid CAA record format: {0}")]
+    InvalidCaaFormat(String),
+
+    #[error("Invalid CAA flag: {0}")]
+    InvalidCaaFlag(ParseIntError),
+
+    #[error("Record type {0:?} is not supported by Njalla")]
+    UnsupportedRecordType(RecordType),
+
+    #[error("Invalid record: {0}")]
+    InvalidRecord(#[from] dns::RecordValidationError),
+}
+
+fn required<T>(
+    value: Option<T>,
+    record_type: RecordType,
+    field: &'static str,
+) -> Result<T, TryFromRecordError> {
+    value.ok_or(TryFromRecordError::MissingField { record_type, field })
+}
+
+fn relative(name: &str) -> String {
+    name.trim_end_matches('.').to_string()
+}
+
+/// Converts a Njalla record into the internal [`dns::Record`] type.
+///
+/// The name stays as Njalla returns it, relative to the domain with `@` for the apex.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::njalla::model::Record;
+/// use dnrs::types::dns::RecordType;
+///
+/// let api_record = Record {
+///     id: Some(1337),
+///     name: "@".to_string(),
+///     r#type: RecordType::MX,
+///     content: "mail.example.com".to_string(),
+///     ttl: Some(10800),
+///     prio: Some(10),
+///     weight: None,
+///     port: None,
+/// };
+///
+/// let record = dnrs::types::dns::Record::try_from(api_record).unwrap();
+/// assert_eq!(record.value.content(), "10 mail.example.com");
+/// ```
+impl TryFrom<Record> for dns::Record {
+    type Error = TryFromRecordError;
+
+    fn try_from(api_record: Record) -> Result<Self, Self::Error> {
+        let record_type = api_record.r#type;
+        let value = match record_type {
+            RecordType::A => RecordValue::A(Ipv4Addr::from_str(&api_record.content)?),
+            RecordType::AAAA => RecordValue::AAAA(Ipv6Addr::from_str(&api_record.content)?),
+            RecordType::CNAME => RecordValue::CNAME(relative(&api_record.content)),
+            RecordType::NS => RecordValue::NS(relative(&api_record.content)),
+            RecordType::TXT => RecordValue::TXT(api_record.content),
+            RecordType::MX => RecordValue::MX(MxRecord {
+                priority: required(api_record.prio, record_type, "prio")?,
+                target: relative(&api_record.content),
+            }),
+            RecordType::SRV => RecordValue::SRV(
+                required(api_record.prio, record_type, "prio")?,
+                required(api_record.weight, record_type, "weight")?,
+                required(api_record.port, record_type, "port")?,
+                relative(&api_record.content),
+            ),
+            RecordType::CAA => {
+                let content = api_record.content;
+                let parts: Vec<&str> = content.splitn(3, ' ').collect();
+                if parts.len() != 3 {
+                    return Err(TryFromRecordError::InvalidCaaFormat(content));
+                }
+
+                let flag = parts[0]
+                    .parse::<u8>()
+                    .map_err(TryFromRecordError::InvalidCaaFlag)?;
+                let value = parts[2].trim_matches('"').to_string();
+                RecordValue::CAA(flag, parts[1].to_string(), value)
+            }
+            RecordType::SPF
+            | RecordType::SOA
+            | RecordType::TLSA
+            | RecordType::SVCB
+            | RecordType::HTTPS
+            | RecordType::DS
+            | RecordType::DNSKEY => {
+                return Err(TryFromRecordError::UnsupportedRecordType(record_type));
+            }
+        };
+
+        Ok(dns::Record::builder(api_record.name, value)
+            .ttl(api_record.ttl)
+            .build()?)
+    }
+}
+
+/// Converts an internal [`dns::Record`] into the Njalla representation.
+///
+/// The name is copied as is and has to be made relative by the caller.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::njalla::model::Record;
+/// use dnrs::types::dns::{self, RecordValue};
+///
+/// let record = dns::Record {
+///     domain: "_sip._tcp".to_string(),
+///     value: RecordValue::SRV(10, 5, 5060, "sip.example.com".to_string()),
+///     ttl: Some(3600),
+/// };
+///
+/// let api_record = Record::from(&record);
+/// assert_eq!(api_record.content, "sip.example.com");
+/// assert_eq!((api_record.prio, api_record.weight, api_record.port), (Some(10), Some(5), Some(5060)));
+/// ```
+impl From<&dns::Record> for Record {
+    fn from(record: &dns::Record) -> Self {
+        let mut api_record = Record {
+            id: None,
+            name: record.domain.clone(),
+            r#type: record.value.wire_type(),
+            content: record.value.content(),
+            ttl: record.ttl,
+            prio: None,
+            weight: None,
+            port: None,
+        };
+
+        match &record.value {
+            RecordValue::MX(mx) => {
+                api_record.content = mx.target.clone();
+                api_record.prio = Some(mx.priority);
+            }
+            RecordValue::SRV(priority, weight, port, target) => {
+                api_record.content = target.clone();
+                api_record.prio = Some(*priority);
+                api_record.weight = Some(*weight);
+                api_record.port = Some(*port);
+            }
+            _ => {}
+        }
+
+        api_record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lum_libs::serde_json;
+
+    #[test]
+    fn test_list_records_response() {
+        let response: Response<ListRecordsResult> = serde_json::from_str(
+            r#"{"jsonrpc": "2.0", "result": {"records": [
+                {"id": 1, "name": "@", "type": "A", "content": "1.2.3.4", "ttl": 10800},
+                {"id": 2, "name": "@", "type": "CAA", "content": "0 issue \"letsencrypt.org\"", "ttl": 3600},
+                {"id": 3, "name": "_sip._tcp", "type": "SRV", "content": "sip.example.com", "ttl": 3600, "prio": 10, "weight": 5, "port": 5060}
+            ]}}"#,
+        )
+        .unwrap();
+
+        let records: Vec<dns::Record> = response
+            .into_result()
+            .unwrap()
+            .records
+            .into_iter()
+            .map(|record| dns::Record::try_from(record).unwrap())
+            .collect();
+        assert_eq!(records[0].value.content(), "1.2.3.4");
+        assert!(
+            matches!(records[1].value, RecordValue::CAA(0, ref tag, ref value)
+            if tag == "issue" && value == "letsencrypt.org")
+        );
+        assert_eq!(records[2].value.content(), "10 5 5060 sip.example.com");
+        assert_eq!(records[2].domain, "_sip._tcp");
+    }
+
+    #[test]
+    fn test_error_response() {
+        let response: Response<ListRecordsResult> = serde_json::from_str(
+            r#"{"jsonrpc": "2.0", "error": {"code": 403, "message": "Permission denied"}}"#,
+        )
+        .unwrap();
+
+        let error = response.into_result().unwrap_err();
+        assert_eq!(error.code, 403);
+        assert_eq!(error.to_string(), "Permission denied (code 403)");
+    }
+
+    #[test]
+    fn test_mx_round_trip() {
+        let api_record: Record = serde_json::from_str(
+            r#"{"id": 4, "name": "@", "type": "MX", "content": "mail.example.com", "ttl": 3600, "prio": 10}"#,
+        )
+        .unwrap();
+
+        let record = dns::Record::try_from(api_record.clone()).unwrap();
+        let round_trip = Record::from(&record);
+        assert!(round_trip.same_value(&api_record));
+
+        let serialized = serde_json::to_value(&round_trip).unwrap();
+        assert!(serialized.get("id").is_none());
+        assert!(serialized.get("port").is_none());
+    }
+
+    #[test]
+    fn test_srv_round_trip() {
+        let record = dns::Record {
+            domain: "_sip._tcp".to_string(),
+            value: RecordValue::SRV(10, 5, 5060, "sip.example.com".to_string()),
+            ttl: Some(3600),
+        };
+
+        let api_record = Record::from(&record);
+        let round_trip = dns::Record::try_from(api_record.clone()).unwrap();
+        assert!(matches!(
+            round_trip.value,
+            RecordValue::SRV(10, 5, 5060, ref target) if target == "sip.example.com"
+        ));
+        assert!(Record::from(&round_trip).same_value(&api_record));
+    }
+}
@@ -0,0 +1,507 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::{
+    Method, Url,
+    header::{AUTHORIZATION, CONTENT_TYPE, HeaderName, HeaderValue, InvalidHeaderValue},
+};
+use thiserror::Error;
+
+use crate::{
+    http::{HttpClient, HttpRequest},
+    provider::{
+        self, AddRecordInput, DeleteRecordInput, Feature, GetAllRecordsInput, Provider,
+        ProviderError, Result, UpdateRecordInput, UpdateRecordsInput, UpsertRecordInput,
+        UpsertRecordsInput,
+    },
+    rate_limit::RateLimiter,
+    types::dns::{self, RecordType},
+};
+
+pub mod config;
+pub mod model;
+pub mod sigv4;
+
+pub use config::{Config, DnsConfig, DomainConfig};
+pub use model::{
+    Change, ChangeAction, ChangeResourceRecordSetsRequest, ListHostedZonesByNameResponse,
+    ListResourceRecordSetsResponse, ResourceRecordSet, TryFromRecordError,
+};
+
+const API_VERSION: &str = "2013-04-01";
+const SERVICE: &str = "route53";
+
+pub struct Route53Provider<'provider_config> {
+    pub provider_config: &'provider_config Config,
+    pub rate_limiter: RateLimiter,
+}
+
+impl<'provider_config> Route53Provider<'provider_config> {
+    pub fn new(provider_config: &'provider_config Config) -> Route53Provider<'provider_config> {
+        Route53Provider {
+            provider_config,
+            rate_limiter: RateLimiter::new(provider_config.requests_per_second),
+        }
+    }
+
+    /// Sends a SigV4-signed request to `path` below the API version and returns the body.
+    async fn send(
+        &self,
+        http: &dyn HttpClient,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<String>,
+    ) -> Result<String> {
+        let path = format!("/{}/{}", API_VERSION, path);
+        let mut url = Url::parse(&self.provider_config.api_base_url).map_err(Error::from)?;
+        url.set_path(&path);
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(Error::from(url::ParseError::EmptyHost).into()),
+        };
+
+        let now = Utc::now();
+        let amz_date = sigv4::amz_date(&now);
+        let body = body.unwrap_or_default();
+        let credentials = sigv4::Credentials {
+            access_key_id: &self.provider_config.access_key_id,
+            secret_access_key: self.provider_config.secret_access_key.as_str(),
+        };
+        let signing_request = sigv4::SigningRequest {
+            method: method.as_str(),
+            path: &path,
+            query,
+            headers: &[("host", &host), ("x-amz-date", &amz_date)],
+            body: body.as_bytes(),
+        };
+        let authorization = sigv4::authorization(
+            &credentials,
+            &self.provider_config.region,
+            SERVICE,
+            &signing_request,
+            &now,
+        );
+
+        let request = HttpRequest::new(method, url)
+            .header(
+                HeaderName::from_static("x-amz-date"),
+                HeaderValue::from_str(&amz_date).map_err(Error::from)?,
+            )
+            .header(
+                AUTHORIZATION,
+                HeaderValue::from_str(&authorization).map_err(Error::from)?,
+            )
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/xml"))
+            .body(body);
+
+        self.rate_limiter.acquire().await;
+        let response = http.send(request).await?;
+
+        if !response.is_success() {
+            return Err(Error::Unsuccessful {
+                status: response.status.as_u16(),
+                body: response.error_body(),
+            }
+            .into());
+        }
+
+        Ok(response.body)
+    }
+
+    /// Returns the configured hosted zone ID or looks it up by `domain`.
+    async fn hosted_zone_id(&self, http: &dyn HttpClient, domain: &str) -> Result<String> {
+        if let Some(hosted_zone_id) = &self.provider_config.hosted_zone_id {
+            return Ok(hosted_zone_id.clone());
+        }
+
+        let text = self
+            .send(
+                http,
+                Method::GET,
+                "hostedzonesbyname",
+                &[("dnsname", domain), ("maxitems", "1")],
+                None,
+            )
+            .await?;
+        let response: ListHostedZonesByNameResponse =
+            quick_xml::de::from_str(&text).map_err(Error::from)?;
+
+        response
+            .zone_id(domain)
+            .ok_or_else(|| ProviderError::DomainNotFound(domain.to_string()))
+    }
+
+    async fn list_record_sets(
+        &self,
+        http: &dyn HttpClient,
+        zone_id: &str,
+    ) -> Result<ListResourceRecordSetsResponse> {
+        let path = format!("hostedzone/{}/rrset", zone_id);
+        let text = self.send(http, Method::GET, &path, &[], None).await?;
+
+        Ok(quick_xml::de::from_str(&text).map_err(Error::from)?)
+    }
+
+    async fn change_record_sets(
+        &self,
+        http: &dyn HttpClient,
+        zone_id: &str,
+        changes: Vec<Change>,
+    ) -> Result<()> {
+        let request = ChangeResourceRecordSetsRequest::new(changes);
+        let body = quick_xml::se::to_string(&request).map_err(Error::from)?;
+
+        let path = format!("hostedzone/{}/rrset/", zone_id);
+        self.send(http, Method::POST, &path, &[], Some(body))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the existing record set holding records of `record`'s name and type.
+    async fn find_record_set(
+        &self,
+        http: &dyn HttpClient,
+        zone_id: &str,
+        record: &dns::Record,
+    ) -> Result<Option<ResourceRecordSet>> {
+        let response = self.list_record_sets(http, zone_id).await?;
+        let record_type = record.value.wire_type();
+
+        Ok(response
+            .resource_record_sets
+            .record_sets
+            .into_iter()
+            .find(|record_set| record_set.matches(&record.domain, record_type)))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("HTTP request failed: {0}")]
+    Reqwest(#[from] reqwest::Error),
+
+    #[error("HTTP response is not successful: {status}, body: {body:?}")]
+    Unsuccessful { status: u16, body: String },
+
+    #[error("Invalid API URL: {0}")]
+    Url(#[from] url::ParseError),
+
+    #[error(
+        "Invalid header value, the credentials may contain characters not allowed in HTTP headers: {0}"
+    )]
+    Header(#[from] InvalidHeaderValue),
+
+    #[error("XML parsing error: {0}")]
+    Xml(#[from] quick_xml::DeError),
+
+    #[error("XML serialization error: {0}")]
+    XmlSerialize(#[from] quick_xml::SeError),
+
+    #[error("Invalid record in response: {0}")]
+    TryFromRecord(#[from] TryFromRecordError),
+}
+
+#[async_trait]
+impl Provider for Route53Provider<'_> {
+    fn get_provider_name(&self) -> &'static str {
+        "Route53"
+    }
+
+    fn get_supported_features(&self) -> Vec<Feature> {
+        vec![
+            Feature::GetRecords,
+            Feature::GetAllRecords,
+            Feature::AddRecord,
+            Feature::UpdateRecord,
+            Feature::DeleteRecord,
+        ]
+    }
+
+    /// Route53 has no TLSA support.
+    fn supported_record_types(&self) -> &'static [RecordType] {
+        &[
+            RecordType::A,
+            RecordType::AAAA,
+            RecordType::CNAME,
+            RecordType::TXT,
+            RecordType::MX,
+            RecordType::NS,
+            RecordType::SOA,
+            RecordType::SRV,
+            RecordType::CAA,
+            RecordType::SVCB,
+            RecordType::HTTPS,
+            RecordType::DS,
+        ]
+    }
+
+    async fn get_all_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &GetAllRecordsInput,
+    ) -> Result<Vec<dns::Record>> {
+        let zone_id = self.hosted_zone_id(http, input.domain).await?;
+        let response = self.list_record_sets(http, &zone_id).await?;
+        let records: Vec<dns::Record> = response.try_into().map_err(Error::from)?;
+
+        Ok(records)
+    }
+
+    /// Adds the value to the record set of the record's name and type, creating it if needed.
+    async fn add_record(&self, http: &dyn HttpClient, input: &AddRecordInput) -> Result<()> {
+        let record = input.record;
+        self.validate_records(std::slice::from_ref(record))?;
+        let zone_id = self.hosted_zone_id(http, input.domain).await?;
+        let existing = self.find_record_set(http, &zone_id, record).await?;
+
+        let value = model::value_of(&record.value);
+        let (mut values, ttl) = match &existing {
+            Some(record_set) => (
+                record_set
+                    .values()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect(),
+                record.ttl.or(record_set.ttl),
+            ),
+            None => (Vec::new(), record.ttl),
+        };
+        if !values.contains(&value) {
+            values.push(value);
+        }
+
+        let change = Change {
+            action: ChangeAction::UPSERT,
+            resource_record_set: ResourceRecordSet::new(
+                &record.domain,
+                record.value.wire_type(),
+                ttl.unwrap_or(model::DEFAULT_TTL),
+                values,
+            ),
+        };
+
+        self.change_record_sets(http, &zone_id, vec![change]).await
+    }
+
+    /// Replaces the record set of the record's name and type with the record's value.
+    async fn update_record(&self, http: &dyn HttpClient, input: &UpdateRecordInput) -> Result<()> {
+        let record = input.record;
+        self.validate_records(std::slice::from_ref(record))?;
+        let zone_id = self.hosted_zone_id(http, input.domain).await?;
+
+        let change = Change {
+            action: ChangeAction::UPSERT,
+            resource_record_set: ResourceRecordSet::new(
+                &record.domain,
+                record.value.wire_type(),
+                record.ttl.unwrap_or(model::DEFAULT_TTL),
+                vec![model::value_of(&record.value)],
+            ),
+        };
+
+        self.change_record_sets(http, &zone_id, vec![change]).await
+    }
+
+    /// Updates are sent as `UPSERT` already, so no lookup is needed.
+    async fn upsert_record(&self, http: &dyn HttpClient, input: &UpsertRecordInput) -> Result<()> {
+        let update_record_input = UpdateRecordInput {
+            domain: input.domain,
+            record: input.record,
+        };
+        self.update_record(http, &update_record_input).await
+    }
+
+    /// Updates are sent as `UPSERT` already, so no lookup is needed.
+    async fn upsert_records(
+        &self,
+        http: &dyn HttpClient,
+        input: &UpsertRecordsInput,
+    ) -> Result<Vec<Result<()>>> {
+        let update_records_input = UpdateRecordsInput {
+            domain: input.domain,
+            records: input.records,
+        };
+        self.update_records(http, &update_records_input).await
+    }
+
+    /// Removes the value from its record set, deleting the set if no values remain.
+    async fn delete_record(&self, http: &dyn HttpClient, input: &DeleteRecordInput) -> Result<()> {
+        let record = input.record;
+        let zone_id = self.hosted_zone_id(http, input.domain).await?;
+        let value = model::value_of(&record.value);
+
+        let record_set = match self.find_record_set(http, &zone_id, record).await? {
+            Some(record_set) if record_set.values().contains(&value.as_str()) => record_set,
+            _ => return provider::already_absent(self.get_provider_name(), record),
+        };
+
+        let remaining: Vec<String> = record_set
+            .values()
+            .into_iter()
+            .filter(|existing| *existing != value)
+            .map(str::to_string)
+            .collect();
+
+        let change = if remaining.is_empty() {
+            Change {
+                action: ChangeAction::DELETE,
+                resource_record_set: record_set,
+            }
+        } else {
+            Change {
+                action: ChangeAction::UPSERT,
+                resource_record_set: ResourceRecordSet::new(
+                    &record_set.name,
+                    record_set.r#type,
+                    record_set.ttl.unwrap_or(model::DEFAULT_TTL),
+                    remaining,
+                ),
+            }
+        };
+
+        self.change_record_sets(http, &zone_id, vec![change]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dns::RecordValue;
+    use std::net::Ipv4Addr;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_string_contains, header_exists, method, path, query_param},
+    };
+
+    const RECORD_SETS: &str = r#"<ListResourceRecordSetsResponse>
+  <ResourceRecordSets>
+    <ResourceRecordSet>
+      <Name>home.example.com.</Name>
+      <Type>A</Type>
+      <TTL>600</TTL>
+      <ResourceRecords>
+        <ResourceRecord><Value>1.2.3.4</Value></ResourceRecord>
+        <ResourceRecord><Value>5.6.7.8</Value></ResourceRecord>
+      </ResourceRecords>
+    </ResourceRecordSet>
+  </ResourceRecordSets>
+  <IsTruncated>false</IsTruncated>
+</ListResourceRecordSetsResponse>"#;
+
+    fn config(server: &MockServer, hosted_zone_id: Option<&str>) -> Config {
+        Config {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "secret".into(),
+            hosted_zone_id: hosted_zone_id.map(str::to_string),
+            api_base_url: server.uri(),
+            ..Default::default()
+        }
+    }
+
+    fn record(ip: Ipv4Addr) -> dns::Record {
+        dns::Record {
+            domain: "home.example.com".to_string(),
+            value: RecordValue::A(ip),
+            ttl: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_all_records_looks_up_hosted_zone() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/2013-04-01/hostedzonesbyname"))
+            .and(query_param("dnsname", "example.com"))
+            .and(header_exists("authorization"))
+            .and(header_exists("x-amz-date"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "<ListHostedZonesByNameResponse><HostedZones><HostedZone>\
+                 <Id>/hostedzone/Z123</Id><Name>example.com.</Name>\
+                 </HostedZone></HostedZones></ListHostedZonesByNameResponse>",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/2013-04-01/hostedzone/Z123/rrset"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(RECORD_SETS))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server, None);
+        let provider = Route53Provider::new(&config);
+        let input = GetAllRecordsInput {
+            domain: "example.com",
+        };
+
+        let records = provider
+            .get_all_records(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].domain, "home.example.com");
+    }
+
+    #[tokio::test]
+    async fn test_delete_record_upserts_remaining_values() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/2013-04-01/hostedzone/Z123/rrset"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(RECORD_SETS))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/2013-04-01/hostedzone/Z123/rrset/"))
+            .and(body_string_contains("<Action>UPSERT</Action>"))
+            .and(body_string_contains(
+                "<ResourceRecords><ResourceRecord><Value>5.6.7.8</Value></ResourceRecord></ResourceRecords>",
+            ))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = config(&server, Some("Z123"));
+        let provider = Route53Provider::new(&config);
+        let record = record(Ipv4Addr::new(1, 2, 3, 4));
+        let input = DeleteRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        provider
+            .delete_record(&reqwest::Client::new(), &input)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete_unknown_record_is_already_absent() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/2013-04-01/hostedzone/Z123/rrset"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(RECORD_SETS))
+            .mount(&server)
+            .await;
+
+        let config = config(&server, Some("Z123"));
+        let provider = Route53Provider::new(&config);
+        let record = record(Ipv4Addr::new(9, 9, 9, 9));
+        let input = DeleteRecordInput {
+            domain: "example.com",
+            record: &record,
+        };
+
+        let result = provider
+            .delete_record(&reqwest::Client::new(), &input)
+            .await;
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use lum_libs::serde::{Deserialize, Serialize};
+
+use crate::config::{dns::RecordConfig, secret::Secret};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Config {
+    pub name: String,
+    pub access_key_id: String,
+    pub secret_access_key: Secret,
+    pub region: String,
+    /// Looked up by domain name if not set.
+    pub hosted_zone_id: Option<String>,
+    pub api_base_url: String,
+    pub requests_per_second: Option<f64>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            name: "Route53_1".to_string(),
+            access_key_id: "your_access_key_id".to_string(),
+            secret_access_key: "your_secret_access_key".into(),
+            region: "us-east-1".to_string(),
+            hosted_zone_id: None,
+            api_base_url: "https://route53.amazonaws.com".to_string(),
+            requests_per_second: None,
+            source_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct DomainConfig {
+    pub domain: String,
+    pub records: Vec<RecordConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct DnsConfig {
+    pub provider_name: String,
+    pub domains: Vec<DomainConfig>,
+
+    /// File this config was loaded from, if any
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
+}
+
+impl Default for DnsConfig {
+    fn default() -> Self {
+        DnsConfig {
+            provider_name: "Route53_1".to_string(),
+            domains: vec![],
+            source_path: None,
+        }
+    }
+}
@@ -0,0 +1,509 @@
+use core::num;
+use std::{
+    net::{self, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use lum_libs::serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::dns::{
+    self, DnssecParseError, MxRecord, RecordType, RecordValue, SvcbParseError, SvcbRecord,
+};
+
+/// XML namespace of the Route53 API version used by this provider.
+pub const XMLNS: &str = "https://route53.amazonaws.com/doc/2013-04-01/";
+
+/// TTL used for new record sets if the record does not specify one.
+pub const DEFAULT_TTL: u32 = 300;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct ResourceRecord {
+    #[serde(rename = "Value")]
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct ResourceRecords {
+    #[serde(rename = "ResourceRecord", default)]
+    pub records: Vec<ResourceRecord>,
+}
+
+/// All values of one name and type, the unit Route53 reads and writes.
+///
+/// Alias record sets have no `resource_records` and are skipped when converting to
+/// [`dns::Record`]s.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct ResourceRecordSet {
+    #[serde(rename = "Name")]
+    pub name: String,
+
+    #[serde(rename = "Type")]
+    pub r#type: RecordType,
+
+    #[serde(rename = "TTL", default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+
+    #[serde(
+        rename = "ResourceRecords",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub resource_records: Option<ResourceRecords>,
+}
+
+impl ResourceRecordSet {
+    /// Builds a record set with the given values. `name` is turned into an absolute name.
+    pub fn new(name: &str, r#type: RecordType, ttl: u32, values: Vec<String>) -> Self {
+        ResourceRecordSet {
+            name: absolute_name(name),
+            r#type,
+            ttl: Some(ttl),
+            resource_records: Some(ResourceRecords {
+                records: values
+                    .into_iter()
+                    .map(|value| ResourceRecord { value })
+                    .collect(),
+            }),
+        }
+    }
+
+    /// Returns the values of this record set.
+    pub fn values(&self) -> Vec<&str> {
+        self.resource_records
+            .iter()
+            .flat_map(|records| records.records.iter())
+            .map(|record| record.value.as_str())
+            .collect()
+    }
+
+    /// Returns whether this record set holds records for `name` and `r#type`.
+    pub fn matches(&self, name: &str, r#type: RecordType) -> bool {
+        self.r#type == r#type && relative_name(&self.name) == relative_name(name)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct ResourceRecordSets {
+    #[serde(rename = "ResourceRecordSet", default)]
+    pub record_sets: Vec<ResourceRecordSet>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct ListResourceRecordSetsResponse {
+    #[serde(rename = "ResourceRecordSets", default)]
+    pub resource_record_sets: ResourceRecordSets,
+
+    #[serde(rename = "IsTruncated", default)]
+    pub is_truncated: bool,
+
+    #[serde(rename = "NextRecordName", default)]
+    pub next_record_name: Option<String>,
+
+    #[serde(rename = "NextRecordType", default)]
+    pub next_record_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct HostedZone {
+    #[serde(rename = "Id")]
+    pub id: String,
+
+    #[serde(rename = "Name")]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct HostedZones {
+    #[serde(rename = "HostedZone", default)]
+    pub hosted_zones: Vec<HostedZone>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct ListHostedZonesByNameResponse {
+    #[serde(rename = "HostedZones", default)]
+    pub hosted_zones: HostedZones,
+}
+
+impl ListHostedZonesByNameResponse {
+    /// Returns the ID of the hosted zone for `domain`, without the `/hostedzone/` prefix.
+    pub fn zone_id(&self, domain: &str) -> Option<String> {
+        self.hosted_zones
+            .hosted_zones
+            .iter()
+            .find(|zone| relative_name(&zone.name) == relative_name(domain))
+            .map(|zone| zone.id.trim_start_matches("/hostedzone/").to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub enum ChangeAction {
+    CREATE,
+    DELETE,
+    UPSERT,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Change {
+    #[serde(rename = "Action")]
+    pub action: ChangeAction,
+
+    #[serde(rename = "ResourceRecordSet")]
+    pub resource_record_set: ResourceRecordSet,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Changes {
+    #[serde(rename = "Change", default)]
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct ChangeBatch {
+    #[serde(rename = "Changes")]
+    pub changes: Changes,
+}
+
+/// Body of a `ChangeResourceRecordSets` request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde", rename = "ChangeResourceRecordSetsRequest")]
+pub struct ChangeResourceRecordSetsRequest {
+    #[serde(rename = "@xmlns")]
+    pub xmlns: String,
+
+    #[serde(rename = "ChangeBatch")]
+    pub change_batch: ChangeBatch,
+}
+
+impl ChangeResourceRecordSetsRequest {
+    pub fn new(changes: Vec<Change>) -> Self {
+        ChangeResourceRecordSetsRequest {
+            xmlns: XMLNS.to_string(),
+            change_batch: ChangeBatch {
+                changes: Changes { changes },
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum TryFromRecordError {
+    #[error("Invalid IP address: {0}")]
+    InvalidIp(#[from] net::AddrParseError),
+
+    #[error("Invalid MX record format: {0}")]
+    InvalidMxFormat(String),
+
+    #[error("Invalid priority in MX record: {0}")]
+    InvalidMxPriority(num::ParseIntError),
+
+    #[error("Invalid SRV record format: {0}")]
+    InvalidSrvFormat(String),
+
+    #[error("Invalid SRV record priority/weight/port: {0}")]
+    InvalidSrvValue(num::ParseIntError),
+
+    #[error("Invalid TLSA record format: {0}")]
+    InvalidTlsaFormat(String),
+
+    #[error("Invalid TLSA record usage/selector/matching type: {0}")]
+    InvalidTlsaValue(num::ParseIntError),
+
+    #[error("Invalid CAA record format: {0}")]
+    InvalidCaaFormat(String),
+
+    #[error("Invalid CAA record flag: {0}")]
+    InvalidCaaFlag(num::ParseIntError),
+
+    #[error("Invalid SVCB/HTTPS record: {0}")]
+    InvalidSvcb(#[from] SvcbParseError),
+
+    #[error("Invalid DNSSEC record: {0}")]
+    InvalidDnssec(#[from] DnssecParseError),
+
+    #[error("Record type {0:?} is not supported by Route53 record sets")]
+    UnsupportedRecordType(RecordType),
+
+    #[error("Invalid record: {0}")]
+    InvalidRecord(#[from] dns::RecordValidationError),
+}
+
+/// Returns `name` with a trailing dot, as Route53 expects it.
+pub fn absolute_name(name: &str) -> String {
+    format!("{}.", name.trim_end_matches('.'))
+}
+
+/// Returns `name` without trailing dot and with Route53's octal escape for `*` decoded.
+pub fn relative_name(name: &str) -> String {
+    name.trim_end_matches('.').replace("\\052", "*")
+}
+
+fn quote(text: &str) -> String {
+    if text.starts_with('"') && text.ends_with('"') && text.len() >= 2 {
+        text.to_string()
+    } else {
+        format!("\"{}\"", text.replace('"', "\\\""))
+    }
+}
+
+fn unquote(text: &str) -> String {
+    match text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+    {
+        Some(text) => text.replace("\\\"", "\""),
+        None => text.to_string(),
+    }
+}
+
+/// Formats a record value the way Route53 expects it in `ResourceRecord/Value`.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::provider::route53::model::value_of;
+/// use dnrs::types::dns::RecordValue;
+///
+/// assert_eq!(value_of(&RecordValue::TXT("hello".to_string())), "\"hello\"");
+/// assert_eq!(value_of(&RecordValue::CNAME("target.example.com".to_string())), "target.example.com");
+/// ```
+pub fn value_of(value: &RecordValue) -> String {
+    match value {
+        RecordValue::TXT(text) | RecordValue::SPF(text) => quote(text),
+        RecordValue::CAA(flag, tag, value) => format!("{} {} {}", flag, tag, quote(value)),
+        _ => value.content(),
+    }
+}
+
+fn split_parts(value: &str, count: usize) -> Option<Vec<&str>> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    (parts.len() == count).then_some(parts)
+}
+
+/// Parses a single `ResourceRecord/Value` of the given type.
+pub fn parse_value(r#type: RecordType, value: &str) -> Result<RecordValue, TryFromRecordError> {
+    let value = match r#type {
+        RecordType::A => RecordValue::A(Ipv4Addr::from_str(value)?),
+        RecordType::AAAA => RecordValue::AAAA(Ipv6Addr::from_str(value)?),
+        RecordType::CNAME => RecordValue::CNAME(value.to_string()),
+        RecordType::TXT => RecordValue::TXT(unquote(value)),
+        RecordType::SPF => RecordValue::SPF(unquote(value)),
+        RecordType::NS => RecordValue::NS(value.to_string()),
+        RecordType::SOA => RecordValue::SOA(value.to_string()),
+        RecordType::MX => {
+            let parts = split_parts(value, 2)
+                .ok_or_else(|| TryFromRecordError::InvalidMxFormat(value.to_string()))?;
+            let priority = parts[0]
+                .parse::<u16>()
+                .map_err(TryFromRecordError::InvalidMxPriority)?;
+
+            RecordValue::MX(MxRecord {
+                priority,
+                target: parts[1].to_string(),
+            })
+        }
+        RecordType::SRV => {
+            let parts = split_parts(value, 4)
+                .ok_or_else(|| TryFromRecordError::InvalidSrvFormat(value.to_string()))?;
+            let numbers = parts[..3]
+                .iter()
+                .map(|part| part.parse::<u16>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(TryFromRecordError::InvalidSrvValue)?;
+
+            let target = parts[3].trim_end_matches('.').to_string();
+            RecordValue::SRV(numbers[0], numbers[1], numbers[2], target)
+        }
+        RecordType::TLSA => {
+            let parts = split_parts(value, 4)
+                .ok_or_else(|| TryFromRecordError::InvalidTlsaFormat(value.to_string()))?;
+            let numbers = parts[..3]
+                .iter()
+                .map(|part| part.parse::<u16>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(TryFromRecordError::InvalidTlsaValue)?;
+
+            RecordValue::TLSA(numbers[0], numbers[1], numbers[2], parts[3].to_string())
+        }
+        RecordType::CAA => {
+            let mut parts = value.splitn(3, ' ');
+            let (Some(flag), Some(tag), Some(caa_value)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                return Err(TryFromRecordError::InvalidCaaFormat(value.to_string()));
+            };
+            let flag = flag
+                .parse::<u8>()
+                .map_err(TryFromRecordError::InvalidCaaFlag)?;
+
+            RecordValue::CAA(flag, tag.to_string(), unquote(caa_value))
+        }
+        RecordType::SVCB => RecordValue::SVCB(SvcbRecord::from_str(value)?),
+        RecordType::HTTPS => RecordValue::HTTPS(SvcbRecord::from_str(value)?),
+        RecordType::DS => RecordValue::parse_ds(value)?,
+        // Route53 signs zones with key-signing keys managed through its DNSSEC API,
+        // DNSKEY records cannot be written as record sets
+        RecordType::DNSKEY => return Err(TryFromRecordError::UnsupportedRecordType(r#type)),
+    };
+
+    Ok(value)
+}
+
+/// Converts a record set into one [`dns::Record`] per value.
+impl TryFrom<&ResourceRecordSet> for Vec<dns::Record> {
+    type Error = TryFromRecordError;
+
+    fn try_from(record_set: &ResourceRecordSet) -> Result<Self, Self::Error> {
+        let domain = relative_name(&record_set.name);
+        record_set
+            .values()
+            .into_iter()
+            .map(|value| {
+                let value = parse_value(record_set.r#type, value)?;
+                Ok(dns::Record::builder(domain.clone(), value)
+                    .ttl(record_set.ttl)
+                    .build()?)
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<ListResourceRecordSetsResponse> for Vec<dns::Record> {
+    type Error = TryFromRecordError;
+
+    fn try_from(response: ListResourceRecordSetsResponse) -> Result<Self, Self::Error> {
+        let mut records = Vec::new();
+        for record_set in response.resource_record_sets.record_sets.iter() {
+            let set_records: Vec<dns::Record> = record_set.try_into()?;
+            records.extend(set_records);
+        }
+
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_srv_record_round_trips() {
+        let value = RecordValue::SRV(10, 5, 5060, "sip.example.com".to_string());
+        let parsed = parse_value(RecordType::SRV, &value_of(&value)).unwrap();
+        assert!(matches!(
+            parsed,
+            RecordValue::SRV(10, 5, 5060, ref target) if target == "sip.example.com"
+        ));
+
+        let parsed = parse_value(RecordType::SRV, "10 5 5060 sip.example.com.").unwrap();
+        assert!(matches!(
+            parsed,
+            RecordValue::SRV(10, 5, 5060, ref target) if target == "sip.example.com"
+        ));
+    }
+
+    #[test]
+    fn test_parse_list_resource_record_sets_response() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListResourceRecordSetsResponse xmlns="https://route53.amazonaws.com/doc/2013-04-01/">
+  <ResourceRecordSets>
+    <ResourceRecordSet>
+      <Name>\052.example.com.</Name>
+      <Type>A</Type>
+      <TTL>300</TTL>
+      <ResourceRecords>
+        <ResourceRecord><Value>1.2.3.4</Value></ResourceRecord>
+        <ResourceRecord><Value>5.6.7.8</Value></ResourceRecord>
+      </ResourceRecords>
+    </ResourceRecordSet>
+    <ResourceRecordSet>
+      <Name>example.com.</Name>
+      <Type>TXT</Type>
+      <TTL>60</TTL>
+      <ResourceRecords>
+        <ResourceRecord><Value>"v=spf1 -all"</Value></ResourceRecord>
+      </ResourceRecords>
+    </ResourceRecordSet>
+  </ResourceRecordSets>
+  <IsTruncated>false</IsTruncated>
+  <MaxItems>100</MaxItems>
+</ListResourceRecordSetsResponse>"#;
+
+        let response: ListResourceRecordSetsResponse = quick_xml::de::from_str(xml).unwrap();
+        let records: Vec<dns::Record> = response.try_into().unwrap();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].domain, "*.example.com");
+        assert!(matches!(records[1].value, RecordValue::A(ip) if ip == Ipv4Addr::new(5, 6, 7, 8)));
+        assert!(matches!(&records[2].value, RecordValue::TXT(text) if text == "v=spf1 -all"));
+        assert_eq!(records[2].ttl, Some(60));
+    }
+
+    #[test]
+    fn test_change_batch_xml() {
+        let request = ChangeResourceRecordSetsRequest::new(vec![
+            Change {
+                action: ChangeAction::UPSERT,
+                resource_record_set: ResourceRecordSet::new(
+                    "home.example.com",
+                    RecordType::A,
+                    300,
+                    vec!["1.2.3.4".to_string()],
+                ),
+            },
+            Change {
+                action: ChangeAction::DELETE,
+                resource_record_set: ResourceRecordSet::new(
+                    "example.com",
+                    RecordType::TXT,
+                    60,
+                    vec![value_of(&RecordValue::TXT("token".to_string()))],
+                ),
+            },
+        ]);
+
+        let xml = quick_xml::se::to_string(&request).unwrap();
+        assert_eq!(
+            xml,
+            "<ChangeResourceRecordSetsRequest xmlns=\"https://route53.amazonaws.com/doc/2013-04-01/\">\
+             <ChangeBatch><Changes>\
+             <Change><Action>UPSERT</Action><ResourceRecordSet>\
+             <Name>home.example.com.</Name><Type>A</Type><TTL>300</TTL>\
+             <ResourceRecords><ResourceRecord><Value>1.2.3.4</Value></ResourceRecord></ResourceRecords>\
+             </ResourceRecordSet></Change>\
+             <Change><Action>DELETE</Action><ResourceRecordSet>\
+             <Name>example.com.</Name><Type>TXT</Type><TTL>60</TTL>\
+             <ResourceRecords><ResourceRecord><Value>\"token\"</Value></ResourceRecord></ResourceRecords>\
+             </ResourceRecordSet></Change>\
+             </Changes></ChangeBatch>\
+             </ChangeResourceRecordSetsRequest>"
+        );
+    }
+
+    #[test]
+    fn test_hosted_zone_id_lookup() {
+        let xml = r#"<ListHostedZonesByNameResponse>
+  <HostedZones>
+    <HostedZone><Id>/hostedzone/Z111</Id><Name>example.com.</Name></HostedZone>
+    <HostedZone><Id>/hostedzone/Z222</Id><Name>example.org.</Name></HostedZone>
+  </HostedZones>
+  <IsTruncated>false</IsTruncated>
+</ListHostedZonesByNameResponse>"#;
+
+        let response: ListHostedZonesByNameResponse = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(response.zone_id("example.org").unwrap(), "Z222");
+        assert!(response.zone_id("example.net").is_none());
+    }
+}
@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use tokio::{sync::Mutex, time::Instant};
+
+/// Spaces out requests so that at most `requests_per_second` requests are started per second.
+///
+/// A limiter without a rate never waits. Waiting callers are served in the order they called
+/// [`RateLimiter::acquire`].
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::rate_limit::RateLimiter;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let limiter = RateLimiter::new(Some(10.0));
+///
+/// // Returns immediately, the second call waits 100ms
+/// limiter.acquire().await;
+/// limiter.acquire().await;
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RateLimiter {
+    min_interval: Option<Duration>,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: Option<f64>) -> Self {
+        let min_interval = requests_per_second
+            .filter(|rate| rate.is_finite() && *rate > 0.0)
+            .map(|rate| Duration::from_secs_f64(1.0 / rate));
+
+        RateLimiter {
+            min_interval,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(None)
+    }
+
+    /// Waits until the next request may be sent.
+    pub async fn acquire(&self) {
+        let min_interval = match self.min_interval {
+            Some(min_interval) => min_interval,
+            None => return,
+        };
+
+        let mut next_slot = self.next_slot.lock().await;
+        let now = Instant::now();
+        if *next_slot > now {
+            tokio::time::sleep_until(*next_slot).await;
+        }
+
+        *next_slot = Instant::now().max(*next_slot) + min_interval;
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rate_limiter_spaces_requests() {
+        let limiter = RateLimiter::new(Some(2.0));
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+
+        // The first request is sent immediately, the following two wait 500ms each
+        assert!(start.elapsed() >= Duration::from_millis(1000));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_unlimited_does_not_wait() {
+        let limiter = RateLimiter::unlimited();
+        let start = Instant::now();
+
+        for _ in 0..100 {
+            limiter.acquire().await;
+        }
+
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_rate_limiter_ignores_invalid_rate() {
+        assert!(RateLimiter::new(Some(0.0)).min_interval.is_none());
+        assert!(RateLimiter::new(Some(-1.0)).min_interval.is_none());
+        assert!(RateLimiter::new(Some(f64::NAN)).min_interval.is_none());
+    }
+}
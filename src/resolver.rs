@@ -3,45 +3,61 @@ use std::{
     str::FromStr,
 };
 
-use lum_libs::serde_json;
-use lum_log::debug;
+use futures::future;
+use lum_libs::{serde::Deserialize, serde_json};
+use lum_log::{debug, warn};
+use reqwest::{RequestBuilder, header::ACCEPT};
 use thiserror::Error;
 
 use crate::{
     Config,
     config::{
         dns::{AutomaticRecordConfig, ResolveType},
-        resolver::{IpResolver, IpResolverType},
+        resolver::{IpResolver, IpResolverType, ResolverAuth},
     },
-    types::dns::{Record, RecordValue},
+    types::dns::{Record, RecordType, RecordValidationError, RecordValue},
 };
 
+pub mod upnp;
+
 #[derive(Debug)]
 pub struct Ipv4ResolverConfig<'resolver> {
-    pub ipv4_resolver: &'resolver IpResolver,
+    pub ipv4_resolvers: &'resolver [IpResolver],
+    pub quorum: Option<u32>,
 }
 
 impl<'config> From<&'config Config> for Ipv4ResolverConfig<'config> {
     fn from(config: &'config Config) -> Self {
         Self {
-            ipv4_resolver: &config.resolver.ipv4,
+            ipv4_resolvers: &config.resolver.ipv4,
+            quorum: config.resolver.quorum,
         }
     }
 }
 
 #[derive(Debug)]
 pub struct Ipv6ResolverConfig<'resolver> {
-    pub ipv6_resolver: &'resolver IpResolver,
+    pub ipv6_resolvers: &'resolver [IpResolver],
+    pub quorum: Option<u32>,
 }
 
 impl<'config> From<&'config Config> for Ipv6ResolverConfig<'config> {
     fn from(config: &'config Config) -> Self {
         Self {
-            ipv6_resolver: &config.resolver.ipv6,
+            ipv6_resolvers: &config.resolver.ipv6,
+            quorum: config.resolver.quorum,
         }
     }
 }
 
+/// Addresses given by the user, e.g. with `--ipv4` and `--ipv6`, that are used instead of
+/// resolving them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AddressOverride {
+    pub ipv4: Option<Ipv4Addr>,
+    pub ipv6: Option<Ipv6Addr>,
+}
+
 #[derive(Debug, Error)]
 pub enum JsonParseError {
     #[error("Could not parse JSON response: {0}")]
@@ -53,13 +69,76 @@ pub enum JsonParseError {
     #[error("JSON path {0} not found at {1}")]
     PathNotFound(String, String),
 
-    #[error("JSON value is not a string")]
+    #[error("JSON value is not a string, number or boolean")]
     NotAString(serde_json::Value),
+
+    #[error("JSON path {0} has an invalid index in {1}")]
+    InvalidIndex(String, String),
+
+    #[error("JSON path {path}: index {index} is out of bounds for an array of length {len}")]
+    IndexOutOfBounds {
+        path: String,
+        index: usize,
+        len: usize,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum RegexParseError {
+    #[error("Invalid regular expression: {0}")]
+    Invalid(#[from] regex::Error),
+
+    #[error("Regular expression {0} does not match the response")]
+    NoMatch(String),
+}
+
+/// Returns the first capture group of `pattern` in a response, or its whole match if the
+/// pattern has no group.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::resolver::parse_regex_response;
+///
+/// let html = "<body>Current IP Address: 1.2.3.4</body>";
+/// let result = parse_regex_response(html, r"Address: ([\d.]+)").unwrap();
+/// assert_eq!(result, "1.2.3.4");
+///
+/// let result = parse_regex_response(html, r"\d+\.\d+\.\d+\.\d+").unwrap();
+/// assert_eq!(result, "1.2.3.4");
+/// ```
+pub fn parse_regex_response(response: &str, pattern: &str) -> Result<String, RegexParseError> {
+    let regex = regex::Regex::new(pattern)?;
+    let captures = regex
+        .captures(response)
+        .ok_or_else(|| RegexParseError::NoMatch(pattern.to_string()))?;
+    let found = captures.get(1).or_else(|| captures.get(0));
+
+    Ok(found.map_or("", |found| found.as_str()).to_string())
+}
+
+/// Splits a path segment like `addresses[1]` into its key and array indices. Segments without
+/// brackets are a plain key, a segment starting with `[` indexes the current value.
+fn parse_path_segment(segment: &str) -> Option<(&str, Vec<usize>)> {
+    let Some(start) = segment.find('[') else {
+        return Some((segment, Vec::new()));
+    };
+
+    let (key, mut rest) = segment.split_at(start);
+    let mut indices = Vec::new();
+    while !rest.is_empty() {
+        let (index, remaining) = rest.strip_prefix('[')?.split_once(']')?;
+        indices.push(index.parse().ok()?);
+        rest = remaining;
+    }
+
+    Some((key, indices))
 }
 
 /// Parses a JSON response and returns the value at the specified path.
 ///
-/// The path uses dot notation to traverse nested objects (e.g., "data.ip").
+/// The path uses dot notation to traverse nested objects (e.g., "data.ip"), array elements
+/// are selected with `[n]` (e.g., "ips[0]" or "data.addresses[1]").
 ///
 /// # Examples
 ///
@@ -73,6 +152,10 @@ pub enum JsonParseError {
 /// let nested_json = r#"{"data": {"ip": "1.2.3.4"}}"#;
 /// let result = parse_json_response(nested_json, "data.ip").unwrap();
 /// assert_eq!(result, "1.2.3.4");
+///
+/// let array_json = r#"{"ips": ["1.2.3.4", "5.6.7.8"]}"#;
+/// let result = parse_json_response(array_json, "ips[1]").unwrap();
+/// assert_eq!(result, "5.6.7.8");
 /// ```
 ///
 /// # Errors
@@ -81,7 +164,9 @@ pub enum JsonParseError {
 /// - The JSON is invalid.
 /// - The path is empty.
 /// - The path does not exist in the JSON.
-/// - The value at the path is not a string.
+/// - An array index is malformed or out of bounds.
+/// - The value at the path is an object, an array or null. Numbers and booleans are returned
+///   in their JSON text form.
 pub fn parse_json_response(response: &str, path: &str) -> Result<String, JsonParseError> {
     let path_parts = path.split('.').collect::<Vec<&str>>();
     if path_parts.is_empty() {
@@ -91,27 +176,62 @@ pub fn parse_json_response(response: &str, path: &str) -> Result<String, JsonPar
     let json: serde_json::Value = serde_json::from_str(response)?;
     let mut current_json = &json;
     for part in path_parts {
-        if let Some(next_json) = current_json.get(part) {
-            current_json = next_json;
-        } else {
-            return Err(JsonParseError::PathNotFound(
+        let Some((key, indices)) = parse_path_segment(part) else {
+            return Err(JsonParseError::InvalidIndex(
                 path.to_string(),
                 part.to_string(),
             ));
+        };
+
+        if !key.is_empty() || indices.is_empty() {
+            current_json = match current_json.get(key) {
+                Some(next_json) => next_json,
+                None => {
+                    return Err(JsonParseError::PathNotFound(
+                        path.to_string(),
+                        part.to_string(),
+                    ));
+                }
+            };
         }
-    }
 
-    let value = match current_json.as_str() {
-        Some(value) => value,
-        None => return Err(JsonParseError::NotAString(current_json.clone())),
-    };
+        for index in indices {
+            let Some(array) = current_json.as_array() else {
+                return Err(JsonParseError::PathNotFound(
+                    path.to_string(),
+                    part.to_string(),
+                ));
+            };
+            current_json = match array.get(index) {
+                Some(next_json) => next_json,
+                None => {
+                    return Err(JsonParseError::IndexOutOfBounds {
+                        path: path.to_string(),
+                        index,
+                        len: array.len(),
+                    });
+                }
+            };
+        }
+    }
 
-    Ok(value.to_string())
+    // Numbers and booleans are converted to text, the caller's parser rejects invalid values
+    match current_json {
+        serde_json::Value::String(value) => Ok(value.clone()),
+        serde_json::Value::Number(value) => Ok(value.to_string()),
+        serde_json::Value::Bool(value) => Ok(value.to_string()),
+        _ => Err(JsonParseError::NotAString(current_json.clone())),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::net::IpAddr;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{header, method, path, query_param},
+    };
 
     #[test]
     fn test_parse_json_response_simple() {
@@ -156,10 +276,90 @@ mod tests {
 
     #[test]
     fn test_parse_json_response_not_a_string() {
-        let response = r#"{"ip": 1234}"#;
-        let path = "ip";
-        let result = parse_json_response(response, path);
-        assert!(matches!(result, Err(JsonParseError::NotAString(_))));
+        let response = r#"{"ip": {"v4": "1.2.3.4"}, "ips": ["1.2.3.4"], "none": null}"#;
+        for path in ["ip", "ips", "none"] {
+            let result = parse_json_response(response, path);
+            assert!(
+                matches!(result, Err(JsonParseError::NotAString(_))),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_json_response_coerces_numbers_and_booleans() {
+        let response = r#"{"ip": 16909060, "port": 8.5, "ok": true}"#;
+        assert_eq!(parse_json_response(response, "ip").unwrap(), "16909060");
+        assert_eq!(parse_json_response(response, "port").unwrap(), "8.5");
+        assert_eq!(parse_json_response(response, "ok").unwrap(), "true");
+    }
+
+    #[test]
+    fn test_parse_regex_response() {
+        let response = "<html><body>Your IP: <b>1.2.3.4</b></body></html>";
+        assert_eq!(
+            parse_regex_response(response, r"<b>([^<]+)</b>").unwrap(),
+            "1.2.3.4"
+        );
+        assert!(matches!(
+            parse_regex_response(response, r"IPv6: (\S+)"),
+            Err(RegexParseError::NoMatch(_))
+        ));
+        assert!(matches!(
+            parse_regex_response(response, r"(unclosed"),
+            Err(RegexParseError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_json_response_array_index() {
+        let response = r#"{"ips": ["1.2.3.4", "5.6.7.8"]}"#;
+        assert_eq!(parse_json_response(response, "ips[0]").unwrap(), "1.2.3.4");
+        assert_eq!(parse_json_response(response, "ips[1]").unwrap(), "5.6.7.8");
+
+        let response = r#"[{"ip": "1.2.3.4"}]"#;
+        assert_eq!(parse_json_response(response, "[0].ip").unwrap(), "1.2.3.4");
+
+        let response = r#"{"ips": [["1.2.3.4"]]}"#;
+        assert_eq!(
+            parse_json_response(response, "ips[0][0]").unwrap(),
+            "1.2.3.4"
+        );
+    }
+
+    #[test]
+    fn test_parse_json_response_nested_array_in_object() {
+        let response = r#"{"data": {"addresses": [{"ip": "1.2.3.4"}, {"ip": "5.6.7.8"}]}}"#;
+        let result = parse_json_response(response, "data.addresses[1].ip").unwrap();
+        assert_eq!(result, "5.6.7.8");
+    }
+
+    #[test]
+    fn test_parse_json_response_index_errors() {
+        let response = r#"{"ips": ["1.2.3.4"], "ip": "1.2.3.4"}"#;
+
+        let result = parse_json_response(response, "ips[1]");
+        assert!(matches!(
+            result,
+            Err(JsonParseError::IndexOutOfBounds {
+                index: 1,
+                len: 1,
+                ..
+            })
+        ));
+
+        for path in ["ips[x]", "ips[0", "ips[0]x", "ips[-1]"] {
+            let result = parse_json_response(response, path);
+            assert!(
+                matches!(result, Err(JsonParseError::InvalidIndex(_, _))),
+                "{}",
+                path
+            );
+        }
+
+        let result = parse_json_response(response, "ip[0]");
+        assert!(matches!(result, Err(JsonParseError::PathNotFound(_, _))));
     }
 
     #[test]
@@ -169,6 +369,282 @@ mod tests {
         let result = parse_json_response(response, path);
         assert!(matches!(result, Err(JsonParseError::SerdeJson(_))));
     }
+
+    fn doh_resolver(server: &MockServer, record_type: RecordType) -> IpResolver {
+        IpResolver {
+            url: format!("{}/resolve", server.uri()),
+            type_: IpResolverType::Doh {
+                query_name: "myip.example.com".to_string(),
+                record_type,
+            },
+            priority: None,
+            weight: 1,
+            auth: None,
+        }
+    }
+
+    fn raw_resolver(server: &MockServer, path: &str, priority: Option<u32>) -> IpResolver {
+        IpResolver {
+            url: format!("{}{}", server.uri(), path),
+            type_: IpResolverType::Raw,
+            priority,
+            weight: 1,
+            auth: None,
+        }
+    }
+
+    async fn mount_raw(server: &MockServer, at: &str, body: &str) {
+        Mock::given(method("GET"))
+            .and(path(at))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_resolver_auth_headers() {
+        let cases = [
+            (
+                ResolverAuth::Basic {
+                    username: "user".to_string(),
+                    password: "pass".into(),
+                },
+                "authorization",
+                "Basic dXNlcjpwYXNz",
+            ),
+            (
+                ResolverAuth::Bearer {
+                    token: "secret-token".into(),
+                },
+                "authorization",
+                "Bearer secret-token",
+            ),
+            (
+                ResolverAuth::Header {
+                    name: "X-Api-Key".to_string(),
+                    value: "secret-key".into(),
+                },
+                "x-api-key",
+                "secret-key",
+            ),
+        ];
+
+        for (auth, name, value) in cases {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/ip"))
+                .and(header(name, value))
+                .respond_with(ResponseTemplate::new(200).set_body_string("1.2.3.4"))
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let mut resolver = raw_resolver(&server, "/ip", None);
+            resolver.auth = Some(auth);
+            let ip: Ipv4Addr = resolve_ip_internal(&resolver, &reqwest::Client::new())
+                .await
+                .unwrap();
+            assert_eq!(ip, Ipv4Addr::new(1, 2, 3, 4));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failover_follows_priority() {
+        let server = MockServer::start().await;
+        mount_raw(&server, "/unordered", "3.3.3.3").await;
+        mount_raw(&server, "/broken", "not an address").await;
+        mount_raw(&server, "/second", "2.2.2.2").await;
+
+        let resolvers = vec![
+            raw_resolver(&server, "/unordered", None),
+            raw_resolver(&server, "/second", Some(2)),
+            raw_resolver(&server, "/broken", Some(1)),
+        ];
+        let order: Vec<Option<u32>> = failover_order(&resolvers)
+            .iter()
+            .map(|resolver| resolver.priority)
+            .collect();
+        assert_eq!(order, vec![Some(1), Some(2), None]);
+
+        let config = Ipv4ResolverConfig {
+            ipv4_resolvers: &resolvers,
+            quorum: None,
+        };
+        let ip = resolve_ipv4(&config, &reqwest::Client::new())
+            .await
+            .unwrap();
+        assert_eq!(ip, Ipv4Addr::new(2, 2, 2, 2));
+    }
+
+    #[tokio::test]
+    async fn test_weighted_quorum() {
+        let server = MockServer::start().await;
+        mount_raw(&server, "/a", "1.1.1.1").await;
+        mount_raw(&server, "/b", "1.1.1.1").await;
+        mount_raw(&server, "/heavy", "9.9.9.9").await;
+
+        let mut heavy = raw_resolver(&server, "/heavy", Some(1));
+        heavy.weight = 3;
+        let resolvers = vec![
+            raw_resolver(&server, "/a", None),
+            raw_resolver(&server, "/b", None),
+            heavy,
+        ];
+
+        let config = Ipv4ResolverConfig {
+            ipv4_resolvers: &resolvers,
+            quorum: Some(3),
+        };
+        let ip = resolve_ipv4(&config, &reqwest::Client::new())
+            .await
+            .unwrap();
+        assert_eq!(ip, Ipv4Addr::new(9, 9, 9, 9));
+
+        let config = Ipv4ResolverConfig {
+            ipv4_resolvers: &resolvers,
+            quorum: Some(4),
+        };
+        let result = resolve_ipv4(&config, &reqwest::Client::new()).await;
+        assert!(matches!(result, Err(IpResolverError::NoQuorum(4))));
+    }
+
+    #[test]
+    fn test_weighted_consensus_tie_prefers_first_vote() {
+        let votes = [("1.1.1.1", 2), ("2.2.2.2", 1), ("2.2.2.2", 1)];
+        assert_eq!(weighted_consensus(&votes, 2), Some("1.1.1.1"));
+        assert_eq!(weighted_consensus::<&str>(&[], 1), None);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_ipv4_over_doh() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/resolve"))
+            .and(query_param("name", "myip.example.com"))
+            .and(query_param("type", "1"))
+            .and(header("accept", "application/dns-json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"Status": 0, "Answer": [
+                    {"name": "myip.example.com.", "type": 5, "TTL": 60, "data": "alias.example.com."},
+                    {"name": "alias.example.com.", "type": 1, "TTL": 60, "data": "1.2.3.4"}
+                ]}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let resolver = doh_resolver(&server, RecordType::A);
+        let config = Ipv4ResolverConfig {
+            ipv4_resolvers: std::slice::from_ref(&resolver),
+            quorum: None,
+        };
+        let ip = resolve_ipv4(&config, &reqwest::Client::new())
+            .await
+            .unwrap();
+        assert_eq!(ip, Ipv4Addr::new(1, 2, 3, 4));
+    }
+
+    #[test]
+    fn test_bracketed_address_is_parsed() {
+        let ip: Ipv6Addr = parse_address(" [2001:db8::1]\n").unwrap();
+        assert_eq!(ip, "2001:db8::1".parse::<Ipv6Addr>().unwrap());
+
+        let ip: IpAddr = parse_address("[2001:db8::1]").unwrap();
+        assert_eq!(ip, "2001:db8::1".parse::<IpAddr>().unwrap());
+
+        let result: Result<Ipv6Addr, _> = parse_address("[2001:db8::1");
+        assert!(matches!(result, Err(IpResolverError::InvalidIpFormat(_))));
+    }
+
+    #[test]
+    fn test_scoped_address_is_rejected() {
+        for response in ["fe80::1%eth0", "[fe80::1%25eth0]"] {
+            let result: Result<Ipv6Addr, _> = parse_address(response);
+            let error = result.unwrap_err();
+            assert!(matches!(error, IpResolverError::ScopedAddress(_)));
+            assert!(error.to_string().contains("zone index"), "{}", error);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_ipv6_over_doh_txt() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/resolve"))
+            .and(query_param("type", "16"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"Status": 0, "Answer": [{"name": "myip.example.com.", "type": 16, "TTL": 60, "data": "\"2001:db8::1\""}]}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let resolver = doh_resolver(&server, RecordType::TXT);
+        let config = Ipv6ResolverConfig {
+            ipv6_resolvers: std::slice::from_ref(&resolver),
+            quorum: None,
+        };
+        let ip = resolve_ipv6(&config, &reqwest::Client::new())
+            .await
+            .unwrap();
+        assert_eq!(ip, "2001:db8::1".parse::<Ipv6Addr>().unwrap());
+    }
+
+    #[test]
+    fn test_parse_doh_json_response_errors() {
+        let nxdomain = r#"{"Status": 3}"#;
+        let result = parse_doh_json_response(nxdomain, RecordType::A);
+        assert!(matches!(result, Err(DohParseError::Status(3))));
+
+        let no_answer = r#"{"Status": 0, "Answer": []}"#;
+        let result = parse_doh_json_response(no_answer, RecordType::AAAA);
+        assert!(matches!(
+            result,
+            Err(DohParseError::NoAnswer(RecordType::AAAA))
+        ));
+
+        let result = parse_doh_json_response(no_answer, RecordType::MX);
+        assert!(matches!(
+            result,
+            Err(DohParseError::UnsupportedRecordType(RecordType::MX))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_to_record_uses_address_override() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("9.9.9.9"))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let mut config = Config::default();
+        config.resolver.ipv4[0].url = server.uri();
+        config.resolver.ipv4[0].type_ = IpResolverType::Raw;
+        let address_override = AddressOverride {
+            ipv4: Some(Ipv4Addr::new(1, 2, 3, 4)),
+            ipv6: None,
+        };
+        let automatic = AutomaticRecordConfig {
+            domain: "home.example.com".to_string(),
+            ttl: Some(60),
+            resolve_type: ResolveType::IPv4,
+            ipv6_suffix: None,
+            providers: Vec::new(),
+        };
+
+        let record = resolve_to_record(
+            &config,
+            &reqwest::Client::new(),
+            &address_override,
+            &automatic,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(record.domain, "home.example.com");
+        assert!(matches!(record.value, RecordValue::A(ip) if ip == Ipv4Addr::new(1, 2, 3, 4)));
+        assert_eq!(record.ttl, Some(60));
+    }
 }
 
 #[derive(Debug, Error)]
@@ -179,10 +655,178 @@ pub enum IpResolverError {
     #[error("Error while parsing JSON response: {0}")]
     JsonParse(#[from] JsonParseError),
 
+    #[error("Error while parsing response: {0}")]
+    RegexParse(#[from] RegexParseError),
+
     #[error("Invalid IP address format: {0}")]
     InvalidIpFormat(#[from] AddrParseError),
+
+    #[error("Address '{0}' has a zone index, scoped addresses can not be published")]
+    ScopedAddress(String),
+
+    #[error("Error while parsing DNS-over-HTTPS response: {0}")]
+    Doh(#[from] DohParseError),
+
+    #[error("Error while querying the gateway over UPnP: {0}")]
+    Upnp(#[from] upnp::UpnpError),
+
+    #[error("No resolver is configured")]
+    NoResolvers,
+
+    #[error("Resolvers did not agree on an address with a weight of at least {0}")]
+    NoQuorum(u32),
+
+    #[error("Invalid record: {0}")]
+    InvalidRecord(#[from] RecordValidationError),
+}
+
+#[derive(Debug, Error)]
+pub enum DohParseError {
+    #[error("Could not parse JSON response: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+
+    #[error("Record type {0:?} can not be queried over DNS-over-HTTPS")]
+    UnsupportedRecordType(RecordType),
+
+    #[error("DNS query failed with response code {0}")]
+    Status(u16),
+
+    #[error("Response contains no {0:?} record")]
+    NoAnswer(RecordType),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+struct DohResponse {
+    #[serde(rename = "Status")]
+    status: u16,
+
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohAnswer>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+struct DohAnswer {
+    #[serde(rename = "type")]
+    type_: u16,
+    data: String,
+}
+
+/// Returns the numeric DNS type of the record types that can hold an IP address.
+fn doh_type_code(record_type: RecordType) -> Option<u16> {
+    match record_type {
+        RecordType::A => Some(1),
+        RecordType::TXT => Some(16),
+        RecordType::AAAA => Some(28),
+        _ => None,
+    }
+}
+
+/// Parses a DNS-over-HTTPS JSON API response and returns the data of the first answer of the
+/// specified type. Quotes around `TXT` data are removed.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::{resolver::parse_doh_json_response, types::dns::RecordType};
+///
+/// let json = r#"{"Status": 0, "Answer": [{"name": "o-o.myaddr.l.google.com.", "type": 16, "TTL": 60, "data": "\"1.2.3.4\""}]}"#;
+/// let result = parse_doh_json_response(json, RecordType::TXT).unwrap();
+/// assert_eq!(result, "1.2.3.4");
+/// ```
+///
+/// # Errors
+///
+/// Returns a [`DohParseError`] if:
+/// - The JSON is invalid.
+/// - The record type can not hold an IP address.
+/// - The response code is not `NOERROR`.
+/// - The response has no answer of the specified type.
+pub fn parse_doh_json_response(
+    response: &str,
+    record_type: RecordType,
+) -> Result<String, DohParseError> {
+    let type_code =
+        doh_type_code(record_type).ok_or(DohParseError::UnsupportedRecordType(record_type))?;
+
+    let response: DohResponse = serde_json::from_str(response)?;
+    if response.status != 0 {
+        return Err(DohParseError::Status(response.status));
+    }
+
+    let answer = response
+        .answer
+        .iter()
+        .find(|answer| answer.type_ == type_code)
+        .ok_or(DohParseError::NoAnswer(record_type))?;
+
+    Ok(answer.data.trim_matches('"').to_string())
+}
+
+/// Adds the credentials of a resolver to a request.
+fn with_auth(request: RequestBuilder, auth: Option<&ResolverAuth>) -> RequestBuilder {
+    match auth {
+        None => request,
+        Some(ResolverAuth::Basic { username, password }) => {
+            request.basic_auth(username, Some(password.as_str()))
+        }
+        Some(ResolverAuth::Bearer { token }) => request.bearer_auth(token.as_str()),
+        Some(ResolverAuth::Header { name, value }) => request.header(name, value.as_str()),
+    }
+}
+
+/// Sends a DNS-over-HTTPS JSON API query for the name and type to the resolver URL.
+async fn query_doh(
+    url: &str,
+    query_name: &str,
+    record_type: RecordType,
+    auth: Option<&ResolverAuth>,
+    reqwest: &reqwest::Client,
+) -> Result<String, IpResolverError> {
+    let type_code =
+        doh_type_code(record_type).ok_or(DohParseError::UnsupportedRecordType(record_type))?;
+
+    let request = reqwest
+        .get(url)
+        .query(&[("name", query_name), ("type", &type_code.to_string())])
+        .header(ACCEPT, "application/dns-json");
+    let response = with_auth(request, auth).send().await?;
+    let body = response.text().await?;
+
+    Ok(parse_doh_json_response(&body, record_type)?)
+}
+
+/// Returns the address in a resolver response without surrounding whitespace and brackets, as in
+/// `[2001:db8::1]`.
+///
+/// Fails with [`IpResolverError::ScopedAddress`] for addresses with a zone index like
+/// `fe80::1%eth0`, which are only valid on one link of the host.
+fn address_text(response: &str) -> Result<&str, IpResolverError> {
+    let text = response.trim();
+    let text = text
+        .strip_prefix('[')
+        .and_then(|text| text.strip_suffix(']'))
+        .unwrap_or(text);
+
+    if text.contains('%') {
+        return Err(IpResolverError::ScopedAddress(text.to_string()));
+    }
+
+    Ok(text)
+}
+
+/// Parses the address in a resolver response, see [`address_text`].
+fn parse_address<T>(response: &str) -> Result<T, IpResolverError>
+where
+    T: FromStr,
+    IpResolverError: From<<T as FromStr>::Err>,
+{
+    Ok(T::from_str(address_text(response)?)?)
 }
-async fn resolve_ip_internal<T>(
+
+/// Queries a single resolver, without failover or quorum.
+pub async fn resolve_ip_internal<T>(
     resolver: &IpResolver,
     reqwest: &reqwest::Client,
 ) -> Result<T, IpResolverError>
@@ -192,58 +836,164 @@ where
 {
     debug!("Resolving address using resolver: {:?}", resolver);
 
-    let response = reqwest.get(&resolver.url).send().await?;
+    if let IpResolverType::Doh {
+        query_name,
+        record_type,
+    } = &resolver.type_
+    {
+        let ip = query_doh(
+            &resolver.url,
+            query_name,
+            *record_type,
+            resolver.auth.as_ref(),
+            reqwest,
+        )
+        .await?;
+        return parse_address(&ip);
+    }
+
+    if let IpResolverType::Upnp = resolver.type_ {
+        let ip = upnp::resolve_external_ip(reqwest).await?;
+        return parse_address(&ip);
+    }
+
+    let request = with_auth(reqwest.get(&resolver.url), resolver.auth.as_ref());
+    let response = request.send().await?;
     let body = response.text().await?.trim().to_string();
 
     let ip = match &resolver.type_ {
-        IpResolverType::Raw => body,
         IpResolverType::JSON(path) => parse_json_response(&body, path)?,
+        IpResolverType::Regex(pattern) => parse_regex_response(&body, pattern)?,
+        _ => body,
+    };
+
+    parse_address(&ip)
+}
+
+/// Returns the resolvers in failover order: by ascending priority, resolvers without a
+/// priority last. Resolvers of equal priority keep their configured order.
+pub fn failover_order(resolvers: &[IpResolver]) -> Vec<&IpResolver> {
+    let mut ordered: Vec<&IpResolver> = resolvers.iter().collect();
+    ordered.sort_by_key(|resolver| resolver.priority.unwrap_or(u32::MAX));
+    ordered
+}
+
+/// Returns the address with the highest total weight of votes if it reaches `quorum`. On a tie,
+/// the address voted for first wins.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::resolver::weighted_consensus;
+///
+/// let votes = [("1.1.1.1", 1), ("2.2.2.2", 3), ("1.1.1.1", 1)];
+/// assert_eq!(weighted_consensus(&votes, 3), Some("2.2.2.2"));
+/// assert_eq!(weighted_consensus(&votes, 4), None);
+/// ```
+pub fn weighted_consensus<T: PartialEq + Clone>(votes: &[(T, u32)], quorum: u32) -> Option<T> {
+    let mut tally: Vec<(T, u32)> = Vec::new();
+    for (address, weight) in votes {
+        match tally.iter_mut().find(|(other, _)| other == address) {
+            Some((_, total)) => *total += weight,
+            None => tally.push((address.clone(), *weight)),
+        }
+    }
+
+    let mut best: Option<(T, u32)> = None;
+    for (address, total) in tally {
+        if best
+            .as_ref()
+            .is_none_or(|(_, best_total)| total > *best_total)
+        {
+            best = Some((address, total));
+        }
+    }
+
+    best.filter(|(_, total)| *total >= quorum)
+        .map(|(address, _)| address)
+}
+
+async fn resolve_ip<T>(
+    resolvers: &[IpResolver],
+    quorum: Option<u32>,
+    reqwest: &reqwest::Client,
+) -> Result<T, IpResolverError>
+where
+    T: FromStr + PartialEq + Clone,
+    IpResolverError: From<<T as FromStr>::Err>,
+{
+    let ordered = failover_order(resolvers);
+
+    let Some(quorum) = quorum else {
+        let mut last_error = IpResolverError::NoResolvers;
+        for resolver in ordered {
+            match resolve_ip_internal(resolver, reqwest).await {
+                Ok(addr) => return Ok(addr),
+                Err(e) => {
+                    warn!("Resolver {} failed: {}", resolver.url, e);
+                    last_error = e;
+                }
+            }
+        }
+
+        return Err(last_error);
     };
 
-    let addr = T::from_str(&ip)?;
-    Ok(addr)
+    let results = future::join_all(
+        ordered
+            .iter()
+            .map(|resolver| resolve_ip_internal::<T>(resolver, reqwest)),
+    )
+    .await;
+
+    let mut votes = Vec::new();
+    for (resolver, result) in ordered.iter().zip(results) {
+        match result {
+            Ok(addr) => votes.push((addr, resolver.weight)),
+            Err(e) => warn!("Resolver {} failed: {}", resolver.url, e),
+        }
+    }
+
+    weighted_consensus(&votes, quorum).ok_or(IpResolverError::NoQuorum(quorum))
 }
 
 pub async fn resolve_ipv4<'resolver>(
     config: &Ipv4ResolverConfig<'resolver>,
     reqwest: &reqwest::Client,
 ) -> Result<Ipv4Addr, IpResolverError> {
-    resolve_ip_internal(config.ipv4_resolver, reqwest).await
+    resolve_ip(config.ipv4_resolvers, config.quorum, reqwest).await
 }
 
 pub async fn resolve_ipv6<'resolver>(
     config: &Ipv6ResolverConfig<'resolver>,
     reqwest: &reqwest::Client,
 ) -> Result<Ipv6Addr, IpResolverError> {
-    resolve_ip_internal(config.ipv6_resolver, reqwest).await
+    resolve_ip(config.ipv6_resolvers, config.quorum, reqwest).await
 }
 
+/// Resolves the address of an automatic record, unless it is given in `address_override`.
 pub async fn resolve_to_record(
     config: &Config,
     reqwest: &reqwest::Client,
+    address_override: &AddressOverride,
     automatic_record_config: &AutomaticRecordConfig,
 ) -> Result<Record, IpResolverError> {
-    let domain = automatic_record_config.domain.clone();
-    let ttl = automatic_record_config.ttl;
-
-    match automatic_record_config.resolve_type {
-        ResolveType::IPv4 => {
-            let ipv4_resolver_config = Ipv4ResolverConfig::from(config);
-            let ipv4 = resolve_ipv4(&ipv4_resolver_config, reqwest).await?;
-            Ok(Record {
-                domain,
-                value: RecordValue::A(ipv4),
-                ttl,
-            })
-        }
-        ResolveType::IPv6 => {
-            let ipv6_resolver_config = Ipv6ResolverConfig::from(config);
-            let ipv6 = resolve_ipv6(&ipv6_resolver_config, reqwest).await?;
-            Ok(Record {
-                domain,
-                value: RecordValue::AAAA(ipv6),
-                ttl,
-            })
-        }
-    }
+    let value = match automatic_record_config.resolve_type {
+        ResolveType::IPv4 => match address_override.ipv4 {
+            Some(ipv4) => RecordValue::A(ipv4),
+            None => RecordValue::A(resolve_ipv4(&Ipv4ResolverConfig::from(config), reqwest).await?),
+        },
+        ResolveType::IPv6 => match address_override.ipv6 {
+            Some(ipv6) => RecordValue::AAAA(ipv6),
+            None => {
+                RecordValue::AAAA(resolve_ipv6(&Ipv6ResolverConfig::from(config), reqwest).await?)
+            }
+        },
+    };
+
+    let record = Record::builder(automatic_record_config.domain.clone(), value)
+        .ttl(automatic_record_config.ttl)
+        .build()?;
+
+    Ok(record)
 }
@@ -0,0 +1,330 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+use lum_log::debug;
+use quick_xml::{Reader, events::Event};
+use reqwest::header::{CONTENT_TYPE, HeaderValue};
+use thiserror::Error;
+use tokio::{net::UdpSocket, time};
+use url::Url;
+
+/// Multicast address SSDP discovery requests are sent to.
+pub const SSDP_ADDR: SocketAddr = SocketAddr::new(
+    std::net::IpAddr::V4(Ipv4Addr::new(239, 255, 255, 250)),
+    1900,
+);
+
+/// How long to wait for an Internet Gateway Device to answer a discovery request.
+pub const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+const IGD_SEARCH_TARGET: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+
+/// Services of an Internet Gateway Device that report the external address.
+const WAN_SERVICE_TYPES: [&str; 2] = [
+    "urn:schemas-upnp-org:service:WANIPConnection:",
+    "urn:schemas-upnp-org:service:WANPPPConnection:",
+];
+
+#[derive(Debug, Error)]
+pub enum UpnpError {
+    #[error("SSDP discovery failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("No Internet Gateway Device answered within {0:?}")]
+    NoGateway(Duration),
+
+    #[error("Gateway sent an invalid location: {0}")]
+    InvalidLocation(#[from] url::ParseError),
+
+    #[error("Error while sending HTTP request: {0}")]
+    Reqwest(#[from] reqwest::Error),
+
+    #[error("Gateway answered with HTTP status {0}")]
+    Unsuccessful(u16),
+
+    #[error("Could not parse gateway XML: {0}")]
+    Xml(#[from] quick_xml::Error),
+
+    #[error("Gateway offers no WANIPConnection or WANPPPConnection service")]
+    NoWanService,
+
+    #[error("Gateway response contains no NewExternalIPAddress")]
+    NoExternalAddress,
+}
+
+/// A WAN connection service of a gateway, with the absolute URL to send SOAP actions to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WanService {
+    pub service_type: String,
+    pub control_url: Url,
+}
+
+/// Returns the SSDP `M-SEARCH` request looking for Internet Gateway Devices.
+pub fn search_request() -> String {
+    format!(
+        "M-SEARCH * HTTP/1.1\r\n\
+         HOST: {SSDP_ADDR}\r\n\
+         MAN: \"ssdp:discover\"\r\n\
+         MX: 2\r\n\
+         ST: {IGD_SEARCH_TARGET}\r\n\r\n"
+    )
+}
+
+/// Returns the `LOCATION` header of an SSDP response, the URL of the device description.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::resolver::upnp::parse_search_response;
+///
+/// let response = "HTTP/1.1 200 OK\r\nLocation: http://192.168.1.1:5000/rootDesc.xml\r\n\r\n";
+/// assert_eq!(parse_search_response(response), Some("http://192.168.1.1:5000/rootDesc.xml"));
+/// ```
+pub fn parse_search_response(response: &str) -> Option<&str> {
+    response.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("location")
+            .then(|| value.trim())
+    })
+}
+
+/// Sends an SSDP search and returns the device description URL of the first gateway to answer.
+pub async fn discover(timeout: Duration) -> Result<Url, UpnpError> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket
+        .send_to(search_request().as_bytes(), SSDP_ADDR)
+        .await?;
+
+    let mut buffer = [0u8; 2048];
+    let receive = async {
+        loop {
+            let (len, from) = socket.recv_from(&mut buffer).await?;
+            let response = String::from_utf8_lossy(&buffer[..len]);
+            match parse_search_response(&response) {
+                Some(location) => {
+                    debug!("Found Internet Gateway Device at {} ({})", location, from);
+                    return Ok::<_, UpnpError>(Url::parse(location)?);
+                }
+                None => debug!("Ignoring SSDP response without location from {}", from),
+            }
+        }
+    };
+
+    time::timeout(timeout, receive)
+        .await
+        .map_err(|_| UpnpError::NoGateway(timeout))?
+}
+
+/// Returns the text of every element named `name`, in document order.
+fn element_texts(xml: &str, name: &[u8]) -> Result<Vec<String>, UpnpError> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut texts = Vec::new();
+    let mut inside = false;
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) if start.local_name().as_ref() == name => inside = true,
+            Event::End(end) if end.local_name().as_ref() == name => inside = false,
+            Event::Text(text) if inside => texts.push(text.unescape()?.into_owned()),
+            Event::Eof => return Ok(texts),
+            _ => {}
+        }
+    }
+}
+
+/// Finds the WAN connection service in a device description.
+///
+/// Relative control URLs are resolved against `location`, the URL the description was
+/// fetched from.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::resolver::upnp::parse_device_description;
+/// use url::Url;
+///
+/// let xml = r#"<root><device><serviceList><service>
+///     <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+///     <controlURL>/ctl/IPConn</controlURL>
+/// </service></serviceList></device></root>"#;
+///
+/// let location = Url::parse("http://192.168.1.1:5000/rootDesc.xml").unwrap();
+/// let service = parse_device_description(xml, &location).unwrap();
+/// assert_eq!(service.control_url.as_str(), "http://192.168.1.1:5000/ctl/IPConn");
+/// ```
+pub fn parse_device_description(xml: &str, location: &Url) -> Result<WanService, UpnpError> {
+    let service_types = element_texts(xml, b"serviceType")?;
+    let control_urls = element_texts(xml, b"controlURL")?;
+
+    let (service_type, control_url) = service_types
+        .into_iter()
+        .zip(control_urls)
+        .find(|(service_type, _)| {
+            WAN_SERVICE_TYPES
+                .iter()
+                .any(|wan_type| service_type.starts_with(wan_type))
+        })
+        .ok_or(UpnpError::NoWanService)?;
+
+    Ok(WanService {
+        service_type,
+        control_url: location.join(&control_url)?,
+    })
+}
+
+/// Returns the SOAP envelope of the `GetExternalIPAddress` action of `service_type`.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::resolver::upnp::external_ip_request;
+///
+/// let body = external_ip_request("urn:schemas-upnp-org:service:WANIPConnection:1");
+/// assert!(body.contains(
+///     r#"<u:GetExternalIPAddress xmlns:u="urn:schemas-upnp-org:service:WANIPConnection:1"/>"#
+/// ));
+/// ```
+pub fn external_ip_request(service_type: &str) -> String {
+    format!(
+        r#"<?xml version="1.0"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/"><s:Body><u:GetExternalIPAddress xmlns:u="{service_type}"/></s:Body></s:Envelope>"#
+    )
+}
+
+/// Returns the `NewExternalIPAddress` of a `GetExternalIPAddress` response.
+pub fn parse_external_ip_response(xml: &str) -> Result<String, UpnpError> {
+    element_texts(xml, b"NewExternalIPAddress")?
+        .into_iter()
+        .next()
+        .ok_or(UpnpError::NoExternalAddress)
+}
+
+/// Reads the external address from the gateway described at `location`.
+pub async fn query_external_ip(
+    location: &Url,
+    reqwest: &reqwest::Client,
+) -> Result<String, UpnpError> {
+    let response = reqwest.get(location.clone()).send().await?;
+    if !response.status().is_success() {
+        return Err(UpnpError::Unsuccessful(response.status().as_u16()));
+    }
+    let service = parse_device_description(&response.text().await?, location)?;
+    debug!("Querying external address from {:?}", service);
+
+    let soap_action = format!("\"{}#GetExternalIPAddress\"", service.service_type);
+    let response = reqwest
+        .post(service.control_url)
+        .header(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/xml; charset=\"utf-8\""),
+        )
+        .header("SOAPAction", soap_action)
+        .body(external_ip_request(&service.service_type))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(UpnpError::Unsuccessful(response.status().as_u16()));
+    }
+
+    parse_external_ip_response(&response.text().await?)
+}
+
+/// Discovers the gateway of the local network and reads its external address.
+pub async fn resolve_external_ip(reqwest: &reqwest::Client) -> Result<String, UpnpError> {
+    let location = discover(DISCOVERY_TIMEOUT).await?;
+    query_external_ip(&location, reqwest).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_string_contains, header, method, path},
+    };
+
+    const DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:InternetGatewayDevice:1</deviceType>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>
+        <controlURL>/ctl/L3F</controlURL>
+      </service>
+    </serviceList>
+    <deviceList>
+      <device>
+        <serviceList>
+          <service>
+            <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+            <controlURL>/ctl/IPConn</controlURL>
+          </service>
+        </serviceList>
+      </device>
+    </deviceList>
+  </device>
+</root>"#;
+
+    #[test]
+    fn test_search_request() {
+        let request = search_request();
+        assert!(request.starts_with("M-SEARCH * HTTP/1.1\r\n"));
+        assert!(request.contains("HOST: 239.255.255.250:1900\r\n"));
+        assert!(request.contains("ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n"));
+        assert!(request.ends_with("\r\n\r\n"));
+    }
+
+    #[test]
+    fn test_parse_device_description_skips_other_services() {
+        let location = Url::parse("http://192.168.1.1:5000/rootDesc.xml").unwrap();
+        let service = parse_device_description(DESCRIPTION, &location).unwrap();
+        assert_eq!(
+            service.service_type,
+            "urn:schemas-upnp-org:service:WANIPConnection:1"
+        );
+        assert_eq!(
+            service.control_url.as_str(),
+            "http://192.168.1.1:5000/ctl/IPConn"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_external_ip_with_mock_gateway() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rootDesc.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(DESCRIPTION))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/ctl/IPConn"))
+            .and(header(
+                "SOAPAction",
+                "\"urn:schemas-upnp-org:service:WANIPConnection:1#GetExternalIPAddress\"",
+            ))
+            .and(body_string_contains("<u:GetExternalIPAddress"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<?xml version="1.0"?>
+<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">
+  <s:Body>
+    <u:GetExternalIPAddressResponse xmlns:u="urn:schemas-upnp-org:service:WANIPConnection:1">
+      <NewExternalIPAddress>203.0.113.7</NewExternalIPAddress>
+    </u:GetExternalIPAddressResponse>
+  </s:Body>
+</s:Envelope>"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let location = Url::parse(&format!("{}/rootDesc.xml", server.uri())).unwrap();
+        let ip = query_external_ip(&location, &reqwest::Client::new())
+            .await
+            .unwrap();
+        assert_eq!(ip, "203.0.113.7");
+    }
+}
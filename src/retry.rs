@@ -0,0 +1,217 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use lum_log::debug;
+use reqwest::{
+    RequestBuilder, Response, StatusCode,
+    header::{HeaderMap, RETRY_AFTER},
+};
+
+/// When and how often a throttled request is sent again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first one.
+    pub max_attempts: u32,
+
+    /// Wait before the first retry if the server does not send `Retry-After`, doubled for every
+    /// further retry.
+    pub backoff: Duration,
+
+    /// Longest `Retry-After` that is waited for. Responses asking for longer waits are returned
+    /// as they are.
+    pub max_wait: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_secs(1),
+            max_wait: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Parses the `Retry-After` header, given either as seconds or as an HTTP-date. Dates in the
+/// past mean no wait.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use chrono::{TimeZone, Utc};
+/// use dnrs::retry::retry_after;
+/// use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+///
+/// let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+/// assert_eq!(retry_after(&headers, now), Some(Duration::from_secs(120)));
+///
+/// headers.insert(RETRY_AFTER, HeaderValue::from_static("Wed, 21 Oct 2015 07:28:30 GMT"));
+/// assert_eq!(retry_after(&headers, now), Some(Duration::from_secs(30)));
+/// ```
+pub fn retry_after(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Returns whether `response` asks to send the request again later: 429 Too Many Requests, or
+/// 503 Service Unavailable with a `Retry-After`.
+fn is_throttled(response: &Response) -> bool {
+    match response.status() {
+        StatusCode::TOO_MANY_REQUESTS => true,
+        StatusCode::SERVICE_UNAVAILABLE => response.headers().contains_key(RETRY_AFTER),
+        _ => false,
+    }
+}
+
+/// Sends `request`, sending it again while the server throttles it, see [`RetryPolicy`].
+///
+/// The wait between attempts is the one the server asks for in `Retry-After`, or the backoff of
+/// `policy` if it does not. Requests with a streaming body can not be repeated and are sent
+/// once.
+pub async fn send(request: RequestBuilder, policy: &RetryPolicy) -> reqwest::Result<Response> {
+    let mut attempt = 1;
+    loop {
+        let retry = match request.try_clone() {
+            Some(retry) if attempt < policy.max_attempts => retry,
+            _ => return request.send().await,
+        };
+
+        let response = retry.send().await?;
+        if !is_throttled(&response) {
+            return Ok(response);
+        }
+
+        let wait = match retry_after(response.headers(), Utc::now()) {
+            Some(wait) if wait > policy.max_wait => return Ok(response),
+            Some(wait) => wait,
+            None => policy.backoff * 2u32.saturating_pow(attempt - 1),
+        };
+
+        debug!(
+            "{} answered {}, retrying in {:?}",
+            response.url(),
+            response.status(),
+            wait
+        );
+        tokio::time::sleep(wait).await;
+        attempt += 1;
+    }
+}
+
+/// Sends requests with the default [`RetryPolicy`].
+#[async_trait]
+pub trait SendWithRetry {
+    async fn send_with_retry(self) -> reqwest::Result<Response>;
+}
+
+#[async_trait]
+impl SendWithRetry for RequestBuilder {
+    async fn send_with_retry(self) -> reqwest::Result<Response> {
+        send(self, &RetryPolicy::default()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use reqwest::header::HeaderValue;
+    use std::time::Instant;
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers::method};
+
+    fn headers(retry_after: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static(retry_after));
+        headers
+    }
+
+    #[test]
+    fn test_retry_after_seconds() {
+        let now = Utc::now();
+        assert_eq!(
+            retry_after(&headers("5"), now),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(retry_after(&headers(" 0 "), now), Some(Duration::ZERO));
+        assert_eq!(retry_after(&headers("soon"), now), None);
+        assert_eq!(retry_after(&HeaderMap::new(), now), None);
+    }
+
+    #[test]
+    fn test_retry_after_http_date() {
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+        assert_eq!(
+            retry_after(&headers("Wed, 21 Oct 2015 07:29:15 GMT"), now),
+            Some(Duration::from_secs(75))
+        );
+        assert_eq!(
+            retry_after(&headers("Wed, 21 Oct 2015 07:00:00 GMT"), now),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_waits_for_retry_after() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let policy = RetryPolicy {
+            backoff: Duration::from_secs(10),
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let response = send(reqwest::Client::new().get(server.uri()), &policy)
+            .await
+            .unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(elapsed >= Duration::from_secs(1), "waited {:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(5), "waited {:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn test_send_gives_up_after_max_attempts() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            ..Default::default()
+        };
+        let response = send(reqwest::Client::new().get(server.uri()), &policy)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    net::{Ipv4Addr, Ipv6Addr},
+    path::Path,
+};
+
+use lum_libs::{
+    serde::{Deserialize, Serialize},
+    serde_json,
+};
+use lum_log::warn;
+use thiserror::Error;
+
+use crate::{
+    lock::{self, DEFAULT_LOCK_TIMEOUT, FileLock, LockError},
+    types::dns::Record,
+};
+
+/// Name of the state file inside the config directory.
+pub const STATE_FILE_NAME: &str = "state.json";
+
+#[derive(Debug, Error)]
+pub enum StateError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Lock(#[from] LockError),
+}
+
+/// The value and TTL last published for a record.
+///
+/// The value is kept in its canonical form, see [`RecordValue::canonical_content`], so a
+/// record that only differs in hostname case or TXT quoting counts as unchanged.
+///
+/// [`RecordValue::canonical_content`]: crate::types::dns::RecordValue::canonical_content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct PublishedRecord {
+    pub value: String,
+    pub ttl: Option<u32>,
+}
+
+impl From<&Record> for PublishedRecord {
+    fn from(record: &Record) -> Self {
+        PublishedRecord {
+            value: record.value.canonical_content(),
+            ttl: record.ttl,
+        }
+    }
+}
+
+/// Records successfully published by previous runs, persisted between runs.
+///
+/// Records are keyed by provider name, record name and record type, so the same name can be
+/// tracked for A and AAAA records and at several providers.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::state::State;
+/// use dnrs::types::dns::{Record, RecordValue};
+/// use std::net::Ipv4Addr;
+///
+/// let record = Record {
+///     domain: "home.example.com".to_string(),
+///     value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+///     ttl: None,
+/// };
+///
+/// let mut state = State::default();
+/// assert!(!state.is_unchanged("hetzner1", &record));
+///
+/// state.record_published("hetzner1", &record);
+/// assert!(state.is_unchanged("hetzner1", &record));
+/// assert!(!state.is_unchanged("netcup1", &record));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct State {
+    pub records: HashMap<String, PublishedRecord>,
+
+    /// Public addresses resolved by the last run, to notice when they change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipv4: Option<Ipv4Addr>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipv6: Option<Ipv6Addr>,
+}
+
+impl State {
+    fn key(provider_name: &str, record: &Record) -> String {
+        format!(
+            "{}/{}/{:?}",
+            provider_name,
+            record.domain,
+            record.value.record_type()
+        )
+    }
+
+    /// Reads the state file at `path`.
+    ///
+    /// A missing or unreadable file results in an empty state, so every record is treated as
+    /// changed.
+    pub fn load(path: &Path) -> State {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return State::default(),
+            Err(e) => {
+                warn!("Failed to read state file {}: {}", path.display(), e);
+                return State::default();
+            }
+        };
+
+        match serde_json::from_str(&content) {
+            Ok(state) => state,
+            Err(e) => {
+                warn!("Ignoring corrupt state file {}: {}", path.display(), e);
+                State::default()
+            }
+        }
+    }
+
+    /// Writes the state to `path`, replacing any existing file.
+    ///
+    /// The state is written to a temporary file first and renamed, so readers never see a
+    /// partially written file.
+    pub fn save(&self, path: &Path) -> Result<(), StateError> {
+        let content = serde_json::to_string_pretty(self)?;
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Adds the records of this state to the state file at `path`. The addresses of this state
+    /// replace the stored ones if they are set.
+    ///
+    /// The file is re-read and written while holding its lock file, see [`lock::lock_path`], so
+    /// concurrent dnrs instances keep each other's records instead of overwriting them.
+    pub fn merge_into_file(&self, path: &Path) -> Result<(), StateError> {
+        let _lock = FileLock::acquire(&lock::lock_path(path), DEFAULT_LOCK_TIMEOUT)?;
+
+        let mut state = State::load(path);
+        state.records.extend(self.records.clone());
+        state.ipv4 = self.ipv4.or(state.ipv4);
+        state.ipv6 = self.ipv6.or(state.ipv6);
+        state.save(path)
+    }
+
+    /// Returns whether `record` was already published with the same value and TTL.
+    pub fn is_unchanged(&self, provider_name: &str, record: &Record) -> bool {
+        self.records
+            .get(&Self::key(provider_name, record))
+            .is_some_and(|published| *published == PublishedRecord::from(record))
+    }
+
+    /// Returns the value and TTL last published for the name and type of `record` at the given
+    /// provider.
+    pub fn published(&self, provider_name: &str, record: &Record) -> Option<&PublishedRecord> {
+        self.records.get(&Self::key(provider_name, record))
+    }
+
+    /// Remembers that `record` was successfully published at the given provider.
+    pub fn record_published(&mut self, provider_name: &str, record: &Record) {
+        self.records.insert(
+            Self::key(provider_name, record),
+            PublishedRecord::from(record),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::dns::RecordValue;
+    use std::net::Ipv4Addr;
+
+    fn temp_state_path(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("dnrs_test_state_{}", name));
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(STATE_FILE_NAME)
+    }
+
+    fn record(ip: Ipv4Addr) -> Record {
+        Record {
+            domain: "home.example.com".to_string(),
+            value: RecordValue::A(ip),
+            ttl: Some(300),
+        }
+    }
+
+    #[test]
+    fn test_write_then_read() {
+        let path = temp_state_path("roundtrip");
+        let mut state = State::default();
+        state.record_published("hetzner1", &record(Ipv4Addr::new(1, 2, 3, 4)));
+        state.save(&path).unwrap();
+
+        let loaded = State::load(&path);
+        assert_eq!(loaded, state);
+        assert!(loaded.is_unchanged("hetzner1", &record(Ipv4Addr::new(1, 2, 3, 4))));
+        assert!(!loaded.is_unchanged("hetzner1", &record(Ipv4Addr::new(5, 6, 7, 8))));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_file_is_treated_as_empty() {
+        let path = temp_state_path("corrupt");
+        fs::write(&path, "{ not json").unwrap();
+
+        let state = State::load(&path);
+        assert!(state.records.is_empty());
+        assert!(!state.is_unchanged("hetzner1", &record(Ipv4Addr::new(1, 2, 3, 4))));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_merges_keep_all_records() {
+        let path = temp_state_path("concurrent");
+
+        let writers: Vec<_> = ["hetzner1", "netcup1"]
+            .into_iter()
+            .map(|provider_name| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for i in 0..20 {
+                        let record = Record {
+                            domain: format!("host{}.example.com", i),
+                            ..record(Ipv4Addr::new(10, 0, 0, i))
+                        };
+                        let mut state = State::default();
+                        state.record_published(provider_name, &record);
+                        state.merge_into_file(&path).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let content = fs::read_to_string(&path).unwrap();
+        let state: State = serde_json::from_str(&content).unwrap();
+        assert_eq!(state.records.len(), 40);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_missing_file_is_treated_as_empty() {
+        let path = temp_state_path("missing");
+        assert!(State::load(&path).records.is_empty());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use lum_libs::{serde::Serialize, serde_json};
+use lum_log::info;
+
+use crate::update::UpdateSummary;
+
+/// Converts a duration to fractional milliseconds for reporting.
+pub fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// How long updating a single domain at a provider took.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct UpdateTiming {
+    pub provider_name: String,
+    pub domain: String,
+    pub success: bool,
+    pub timed_out: bool,
+    pub elapsed_ms: f64,
+}
+
+/// Timings of the phases of an update run.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::timing::Timings;
+/// use dnrs::update::UpdateSummary;
+/// use std::time::Duration;
+///
+/// let timings = Timings::new(Duration::from_millis(120), &UpdateSummary::default());
+/// assert_eq!(timings.resolution_ms, 120.0);
+/// assert!(timings.to_json().unwrap().contains("\"resolution_ms\":120.0"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct Timings {
+    pub resolution_ms: f64,
+    pub updates: Vec<UpdateTiming>,
+}
+
+impl Timings {
+    pub fn new(resolution: Duration, summary: &UpdateSummary) -> Self {
+        let updates = summary
+            .results
+            .iter()
+            .map(|result| UpdateTiming {
+                provider_name: result.provider_name.clone(),
+                domain: result.domain.clone(),
+                success: result.is_success(),
+                timed_out: result.is_timed_out(),
+                elapsed_ms: millis(result.elapsed),
+            })
+            .collect();
+
+        Timings {
+            resolution_ms: millis(resolution),
+            updates,
+        }
+    }
+
+    /// Logs one `key=value` line per phase, for log-based monitoring.
+    pub fn log(&self) {
+        info!(
+            "timing phase=resolution elapsed_ms={:.1}",
+            self.resolution_ms
+        );
+        for update in self.updates.iter() {
+            info!(
+                "timing phase=update provider={} domain={} success={} timed_out={} elapsed_ms={:.1}",
+                update.provider_name,
+                update.domain,
+                update.success,
+                update.timed_out,
+                update.elapsed_ms
+            );
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
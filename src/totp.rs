@@ -0,0 +1,156 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use thiserror::Error;
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// Length of a time step in seconds, as used by authenticator apps.
+pub const TIME_STEP: u64 = 30;
+
+/// Number of digits of the codes returned by [`totp`].
+pub const DIGITS: u32 = 6;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(Debug, Error)]
+pub enum TotpError {
+    #[error("Invalid character {0:?} in base32 secret")]
+    InvalidBase32(char),
+
+    #[error("Secret is empty")]
+    EmptySecret,
+}
+
+/// Decodes an RFC 4648 base32 string. Case, spaces, dashes and `=` padding are ignored, as
+/// shared secrets are often shown grouped for readability.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::totp::decode_base32;
+///
+/// assert_eq!(decode_base32("MZXW6===").unwrap(), b"foo");
+/// assert_eq!(decode_base32("mzxw 6").unwrap(), b"foo");
+/// ```
+pub fn decode_base32(encoded: &str) -> Result<Vec<u8>, TotpError> {
+    let mut bytes = Vec::with_capacity(encoded.len() * 5 / 8);
+    let mut buffer: u64 = 0;
+    let mut bits = 0;
+
+    for character in encoded.chars() {
+        if character.is_whitespace() || character == '-' || character == '=' {
+            continue;
+        }
+
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&symbol| symbol as char == character.to_ascii_uppercase())
+            .ok_or(TotpError::InvalidBase32(character))?;
+
+        buffer = (buffer << 5) | value as u64;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Computes the RFC 4226 HOTP code with `digits` digits for `counter`, using HMAC-SHA1.
+pub fn hotp(key: &[u8], counter: u64, digits: u32) -> u32 {
+    let mut mac = HmacSha1::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let code = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    code % 10u32.pow(digits)
+}
+
+/// Computes the RFC 6238 TOTP code with `digits` digits at `unix_time`.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::totp::totp_at;
+///
+/// assert_eq!(totp_at(b"12345678901234567890", 59, 8), 94287082);
+/// ```
+pub fn totp_at(key: &[u8], unix_time: u64, digits: u32) -> u32 {
+    hotp(key, unix_time / TIME_STEP, digits)
+}
+
+/// Returns the current 6-digit TOTP code for a base32 shared secret, zero-padded.
+pub fn totp(secret: &str) -> Result<String, TotpError> {
+    let key = decode_base32(secret)?;
+    if key.is_empty() {
+        return Err(TotpError::EmptySecret);
+    }
+
+    let unix_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is after the Unix epoch")
+        .as_secs();
+    let code = totp_at(&key, unix_time, DIGITS);
+
+    Ok(format!("{:0width$}", code, width = DIGITS as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The SHA1 secret of RFC 6238, appendix B.
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn test_rfc6238_vectors() {
+        let vectors = [
+            (59, 94287082),
+            (1111111109, 7081804),
+            (1111111111, 14050471),
+            (1234567890, 89005924),
+            (2000000000, 69279037),
+            (20000000000, 65353130),
+        ];
+
+        for (unix_time, expected) in vectors {
+            assert_eq!(
+                totp_at(RFC_SECRET, unix_time, 8),
+                expected,
+                "at {}",
+                unix_time
+            );
+        }
+    }
+
+    #[test]
+    fn test_six_digit_code_from_base32_secret() {
+        let key = decode_base32("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap();
+        assert_eq!(key, RFC_SECRET);
+        assert_eq!(totp_at(&key, 59, DIGITS), 287082);
+
+        let code = totp("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap();
+        assert_eq!(code.len(), 6);
+        assert!(code.chars().all(|c| c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn test_invalid_secret() {
+        assert!(matches!(
+            decode_base32("MZXW1"),
+            Err(TotpError::InvalidBase32('1'))
+        ));
+        assert!(matches!(totp(""), Err(TotpError::EmptySecret)));
+    }
+}
@@ -1,6 +1,13 @@
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    net::{Ipv4Addr, Ipv6Addr},
+    num,
+    str::FromStr,
+};
 
 use lum_libs::serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
@@ -9,8 +16,176 @@ pub struct MxRecord {
     pub target: String,
 }
 
+/// The RDATA of an SVCB or HTTPS record (RFC 9460).
+///
+/// A priority of 0 is AliasMode and carries no parameters, any other priority is
+/// ServiceMode. A target of `.` refers to the owner name itself.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::types::dns::SvcbRecord;
+///
+/// let svcb: SvcbRecord = "1 . alpn=h2,h3".parse().unwrap();
+/// assert_eq!(svcb.priority, 1);
+/// assert_eq!(svcb.target, ".");
+/// assert_eq!(svcb.params["alpn"], "h2,h3");
+/// assert_eq!(svcb.to_string(), "1 . alpn=h2,h3");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "lum_libs::serde")]
+pub struct SvcbRecord {
+    pub priority: u16,
+    pub target: String,
+    /// SvcParams by key, keys without a value (like `no-default-alpn`) map to "".
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SvcbParseError {
+    #[error("SVCB record needs a priority and a target: {0}")]
+    MissingField(String),
+
+    #[error("Invalid SVCB priority: {0}")]
+    InvalidPriority(num::ParseIntError),
+
+    #[error("Invalid SVCB parameter key '{0}'")]
+    InvalidKey(String),
+
+    #[error("Duplicate SVCB parameter '{0}'")]
+    DuplicateKey(String),
+
+    #[error("Unterminated quoted string in SVCB record: {0}")]
+    UnterminatedQuote(String),
+}
+
+/// Splits SVCB presentation format into whitespace separated fields. Quoted parts (as in
+/// `alpn="h2,h3"`) are unquoted and may contain whitespace.
+fn svcb_fields(value: &str) -> Result<Vec<String>, SvcbParseError> {
+    let mut fields = Vec::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut field = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            chars.next();
+            if c != '"' {
+                field.push(c);
+                continue;
+            }
+
+            loop {
+                match chars.next() {
+                    Some('\\') => match chars.next() {
+                        Some(escaped) => field.push(escaped),
+                        None => return Err(SvcbParseError::UnterminatedQuote(value.to_string())),
+                    },
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => return Err(SvcbParseError::UnterminatedQuote(value.to_string())),
+                }
+            }
+        }
+        fields.push(field);
+    }
+
+    Ok(fields)
+}
+
+fn is_valid_svc_param_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+impl FromStr for SvcbRecord {
+    type Err = SvcbParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let fields = svcb_fields(value)?;
+        let [priority, target, params @ ..] = fields.as_slice() else {
+            return Err(SvcbParseError::MissingField(value.to_string()));
+        };
+
+        let priority = priority
+            .parse::<u16>()
+            .map_err(SvcbParseError::InvalidPriority)?;
+
+        let mut parsed = BTreeMap::new();
+        for param in params {
+            let (key, param_value) = param.split_once('=').unwrap_or((param, ""));
+            let key = key.to_ascii_lowercase();
+            if !is_valid_svc_param_key(&key) {
+                return Err(SvcbParseError::InvalidKey(key));
+            }
+            if parsed
+                .insert(key.clone(), param_value.to_string())
+                .is_some()
+            {
+                return Err(SvcbParseError::DuplicateKey(key));
+            }
+        }
+
+        Ok(SvcbRecord {
+            priority,
+            target: target.clone(),
+            params: parsed,
+        })
+    }
+}
+
+impl SvcbRecord {
+    /// Formats the parameters as `key=value` pairs separated by spaces, quoting values that
+    /// contain whitespace.
+    pub fn params_content(&self) -> String {
+        self.params
+            .iter()
+            .map(|(key, value)| {
+                if value.is_empty() {
+                    key.clone()
+                } else if value.contains(char::is_whitespace) || value.contains('"') {
+                    format!("{}=\"{}\"", key, value.replace('"', "\\\""))
+                } else {
+                    format!("{}={}", key, value)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn format_with_target(&self, target: &str) -> String {
+        let params = self.params_content();
+        if params.is_empty() {
+            format!("{} {}", self.priority, target)
+        } else {
+            format!("{} {} {}", self.priority, target, params)
+        }
+    }
+}
+
+/// Formats the record as its content, with the target as stored.
+impl fmt::Display for SvcbRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format_with_target(&self.target))
+    }
+}
+
 /// Represents the value of a DNS record.
 ///
+/// A record holds a single value. Several values of one name and type, like two TXT records on
+/// the apex, are several records; providers with RRset APIs group them when writing. A TXT value
+/// made of several character strings is written as quoted strings, `"part one" "part two"`.
+///
 /// # Examples
 ///
 /// ```
@@ -37,6 +212,502 @@ pub enum RecordValue {
     SRV(u16, u16, u16, String),
     TLSA(u16, u16, u16, String),
     CAA(u8, String, String),
+    SVCB(SvcbRecord),
+    HTTPS(SvcbRecord),
+    /// Key tag, algorithm, digest type and hex digest of a delegated zone's key.
+    DS(u16, u8, u8, String),
+    /// Flags, protocol, algorithm and base64 public key.
+    DNSKEY(u16, u8, u8, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DnssecParseError {
+    #[error("{0:?} record needs 4 fields: {1}")]
+    InvalidFormat(RecordType, String),
+
+    #[error("Invalid {0:?} record number: {1}")]
+    InvalidNumber(RecordType, num::ParseIntError),
+}
+
+/// Parses the three numeric fields and the data of a DS or DNSKEY record. The data may be
+/// split by whitespace, as zone files often do for long keys.
+fn parse_dnssec_fields(
+    record_type: RecordType,
+    content: &str,
+) -> Result<(u16, u8, u8, String), DnssecParseError> {
+    let parts: Vec<&str> = content.split_whitespace().collect();
+    if parts.len() < 4 {
+        return Err(DnssecParseError::InvalidFormat(
+            record_type,
+            content.to_string(),
+        ));
+    }
+
+    let invalid_number = |error| DnssecParseError::InvalidNumber(record_type, error);
+    Ok((
+        parts[0].parse().map_err(invalid_number)?,
+        parts[1].parse().map_err(invalid_number)?,
+        parts[2].parse().map_err(invalid_number)?,
+        parts[3..].concat(),
+    ))
+}
+
+/// Formats the value as zone file RDATA: names are absolute and text is quoted.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::types::dns::{MxRecord, RecordValue};
+///
+/// let value = RecordValue::MX(MxRecord { priority: 10, target: "mail.example.com".to_string() });
+/// assert_eq!(value.to_string(), "10 mail.example.com.");
+///
+/// let value = RecordValue::TXT("v=spf1 -all".to_string());
+/// assert_eq!(value.to_string(), "\"v=spf1 -all\"");
+/// ```
+impl fmt::Display for RecordValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordValue::CNAME(target) | RecordValue::NS(target) => {
+                write!(f, "{}", absolute_name(target))
+            }
+            RecordValue::TXT(text) | RecordValue::SPF(text) => write!(f, "{}", quote_text(text)),
+            RecordValue::MX(mx) => write!(f, "{} {}", mx.priority, absolute_name(&mx.target)),
+            RecordValue::SRV(priority, weight, port, target) => write!(
+                f,
+                "{} {} {} {}",
+                priority,
+                weight,
+                port,
+                absolute_name(target)
+            ),
+            RecordValue::CAA(flag, tag, value) => {
+                write!(f, "{} {} {}", flag, tag, quote_text(value))
+            }
+            RecordValue::SVCB(svcb) | RecordValue::HTTPS(svcb) => {
+                write!(
+                    f,
+                    "{}",
+                    svcb.format_with_target(&absolute_name(&svcb.target))
+                )
+            }
+            _ => write!(f, "{}", self.content()),
+        }
+    }
+}
+
+/// Returns `name` with a trailing dot, the root stays `.`.
+pub fn absolute_name(name: &str) -> String {
+    format!("{}.", name.trim_end_matches('.'))
+}
+
+/// Quotes text as a zone file character string. Text that already consists of quoted strings
+/// (`"part one" "part two"`) is returned unchanged.
+fn quote_text(text: &str) -> String {
+    if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+        return text.to_string();
+    }
+
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Returns `name` lowercased and without trailing dot, for comparing hostnames.
+fn canonical_name(name: &str) -> String {
+    match name {
+        "." => ".".to_string(),
+        name => name.trim_end_matches('.').to_ascii_lowercase(),
+    }
+}
+
+/// Returns the text of TXT content with quoting removed: quoted strings are unescaped and
+/// joined, unquoted text is trimmed.
+fn canonical_text(text: &str) -> String {
+    let trimmed = text.trim();
+    if !(trimmed.len() >= 2 && trimmed.starts_with('"') && trimmed.ends_with('"')) {
+        return trimmed.to_string();
+    }
+
+    txt_chunks(trimmed)
+        .into_iter()
+        .map(|chunk| chunk.replace("\\\"", "\"").replace("\\\\", "\\"))
+        .collect()
+}
+
+/// Maximum length of a single TXT character string.
+pub const MAX_TXT_CHUNK_LENGTH: usize = 255;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RecordValidationError {
+    #[error("Invalid hostname '{0}'")]
+    InvalidHostname(String),
+
+    #[error("TXT chunk is {0} characters long, the maximum is {MAX_TXT_CHUNK_LENGTH}")]
+    TxtTooLong(usize),
+
+    #[error("Invalid SRV port {0}")]
+    InvalidSrvPort(u16),
+
+    #[error("Invalid TLSA {field} {value}")]
+    InvalidTlsaParameter { field: &'static str, value: u16 },
+
+    #[error("TLSA certificate data is not valid hex: {0}")]
+    InvalidTlsaData(String),
+
+    #[error("Invalid CAA flag {0}")]
+    InvalidCaaFlag(u8),
+
+    #[error("Invalid CAA tag '{0}'")]
+    InvalidCaaTag(String),
+
+    #[error("SVCB/HTTPS records with priority 0 (AliasMode) must not have parameters")]
+    SvcbAliasWithParams,
+
+    #[error("DS digest does not match digest type {digest_type}: {digest}")]
+    InvalidDsDigest { digest_type: u8, digest: String },
+
+    #[error("Invalid DNSKEY protocol {0}, must be 3")]
+    InvalidDnskeyProtocol(u8),
+
+    #[error("DNSKEY public key is not valid base64: {0}")]
+    InvalidDnskeyKey(String),
+}
+
+/// Returns whether `name` looks like a hostname: dot-separated labels of at most 63 letters,
+/// digits, hyphens or underscores, not starting or ending with a hyphen. A trailing dot is
+/// allowed.
+pub fn is_valid_hostname(name: &str) -> bool {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    if name.is_empty() || name.len() > 253 {
+        return false;
+    }
+
+    name.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    })
+}
+
+/// Splits TXT content into its character strings.
+///
+/// Content written as quoted strings (`"part one" "part two"`) is split into the parts, any
+/// other content is a single string.
+pub(crate) fn txt_chunks(text: &str) -> Vec<&str> {
+    let trimmed = text.trim();
+    if !(trimmed.starts_with('"') && trimmed.ends_with('"') && trimmed.len() >= 2) {
+        return vec![text];
+    }
+
+    trimmed[1..trimmed.len() - 1].split("\" \"").collect()
+}
+
+fn validate_hostname(name: &str) -> Result<(), RecordValidationError> {
+    if is_valid_hostname(name) {
+        Ok(())
+    } else {
+        Err(RecordValidationError::InvalidHostname(name.to_string()))
+    }
+}
+
+impl RecordValue {
+    /// Parses DS content in the form `<key tag> <algorithm> <digest type> <digest>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::types::dns::RecordValue;
+    ///
+    /// let value = RecordValue::parse_ds("2371 13 2 1F987CC6583E92DF0890718C42").unwrap();
+    /// assert!(matches!(value, RecordValue::DS(2371, 13, 2, _)));
+    /// ```
+    pub fn parse_ds(content: &str) -> Result<RecordValue, DnssecParseError> {
+        let (key_tag, algorithm, digest_type, digest) =
+            parse_dnssec_fields(RecordType::DS, content)?;
+        Ok(RecordValue::DS(key_tag, algorithm, digest_type, digest))
+    }
+
+    /// Parses DNSKEY content in the form `<flags> <protocol> <algorithm> <public key>`.
+    pub fn parse_dnskey(content: &str) -> Result<RecordValue, DnssecParseError> {
+        let (flags, protocol, algorithm, public_key) =
+            parse_dnssec_fields(RecordType::DNSKEY, content)?;
+        Ok(RecordValue::DNSKEY(flags, protocol, algorithm, public_key))
+    }
+
+    /// Returns the [`RecordType`] of this value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::types::dns::{RecordType, RecordValue};
+    ///
+    /// let value = RecordValue::TXT("hello".to_string());
+    /// assert!(matches!(value.record_type(), RecordType::TXT));
+    /// ```
+    pub fn record_type(&self) -> RecordType {
+        match self {
+            RecordValue::A(_) => RecordType::A,
+            RecordValue::AAAA(_) => RecordType::AAAA,
+            RecordValue::CNAME(_) => RecordType::CNAME,
+            RecordValue::TXT(_) => RecordType::TXT,
+            RecordValue::SPF(_) => RecordType::SPF,
+            RecordValue::MX(_) => RecordType::MX,
+            RecordValue::NS(_) => RecordType::NS,
+            RecordValue::SOA(_) => RecordType::SOA,
+            RecordValue::SRV(..) => RecordType::SRV,
+            RecordValue::TLSA(..) => RecordType::TLSA,
+            RecordValue::CAA(..) => RecordType::CAA,
+            RecordValue::SVCB(_) => RecordType::SVCB,
+            RecordValue::HTTPS(_) => RecordType::HTTPS,
+            RecordValue::DS(..) => RecordType::DS,
+            RecordValue::DNSKEY(..) => RecordType::DNSKEY,
+        }
+    }
+
+    /// Formats the value as the single-string record content most provider APIs use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::types::dns::{MxRecord, RecordValue};
+    ///
+    /// let value = RecordValue::MX(MxRecord { priority: 10, target: "mail.example.com".to_string() });
+    /// assert_eq!(value.content(), "10 mail.example.com");
+    ///
+    /// let value = RecordValue::SRV(0, 5, 5060, "sip.example.com".to_string());
+    /// assert_eq!(value.content(), "0 5 5060 sip.example.com");
+    /// ```
+    pub fn content(&self) -> String {
+        match self {
+            RecordValue::A(ip) => ip.to_string(),
+            RecordValue::AAAA(ip) => ip.to_string(),
+            RecordValue::CNAME(value)
+            | RecordValue::TXT(value)
+            | RecordValue::SPF(value)
+            | RecordValue::NS(value)
+            | RecordValue::SOA(value) => value.clone(),
+            RecordValue::MX(mx) => format!("{} {}", mx.priority, mx.target),
+            RecordValue::SRV(priority, weight, port, target) => {
+                format!("{} {} {} {}", priority, weight, port, target)
+            }
+            RecordValue::TLSA(usage, selector, matching_type, cert_data) => {
+                format!("{} {} {} {}", usage, selector, matching_type, cert_data)
+            }
+            RecordValue::CAA(flag, tag, value) => format!("{} {} {}", flag, tag, value),
+            RecordValue::SVCB(svcb) | RecordValue::HTTPS(svcb) => svcb.to_string(),
+            RecordValue::DS(key_tag, algorithm, digest_type, digest) => {
+                format!("{} {} {} {}", key_tag, algorithm, digest_type, digest)
+            }
+            RecordValue::DNSKEY(flags, protocol, algorithm, public_key) => {
+                format!("{} {} {} {}", flags, protocol, algorithm, public_key)
+            }
+        }
+    }
+
+    /// Returns the type this value is written to providers as, see [`RecordType::wire_type`].
+    pub fn wire_type(&self) -> RecordType {
+        self.record_type().wire_type()
+    }
+
+    /// Formats the value like [`RecordValue::content`], but normalized so that values a DNS
+    /// server treats as equal format the same: hostnames are lowercased without trailing dot,
+    /// TXT quoting is removed and hex data is lowercased.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::types::dns::RecordValue;
+    ///
+    /// let value = RecordValue::CNAME("Target.Example.com.".to_string());
+    /// assert_eq!(value.canonical_content(), "target.example.com");
+    ///
+    /// let value = RecordValue::TXT("\"v=spf1 \" \"-all\"".to_string());
+    /// assert_eq!(value.canonical_content(), "v=spf1 -all");
+    /// ```
+    pub fn canonical_content(&self) -> String {
+        match self {
+            RecordValue::A(_) | RecordValue::AAAA(_) => self.content(),
+            RecordValue::CNAME(target) | RecordValue::NS(target) => canonical_name(target),
+            RecordValue::TXT(text) | RecordValue::SPF(text) => canonical_text(text),
+            RecordValue::SOA(value) => value
+                .split_whitespace()
+                .map(canonical_name)
+                .collect::<Vec<_>>()
+                .join(" "),
+            RecordValue::MX(mx) => format!("{} {}", mx.priority, canonical_name(&mx.target)),
+            RecordValue::SRV(priority, weight, port, target) => {
+                format!(
+                    "{} {} {} {}",
+                    priority,
+                    weight,
+                    port,
+                    canonical_name(target)
+                )
+            }
+            RecordValue::TLSA(usage, selector, matching_type, cert_data) => format!(
+                "{} {} {} {}",
+                usage,
+                selector,
+                matching_type,
+                cert_data.to_ascii_lowercase()
+            ),
+            RecordValue::CAA(flag, tag, value) => format!(
+                "{} {} {}",
+                flag,
+                tag.to_ascii_lowercase(),
+                canonical_text(value)
+            ),
+            RecordValue::SVCB(svcb) | RecordValue::HTTPS(svcb) => SvcbRecord {
+                target: canonical_name(&svcb.target),
+                ..svcb.clone()
+            }
+            .to_string(),
+            RecordValue::DS(key_tag, algorithm, digest_type, digest) => format!(
+                "{} {} {} {}",
+                key_tag,
+                algorithm,
+                digest_type,
+                digest.to_ascii_lowercase()
+            ),
+            RecordValue::DNSKEY(flags, protocol, algorithm, public_key) => format!(
+                "{} {} {} {}",
+                flags,
+                protocol,
+                algorithm,
+                public_key.split_whitespace().collect::<String>()
+            ),
+        }
+    }
+
+    /// Returns whether both values are published the same, see
+    /// [`RecordValue::canonical_content`]. SPF and TXT values with the same text are equal.
+    pub fn semantically_equals(&self, other: &RecordValue) -> bool {
+        self.wire_type() == other.wire_type()
+            && self.canonical_content() == other.canonical_content()
+    }
+
+    /// Checks the value for mistakes a provider would reject, before sending it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::types::dns::{RecordValidationError, RecordValue};
+    ///
+    /// let value = RecordValue::CNAME("target.example.com".to_string());
+    /// assert!(value.validate().is_ok());
+    ///
+    /// let value = RecordValue::CNAME("not a hostname".to_string());
+    /// assert!(matches!(value.validate(), Err(RecordValidationError::InvalidHostname(_))));
+    /// ```
+    pub fn validate(&self) -> Result<(), RecordValidationError> {
+        match self {
+            RecordValue::A(_) | RecordValue::AAAA(_) | RecordValue::SOA(_) => Ok(()),
+            RecordValue::CNAME(target) | RecordValue::NS(target) => validate_hostname(target),
+            RecordValue::MX(mx) => validate_hostname(&mx.target),
+            RecordValue::TXT(text) | RecordValue::SPF(text) => {
+                match txt_chunks(text)
+                    .into_iter()
+                    .find(|chunk| chunk.len() > MAX_TXT_CHUNK_LENGTH)
+                {
+                    Some(chunk) => Err(RecordValidationError::TxtTooLong(chunk.len())),
+                    None => Ok(()),
+                }
+            }
+            RecordValue::SRV(_, _, port, target) => {
+                // A target of "." means the service is not available, the port is irrelevant
+                if target == "." {
+                    return Ok(());
+                }
+                if *port == 0 {
+                    return Err(RecordValidationError::InvalidSrvPort(*port));
+                }
+                validate_hostname(target)
+            }
+            RecordValue::TLSA(usage, selector, matching_type, cert_data) => {
+                for (field, value, max) in [
+                    ("usage", *usage, 3),
+                    ("selector", *selector, 1),
+                    ("matching type", *matching_type, 2),
+                ] {
+                    if value > max {
+                        return Err(RecordValidationError::InvalidTlsaParameter { field, value });
+                    }
+                }
+
+                let expected_length = match matching_type {
+                    1 => Some(64),
+                    2 => Some(128),
+                    _ => None,
+                };
+                let is_hex = !cert_data.is_empty()
+                    && cert_data.len() % 2 == 0
+                    && cert_data.chars().all(|c| c.is_ascii_hexdigit());
+                if !is_hex || expected_length.is_some_and(|length| cert_data.len() != length) {
+                    return Err(RecordValidationError::InvalidTlsaData(cert_data.clone()));
+                }
+
+                Ok(())
+            }
+            RecordValue::CAA(flag, tag, _) => {
+                if *flag != 0 && *flag != 128 {
+                    return Err(RecordValidationError::InvalidCaaFlag(*flag));
+                }
+                if tag.is_empty() || !tag.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    return Err(RecordValidationError::InvalidCaaTag(tag.clone()));
+                }
+
+                Ok(())
+            }
+            RecordValue::SVCB(svcb) | RecordValue::HTTPS(svcb) => {
+                if svcb.priority == 0 && !svcb.params.is_empty() {
+                    return Err(RecordValidationError::SvcbAliasWithParams);
+                }
+                if svcb.target == "." {
+                    return Ok(());
+                }
+                validate_hostname(&svcb.target)
+            }
+            RecordValue::DS(_, _, digest_type, digest) => {
+                // SHA-1, SHA-256 and SHA-384 (RFC 4034, 4509, 6605), other types are not checked
+                let expected_length = match digest_type {
+                    1 => Some(40),
+                    2 => Some(64),
+                    4 => Some(96),
+                    _ => None,
+                };
+                let is_hex = !digest.is_empty()
+                    && digest.len() % 2 == 0
+                    && digest.chars().all(|c| c.is_ascii_hexdigit());
+                if !is_hex || expected_length.is_some_and(|length| digest.len() != length) {
+                    return Err(RecordValidationError::InvalidDsDigest {
+                        digest_type: *digest_type,
+                        digest: digest.clone(),
+                    });
+                }
+
+                Ok(())
+            }
+            RecordValue::DNSKEY(_, protocol, _, public_key) => {
+                if *protocol != 3 {
+                    return Err(RecordValidationError::InvalidDnskeyProtocol(*protocol));
+                }
+                let is_base64 = !public_key.is_empty()
+                    && public_key.len() % 4 == 0
+                    && public_key
+                        .trim_end_matches('=')
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/');
+                if !is_base64 {
+                    return Err(RecordValidationError::InvalidDnskeyKey(public_key.clone()));
+                }
+
+                Ok(())
+            }
+        }
+    }
 }
 
 /// Represents a DNS record.
@@ -64,7 +735,119 @@ pub struct Record {
     pub ttl: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Record {
+    /// Returns whether both records are published the same, ignoring the TTL.
+    ///
+    /// Names and hostname values are compared case-insensitively and TXT values regardless of
+    /// their quoting, see [`RecordValue::canonical_content`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::types::dns::{Record, RecordValue};
+    ///
+    /// let configured = Record {
+    ///     domain: "WWW.example.com".to_string(),
+    ///     value: RecordValue::CNAME("Example.com".to_string()),
+    ///     ttl: Some(300),
+    /// };
+    /// let live = Record {
+    ///     domain: "www.example.com.".to_string(),
+    ///     value: RecordValue::CNAME("example.com.".to_string()),
+    ///     ttl: Some(3600),
+    /// };
+    ///
+    /// assert!(configured.semantically_equals(&live));
+    /// assert!(!configured.semantically_equals_with_ttl(&live));
+    /// ```
+    pub fn semantically_equals(&self, other: &Record) -> bool {
+        canonical_name(&self.domain) == canonical_name(&other.domain)
+            && self.value.semantically_equals(&other.value)
+    }
+
+    /// Like [`Record::semantically_equals`], but records with different TTLs differ.
+    pub fn semantically_equals_with_ttl(&self, other: &Record) -> bool {
+        self.ttl == other.ttl && self.semantically_equals(other)
+    }
+
+    /// Returns a builder for a record of `domain` with `value`, see [`RecordBuilder`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::types::dns::{Record, RecordValue};
+    /// use std::net::Ipv4Addr;
+    ///
+    /// let record = Record::builder("WWW.Example.com.", RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)))
+    ///     .ttl(Some(300))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(record.domain, "www.example.com");
+    /// assert_eq!(record.ttl, Some(300));
+    /// ```
+    pub fn builder(domain: impl Into<String>, value: RecordValue) -> RecordBuilder {
+        RecordBuilder {
+            domain: domain.into(),
+            value,
+            ttl: None,
+        }
+    }
+}
+
+/// Builds a [`Record`], normalizing its domain to lowercase without a trailing dot and
+/// validating its value, see [`RecordValue::validate`].
+#[derive(Debug, Clone)]
+pub struct RecordBuilder {
+    domain: String,
+    value: RecordValue,
+    ttl: Option<u32>,
+}
+
+impl RecordBuilder {
+    /// Sets the TTL of the record, `None` for the default of the provider.
+    pub fn ttl(mut self, ttl: Option<u32>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn build(self) -> Result<Record, RecordValidationError> {
+        self.value.validate()?;
+
+        Ok(Record {
+            domain: canonical_name(&self.domain),
+            value: self.value,
+            ttl: self.ttl,
+        })
+    }
+}
+
+/// Formats the record as an RFC 1035 zone file line with an absolute owner name.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::types::dns::{Record, RecordValue};
+///
+/// let record = Record {
+///     domain: "www.example.com".to_string(),
+///     value: RecordValue::CNAME("example.com".to_string()),
+///     ttl: Some(300),
+/// };
+///
+/// assert_eq!(record.to_string(), "www.example.com. 300 IN CNAME example.com.");
+/// ```
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", absolute_name(&self.domain))?;
+        if let Some(ttl) = self.ttl {
+            write!(f, "{} ", ttl)?;
+        }
+        write!(f, "IN {:?} {}", self.value.record_type(), self.value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(crate = "lum_libs::serde")]
 pub enum RecordType {
     A,
@@ -78,4 +861,371 @@ pub enum RecordType {
     SRV,
     TLSA,
     CAA,
+    SVCB,
+    HTTPS,
+    DS,
+    DNSKEY,
+}
+
+impl RecordType {
+    pub const ALL: [RecordType; 15] = [
+        RecordType::A,
+        RecordType::AAAA,
+        RecordType::CNAME,
+        RecordType::TXT,
+        RecordType::SPF,
+        RecordType::MX,
+        RecordType::NS,
+        RecordType::SOA,
+        RecordType::SRV,
+        RecordType::TLSA,
+        RecordType::CAA,
+        RecordType::SVCB,
+        RecordType::HTTPS,
+        RecordType::DS,
+        RecordType::DNSKEY,
+    ];
+
+    /// Returns the type records of this type are written as.
+    ///
+    /// The SPF type is deprecated (RFC 7208) and SPF policies are published as TXT records, so
+    /// SPF maps to TXT. All other types map to themselves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dnrs::types::dns::RecordType;
+    ///
+    /// assert_eq!(RecordType::SPF.wire_type(), RecordType::TXT);
+    /// assert_eq!(RecordType::MX.wire_type(), RecordType::MX);
+    /// ```
+    pub fn wire_type(self) -> RecordType {
+        match self {
+            RecordType::SPF => RecordType::TXT,
+            record_type => record_type,
+        }
+    }
+}
+
+fn record_type_list() -> String {
+    RecordType::ALL
+        .map(|record_type| record_type.to_string())
+        .join(", ")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Unknown record type '{0}', expected one of: {types}", types = record_type_list())]
+pub struct ParseRecordTypeError(pub String);
+
+/// Parses a record type, ignoring case.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::types::dns::RecordType;
+///
+/// assert_eq!("aaaa".parse::<RecordType>(), Ok(RecordType::AAAA));
+/// assert!("AXFR".parse::<RecordType>().is_err());
+/// ```
+impl FromStr for RecordType {
+    type Err = ParseRecordTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RecordType::ALL
+            .into_iter()
+            .find(|record_type| record_type.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseRecordTypeError(s.to_string()))
+    }
+}
+
+/// Displays the record type as written in zone files, e.g. `AAAA`.
+impl fmt::Display for RecordType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_normalizes_domain() {
+        let record = Record::builder(
+            "Mail.EXAMPLE.com.",
+            RecordValue::CNAME("example.com".into()),
+        )
+        .build()
+        .unwrap();
+
+        assert_eq!(record.domain, "mail.example.com");
+        assert_eq!(record.ttl, None);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_value() {
+        let result = Record::builder("www.example.com", RecordValue::CNAME("not a host".into()))
+            .ttl(Some(300))
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(RecordValidationError::InvalidHostname(name)) if name == "not a host"
+        ));
+    }
+
+    #[test]
+    fn test_record_type_parse_and_display_round_trip() {
+        for record_type in RecordType::ALL {
+            let text = record_type.to_string();
+            assert_eq!(text, text.to_uppercase());
+            assert_eq!(text.parse::<RecordType>(), Ok(record_type));
+            assert_eq!(text.to_lowercase().parse::<RecordType>(), Ok(record_type));
+        }
+    }
+
+    #[test]
+    fn test_record_type_parse_error_lists_valid_types() {
+        let error = "PTR".parse::<RecordType>().unwrap_err();
+        assert_eq!(error, ParseRecordTypeError("PTR".to_string()));
+
+        let message = error.to_string();
+        assert!(message.starts_with("Unknown record type 'PTR'"));
+        assert!(message.contains("A, AAAA, CNAME"));
+        assert!(message.ends_with("DNSKEY"));
+        assert!("".parse::<RecordType>().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_over_long_txt() {
+        let value = RecordValue::TXT("a".repeat(300));
+        assert_eq!(
+            value.validate(),
+            Err(RecordValidationError::TxtTooLong(300))
+        );
+
+        let chunked = format!("\"{}\" \"{}\"", "a".repeat(255), "b".repeat(45));
+        assert!(RecordValue::TXT(chunked).validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_cname_target() {
+        for target in [
+            "",
+            "exa mple.com",
+            "-bad.example.com",
+            "a..b",
+            &"a".repeat(64),
+        ] {
+            let value = RecordValue::CNAME(target.to_string());
+            assert!(
+                matches!(
+                    value.validate(),
+                    Err(RecordValidationError::InvalidHostname(_))
+                ),
+                "expected {:?} to be rejected",
+                target
+            );
+        }
+
+        let value = RecordValue::CNAME("_acme-challenge.example.com.".to_string());
+        assert!(value.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_tlsa_and_srv() {
+        let value = RecordValue::TLSA(3, 1, 1, "ab".repeat(32));
+        assert!(value.validate().is_ok());
+
+        let value = RecordValue::TLSA(3, 1, 1, "zz".repeat(32));
+        assert!(matches!(
+            value.validate(),
+            Err(RecordValidationError::InvalidTlsaData(_))
+        ));
+
+        let value = RecordValue::SRV(0, 5, 0, "sip.example.com".to_string());
+        assert_eq!(
+            value.validate(),
+            Err(RecordValidationError::InvalidSrvPort(0))
+        );
+    }
+
+    #[test]
+    fn test_svcb_parses_and_formats_alpn() {
+        let svcb = SvcbRecord::from_str("1 . alpn=h2,h3").unwrap();
+        assert_eq!(svcb.priority, 1);
+        assert_eq!(svcb.target, ".");
+        assert_eq!(
+            svcb.params,
+            BTreeMap::from([("alpn".to_string(), "h2,h3".to_string())])
+        );
+
+        let value = RecordValue::HTTPS(svcb.clone());
+        assert_eq!(value.content(), "1 . alpn=h2,h3");
+        assert_eq!(value.to_string(), "1 . alpn=h2,h3");
+        assert!(value.validate().is_ok());
+
+        let quoted = SvcbRecord::from_str("1 . alpn=\"h2,h3\"").unwrap();
+        assert_eq!(quoted, svcb);
+    }
+
+    #[test]
+    fn test_svcb_parameters_and_target() {
+        let svcb = SvcbRecord::from_str("16 svc.example.com port=8443 no-default-alpn ech=\"a b\"")
+            .unwrap();
+        assert_eq!(svcb.params["no-default-alpn"], "");
+        assert_eq!(svcb.params["ech"], "a b");
+        assert_eq!(
+            RecordValue::SVCB(svcb.clone()).to_string(),
+            "16 svc.example.com. ech=\"a b\" no-default-alpn port=8443"
+        );
+        assert_eq!(
+            SvcbRecord::from_str(&RecordValue::SVCB(svcb.clone()).content()).unwrap(),
+            svcb
+        );
+
+        assert!(matches!(
+            SvcbRecord::from_str("1"),
+            Err(SvcbParseError::MissingField(_))
+        ));
+        assert!(matches!(
+            SvcbRecord::from_str("1 . alpn=h2 alpn=h3"),
+            Err(SvcbParseError::DuplicateKey(_))
+        ));
+
+        let alias = SvcbRecord::from_str("0 pool.example.com alpn=h2").unwrap();
+        assert_eq!(
+            RecordValue::HTTPS(alias).validate(),
+            Err(RecordValidationError::SvcbAliasWithParams)
+        );
+    }
+
+    fn record(domain: &str, value: RecordValue, ttl: Option<u32>) -> Record {
+        Record {
+            domain: domain.to_string(),
+            value,
+            ttl,
+        }
+    }
+
+    #[test]
+    fn test_semantically_equals_ignores_hostname_case() {
+        let configured = record(
+            "Www.Example.com",
+            RecordValue::CNAME("Target.Example.COM".to_string()),
+            None,
+        );
+        let live = record(
+            "www.example.com.",
+            RecordValue::CNAME("target.example.com.".to_string()),
+            Some(300),
+        );
+        assert!(configured.semantically_equals(&live));
+        assert!(!configured.semantically_equals_with_ttl(&live));
+
+        let other_target = record(
+            "www.example.com",
+            RecordValue::CNAME("other.example.com".to_string()),
+            None,
+        );
+        assert!(!configured.semantically_equals(&other_target));
+
+        let mx = |target: &str| {
+            RecordValue::MX(MxRecord {
+                priority: 10,
+                target: target.to_string(),
+            })
+        };
+        assert!(mx("Mail.Example.com.").semantically_equals(&mx("mail.example.com")));
+    }
+
+    #[test]
+    fn test_semantically_equals_normalizes_txt_quoting() {
+        let unquoted = record(
+            "example.com",
+            RecordValue::TXT("v=spf1 include:_spf.example.com -all".to_string()),
+            Some(300),
+        );
+        for quoted in [
+            "\"v=spf1 include:_spf.example.com -all\"",
+            "\"v=spf1 include:_spf.example.com \" \"-all\"",
+            "  v=spf1 include:_spf.example.com -all ",
+        ] {
+            let live = record(
+                "example.com",
+                RecordValue::TXT(quoted.to_string()),
+                Some(300),
+            );
+            assert!(unquoted.semantically_equals_with_ttl(&live), "{}", quoted);
+        }
+
+        let spf = record(
+            "example.com",
+            RecordValue::SPF("v=spf1 include:_spf.example.com -all".to_string()),
+            Some(300),
+        );
+        assert!(unquoted.semantically_equals(&spf));
+
+        let different = record(
+            "example.com",
+            RecordValue::TXT("v=spf1 -all".to_string()),
+            None,
+        );
+        assert!(!unquoted.semantically_equals(&different));
+    }
+
+    #[test]
+    fn test_ds_parses_and_formats() {
+        let digest = "E2D3C916F6DEEAC73294E8268FB5885044A833FC5459588F4A9184CFC41A5766";
+        let value = RecordValue::parse_ds(&format!("2371 13 2 {}", digest)).unwrap();
+        assert!(
+            matches!(&value, RecordValue::DS(2371, 13, 2, parsed) if parsed == digest),
+            "{:?}",
+            value
+        );
+        assert!(value.validate().is_ok());
+
+        let record = Record {
+            domain: "sub.example.com".to_string(),
+            value,
+            ttl: Some(3600),
+        };
+        assert_eq!(
+            record.to_string(),
+            format!("sub.example.com. 3600 IN DS 2371 13 2 {}", digest)
+        );
+
+        assert!(matches!(
+            RecordValue::parse_ds("2371 13 2"),
+            Err(DnssecParseError::InvalidFormat(RecordType::DS, _))
+        ));
+        assert!(matches!(
+            RecordValue::parse_ds("70000 13 2 abcd"),
+            Err(DnssecParseError::InvalidNumber(RecordType::DS, _))
+        ));
+
+        let sha1_length_for_sha256 = RecordValue::DS(2371, 13, 2, "ab".repeat(20));
+        assert!(matches!(
+            sha1_length_for_sha256.validate(),
+            Err(RecordValidationError::InvalidDsDigest { digest_type: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_dnskey_joins_split_key() {
+        let value =
+            RecordValue::parse_dnskey("257 3 13 mdsswUyr3DPW132mOi8V9xESWE8jTo0d xbjjgopNf0sDkA==")
+                .unwrap();
+        assert!(matches!(
+            &value,
+            RecordValue::DNSKEY(257, 3, 13, key) if key == "mdsswUyr3DPW132mOi8V9xESWE8jTo0dxbjjgopNf0sDkA=="
+        ));
+        assert!(value.validate().is_ok());
+
+        let value = RecordValue::DNSKEY(257, 2, 13, "AAAA".to_string());
+        assert_eq!(
+            value.validate(),
+            Err(RecordValidationError::InvalidDnskeyProtocol(2))
+        );
+    }
 }
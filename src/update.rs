@@ -0,0 +1,961 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    net::{Ipv4Addr, Ipv6Addr},
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
+
+use futures::{StreamExt, stream};
+use lum_log::{debug, error};
+
+use crate::{
+    config::dns::{AutomaticRecordConfig, RecordConfig, ResolveType},
+    http::HttpClient,
+    provider::{Provider, ProviderError, Result, UpsertRecordsInput},
+    types::dns::{Record, RecordValue},
+};
+
+/// All records of a single domain that should be updated at one provider.
+pub struct DomainUpdate<'provider> {
+    pub provider_name: &'provider str,
+    pub provider: &'provider dyn Provider,
+    pub domain: String,
+    pub records: Vec<Record>,
+}
+
+/// The outcome of a single record of a [`DomainUpdate`].
+pub type RecordOutcome = (Record, Result<()>);
+
+/// The outcome of a single [`DomainUpdate`].
+///
+/// `result` is an error if the whole domain failed, e.g. because a batch request was rejected.
+/// Otherwise it holds the outcome of every record, in the order of [`DomainUpdate::records`].
+#[derive(Debug)]
+pub struct DomainUpdateResult {
+    pub provider_name: String,
+    pub domain: String,
+    pub result: Result<Vec<RecordOutcome>>,
+    pub elapsed: Duration,
+}
+
+impl DomainUpdateResult {
+    /// Returns whether the domain and all of its records were updated.
+    pub fn is_success(&self) -> bool {
+        self.result
+            .as_ref()
+            .is_ok_and(|outcomes| outcomes.iter().all(|(_, result)| result.is_ok()))
+    }
+
+    /// Returns whether the update was aborted because it took longer than the timeout given to
+    /// [`update_domains`].
+    pub fn is_timed_out(&self) -> bool {
+        matches!(self.result, Err(ProviderError::Timeout(_)))
+    }
+
+    /// Returns the records that were updated.
+    pub fn updated_records(&self) -> impl Iterator<Item = &Record> {
+        self.result
+            .iter()
+            .flatten()
+            .filter(|(_, result)| result.is_ok())
+            .map(|(record, _)| record)
+    }
+
+    /// Returns why the update failed, `None` if it succeeded.
+    pub fn error_message(&self) -> Option<String> {
+        let outcomes = match &self.result {
+            Ok(outcomes) => outcomes,
+            Err(e) => return Some(e.to_string()),
+        };
+
+        let errors: Vec<_> = outcomes
+            .iter()
+            .filter_map(|(record, result)| {
+                let error = result.as_ref().err()?;
+                Some(format!("{}: {}", record.domain, error))
+            })
+            .collect();
+        (!errors.is_empty()).then(|| errors.join(", "))
+    }
+
+    /// Returns one line per record, marked with ✓ or ✗ and the reason of a failure, together
+    /// with whether the record was updated. If the whole domain failed, a single line for the
+    /// domain is returned.
+    pub fn outcome_lines(&self) -> Vec<(bool, String)> {
+        let outcomes = match &self.result {
+            Ok(outcomes) => outcomes,
+            Err(e) => {
+                let line = format!("✗ {} at {}: {}", self.domain, self.provider_name, e);
+                return vec![(false, line)];
+            }
+        };
+
+        outcomes
+            .iter()
+            .map(|(record, result)| {
+                let record_type = record.value.record_type();
+                let line = match result {
+                    Ok(()) => format!(
+                        "✓ {} {:?} at {}",
+                        record.domain, record_type, self.provider_name
+                    ),
+                    Err(e) => format!(
+                        "✗ {} {:?} at {}: {}",
+                        record.domain, record_type, self.provider_name, e
+                    ),
+                };
+                (result.is_ok(), line)
+            })
+            .collect()
+    }
+}
+
+/// A domain that failed to update, see [`UpdateSummary::failures`].
+#[derive(Debug, Clone)]
+pub struct DomainFailure {
+    pub provider_name: String,
+    pub domain: String,
+    pub error: String,
+}
+
+impl Display for DomainFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {}: {}",
+            self.domain, self.provider_name, self.error
+        )
+    }
+}
+
+/// Aggregated results of an update pass.
+#[derive(Debug, Default)]
+pub struct UpdateSummary {
+    pub results: Vec<DomainUpdateResult>,
+}
+
+impl UpdateSummary {
+    pub fn succeeded(&self) -> impl Iterator<Item = &DomainUpdateResult> {
+        self.results.iter().filter(|result| result.is_success())
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &DomainUpdateResult> {
+        self.results.iter().filter(|result| !result.is_success())
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.failed().next().is_some()
+    }
+
+    /// Returns the failed updates that timed out, see [`DomainUpdateResult::is_timed_out`].
+    pub fn timed_out(&self) -> impl Iterator<Item = &DomainUpdateResult> {
+        self.results.iter().filter(|result| result.is_timed_out())
+    }
+
+    /// Returns the failed domains with their error messages.
+    pub fn failures(&self) -> Vec<DomainFailure> {
+        self.failed()
+            .filter_map(|result| {
+                Some(DomainFailure {
+                    provider_name: result.provider_name.clone(),
+                    domain: result.domain.clone(),
+                    error: result.error_message()?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Combines the /64 prefix of `address` with the low 64 bits of `suffix`.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::update::with_ipv6_suffix;
+/// use std::net::Ipv6Addr;
+///
+/// let resolved: Ipv6Addr = "2001:db8::abcd:ef01".parse().unwrap();
+/// let suffix: Ipv6Addr = "::1234".parse().unwrap();
+/// assert_eq!(with_ipv6_suffix(resolved, suffix), "2001:db8::1234".parse::<Ipv6Addr>().unwrap());
+/// ```
+pub fn with_ipv6_suffix(address: Ipv6Addr, suffix: Ipv6Addr) -> Ipv6Addr {
+    const PREFIX_MASK: u128 = !0 << 64;
+    Ipv6Addr::from_bits((address.to_bits() & PREFIX_MASK) | (suffix.to_bits() & !PREFIX_MASK))
+}
+
+/// Builds the record for an automatic record config from the already resolved addresses.
+///
+/// Returns `None` if the address family required by the record could not be resolved.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::config::dns::{AutomaticRecordConfig, ResolveType};
+/// use dnrs::update::automatic_record;
+/// use std::net::Ipv4Addr;
+///
+/// let config = AutomaticRecordConfig {
+///     domain: "home.example.com".to_string(),
+///     ttl: Some(300),
+///     resolve_type: ResolveType::IPv4,
+///     ipv6_suffix: None,
+///     providers: vec![],
+/// };
+///
+/// let record = automatic_record(&config, Some(Ipv4Addr::new(1, 2, 3, 4)), None).unwrap();
+/// assert_eq!(record.domain, "home.example.com");
+///
+/// assert!(automatic_record(&config, None, None).is_none());
+/// ```
+pub fn automatic_record(
+    config: &AutomaticRecordConfig,
+    ipv4: Option<Ipv4Addr>,
+    ipv6: Option<Ipv6Addr>,
+) -> Option<Record> {
+    let value = match config.resolve_type {
+        ResolveType::IPv4 => RecordValue::A(ipv4?),
+        ResolveType::IPv6 => {
+            let ipv6 = ipv6?;
+            RecordValue::AAAA(match config.ipv6_suffix {
+                Some(suffix) => with_ipv6_suffix(ipv6, suffix),
+                None => ipv6,
+            })
+        }
+    };
+
+    Some(Record {
+        domain: config.domain.clone(),
+        value,
+        ttl: config.ttl,
+    })
+}
+
+/// Converts the record configs of a domain into the records that should be published.
+///
+/// Automatic records whose address family could not be resolved are skipped.
+pub fn records_for_domain(
+    record_configs: &[RecordConfig],
+    ipv4: Option<Ipv4Addr>,
+    ipv6: Option<Ipv6Addr>,
+) -> Vec<Record> {
+    record_configs
+        .iter()
+        .filter_map(|record_config| match record_config {
+            RecordConfig::Manual(record) => Some(record.clone()),
+            RecordConfig::Automatic(automatic_config) => {
+                let record = automatic_record(automatic_config, ipv4, ipv6);
+                if record.is_none() {
+                    error!(
+                        "Skipping {}: no {:?} address available",
+                        automatic_config.domain, automatic_config.resolve_type
+                    );
+                }
+                record
+            }
+        })
+        .collect()
+}
+
+/// Converts the record configs of a domain like [`records_for_domain`], grouping the records
+/// by the providers they are published to, see [`RecordConfig::target_providers`].
+///
+/// Providers are returned in the order they are first referenced.
+pub fn records_by_provider<'config>(
+    default_provider: &'config str,
+    record_configs: &'config [RecordConfig],
+    ipv4: Option<Ipv4Addr>,
+    ipv6: Option<Ipv6Addr>,
+) -> Vec<(&'config str, Vec<Record>)> {
+    let mut groups: Vec<(&str, Vec<Record>)> = Vec::new();
+    for record_config in record_configs {
+        let records = records_for_domain(std::slice::from_ref(record_config), ipv4, ipv6);
+        if records.is_empty() {
+            continue;
+        }
+
+        for provider_name in record_config.target_providers(default_provider) {
+            match groups.iter_mut().find(|(name, _)| *name == provider_name) {
+                Some((_, group)) => group.extend(records.iter().cloned()),
+                None => groups.push((provider_name, records.clone())),
+            }
+        }
+    }
+
+    groups
+}
+
+/// Updates all records of a domain, in a single call if the provider supports batch writes.
+/// Records that do not exist at the provider yet are added, see [`Provider::upsert_records`].
+///
+/// A failing record does not stop the others, the outcome of every record is returned. The
+/// outer error is for failures of the whole domain.
+async fn update_domain(
+    http: &dyn HttpClient,
+    update: &DomainUpdate<'_>,
+) -> Result<Vec<RecordOutcome>> {
+    let records: Vec<Record> = update
+        .records
+        .iter()
+        .map(|record| update.provider.clamp_ttl(record))
+        .collect();
+    let input = UpsertRecordsInput {
+        domain: &update.domain,
+        records: &records,
+    };
+
+    update.provider.validate_zone(&update.domain, &records)?;
+    let results = update.provider.upsert_records(http, &input).await?;
+    let outcomes = update
+        .records
+        .iter()
+        .cloned()
+        .zip(results)
+        .inspect(|(record, result)| {
+            if result.is_ok() {
+                debug!(
+                    "Updated {} at {} ({})",
+                    record.domain, update.provider_name, update.domain
+                );
+            }
+        })
+        .collect();
+
+    Ok(outcomes)
+}
+
+/// Updates the given domains concurrently, running at most `concurrency` updates at a time.
+///
+/// A failing domain does not abort the others. An update taking longer than `timeout` is
+/// aborted and fails with [`ProviderError::Timeout`], so a slow provider does not hold up the
+/// run. Every domain gets an entry in the returned [`UpdateSummary`], in completion order.
+pub async fn update_domains(
+    http: &dyn HttpClient,
+    updates: &[DomainUpdate<'_>],
+    concurrency: NonZeroUsize,
+    timeout: Option<Duration>,
+) -> UpdateSummary {
+    let results = stream::iter(updates)
+        .map(|update| async move {
+            let start = Instant::now();
+            let result = match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, update_domain(http, update))
+                    .await
+                    .unwrap_or(Err(ProviderError::Timeout(timeout))),
+                None => update_domain(http, update).await,
+            };
+            DomainUpdateResult {
+                provider_name: update.provider_name.to_string(),
+                domain: update.domain.clone(),
+                result,
+                elapsed: start.elapsed(),
+            }
+        })
+        .buffer_unordered(concurrency.get())
+        .collect()
+        .await;
+
+    UpdateSummary { results }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{
+        AddRecordInput, DeleteRecordInput, Feature, GetAllRecordsInput, ProviderError,
+        UpdateRecordInput, UpdateRecordsInput,
+    };
+    use crate::{RuntimeError, cli::command};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct DelayedProvider {
+        delay: Duration,
+        failing_domain: Option<&'static str>,
+        failing_record: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl Provider for DelayedProvider {
+        fn get_provider_name(&self) -> &'static str {
+            "Delayed"
+        }
+
+        fn get_supported_features(&self) -> Vec<Feature> {
+            vec![Feature::UpdateRecord]
+        }
+
+        async fn get_all_records(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &GetAllRecordsInput,
+        ) -> Result<Vec<Record>> {
+            unimplemented!()
+        }
+
+        async fn add_record(&self, _http: &dyn HttpClient, _input: &AddRecordInput) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn update_record(
+            &self,
+            _http: &dyn HttpClient,
+            input: &UpdateRecordInput,
+        ) -> Result<()> {
+            tokio::time::sleep(self.delay).await;
+            if self.failing_domain == Some(input.domain) {
+                return Err(ProviderError::DomainNotFound(input.domain.to_string()));
+            }
+            if self.failing_record == Some(input.record.domain.as_str()) {
+                return Err(ProviderError::RecordNotFound(input.record.domain.clone()));
+            }
+
+            Ok(())
+        }
+
+        /// Every record exists, so upserts are updates.
+        async fn upsert_records(
+            &self,
+            http: &dyn HttpClient,
+            input: &UpsertRecordsInput,
+        ) -> Result<Vec<Result<()>>> {
+            let input = UpdateRecordsInput {
+                domain: input.domain,
+                records: input.records,
+            };
+            self.update_records(http, &input).await
+        }
+
+        async fn delete_record(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &DeleteRecordInput,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    fn domain_update<'provider>(
+        provider: &'provider dyn Provider,
+        domain: &str,
+    ) -> DomainUpdate<'provider> {
+        DomainUpdate {
+            provider_name: "Delayed1",
+            provider,
+            domain: domain.to_string(),
+            records: vec![Record {
+                domain: format!("home.{}", domain),
+                value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+                ttl: None,
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_domains_runs_concurrently() {
+        let provider = DelayedProvider {
+            delay: Duration::from_millis(200),
+            failing_domain: None,
+            failing_record: None,
+        };
+        let updates = vec![
+            domain_update(&provider, "a.com"),
+            domain_update(&provider, "b.com"),
+            domain_update(&provider, "c.com"),
+            domain_update(&provider, "d.com"),
+        ];
+
+        let reqwest = reqwest::Client::new();
+        let start = Instant::now();
+        let summary = update_domains(&reqwest, &updates, NonZeroUsize::new(4).unwrap(), None).await;
+        let elapsed = start.elapsed();
+
+        assert_eq!(summary.results.len(), 4);
+        assert!(!summary.has_failures());
+        assert!(
+            elapsed < Duration::from_millis(800),
+            "expected concurrent updates, took {:?}",
+            elapsed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_domains_failure_does_not_abort_others() {
+        let provider = DelayedProvider {
+            delay: Duration::from_millis(10),
+            failing_domain: Some("b.com"),
+            failing_record: None,
+        };
+        let updates = vec![
+            domain_update(&provider, "a.com"),
+            domain_update(&provider, "b.com"),
+            domain_update(&provider, "c.com"),
+        ];
+
+        let reqwest = reqwest::Client::new();
+        let summary = update_domains(&reqwest, &updates, NonZeroUsize::new(1).unwrap(), None).await;
+
+        assert_eq!(summary.succeeded().count(), 2);
+        let failed: Vec<_> = summary.failed().collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].domain, "b.com");
+    }
+
+    #[tokio::test]
+    async fn test_records_outside_zone_are_not_written() {
+        let provider = DelayedProvider {
+            delay: Duration::from_millis(10),
+            failing_domain: None,
+            failing_record: None,
+        };
+        let mut outside = domain_update(&provider, "example.co.uk");
+        outside.records[0].domain = "home.example.com".to_string();
+        let updates = vec![
+            domain_update(&provider, "example.co.uk"),
+            outside,
+            domain_update(&provider, "co.uk"),
+        ];
+
+        let reqwest = reqwest::Client::new();
+        let summary = update_domains(&reqwest, &updates, NonZeroUsize::new(1).unwrap(), None).await;
+
+        assert_eq!(summary.succeeded().count(), 1);
+        let errors: Vec<_> = summary
+            .failed()
+            .filter_map(|result| result.error_message())
+            .collect();
+        assert_eq!(errors.len(), 2);
+        assert!(
+            errors
+                .iter()
+                .any(|error| error.contains("home.example.com"))
+        );
+        assert!(errors.iter().any(|error| error.contains("public suffix")));
+    }
+
+    #[tokio::test]
+    async fn test_failed_record_does_not_mask_others() {
+        let provider = DelayedProvider {
+            delay: Duration::from_millis(1),
+            failing_domain: None,
+            failing_record: Some("b.example.com"),
+        };
+        let mut update = domain_update(&provider, "example.com");
+        update.records = ["a", "b", "c"]
+            .into_iter()
+            .map(|host| Record {
+                domain: format!("{}.example.com", host),
+                value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+                ttl: None,
+            })
+            .collect();
+
+        let reqwest = reqwest::Client::new();
+        let summary =
+            update_domains(&reqwest, &[update], NonZeroUsize::new(1).unwrap(), None).await;
+
+        let result = &summary.results[0];
+        assert!(!result.is_success());
+        let updated: Vec<_> = result
+            .updated_records()
+            .map(|record| record.domain.as_str())
+            .collect();
+        assert_eq!(updated, vec!["a.example.com", "c.example.com"]);
+        assert_eq!(
+            result.outcome_lines(),
+            vec![
+                (true, "✓ a.example.com A at Delayed1".to_string()),
+                (
+                    false,
+                    "✗ b.example.com A at Delayed1: Record 'b.example.com' not found".to_string()
+                ),
+                (true, "✓ c.example.com A at Delayed1".to_string()),
+            ]
+        );
+        assert_eq!(
+            summary.failures()[0].error,
+            "b.example.com: Record 'b.example.com' not found"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slow_provider_times_out_without_holding_up_others() {
+        let fast = DelayedProvider {
+            delay: Duration::from_millis(1),
+            failing_domain: None,
+            failing_record: None,
+        };
+        let slow = DelayedProvider {
+            delay: Duration::from_secs(30),
+            failing_domain: None,
+            failing_record: None,
+        };
+        let mut slow_update = domain_update(&slow, "slow.com");
+        slow_update.provider_name = "Slow1";
+        let updates = vec![
+            slow_update,
+            domain_update(&fast, "a.com"),
+            domain_update(&fast, "b.com"),
+        ];
+
+        let reqwest = reqwest::Client::new();
+        let start = Instant::now();
+        let summary = update_domains(
+            &reqwest,
+            &updates,
+            NonZeroUsize::new(2).unwrap(),
+            Some(Duration::from_millis(100)),
+        )
+        .await;
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(summary.succeeded().count(), 2);
+        let timed_out: Vec<_> = summary.timed_out().collect();
+        assert_eq!(timed_out.len(), 1);
+        assert_eq!(timed_out[0].provider_name, "Slow1");
+        assert_eq!(timed_out[0].domain, "slow.com");
+        assert_eq!(
+            summary.failures()[0].to_string(),
+            "slow.com at Slow1: Timed out after 100ms"
+        );
+    }
+
+    /// Runs the updates of the domains and returns the exit code `dnrs auto` would end with.
+    async fn exit_code(provider: &DelayedProvider, domains: &[&str]) -> i32 {
+        let updates: Vec<_> = domains
+            .iter()
+            .map(|domain| domain_update(provider, domain))
+            .collect();
+
+        let reqwest = reqwest::Client::new();
+        let summary = update_domains(&reqwest, &updates, NonZeroUsize::new(2).unwrap(), None).await;
+        match crate::cli::auto::check_summary(&summary) {
+            Ok(()) => 0,
+            Err(e) => RuntimeError::from(command::Error::from(e)).exit_code(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exit_codes_of_update_outcomes() {
+        let provider = DelayedProvider {
+            delay: Duration::from_millis(1),
+            failing_domain: Some("b.com"),
+            failing_record: None,
+        };
+
+        assert_eq!(exit_code(&provider, &["a.com", "c.com"]).await, 0);
+        assert_eq!(exit_code(&provider, &["a.com", "b.com", "c.com"]).await, 2);
+        assert_eq!(exit_code(&provider, &["b.com"]).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_partial_failure_details_failed_domains() {
+        let provider = DelayedProvider {
+            delay: Duration::from_millis(1),
+            failing_domain: Some("b.com"),
+            failing_record: None,
+        };
+        let updates = vec![
+            domain_update(&provider, "a.com"),
+            domain_update(&provider, "b.com"),
+        ];
+
+        let reqwest = reqwest::Client::new();
+        let summary = update_domains(&reqwest, &updates, NonZeroUsize::new(1).unwrap(), None).await;
+        let error = crate::cli::auto::check_summary(&summary).unwrap_err();
+
+        match RuntimeError::from(command::Error::from(error)) {
+            RuntimeError::PartialFailure { succeeded, failed } => {
+                assert_eq!(succeeded, 1);
+                assert_eq!(failed.len(), 1);
+                assert_eq!(failed[0].domain, "b.com");
+                assert_eq!(failed[0].provider_name, "Delayed1");
+            }
+            error => panic!("Expected partial failure, got {}", error),
+        }
+    }
+
+    /// Counts provider calls, answering [`Provider::update_records`] itself if `batch` is set.
+    struct CountingProvider {
+        batch: bool,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Provider for CountingProvider {
+        fn get_provider_name(&self) -> &'static str {
+            "Counting"
+        }
+
+        fn get_supported_features(&self) -> Vec<Feature> {
+            if self.batch {
+                vec![Feature::UpdateRecord, Feature::BatchWrite]
+            } else {
+                vec![Feature::UpdateRecord]
+            }
+        }
+
+        async fn get_all_records(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &GetAllRecordsInput,
+        ) -> Result<Vec<Record>> {
+            Ok(many_records_update(self).records)
+        }
+
+        async fn add_record(&self, _http: &dyn HttpClient, _input: &AddRecordInput) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn update_record(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &UpdateRecordInput,
+        ) -> Result<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn update_records(
+            &self,
+            http: &dyn HttpClient,
+            input: &UpdateRecordsInput,
+        ) -> Result<Vec<Result<()>>> {
+            if !self.batch {
+                let mut results = Vec::new();
+                for record in input.records {
+                    let input = UpdateRecordInput {
+                        domain: input.domain,
+                        record,
+                    };
+                    results.push(self.update_record(http, &input).await);
+                }
+                return Ok(results);
+            }
+
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(input.records.iter().map(|_| Ok(())).collect())
+        }
+
+        async fn delete_record(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &DeleteRecordInput,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    fn many_records_update<'provider>(
+        provider: &'provider dyn Provider,
+    ) -> DomainUpdate<'provider> {
+        DomainUpdate {
+            provider_name: "Counting1",
+            provider,
+            domain: "example.com".to_string(),
+            records: (1..=5)
+                .map(|i| Record {
+                    domain: format!("host{}.example.com", i),
+                    value: RecordValue::A(Ipv4Addr::new(1, 2, 3, i)),
+                    ttl: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_domains_prefers_batch_write() {
+        let batch = CountingProvider {
+            batch: true,
+            calls: AtomicUsize::new(0),
+        };
+        let single = CountingProvider {
+            batch: false,
+            calls: AtomicUsize::new(0),
+        };
+        let updates = vec![many_records_update(&batch), many_records_update(&single)];
+
+        let reqwest = reqwest::Client::new();
+        let summary = update_domains(&reqwest, &updates, NonZeroUsize::new(2).unwrap(), None).await;
+
+        assert!(!summary.has_failures());
+        assert_eq!(batch.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(single.calls.load(Ordering::SeqCst), 5);
+    }
+
+    /// Holds `existing` records and logs which write method was called for which record.
+    struct RecordingProvider {
+        existing: Vec<Record>,
+        calls: std::sync::Mutex<Vec<(&'static str, String)>>,
+    }
+
+    #[async_trait]
+    impl Provider for RecordingProvider {
+        fn get_provider_name(&self) -> &'static str {
+            "Recording"
+        }
+
+        fn get_supported_features(&self) -> Vec<Feature> {
+            vec![Feature::AddRecord, Feature::UpdateRecord]
+        }
+
+        async fn get_all_records(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &GetAllRecordsInput,
+        ) -> Result<Vec<Record>> {
+            Ok(self.existing.clone())
+        }
+
+        async fn add_record(&self, _http: &dyn HttpClient, input: &AddRecordInput) -> Result<()> {
+            let call = ("add", input.record.domain.clone());
+            self.calls.lock().unwrap().push(call);
+            Ok(())
+        }
+
+        async fn update_record(
+            &self,
+            _http: &dyn HttpClient,
+            input: &UpdateRecordInput,
+        ) -> Result<()> {
+            let call = ("update", input.record.domain.clone());
+            self.calls.lock().unwrap().push(call);
+            Ok(())
+        }
+
+        async fn delete_record(
+            &self,
+            _http: &dyn HttpClient,
+            _input: &DeleteRecordInput,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_record_is_created() {
+        let provider = RecordingProvider {
+            existing: vec![Record {
+                domain: "home.example.com".to_string(),
+                value: RecordValue::A(Ipv4Addr::new(1, 1, 1, 1)),
+                ttl: None,
+            }],
+            calls: Default::default(),
+        };
+        let mut update = domain_update(&provider, "example.com");
+        update.records.push(Record {
+            domain: "new.example.com".to_string(),
+            value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+            ttl: None,
+        });
+
+        let reqwest = reqwest::Client::new();
+        let summary =
+            update_domains(&reqwest, &[update], NonZeroUsize::new(1).unwrap(), None).await;
+
+        assert!(!summary.has_failures());
+        assert_eq!(
+            *provider.calls.lock().unwrap(),
+            vec![
+                ("update", "home.example.com".to_string()),
+                ("add", "new.example.com".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_records_for_domain_skips_unresolved() {
+        let record_configs = vec![
+            RecordConfig::Automatic(AutomaticRecordConfig {
+                domain: "home.example.com".to_string(),
+                ttl: None,
+                resolve_type: ResolveType::IPv4,
+                ipv6_suffix: None,
+                providers: vec![],
+            }),
+            RecordConfig::Automatic(AutomaticRecordConfig {
+                domain: "home.example.com".to_string(),
+                ttl: None,
+                resolve_type: ResolveType::IPv6,
+                ipv6_suffix: None,
+                providers: vec![],
+            }),
+        ];
+
+        let records = records_for_domain(&record_configs, Some(Ipv4Addr::new(1, 2, 3, 4)), None);
+
+        assert_eq!(records.len(), 1);
+        assert!(matches!(records[0].value, RecordValue::A(_)));
+    }
+    #[test]
+    fn test_ipv6_suffix_is_combined_with_prefix() {
+        let prefix: Ipv6Addr = "2001:db8::".parse().unwrap();
+        let suffix: Ipv6Addr = "::1234".parse().unwrap();
+        assert_eq!(
+            with_ipv6_suffix(prefix, suffix),
+            "2001:db8::1234".parse::<Ipv6Addr>().unwrap()
+        );
+
+        // Only the low 64 bits of the suffix and the /64 prefix of the address are used
+        let router: Ipv6Addr = "2001:db8:0:0:aaaa:bbbb:cccc:dddd".parse().unwrap();
+        let suffix: Ipv6Addr = "ffff::1234".parse().unwrap();
+        assert_eq!(
+            with_ipv6_suffix(router, suffix),
+            "2001:db8::1234".parse::<Ipv6Addr>().unwrap()
+        );
+
+        let config = AutomaticRecordConfig {
+            domain: "nas.example.com".to_string(),
+            ttl: None,
+            resolve_type: ResolveType::IPv6,
+            ipv6_suffix: Some("::1234".parse().unwrap()),
+            providers: vec![],
+        };
+        let record = automatic_record(&config, None, Some(router)).unwrap();
+        assert_eq!(record.value.content(), "2001:db8::1234");
+    }
+
+    #[test]
+    fn test_records_by_provider_fans_out() {
+        let record_configs = vec![
+            RecordConfig::Automatic(AutomaticRecordConfig {
+                domain: "home.example.com".to_string(),
+                ttl: None,
+                resolve_type: ResolveType::IPv4,
+                ipv6_suffix: None,
+                providers: vec!["Primary1".to_string(), "Secondary1".to_string()],
+            }),
+            RecordConfig::Automatic(AutomaticRecordConfig {
+                domain: "nas.example.com".to_string(),
+                ttl: None,
+                resolve_type: ResolveType::IPv4,
+                ipv6_suffix: None,
+                providers: vec![],
+            }),
+        ];
+
+        let groups = records_by_provider(
+            "Primary1",
+            &record_configs,
+            Some(Ipv4Addr::new(1, 2, 3, 4)),
+            None,
+        );
+
+        let summary: Vec<(&str, Vec<&str>)> = groups
+            .iter()
+            .map(|(name, records)| {
+                let domains = records
+                    .iter()
+                    .map(|record| record.domain.as_str())
+                    .collect();
+                (*name, domains)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Primary1", vec!["home.example.com", "nas.example.com"]),
+                ("Secondary1", vec!["home.example.com"]),
+            ]
+        );
+    }
+}
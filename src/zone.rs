@@ -0,0 +1,496 @@
+use std::{
+    fmt::Write as _,
+    net::{Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+use crate::types::dns::{MxRecord, Record, RecordType, RecordValue, SvcbRecord};
+
+/// TTL written as `$TTL` directive of exported zone files.
+pub const DEFAULT_TTL: u32 = 3600;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ZoneError {
+    #[error("Line {line}: {message}")]
+    Syntax { line: usize, message: String },
+}
+
+/// Returns the owner name of `name` within `origin`: `@` for the apex, a relative name for
+/// names inside the zone, an absolute name otherwise.
+fn relative_owner(name: &str, origin: &str) -> String {
+    let name = name.trim_end_matches('.');
+    let origin = origin.trim_end_matches('.');
+    if name == origin {
+        return "@".to_string();
+    }
+
+    match name
+        .strip_suffix(origin)
+        .and_then(|name| name.strip_suffix('.'))
+    {
+        Some(relative) => relative.to_string(),
+        None => format!("{}.", name),
+    }
+}
+
+/// Renders `records` of the zone `origin` as an RFC 1035 zone file.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::types::dns::{Record, RecordValue};
+/// use dnrs::zone::render;
+/// use std::net::Ipv4Addr;
+///
+/// let records = vec![Record {
+///     domain: "example.com".to_string(),
+///     value: RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+///     ttl: Some(300),
+/// }];
+///
+/// let zone = render("example.com", &records);
+/// assert!(zone.starts_with("$ORIGIN example.com.\n$TTL 3600\n"));
+/// assert!(zone.contains("@\t300\tIN\tA\t1.2.3.4"));
+/// ```
+pub fn render(origin: &str, records: &[Record]) -> String {
+    let mut zone = String::new();
+    let _ = writeln!(zone, "$ORIGIN {}.", origin.trim_end_matches('.'));
+    let _ = writeln!(zone, "$TTL {}", DEFAULT_TTL);
+
+    for record in records {
+        let _ = write!(zone, "{}\t", relative_owner(&record.domain, origin));
+        if let Some(ttl) = record.ttl {
+            let _ = write!(zone, "{}\t", ttl);
+        }
+        let _ = writeln!(
+            zone,
+            "IN\t{:?}\t{}",
+            record.value.record_type(),
+            record.value
+        );
+    }
+
+    zone
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+/// Splits a zone file line into tokens, honoring quoted strings and stopping at comments.
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == ';' {
+            break;
+        } else if c == '"' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some('\\') => match chars.next() {
+                        Some(escaped) => text.push(escaped),
+                        None => return Err("Unterminated escape sequence".to_string()),
+                    },
+                    Some('"') => break,
+                    Some(c) => text.push(c),
+                    None => return Err("Unterminated quoted string".to_string()),
+                }
+            }
+            tokens.push(Token { text, quoted: true });
+        } else {
+            let mut text = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == ';' || c == '"' {
+                    break;
+                }
+                text.push(c);
+                chars.next();
+            }
+            tokens.push(Token {
+                text,
+                quoted: false,
+            });
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Resolves a name of a zone file relative to `origin`, returning it without trailing dot.
+fn resolve_name(name: &str, origin: &str) -> String {
+    if name == "@" {
+        return origin.to_string();
+    }
+
+    match name.strip_suffix('.') {
+        Some(absolute) => absolute.to_string(),
+        None if origin.is_empty() => name.to_string(),
+        None => format!("{}.{}", name, origin),
+    }
+}
+
+fn parse_number<T: FromStr>(token: Option<&Token>, what: &str) -> Result<T, String> {
+    let token = token.ok_or_else(|| format!("Missing {}", what))?;
+    token
+        .text
+        .parse()
+        .map_err(|_| format!("Invalid {} '{}'", what, token.text))
+}
+
+fn expect_count(rdata: &[Token], count: usize, record_type: RecordType) -> Result<(), String> {
+    if rdata.len() != count {
+        return Err(format!(
+            "{:?} record needs {} fields, found {}",
+            record_type,
+            count,
+            rdata.len()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Joins the character strings of a TXT record. A single string is stored as is, several
+/// strings are stored in their quoted form `"part one" "part two"`.
+fn join_text(rdata: &[Token]) -> Result<String, String> {
+    match rdata {
+        [] => Err("Missing text".to_string()),
+        [token] => Ok(token.text.clone()),
+        tokens => Ok(tokens
+            .iter()
+            .map(|token| format!("\"{}\"", token.text.replace('"', "\\\"")))
+            .collect::<Vec<_>>()
+            .join(" ")),
+    }
+}
+
+fn joined(rdata: &[Token]) -> String {
+    rdata
+        .iter()
+        .map(|token| token.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parses SVCB/HTTPS RDATA. Quoted parameter values (`alpn="h2,h3"`) are split off by the
+/// tokenizer and joined back to their key before parsing.
+fn svcb_value(rdata: &[Token], origin: &str) -> Result<SvcbRecord, String> {
+    let mut content = String::new();
+    for token in rdata {
+        let joins_key = token.quoted && content.ends_with('=');
+        if !content.is_empty() && !joins_key {
+            content.push(' ');
+        }
+        if token.quoted {
+            content.push_str(&format!("\"{}\"", token.text.replace('"', "\\\"")));
+        } else {
+            content.push_str(&token.text);
+        }
+    }
+
+    let mut svcb = SvcbRecord::from_str(&content).map_err(|error| error.to_string())?;
+    if svcb.target != "." {
+        svcb.target = resolve_name(&svcb.target, origin);
+    }
+
+    Ok(svcb)
+}
+
+fn rdata_value(
+    record_type: RecordType,
+    rdata: &[Token],
+    origin: &str,
+) -> Result<RecordValue, String> {
+    let value = match record_type {
+        RecordType::A => {
+            expect_count(rdata, 1, record_type)?;
+            let ip = Ipv4Addr::from_str(&rdata[0].text)
+                .map_err(|_| format!("Invalid IPv4 address '{}'", rdata[0].text))?;
+            RecordValue::A(ip)
+        }
+        RecordType::AAAA => {
+            expect_count(rdata, 1, record_type)?;
+            let ip = Ipv6Addr::from_str(&rdata[0].text)
+                .map_err(|_| format!("Invalid IPv6 address '{}'", rdata[0].text))?;
+            RecordValue::AAAA(ip)
+        }
+        RecordType::CNAME => {
+            expect_count(rdata, 1, record_type)?;
+            RecordValue::CNAME(resolve_name(&rdata[0].text, origin))
+        }
+        RecordType::NS => {
+            expect_count(rdata, 1, record_type)?;
+            RecordValue::NS(resolve_name(&rdata[0].text, origin))
+        }
+        RecordType::TXT => RecordValue::TXT(join_text(rdata)?),
+        RecordType::SPF => RecordValue::SPF(join_text(rdata)?),
+        RecordType::SOA => {
+            expect_count(rdata, 7, record_type)?;
+            let fields: Vec<&str> = rdata.iter().map(|token| token.text.as_str()).collect();
+            RecordValue::SOA(fields.join(" "))
+        }
+        RecordType::MX => {
+            expect_count(rdata, 2, record_type)?;
+            RecordValue::MX(MxRecord {
+                priority: parse_number(rdata.first(), "MX priority")?,
+                target: resolve_name(&rdata[1].text, origin),
+            })
+        }
+        RecordType::SRV => {
+            expect_count(rdata, 4, record_type)?;
+            RecordValue::SRV(
+                parse_number(rdata.first(), "SRV priority")?,
+                parse_number(rdata.get(1), "SRV weight")?,
+                parse_number(rdata.get(2), "SRV port")?,
+                resolve_name(&rdata[3].text, origin),
+            )
+        }
+        RecordType::TLSA => {
+            expect_count(rdata, 4, record_type)?;
+            RecordValue::TLSA(
+                parse_number(rdata.first(), "TLSA usage")?,
+                parse_number(rdata.get(1), "TLSA selector")?,
+                parse_number(rdata.get(2), "TLSA matching type")?,
+                rdata[3].text.clone(),
+            )
+        }
+        RecordType::CAA => {
+            expect_count(rdata, 3, record_type)?;
+            RecordValue::CAA(
+                parse_number(rdata.first(), "CAA flag")?,
+                rdata[1].text.clone(),
+                rdata[2].text.clone(),
+            )
+        }
+        RecordType::SVCB => RecordValue::SVCB(svcb_value(rdata, origin)?),
+        RecordType::HTTPS => RecordValue::HTTPS(svcb_value(rdata, origin)?),
+        RecordType::DS => {
+            RecordValue::parse_ds(&joined(rdata)).map_err(|error| error.to_string())?
+        }
+        RecordType::DNSKEY => {
+            RecordValue::parse_dnskey(&joined(rdata)).map_err(|error| error.to_string())?
+        }
+    };
+
+    Ok(value)
+}
+
+/// Parses an RFC 1035 zone file into records.
+///
+/// `origin` is the initial origin, `$ORIGIN` and `$TTL` directives are honored. Records
+/// without a TTL get the `$TTL` value if one was given. Owner names may be omitted to repeat
+/// the previous owner. Records spanning several lines in parentheses are not supported.
+///
+/// # Examples
+///
+/// ```
+/// use dnrs::zone::parse;
+///
+/// let records = parse("$TTL 300\nwww IN A 1.2.3.4\n", "example.com").unwrap();
+/// assert_eq!(records[0].domain, "www.example.com");
+/// assert_eq!(records[0].ttl, Some(300));
+/// ```
+pub fn parse(text: &str, origin: &str) -> Result<Vec<Record>, ZoneError> {
+    let mut origin = origin.trim_end_matches('.').to_string();
+    let mut default_ttl = None;
+    let mut previous_owner: Option<String> = None;
+    let mut records = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let syntax_error = |message: String| ZoneError::Syntax {
+            line: index + 1,
+            message,
+        };
+
+        let tokens = tokenize(line).map_err(syntax_error)?;
+        let Some(first) = tokens.first() else {
+            continue;
+        };
+
+        if first.text.starts_with('$') && !first.quoted {
+            let argument = tokens
+                .get(1)
+                .ok_or_else(|| syntax_error(format!("Missing argument for {}", first.text)))?;
+            match first.text.to_ascii_uppercase().as_str() {
+                "$ORIGIN" => origin = resolve_name(&argument.text, &origin),
+                "$TTL" => {
+                    default_ttl = Some(parse_number(Some(argument), "TTL").map_err(syntax_error)?)
+                }
+                directive => {
+                    return Err(syntax_error(format!("Unsupported directive {}", directive)));
+                }
+            }
+            continue;
+        }
+
+        let mut rest = tokens.as_slice();
+        let owner = if line.starts_with(char::is_whitespace) {
+            previous_owner
+                .clone()
+                .ok_or_else(|| syntax_error("Missing owner name".to_string()))?
+        } else {
+            rest = &rest[1..];
+            resolve_name(&first.text, &origin)
+        };
+
+        let mut ttl = None;
+        let record_type = loop {
+            let token = rest
+                .first()
+                .ok_or_else(|| syntax_error("Missing record type".to_string()))?;
+            rest = &rest[1..];
+
+            if token.text.eq_ignore_ascii_case("IN") {
+                continue;
+            }
+            if let Ok(value) = token.text.parse::<u32>() {
+                ttl = Some(value);
+                continue;
+            }
+            break token
+                .text
+                .parse::<RecordType>()
+                .map_err(|_| syntax_error(format!("Unsupported record type {}", token.text)))?;
+        };
+
+        let value = rdata_value(record_type, rest, &origin).map_err(syntax_error)?;
+        records.push(Record {
+            domain: owner.clone(),
+            value,
+            ttl: ttl.or(default_ttl),
+        });
+        previous_owner = Some(owner);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_records() -> Vec<Record> {
+        let record = |domain: &str, value: RecordValue, ttl: Option<u32>| Record {
+            domain: domain.to_string(),
+            value,
+            ttl,
+        };
+
+        vec![
+            record(
+                "example.com",
+                RecordValue::A(Ipv4Addr::new(1, 2, 3, 4)),
+                Some(300),
+            ),
+            record(
+                "example.com",
+                RecordValue::AAAA("2001:db8::1".parse().unwrap()),
+                Some(300),
+            ),
+            record(
+                "example.com",
+                RecordValue::MX(MxRecord {
+                    priority: 10,
+                    target: "mail.example.com".to_string(),
+                }),
+                Some(3600),
+            ),
+            record(
+                "example.com",
+                RecordValue::TXT("v=spf1 include:\"quoted\" -all".to_string()),
+                None,
+            ),
+            record(
+                "www.example.com",
+                RecordValue::CNAME("example.com".to_string()),
+                Some(60),
+            ),
+            record(
+                "_sip._tcp.example.com",
+                RecordValue::SRV(10, 5, 5060, "sip.other.org".to_string()),
+                Some(600),
+            ),
+            record(
+                "example.com",
+                RecordValue::CAA(0, "issue".to_string(), "letsencrypt.org".to_string()),
+                Some(3600),
+            ),
+            record(
+                "_443._tcp.www.example.com",
+                RecordValue::TLSA(3, 1, 1, "ab".repeat(32)),
+                Some(3600),
+            ),
+            record(
+                "sub.example.com",
+                RecordValue::DS(2371, 13, 2, "ab".repeat(32)),
+                Some(3600),
+            ),
+            record(
+                "example.com",
+                RecordValue::HTTPS("1 . alpn=h2,h3 ech=\"a b\"".parse().unwrap()),
+                Some(300),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_render_uses_apex_and_relative_names() {
+        let zone = render("example.com", &sample_records());
+        let lines: Vec<&str> = zone.lines().collect();
+
+        assert_eq!(lines[0], "$ORIGIN example.com.");
+        assert_eq!(lines[1], "$TTL 3600");
+        assert_eq!(lines[2], "@\t300\tIN\tA\t1.2.3.4");
+        assert_eq!(lines[6], "www\t60\tIN\tCNAME\texample.com.");
+        assert_eq!(
+            lines[7],
+            "_sip._tcp\t600\tIN\tSRV\t10 5 5060 sip.other.org."
+        );
+    }
+
+    #[test]
+    fn test_exported_zone_round_trips() {
+        let records = sample_records();
+        let zone = render("example.com", &records);
+
+        let parsed = parse(&zone, "other.org").unwrap();
+
+        assert_eq!(parsed.len(), records.len());
+        for (parsed, record) in parsed.iter().zip(records.iter()) {
+            assert_eq!(parsed.domain, record.domain);
+            assert_eq!(format!("{:?}", parsed.value), format!("{:?}", record.value));
+            assert_eq!(parsed.ttl, Some(record.ttl.unwrap_or(DEFAULT_TTL)));
+        }
+    }
+
+    #[test]
+    fn test_parse_svcb_with_relative_target() {
+        let records = parse(
+            "_dns IN SVCB 1 dns alpn=\"h2,h3\" port=853\n",
+            "example.com",
+        )
+        .unwrap();
+
+        let RecordValue::SVCB(svcb) = &records[0].value else {
+            panic!("Expected SVCB record");
+        };
+        assert_eq!(svcb.target, "dns.example.com");
+        assert_eq!(svcb.params["alpn"], "h2,h3");
+        assert_eq!(svcb.params["port"], "853");
+    }
+
+    #[test]
+    fn test_parse_reports_line_of_error() {
+        let result = parse("$TTL 300\nwww IN A not-an-ip\n", "example.com");
+        assert!(matches!(result, Err(ZoneError::Syntax { line: 2, .. })));
+    }
+}